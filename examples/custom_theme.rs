@@ -0,0 +1,85 @@
+// One frame of the spectrum in a gradient of your own, printed to the terminal
+// Run with: cargo run --example custom_theme [config.toml theme-name]
+//
+// The gradient is read from a [themes.<name>] section, the same as in
+// gruvberry's config file, and put on a Theme; without arguments the
+// section below is used. The audio is a made-up set of tones across the
+// range, so every part of the gradient shows.
+
+use std::error::Error;
+use std::f32::consts::TAU;
+use crossterm::style::{Color as TermColor, Stylize};
+use gruvberry::analysis::{self, Analyzer};
+use gruvberry::gradient::Anchor;
+use gruvberry::palette;
+use gruvberry::spectrum::{self, SpectrumWidget};
+use gruvberry::theme::Theme;
+use gruvberry::tilt::Tilt;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::Widget;
+
+const SAMPLE_RATE: u32 = 44100;
+const HEIGHT: u16 = 12;
+
+const EXAMPLE: &str = "\
+[themes.sea]
+low_hue = 170
+low_saturation = 0.9
+high_hue = 260
+high_saturation = 0.4
+";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (text, name) = match args.as_slice() {
+        [] => (EXAMPLE.to_string(), "sea"),
+        [path, name] => (std::fs::read_to_string(path)?, name.as_str()),
+        _ => return Err("usage: custom_theme [config.toml theme-name]".into()),
+    };
+    let config: toml::Table = text.parse()?;
+    let section = config
+        .get(palette::TABLE)
+        .and_then(|themes| themes.get(name))
+        .and_then(|theme| theme.as_table())
+        .ok_or_else(|| format!("no [{}.{}] section", palette::TABLE, name))?;
+    let mut theme = Theme::default();
+    theme.palette = palette::parse(section)?;
+
+    // A tone every half octave from 40 Hz
+    let tones: Vec<f32> = (0..18).map(|i| 40.0 * 2f32.powf(i as f32 / 2.0)).collect();
+    let mut analyzer = Analyzer::new(SAMPLE_RATE, analysis::DEFAULT_FFT_SIZE, 0.0, Tilt::default());
+    let samples: Vec<f32> = (0..analyzer.state().fft_size())
+        .map(|n| {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            tones.iter().map(|freq| (TAU * freq * t).sin()).sum::<f32>() / tones.len() as f32
+        })
+        .collect();
+
+    let width = crossterm::terminal::size().map_or(80, |(columns, _)| columns.max(1));
+    let num_bands = width as usize;
+    let bands = analyzer.process(&samples, num_bands);
+    let levels = analysis::normalize(bands, analysis::max_amplitude(bands));
+    // Colored by frequency, as gruvberry does, rather than by position
+    let colors = spectrum::band_colors(num_bands, analyzer.range(), Anchor::Frequency, &theme);
+
+    // Drawn into a buffer of our own rather than through a Terminal, so it
+    // prints like any other output (and can be piped to a file)
+    let area = Rect::new(0, 0, width, HEIGHT);
+    let mut buffer = Buffer::empty(area);
+    SpectrumWidget::new(&levels, &theme).colors(&colors).render(area, &mut buffer);
+    for y in 0..HEIGHT {
+        let line: String = (0..width)
+            .map(|x| {
+                let cell = &buffer[(x, y)];
+                match cell.fg {
+                    Color::Rgb(r, g, b) => cell.symbol().with(TermColor::Rgb { r, g, b }).to_string(),
+                    _ => cell.symbol().to_string(),
+                }
+            })
+            .collect();
+        println!("{}", line);
+    }
+    Ok(())
+}
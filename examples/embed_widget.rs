@@ -0,0 +1,76 @@
+// The spectrum as one widget among others in a small ratatui app
+// Run with: cargo run --example embed_widget -- song.wav   (q quits)
+//
+// A file plays through a capture tap that fills a SampleRing; thirty times a
+// second the app analyzes the newest window and draws it with
+// SpectrumWidget under a title line and above a status line of its own. The
+// widget brings no border, layout or keys of its own, only the bars.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Duration;
+use crossterm::event::{self, Event, KeyCode};
+use gruvberry::analysis::{self, Analyzer};
+use gruvberry::capture::{self, SampleCapture, SampleRing};
+use gruvberry::spectrum::SpectrumWidget;
+use gruvberry::theme::Theme;
+use gruvberry::tilt::Tilt;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use rodio::{Decoder, OutputStreamBuilder, Sink, Source};
+
+const FRAME: Duration = Duration::from_millis(33);
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = std::env::args().nth(1).ok_or("usage: embed_widget <file>")?;
+    let file = File::open(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let decoder = Decoder::new(BufReader::new(file))?;
+    let sample_rate = decoder.sample_rate();
+
+    let mut analyzer =
+        Analyzer::new(sample_rate, analysis::DEFAULT_FFT_SIZE, analysis::DEFAULT_SMOOTHING, Tilt::default());
+    let window = analyzer.state().fft_size();
+    let ring = SampleRing::shared(capture::ring_capacity(window, sample_rate, 0));
+
+    let stream = OutputStreamBuilder::open_default_stream()?;
+    let sink = Sink::connect_new(stream.mixer());
+    sink.append(SampleCapture::new(decoder, ring.clone()));
+
+    let theme = Theme::detect(false);
+    let mut terminal = ratatui::init();
+    let result = (|| -> Result<(), Box<dyn Error>> {
+        let mut levels: Vec<f32> = Vec::new();
+        while !sink.empty() {
+            let size = terminal.size()?;
+            // One band per column inside the border
+            let num_bands = size.width.saturating_sub(2).max(1) as usize;
+            let newest = ring.lock().map_err(|_| "capture ring poisoned")?.window(window, 0);
+            if let Some(samples) = newest {
+                let bands = analyzer.process_after(&samples, num_bands, FRAME.as_secs_f32());
+                levels = analysis::normalize(bands, analysis::max_amplitude(bands));
+            }
+
+            terminal.draw(|frame| {
+                let [title, spectrum, status] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Fill(1), Constraint::Length(1)])
+                        .areas(frame.area());
+                frame.render_widget(Paragraph::new(format!("Now playing: {}", path)), title);
+                let block = Block::default().borders(Borders::ALL);
+                frame.render_widget(SpectrumWidget::new(&levels, &theme), block.inner(spectrum));
+                frame.render_widget(block, spectrum);
+                frame.render_widget(Paragraph::new("q quits").style(theme.dim), status);
+            })?;
+
+            if event::poll(FRAME)?
+                && let Event::Key(key) = event::read()?
+                && key.code == KeyCode::Char('q')
+            {
+                break;
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,57 @@
+// Band levels of a whole file as CSV, one row per analysis frame
+// Run with: cargo run --example extract_bands -- song.wav [bands] > bands.csv
+//
+// The file is analyzed the way the visualizer does it (downmixed, FFT of the
+// newest window, log-spaced bands, smoothed), only as fast as it decodes
+// rather than as it plays. Levels are dB relative to a full-scale sine.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use gruvberry::analysis::{self, Analyzer};
+use gruvberry::tilt::Tilt;
+use rodio::{Decoder, Source};
+
+// Frames per second of audio, the visualizer's analysis rate
+const FPS: usize = 30;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().ok_or("usage: extract_bands <file> [bands]")?;
+    let num_bands: usize = match args.next() {
+        Some(bands) => bands.parse().map_err(|_| format!("not a number of bands: {}", bands))?,
+        None => 32,
+    };
+
+    let file = File::open(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let decoder = Decoder::new(BufReader::new(file))?;
+    let (channels, sample_rate) = (decoder.channels() as usize, decoder.sample_rate());
+    let interleaved: Vec<f32> = decoder.collect();
+    let mono: Vec<f32> = interleaved
+        .chunks_exact(channels.max(1))
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    let mut analyzer = Analyzer::new(sample_rate, analysis::DEFAULT_FFT_SIZE, analysis::DEFAULT_SMOOTHING, Tilt::Flat);
+    // Higher rates get a longer window, so ask the analyzer rather than
+    // assuming DEFAULT_FFT_SIZE
+    let window = analyzer.state().fft_size();
+    let hop = sample_rate as usize / FPS;
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let range = analyzer.range();
+    let centers = (0..num_bands).map(|band| {
+        let (low, high) = range.band_edges(band, num_bands);
+        format!("{:.0} Hz", (low * high).sqrt())
+    });
+    writeln!(out, "time,{}", centers.collect::<Vec<_>>().join(","))?;
+
+    // Each frame ends `hop` samples after the last, as the ring would hold them
+    for end in (window..=mono.len()).step_by(hop.max(1)) {
+        analyzer.process_after(&mono[end - window..end], num_bands, 1.0 / FPS as f32);
+        let levels: Vec<String> = analyzer.levels_db().iter().map(|db| format!("{:.1}", db)).collect();
+        writeln!(out, "{:.3},{}", end as f32 / sample_rate as f32, levels.join(","))?;
+    }
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,82 @@
+// RMS and peak level of the default microphone, four readings a second
+// Run with: cargo run --example live_mic   (Ctrl+C stops)
+//
+// The input stream is opened with cpal (through rodio's re-export) and its
+// callback hands every buffer to a SampleRing, the same observer the
+// visualizer reads from, so the main thread only ever looks at the newest
+// quarter second. Levels are of the downmix, in dBFS.
+
+use std::error::Error;
+use std::time::Duration;
+use gruvberry::capture::{SampleObserver, SampleRing, SharedRing};
+use gruvberry::dynamics;
+use rodio::cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use rodio::cpal::{self, FromSample, SampleFormat, SizedSample};
+
+const READING: Duration = Duration::from_millis(250);
+// Width of the meter for -60 to 0 dBFS
+const METER_WIDTH: usize = 40;
+const FLOOR_DB: f32 = -60.0;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let host = cpal::default_host();
+    let device = host.default_input_device().ok_or("no input device")?;
+    let supported = device.default_input_config()?;
+    let config = supported.config();
+    let (channels, sample_rate) = (config.channels, config.sample_rate.0);
+    eprintln!("{}: {} Hz, {} channel(s)", device.name()?, sample_rate, channels);
+
+    let window = (sample_rate as f32 * READING.as_secs_f32()) as usize;
+    let ring = SampleRing::shared(window * 2);
+    let stream = match supported.sample_format() {
+        SampleFormat::F32 => listen::<f32>(&device, &config, ring.clone())?,
+        SampleFormat::I16 => listen::<i16>(&device, &config, ring.clone())?,
+        SampleFormat::U16 => listen::<u16>(&device, &config, ring.clone())?,
+        format => return Err(format!("unsupported input sample format {}", format).into()),
+    };
+    stream.play()?;
+
+    loop {
+        std::thread::sleep(READING);
+        let Some(samples) = ring.lock().map_err(|_| "capture ring poisoned")?.window(window, 0) else {
+            continue;
+        };
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        let db = |level: f32| 20.0 * level.max(1e-10).log10();
+        let filled = ((1.0 - db(rms).max(FLOOR_DB) / FLOOR_DB) * METER_WIDTH as f32) as usize;
+        let crest = dynamics::crest_factor_db(&samples).map_or("-".to_string(), |crest| format!("{:.1}", crest));
+        println!(
+            "rms {:6.1}  peak {:6.1}  crest {:>5}  {}",
+            db(rms),
+            db(peak),
+            crest,
+            "#".repeat(filled)
+        );
+    }
+}
+
+// An input stream converting samples of type T to f32 for the ring
+fn listen<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut ring: SharedRing,
+) -> Result<cpal::Stream, Box<dyn Error>>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let (channels, sample_rate) = (config.channels, config.sample_rate.0);
+    let mut converted: Vec<f32> = Vec::new();
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            converted.clear();
+            converted.extend(data.iter().map(|&sample| f32::from_sample_(sample)));
+            ring.on_samples(&converted, channels, sample_rate);
+        },
+        |error| eprintln!("input stream error: {}", error),
+        None,
+    )?;
+    Ok(stream)
+}
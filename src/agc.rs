@@ -0,0 +1,169 @@
+//! Gain staging for a live input's analysis path.
+//!
+//! A microphone or line input arrives at whatever level the OS mixer leaves
+//! it: a sliver of a spectrum one day, a clipped one the next. [`AutoGain`]
+//! slowly steers the level the analyzer sees toward [`TARGET_RMS_DB`], within
+//! [`MIN_GAIN_DB`] to [`MAX_GAIN_DB`], and only ever scales samples it is
+//! handed: nothing is written back to the system mixer. It can be switched
+//! off for measurements, where the gain must stay at unity.
+//!
+//! No gain fixes an input that clips before it reaches us, or one buried in
+//! noise, so [`LevelMonitor`] watches the raw level for that and raises a
+//! [`LevelWarning`] once a problem has lasted long enough to not be a single
+//! loud or quiet moment.
+//!
+//! ```
+//! use gruvberry::agc::{AutoGain, TARGET_RMS_DB};
+//!
+//! // A quiet input, -40 dBFS, for half a minute in 100 ms blocks
+//! let mut agc = AutoGain::new();
+//! for _ in 0..300 {
+//!     agc.update(0.01, 0.1);
+//! }
+//! assert!((-40.0 + agc.gain_db() - TARGET_RMS_DB).abs() < 0.5);
+//! ```
+
+/// Level the analysis path is steered toward (RMS, dBFS).
+pub const TARGET_RMS_DB: f32 = -20.0;
+/// Least and most gain applied.
+pub const MIN_GAIN_DB: f32 = -20.0;
+pub const MAX_GAIN_DB: f32 = 40.0;
+/// Time constant of the gain's moves: slow, so the music's own dynamics
+/// survive.
+pub const ADJUST_SECS: f32 = 2.0;
+
+/// Input below this (RMS, dBFS) is too quiet to analyze well.
+pub const LOW_RMS_DB: f32 = -50.0;
+/// Input above this (RMS, dBFS) is so hot its peaks are surely clipping.
+pub const CLIP_RMS_DB: f32 = -6.0;
+/// How long a level must last before it is warned about.
+pub const LOW_HOLD_SECS: f32 = 2.0;
+pub const CLIP_HOLD_SECS: f32 = 0.5;
+
+// Below this (RMS, dBFS) the input is taken as a pause, which must not pump
+// the gain up to the maximum
+const GATE_RMS_DB: f32 = -70.0;
+
+/// RMS of `samples` in dBFS (a full-scale square wave is 0 dB).
+pub fn rms_db(samples: &[f32]) -> f32 {
+    let mean_square = samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32;
+    10.0 * mean_square.max(1e-12).log10()
+}
+
+/// Slow automatic gain control.
+#[derive(Clone, Debug)]
+pub struct AutoGain {
+    gain_db: f32,
+    enabled: bool,
+}
+
+impl AutoGain {
+    /// At unity gain, enabled.
+    pub fn new() -> Self {
+        AutoGain {
+            gain_db: 0.0,
+            enabled: true,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Switch the control on or off; off holds the gain at unity.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.gain_db = 0.0;
+        }
+    }
+
+    /// Gain applied now, in dB, for the readout.
+    pub fn gain_db(&self) -> f32 {
+        self.gain_db
+    }
+
+    /// Gain applied now, as a factor.
+    pub fn gain(&self) -> f32 {
+        10f32.powf(self.gain_db / 20.0)
+    }
+
+    /// Move the gain on for a block of `secs` of input measuring `rms`
+    /// (linear, before any gain). Returns the gain to apply to the block.
+    pub fn update(&mut self, rms: f32, secs: f32) -> f32 {
+        let level_db = 20.0 * rms.max(1e-12).log10();
+        if self.enabled && level_db > GATE_RMS_DB {
+            let wanted = (TARGET_RMS_DB - level_db).clamp(MIN_GAIN_DB, MAX_GAIN_DB);
+            let step = 1.0 - (-secs.max(0.0) / ADJUST_SECS).exp();
+            self.gain_db += (wanted - self.gain_db) * step;
+        }
+        self.gain()
+    }
+
+    /// Scale a block of `samples` at `sample_rate` (interleaved `channels`)
+    /// in place, moving the gain on by the block first.
+    pub fn apply(&mut self, samples: &mut [f32], channels: u16, sample_rate: u32) {
+        let rms = 10f32.powf(rms_db(samples) / 20.0);
+        let secs = samples.len() as f32 / (channels.max(1) as u32 * sample_rate.max(1)) as f32;
+        let gain = self.update(rms, secs);
+        for sample in samples {
+            *sample *= gain;
+        }
+    }
+}
+
+impl Default for AutoGain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A level problem the input gain has to be fixed at the source for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LevelWarning {
+    TooLow,
+    Clipping,
+}
+
+impl LevelWarning {
+    /// Text for the status area.
+    pub fn message(self) -> &'static str {
+        match self {
+            LevelWarning::TooLow => "Input too low: raise the input level in the system mixer",
+            LevelWarning::Clipping => "Input clipping: lower the input level in the system mixer",
+        }
+    }
+}
+
+/// Watches the raw input level for sustained problems.
+#[derive(Clone, Debug, Default)]
+pub struct LevelMonitor {
+    // How long the input has been too low and too hot without a break
+    low_secs: f32,
+    hot_secs: f32,
+}
+
+impl LevelMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a block of `secs` of raw input measuring `rms_db`. Returns the
+    /// warning that applies now, if any.
+    pub fn update(&mut self, rms_db: f32, secs: f32) -> Option<LevelWarning> {
+        self.low_secs = if rms_db < LOW_RMS_DB { self.low_secs + secs } else { 0.0 };
+        self.hot_secs = if rms_db > CLIP_RMS_DB { self.hot_secs + secs } else { 0.0 };
+        self.warning()
+    }
+
+    /// The warning that applies, if any.
+    pub fn warning(&self) -> Option<LevelWarning> {
+        if self.hot_secs >= CLIP_HOLD_SECS {
+            Some(LevelWarning::Clipping)
+        } else if self.low_secs >= LOW_HOLD_SECS {
+            Some(LevelWarning::TooLow)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Bell and border-flash alerts on clipping or beats.
+//!
+//! The analysis publishes [`AnalysisEvent`]s; [`Alerts`] turns the ones the
+//! `alert*` settings ask for into at most `max_per_sec` alerts a second.
+
+use std::time::{Duration, Instant};
+
+/// Something in the audio worth telling the user about, published on the
+/// visualizer's analysis event bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalysisEvent {
+    /// A sample of what is being heard reached full scale.
+    Clipped,
+    /// An onset in what is being heard (there's no tempo tracking, so every
+    /// onset counts as a beat).
+    Beat,
+}
+
+/// How an alert gets attention.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertStyle {
+    Bell,
+    /// The spectrum border lights up, for terminals with the bell muted.
+    Flash,
+    Both,
+}
+
+impl AlertStyle {
+    /// "bell", "flash" or "both"; anything else (e.g. "off") is None.
+    pub fn parse(value: &str) -> Option<AlertStyle> {
+        match value {
+            "bell" => Some(AlertStyle::Bell),
+            "flash" => Some(AlertStyle::Flash),
+            "both" => Some(AlertStyle::Both),
+            _ => None,
+        }
+    }
+
+    pub fn bell(self) -> bool {
+        self != AlertStyle::Flash
+    }
+
+    pub fn flash(self) -> bool {
+        self != AlertStyle::Bell
+    }
+}
+
+/// What the alert setting asks for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlertSettings {
+    /// None: no alerts at all.
+    pub style: Option<AlertStyle>,
+    pub on_clip: bool,
+    /// Alert on every Nth beat, a silent metronome; 0 never.
+    pub every_beats: u32,
+    /// Never more alerts than this per second.
+    pub max_per_sec: f32,
+}
+
+impl Default for AlertSettings {
+    fn default() -> Self {
+        AlertSettings {
+            style: None,
+            on_clip: true,
+            every_beats: 0,
+            max_per_sec: 2.0,
+        }
+    }
+}
+
+/// Turns analysis events into rate-limited alerts.
+pub struct Alerts {
+    settings: AlertSettings,
+    beats: u32,
+    last: Option<Instant>,
+}
+
+impl Alerts {
+    pub fn new(settings: AlertSettings) -> Self {
+        Alerts {
+            settings,
+            beats: 0,
+            last: None,
+        }
+    }
+
+    /// The style to alert with for `event`, if it should alert at all.
+    pub fn on_event(&mut self, event: AnalysisEvent, now: Instant) -> Option<AlertStyle> {
+        let style = self.settings.style?;
+        let wanted = match event {
+            AnalysisEvent::Clipped => self.settings.on_clip,
+            AnalysisEvent::Beat => {
+                self.beats += 1;
+                self.settings.every_beats > 0 && self.beats.is_multiple_of(self.settings.every_beats)
+            }
+        };
+        let gap = Duration::from_secs_f32(1.0 / self.settings.max_per_sec);
+        if !wanted || self.last.is_some_and(|last| now.duration_since(last) < gap) {
+            return None;
+        }
+        self.last = Some(now);
+        Some(style)
+    }
+}
@@ -0,0 +1,700 @@
+//! Spectrum analysis: FFT, log-spaced bands, smoothing and peak finding.
+//!
+//! An [`Analyzer`] turns windows of mono samples into band magnitudes for
+//! the bars, with the [`Tilt`] applied and smoothed from frame to frame by
+//! a [`Smoother`]. The samples go in as they are (a rectangular [`Window`])
+//! unless a Hann window is asked for.
+
+use std::sync::Arc;
+use rustfft::{Fft, FftPlanner, num_complex::Complex};
+use crate::rate;
+use crate::reference;
+use crate::smoothing::{self, ExponentialSmoother, Smoother};
+use crate::tilt::Tilt;
+
+/// Default FFT window length in samples (overridable in the config file),
+/// for rates up to [`rate::HIGH_RATE`]; see [`rate::fft_size_for`].
+pub const DEFAULT_FFT_SIZE: usize = 1024;
+
+/// Largest FFT window length.
+pub const MAX_FFT_SIZE: usize = 16384;
+
+/// Lowest displayed frequency (human hearing starts ~20 Hz).
+pub const MIN_FREQ: f32 = 20.0;
+
+/// Frames after a flush() that don't count as settled analysis yet.
+const PRIME_FRAMES: u32 = 4;
+
+/// Default exponential smoothing (0.3 = smooth, 0.7 = responsive, 1.0 = off),
+/// see [`ExponentialSmoother`].
+pub const DEFAULT_SMOOTHING: f32 = 0.3;
+
+/// Most visual gain either way (dB), see [`apply_visual_gain`].
+pub const MAX_VISUAL_GAIN_DB: f32 = 24.0;
+
+/// What the samples of a window are weighted by before the FFT.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Window {
+    /// As they are: a tone between two bins leaks into bands far from it.
+    #[default]
+    Rectangular,
+    /// Tapered to nothing at both ends (periodic Hann): a tone stays within
+    /// a few bins, at half the rectangular window's gain, which
+    /// [`Analyzer::levels_db`] takes back out.
+    Hann,
+}
+
+impl Window {
+    /// Weight of each of `len` samples.
+    pub fn weights(self, len: usize) -> Vec<f32> {
+        match self {
+            Window::Rectangular => vec![1.0; len],
+            Window::Hann => (0..len)
+                .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / len as f32).cos())
+                .collect(),
+        }
+    }
+}
+
+/// FFT, log-spaced band aggregation and smoothing for one audio stream,
+/// at the sample rate its samples were captured at (see [`crate::rate`]).
+pub struct Analyzer {
+    sample_rate: u32,
+    /// FFT size at rates up to [`rate::HIGH_RATE`], scaled up for higher ones.
+    fft_size: usize,
+    /// Configured top of the displayed range; None picks one by the rate.
+    max_display_freq: Option<f32>,
+    tilt: Tilt,
+    /// Frequencies the bands span, the whole range unless zoomed.
+    range: FreqRange,
+    /// FFT plan, band table, buffers and smoother, each rebuilt only when
+    /// what it depends on changes.
+    state: AnalyzerState,
+    /// False until the first frame after creation or reset(), which is taken
+    /// as-is instead of being smoothed up from silence.
+    primed: bool,
+    /// Frames left until the analysis has settled after a flush().
+    priming: u32,
+}
+
+impl Analyzer {
+    /// An analyzer smoothing with an [`ExponentialSmoother`] of `smoothing`,
+    /// with windows of `fft_size` samples at rates up to
+    /// [`rate::HIGH_RATE`] and proportionally more above.
+    pub fn new(sample_rate: u32, fft_size: usize, smoothing: f32, tilt: Tilt) -> Self {
+        Analyzer {
+            sample_rate,
+            fft_size,
+            max_display_freq: None,
+            tilt,
+            range: FreqRange::full(rate::display_max_freq(sample_rate, None)),
+            state: AnalyzerState::new(
+                rate::fft_size_for(fft_size, sample_rate),
+                Box::new(ExponentialSmoother::new(smoothing)),
+            ),
+            primed: false,
+            priming: 0,
+        }
+    }
+
+    /// Smoothing can change live (config reload): exponential by
+    /// `smoothing`.
+    pub fn set_smoothing(&mut self, smoothing: f32) {
+        self.set_smoother(Box::new(ExponentialSmoother::new(smoothing)));
+    }
+
+    /// Smooth with `smoother` from the next frame, from the levels shown.
+    pub fn set_smoother(&mut self, smoother: Box<dyn Smoother>) {
+        self.state.smoother = smoother;
+    }
+
+    pub fn tilt(&self) -> &Tilt {
+        &self.tilt
+    }
+
+    /// The tilt can change live too.
+    pub fn set_tilt(&mut self, tilt: Tilt) {
+        self.tilt = tilt;
+    }
+
+    pub fn range(&self) -> FreqRange {
+        self.range
+    }
+
+    /// Spread the bands over `range` instead (a zoom), from the next frame.
+    pub fn set_range(&mut self, range: FreqRange) {
+        self.range = range;
+    }
+
+    /// Analyze windows of `fft_size` samples from now on (more at high
+    /// rates).
+    pub fn set_fft_size(&mut self, fft_size: usize) {
+        self.fft_size = fft_size;
+        self.state.set_fft_size(rate::fft_size_for(fft_size, self.sample_rate));
+    }
+
+    /// Spread the bands up to `freq` (Hz, at most Nyquist) instead of the
+    /// top [`rate::display_max_freq`] picks for the rate; the range starts
+    /// over unzoomed when that changes it.
+    pub fn set_max_display_freq(&mut self, freq: Option<f32>) {
+        if freq != self.max_display_freq {
+            self.max_display_freq = freq;
+            self.range = FreqRange::full(self.max_freq());
+        }
+    }
+
+    pub fn window(&self) -> Window {
+        self.state.window
+    }
+
+    /// Weight the samples by `window` from the next frame.
+    pub fn set_window(&mut self, window: Window) {
+        if window != self.state.window {
+            self.state.window = window;
+            self.state.weights = window.weights(self.state.fft_size);
+        }
+    }
+
+    /// The samples now come at `sample_rate` (a track at another rate). The
+    /// FFT plan is kept unless a high rate needs a longer window; the first
+    /// frame at the new rate is taken as-is, as after reset(), since the old
+    /// levels were for other frequencies.
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.state.set_fft_size(rate::fft_size_for(self.fft_size, sample_rate));
+            self.range = FreqRange::full(self.max_freq());
+            self.primed = false;
+        }
+    }
+
+    pub fn state(&self) -> &AnalyzerState {
+        &self.state
+    }
+
+    /// Forget the smoothing history, e.g. when a new track starts, so its
+    /// first frame shows at full height instead of rising from the previous
+    /// track's levels.
+    pub fn reset(&mut self) {
+        self.primed = false;
+    }
+
+    /// The audio jumped (a track was cut short), so the window the bars come
+    /// from may mix two unrelated positions. Unlike reset() the bars aren't
+    /// replaced outright: fed silence until the new audio fills a window,
+    /// they fall and then rise with it over the next few frames, during which
+    /// priming() tells anything that latches onto levels to hold off.
+    pub fn flush(&mut self) {
+        self.priming = PRIME_FRAMES;
+    }
+
+    pub fn priming(&self) -> bool {
+        self.priming > 0
+    }
+
+    /// Rate the analyzed samples are expected at.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Top of the displayed range unzoomed: Nyquist (22.05kHz for 44.1kHz)
+    /// up to 48 kHz, 20 kHz above, or as configured.
+    pub fn max_freq(&self) -> f32 {
+        rate::display_max_freq(self.sample_rate, self.max_display_freq)
+    }
+
+    /// Analyze one `fft_size` window into `num_bands` smoothed band
+    /// magnitudes, a frame at [`REFERENCE_FPS`](smoothing::REFERENCE_FPS)
+    /// after the last.
+    pub fn process(&mut self, samples: &[f32], num_bands: usize) -> &[f32] {
+        self.process_after(samples, num_bands, 1.0 / smoothing::REFERENCE_FPS)
+    }
+
+    /// Analyze one window, as [`process`](Analyzer::process), `dt` seconds
+    /// after the last.
+    pub fn process_after(&mut self, samples: &[f32], num_bands: usize, dt: f32) -> &[f32] {
+        let state = &mut self.state;
+        state.resize_bands(num_bands);
+        state.update_band_table(self.sample_rate, num_bands, self.range, &self.tilt);
+
+        // A sample that isn't a finite number would turn every bin into
+        // NaN, so it counts as silence; a short window is padded with it
+        let mut samples = samples.iter();
+        for (slot, weight) in state.input.iter_mut().zip(&state.weights) {
+            let sample = samples.next().copied().filter(|s| s.is_finite()).unwrap_or(0.0);
+            *slot = Complex { re: sample * weight, im: 0.0 };
+        }
+        state.fft.process_with_scratch(&mut state.input, &mut state.scratch);
+
+        // Magnitude of each bin up to Nyquist
+        for (magnitude, c) in state.magnitudes.iter_mut().zip(&state.input) {
+            *magnitude = (c.re * c.re + c.im * c.im).sqrt();
+        }
+        state.band_table.aggregate_into(&state.magnitudes, &mut state.bands);
+
+        if !self.primed {
+            state.smoothed.copy_from_slice(&state.bands);
+            self.primed = true;
+        }
+        self.priming = self.priming.saturating_sub(1);
+        for (smoothed, new_value) in state.smoothed.iter_mut().zip(state.bands.iter_mut()) {
+            if !new_value.is_finite() {
+                *new_value = 0.0;
+            }
+            // Once NaN, a band would stay NaN through the smoothing for good
+            if !smoothed.is_finite() {
+                *smoothed = *new_value;
+            }
+        }
+        state.smoother.process(&state.bands, &mut state.smoothed, dt);
+
+        &state.smoothed
+    }
+
+    /// Up to `count` of the strongest local maxima in the last window within
+    /// the range, loudest first, at most one per band. Each frequency is
+    /// refined between bins by fitting a parabola through the peak bin and
+    /// its neighbours (in dB).
+    pub fn peaks(&self, count: usize) -> Vec<Peak> {
+        let num_bands = self.state.band_table.len();
+        let fft_size = self.state.fft_size;
+        // The bars use the plain (rectangular window) spectrum, whose peaks
+        // are too lopsided to interpolate; a Hann window applied in the
+        // frequency domain (-1/4, 1/2, -1/4 over neighbouring bins) makes
+        // them symmetric enough for the parabola
+        let x = self.state.spectrum();
+        let mut m = vec![0.0f32; x.len()];
+        for k in 1..x.len().saturating_sub(1) {
+            m[k] = (x[k] * 0.5 - (x[k - 1] + x[k + 1]) * 0.25).norm();
+        }
+        let loudest = m.iter().cloned().fold(0.0f32, f32::max);
+        let first = (rate::frequency_bin(self.range.low, self.sample_rate, fft_size) as usize).max(1);
+        let last = (rate::frequency_bin(self.range.high, self.sample_rate, fft_size).ceil() as usize)
+            .min(m.len().saturating_sub(1));
+
+        let mut candidates: Vec<Peak> = (first..last)
+            // Local maxima no more than 40 dB under the loudest bin
+            .filter(|&k| m[k] > m[k - 1] && m[k] >= m[k + 1] && m[k] > loudest * PEAK_FLOOR)
+            .map(|k| {
+                let db = |v: f32| 20.0 * v.max(1e-10).log10();
+                let (a, b, c) = (db(m[k - 1]), db(m[k]), db(m[k + 1]));
+                let curvature = a - 2.0 * b + c;
+                let shift = if curvature < 0.0 { 0.5 * (a - c) / curvature } else { 0.0 };
+                Peak {
+                    freq: rate::bin_frequency(k as f32 + shift, self.sample_rate, fft_size),
+                    magnitude: m[k],
+                }
+            })
+            .filter(|peak| (self.range.low..=self.range.high).contains(&peak.freq))
+            .collect();
+        candidates.sort_by(|a, b| b.magnitude.total_cmp(&a.magnitude));
+
+        let mut peaks: Vec<Peak> = Vec::new();
+        for peak in candidates {
+            let band = self.range.band_of(peak.freq, num_bands);
+            if peaks.iter().all(|p| self.range.band_of(p.freq, num_bands) != band) {
+                peaks.push(peak);
+            }
+            if peaks.len() == count {
+                break;
+            }
+        }
+        peaks
+    }
+
+    /// Bin magnitudes of the last window, up to Nyquist (silence before the
+    /// first).
+    pub fn magnitudes(&self) -> Vec<f32> {
+        self.state.magnitudes.clone()
+    }
+
+    /// Current band levels in dB relative to a full-scale sine, with the tilt
+    /// taken back out, so they mean the same whatever the display does. The
+    /// magnitudes are normalized by the window's length (its sum, for the
+    /// gain of a Hann window), so a full-scale sine reads 0 dB either way.
+    pub fn levels_db(&self) -> Vec<f32> {
+        let full_scale = self.state.weights.iter().sum::<f32>() / 2.0;
+        self.state
+            .smoothed
+            .iter()
+            .zip(&self.state.band_table.gains)
+            .map(|(&band, &gain)| 20.0 * (band / gain / full_scale).max(1e-10).log10())
+            .collect()
+    }
+
+    /// Gain the display puts on each band, in dB, as the band table holds it.
+    pub fn band_gains_db(&self) -> Vec<f32> {
+        self.state.band_table.gains_db()
+    }
+}
+
+/// How many times each part of an [`AnalyzerState`] has been built.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rebuilds {
+    /// FFT plans, with their buffers.
+    pub fft: u32,
+    pub band_table: u32,
+    /// Band buffers the smoothing runs in.
+    pub bands: u32,
+}
+
+/// What an [`Analyzer`] keeps from frame to frame, in parts that depend on
+/// different things and are each rebuilt only when those change:
+///
+/// - the FFT plan with its input, scratch and magnitude buffers: the FFT
+///   size alone (the window's weights: that and the [`Window`]);
+/// - the [`BandTable`]: the sample rate, FFT size, band count, range and
+///   tilt;
+/// - the band buffers: the band count, which follows the terminal's size.
+///
+/// So a resize builds a new band table and band buffers but never plans an
+/// FFT, and a new tilt or zoom only builds a band table. The [`Smoother`]
+/// is kept through all of it; a resize resamples the levels it left to the
+/// new band count, so the bars don't drop to nothing and rise again. The
+/// [`rebuilds`](AnalyzerState::rebuilds) counters show what was built.
+pub struct AnalyzerState {
+    fft: Arc<dyn Fft<f32>>,
+    fft_size: usize,
+    // What each sample is weighted by going in
+    window: Window,
+    weights: Vec<f32>,
+    // The window going in, transformed in place into the spectrum
+    input: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+    magnitudes: Vec<f32>,
+    band_table: BandTable,
+    // What band_table was built from
+    band_key: Option<BandKey>,
+    // This frame's bands, and the smoothed ones shown
+    bands: Vec<f32>,
+    smoothed: Vec<f32>,
+    smoother: Box<dyn Smoother>,
+    rebuilds: Rebuilds,
+}
+
+// Everything a band table is built from
+#[derive(Clone, Debug, PartialEq)]
+struct BandKey {
+    sample_rate: u32,
+    fft_size: usize,
+    num_bands: usize,
+    range: FreqRange,
+    tilt: Tilt,
+}
+
+impl AnalyzerState {
+    fn new(fft_size: usize, smoother: Box<dyn Smoother>) -> Self {
+        let mut state = AnalyzerState {
+            fft: FftPlanner::new().plan_fft_forward(fft_size),
+            fft_size: 0,
+            window: Window::default(),
+            weights: Vec::new(),
+            input: Vec::new(),
+            scratch: Vec::new(),
+            magnitudes: Vec::new(),
+            band_table: BandTable::default(),
+            band_key: None,
+            bands: Vec::new(),
+            smoothed: Vec::new(),
+            smoother,
+            rebuilds: Rebuilds::default(),
+        };
+        state.set_fft_size(fft_size);
+        state
+    }
+
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    pub fn rebuilds(&self) -> Rebuilds {
+        self.rebuilds
+    }
+
+    fn set_fft_size(&mut self, fft_size: usize) {
+        if fft_size == self.fft_size {
+            return;
+        }
+        // The plan made for new() is used as it is
+        if self.fft.len() != fft_size {
+            self.fft = FftPlanner::new().plan_fft_forward(fft_size);
+        }
+        self.fft_size = fft_size;
+        self.weights = self.window.weights(fft_size);
+        self.input = vec![Complex::default(); fft_size];
+        self.scratch = vec![Complex::default(); self.fft.get_inplace_scratch_len()];
+        self.magnitudes = vec![0.0; fft_size / 2];
+        self.rebuilds.fft += 1;
+    }
+
+    // Band buffers for `num_bands`, the levels shown resampled to them
+    fn resize_bands(&mut self, num_bands: usize) {
+        if self.smoothed.len() != num_bands {
+            self.bands.resize(num_bands, 0.0);
+            self.smoothed = reference::resample(&self.smoothed, num_bands);
+            self.rebuilds.bands += 1;
+        }
+    }
+
+    fn update_band_table(&mut self, sample_rate: u32, num_bands: usize, range: FreqRange, tilt: &Tilt) {
+        let key = BandKey {
+            sample_rate,
+            fft_size: self.fft_size,
+            num_bands,
+            range,
+            tilt: tilt.clone(),
+        };
+        if self.band_key.as_ref() != Some(&key) {
+            self.band_table = BandTable::for_range(sample_rate, self.fft_size, num_bands, range, tilt);
+            self.band_key = Some(key);
+            self.rebuilds.band_table += 1;
+        }
+    }
+
+    // The last window's spectrum, up to Nyquist
+    fn spectrum(&self) -> &[Complex<f32>] {
+        &self.input[..self.fft_size / 2]
+    }
+}
+
+/// Which FFT bins make up each band and how much each counts, tilt gain
+/// included.
+#[derive(Clone, Debug, Default)]
+pub struct BandTable {
+    /// (bin, weight) pairs of each band.
+    pub bins: Vec<Vec<(usize, f32)>>,
+    /// Tilt gain in each band, to take back out for readouts in dB.
+    pub gains: Vec<f32>,
+}
+
+impl BandTable {
+    /// Split the bins of an `fft_size` FFT at `sample_rate` into `num_bands`
+    /// log-spaced bands (more bins for low frequencies, fewer for high). A
+    /// band that covers a whole bin averages the bins it covers; one narrower
+    /// than a bin, common at the low end with many bands, interpolates
+    /// between the two bins around its center so no column is stuck at zero.
+    /// The DC bin is never used, and every band gets at least one bin.
+    pub fn new(sample_rate: u32, fft_size: usize, num_bands: usize, tilt: &Tilt) -> Self {
+        BandTable::for_range(sample_rate, fft_size, num_bands, FreqRange::full(rate::nyquist(sample_rate)), tilt)
+    }
+
+    /// The same for bands spread over `range` only.
+    pub fn for_range(sample_rate: u32, fft_size: usize, num_bands: usize, range: FreqRange, tilt: &Tilt) -> Self {
+        let bin = |freq: f32| rate::frequency_bin(freq, sample_rate, fft_size);
+        let last_bin = fft_size / 2 - 1;
+
+        let gains: Vec<f32> = (0..num_bands)
+            .map(|i| {
+                // Tilt gain at the (geometric) center of the band
+                let (start, end) = range.band_edges(i, num_bands);
+                tilt.gain((start * end).sqrt())
+            })
+            .collect();
+        let bins = (0..num_bands)
+            .map(|i| {
+                let (freq_start, freq_end) = range.band_edges(i, num_bands);
+                let gain = gains[i];
+
+                // Bins whose center frequency falls inside the band
+                let bin_start = (bin(freq_start).ceil() as usize).max(1);
+                let bin_end = (bin(freq_end).ceil() as usize).min(last_bin + 1);
+                if bin_start < bin_end {
+                    let weight = gain / (bin_end - bin_start) as f32;
+                    return (bin_start..bin_end).map(|bin| (bin, weight)).collect();
+                }
+
+                let center = (freq_start * freq_end).sqrt();
+                let position = bin(center).clamp(1.0, last_bin as f32);
+                let below = position as usize;
+                let fraction = position - below as f32;
+                if fraction == 0.0 || below == last_bin {
+                    vec![(below, gain)]
+                } else {
+                    vec![(below, gain * (1.0 - fraction)), (below + 1, gain * fraction)]
+                }
+            })
+            .collect();
+        BandTable { bins, gains }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bins.is_empty()
+    }
+
+    /// The gain of each band in dB.
+    pub fn gains_db(&self) -> Vec<f32> {
+        self.gains.iter().map(|gain| 20.0 * gain.log10()).collect()
+    }
+
+    /// Band magnitudes of a spectrum's bin `magnitudes` (up to Nyquist).
+    pub fn aggregate(&self, magnitudes: &[f32]) -> Vec<f32> {
+        let mut bands = vec![0.0; self.len()];
+        self.aggregate_into(magnitudes, &mut bands);
+        bands
+    }
+
+    /// The same into `bands`, one per band, without allocating.
+    pub fn aggregate_into(&self, magnitudes: &[f32], bands: &mut [f32]) {
+        for (band, weights) in bands.iter_mut().zip(&self.bins) {
+            *band = weights.iter().map(|&(bin, weight)| magnitudes[bin] * weight).sum();
+        }
+    }
+}
+
+/// The frequencies (Hz) the bands are log-spaced over: MIN_FREQ to Nyquist
+/// unless zoomed in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FreqRange {
+    pub low: f32,
+    pub high: f32,
+}
+
+impl FreqRange {
+    /// The whole range, from MIN_FREQ up to `max_freq`.
+    pub fn full(max_freq: f32) -> Self {
+        FreqRange { low: MIN_FREQ, high: max_freq }
+    }
+
+    /// Width of the range in octaves.
+    pub fn octaves(&self) -> f32 {
+        (self.high / self.low).log2()
+    }
+
+    /// Where `freq` falls on the range's log scale, 0.0 at `low` to 1.0 at
+    /// `high` (beyond them outside it).
+    pub fn position(&self, freq: f32) -> f32 {
+        (freq / self.low).ln() / (self.high / self.low).ln()
+    }
+
+    /// The frequency at `position` on the log scale, the inverse of
+    /// [`position`](FreqRange::position).
+    pub fn frequency(&self, position: f32) -> f32 {
+        self.low * (self.high / self.low).powf(position)
+    }
+
+    /// Frequency range of `band` out of `num_bands`.
+    pub fn band_edges(&self, band: usize, num_bands: usize) -> (f32, f32) {
+        let at = |position: usize| self.frequency(position as f32 / num_bands as f32);
+        (at(band), at(band + 1))
+    }
+
+    /// The range bands `first` to `last` (either way round) out of
+    /// `num_bands` cover together.
+    pub fn bands(&self, first: usize, last: usize, num_bands: usize) -> FreqRange {
+        let (low, _) = self.band_edges(first.min(last), num_bands);
+        let (_, high) = self.band_edges(first.max(last), num_bands);
+        FreqRange { low, high }
+    }
+
+    /// Band (out of `num_bands`) that `freq` falls into, the first or last
+    /// for one outside the range.
+    pub fn band_of(&self, freq: f32, num_bands: usize) -> usize {
+        ((self.position(freq) * num_bands as f32).max(0.0) as usize).min(num_bands.saturating_sub(1))
+    }
+}
+
+/// Frequency range (Hz) of `band` out of `num_bands` log-spaced bands from
+/// MIN_FREQ up to `max_freq`.
+pub fn band_edges(band: usize, num_bands: usize, max_freq: f32) -> (f32, f32) {
+    FreqRange::full(max_freq).band_edges(band, num_bands)
+}
+
+/// Band (out of `num_bands` up to `max_freq`) that `freq` falls into.
+pub fn band_of(freq: f32, num_bands: usize, max_freq: f32) -> usize {
+    FreqRange::full(max_freq).band_of(freq, num_bands)
+}
+
+/// Weakest peak worth labeling, relative to the loudest bin (-40 dB).
+const PEAK_FLOOR: f32 = 0.01;
+
+/// A spectral peak: refined frequency (Hz) and raw bin magnitude.
+#[derive(Clone, Copy, Debug)]
+pub struct Peak {
+    pub freq: f32,
+    pub magnitude: f32,
+}
+
+/// Steadies peak labels from frame to frame: a peak found close to one from
+/// the previous frame eases toward its new frequency instead of jumping, so
+/// labels don't flicker between neighbouring bands.
+#[derive(Default)]
+pub struct PeakTracker {
+    peaks: Vec<Peak>,
+}
+
+impl PeakTracker {
+    pub fn update(&mut self, found: Vec<Peak>) -> &[Peak] {
+        let previous = std::mem::take(&mut self.peaks);
+        self.peaks = found
+            .into_iter()
+            .map(|peak| {
+                let close = previous
+                    .iter()
+                    .filter(|old| (peak.freq / old.freq).ln().abs() < PEAK_MATCH_RATIO.ln())
+                    .min_by(|a, b| (peak.freq / a.freq).ln().abs().total_cmp(&(peak.freq / b.freq).ln().abs()));
+                match close {
+                    // Geometric blend: the same pull at any frequency
+                    Some(old) => Peak {
+                        freq: old.freq.powf(1.0 - PEAK_EASING) * peak.freq.powf(PEAK_EASING),
+                        ..peak
+                    },
+                    None => peak,
+                }
+            })
+            .collect();
+        &self.peaks
+    }
+
+    pub fn clear(&mut self) {
+        self.peaks.clear();
+    }
+}
+
+/// Peaks within this frequency ratio (about a semitone) count as the same one.
+const PEAK_MATCH_RATIO: f32 = 1.06;
+/// How far a tracked peak moves toward the newly found frequency each frame.
+const PEAK_EASING: f32 = 0.3;
+
+/// Scale band magnitudes to the 0-100 range used for drawing. Bands that
+/// aren't finite numbers come out as 0.
+pub fn normalize(bands: &[f32], max_amplitude: f32) -> Vec<f32> {
+    bands
+        .iter()
+        .map(|&band| (band / max_amplitude) * 100.0)
+        .map(|level| if level.is_finite() { level } else { 0.0 })
+        .collect()
+}
+
+/// The largest finite magnitude among `bands`, at least 1.0: what
+/// [`normalize`] scales against. Non-finite bands are left out so one can't
+/// take the whole display down with it.
+pub fn max_amplitude<'a>(bands: impl IntoIterator<Item = &'a f32>) -> f32 {
+    bands.into_iter().cloned().filter(|band| band.is_finite()).fold(0.0f32, f32::max).max(1.0)
+}
+
+/// Scale [`normalize`]d levels by `gain_db` (within
+/// ±[`MAX_VISUAL_GAIN_DB`]) for the display only: it comes after the
+/// normalization and any input gain, so +6 dB draws every bar twice as
+/// tall, up to full height.
+///
+/// ```
+/// let mut levels = [10.0, 40.0, 80.0];
+/// gruvberry::analysis::apply_visual_gain(&mut levels, 6.0);
+/// // About twice as tall, the loudest bar stopping at the top
+/// assert!((levels[0] / 10.0 - 2.0).abs() < 0.01 && (levels[1] / 40.0 - 2.0).abs() < 0.01);
+/// assert_eq!(levels[2], 100.0);
+/// ```
+pub fn apply_visual_gain(levels: &mut [f32], gain_db: f32) {
+    let gain_db = gain_db.clamp(-MAX_VISUAL_GAIN_DB, MAX_VISUAL_GAIN_DB);
+    if gain_db == 0.0 {
+        return;
+    }
+    let factor = 10f32.powf(gain_db / 20.0);
+    for level in levels {
+        *level = (*level * factor).min(100.0);
+    }
+}
@@ -0,0 +1,129 @@
+// Terminal setup and teardown without any audio, for checking a platform or
+// terminal emulator quickly: draws the gradient with box borders, reports
+// what was detected and echoes keys until 'q' (or after a few seconds).
+// The window title counts the seconds and should be back to what it was
+// afterwards. With --panic it panics mid-session to check the panic path
+// restores the terminal too. Ctrl+Z (or SIGTSTP) hands the terminal back
+// and stops it, and `fg` should bring the check back fully redrawn; SIGTERM
+// or SIGHUP end it the way 'q' does.
+//
+// --no-alt-screen draws in the current screen instead, leaving the last
+// frame in the scrollback with the prompt below it.
+//
+//     cargo run --bin term-smoke
+//     cargo run --bin term-smoke -- --panic
+//     cargo run --bin term-smoke -- --no-alt-screen
+
+#[path = "../terminal.rs"]
+mod terminal;
+
+use std::time::{Duration, Instant};
+use crossterm::event::{poll, read, Event, KeyCode, KeyModifiers};
+use ratatui::{
+    backend::CrosstermBackend,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Terminal,
+};
+
+// Exit on its own so scripted runs can't hang
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let panic = std::env::args().any(|arg| arg == "--panic");
+    let setup = terminal::Setup {
+        alternate_screen: !std::env::args().any(|arg| arg == "--no-alt-screen"),
+        ..Default::default()
+    };
+    terminal::install_panic_hook();
+
+    let support = terminal::ColorSupport::detect();
+    let mut last_key = String::from("none yet");
+    {
+        let signals = terminal::Signals::install();
+        let mut guard = terminal::TerminalGuard::enter(setup)?;
+        let mut term = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+        let start = Instant::now();
+        let mut title = terminal::WindowTitle::default();
+        let mut suspend = false;
+
+        while start.elapsed() < TIMEOUT {
+            if signals.quit() {
+                break;
+            }
+            // Stopped and continued, by us or from outside, the screen has
+            // to be drawn from scratch
+            let continued = if signals.take_stop() || std::mem::take(&mut suspend) {
+                guard.suspend()?;
+                signals.take_continued();
+                true
+            } else if signals.take_continued() {
+                guard.reset()?;
+                true
+            } else {
+                false
+            };
+            if continued {
+                term.clear()?;
+                title.clear();
+            }
+            title.update(|| format!("gruvberry terminal check {}s", start.elapsed().as_secs()));
+            term.draw(|f| {
+                let width = f.area().width.saturating_sub(2) as usize;
+                let gradient: Vec<Span> = (0..width)
+                    .map(|i| Span::styled("█", Style::default().fg(terminal::adapt(hue(i, width)))))
+                    .collect();
+                let lines = vec![
+                    Line::from(gradient),
+                    Line::from(format!("Color support: {:?}", support)),
+                    Line::from(format!("Size: {}x{}", f.area().width, f.area().height)),
+                    Line::from(format!("Last key: {}", last_key)),
+                    Line::from("Press keys to check they arrive once each; 'q' or Ctrl+C quits, Ctrl+Z suspends"),
+                ];
+                let block = Block::default().borders(Borders::ALL).title("gruvberry terminal check");
+                f.render_widget(Paragraph::new(lines).block(block), f.area());
+            })?;
+
+            if panic && start.elapsed() > Duration::from_millis(500) {
+                panic!("panicking on purpose (--panic); the terminal should be usable after this");
+            }
+
+            if poll(Duration::from_millis(50))?
+                && let Event::Key(key) = read()?
+            {
+                last_key = format!("{:?} {:?} ({:?})", key.code, key.modifiers, key.kind);
+                if key.code == KeyCode::Char('q')
+                    || key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+                {
+                    break;
+                }
+                // Raw mode turns Ctrl+Z into a key rather than SIGTSTP
+                suspend = key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL);
+            }
+        }
+        // The guard would put the title back too; this is the path for
+        // switching it off while running
+        title.clear();
+        // With the terminal gone there is no one to tell
+        if signals.hung_up() {
+            return Ok(());
+        }
+    }
+
+    println!("Terminal restored. Color support: {:?}; last key: {}", support, last_key);
+    Ok(())
+}
+
+// Full-saturation hue sweep from red round to magenta
+fn hue(index: usize, total: usize) -> Color {
+    let h = index as f32 / total.max(1) as f32 * 5.0;
+    let x = ((1.0 - (h % 2.0 - 1.0).abs()) * 255.0) as u8;
+    match h as u32 {
+        0 => Color::Rgb(255, x, 0),
+        1 => Color::Rgb(x, 255, 0),
+        2 => Color::Rgb(0, 255, x),
+        3 => Color::Rgb(0, x, 255),
+        _ => Color::Rgb(x, 0, 255),
+    }
+}
@@ -0,0 +1,62 @@
+use rodio::Source;
+
+// Click track settings for --calibrate
+pub const SAMPLE_RATE: u32 = 44100;
+const CLICK_INTERVAL_SECS: f32 = 1.0;
+const CLICK_LENGTH_SECS: f32 = 0.01;
+const CLICK_FREQUENCY: f32 = 2000.0;
+
+// Peak level in the analyzed window that counts as "the click is on screen"
+pub const FLASH_THRESHOLD: f32 = 0.2;
+
+// Endless mono source: a short decaying 2 kHz blip once per second
+pub struct ClickTrack {
+    sample_rate: u32,
+    position: u64,
+}
+
+impl ClickTrack {
+    pub fn new(sample_rate: u32) -> Self {
+        ClickTrack {
+            sample_rate,
+            position: 0,
+        }
+    }
+}
+
+impl Iterator for ClickTrack {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let period = (CLICK_INTERVAL_SECS * self.sample_rate as f32) as u64;
+        let click_len = (CLICK_LENGTH_SECS * self.sample_rate as f32) as u64;
+        let t = self.position % period;
+        self.position += 1;
+
+        if t < click_len {
+            let phase = 2.0 * std::f32::consts::PI * CLICK_FREQUENCY * t as f32 / self.sample_rate as f32;
+            let envelope = 1.0 - t as f32 / click_len as f32;
+            Some(phase.sin() * envelope * 0.8)
+        } else {
+            Some(0.0)
+        }
+    }
+}
+
+impl Source for ClickTrack {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        None
+    }
+}
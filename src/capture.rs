@@ -0,0 +1,502 @@
+//! A transparent tap for rodio sources.
+//!
+//! [`SampleCapture`] wraps any `Source` and forwards every sample unchanged,
+//! with the same channel count, sample rate, span lengths and total duration
+//! (and seeks), while handing copies of the samples to a [`SampleObserver`]
+//! as they are pulled by the output. Gruvberry's own observer is
+//! [`SampleRing`], which keeps the most recent samples for the spectrum
+//! analyzer.
+//!
+//! ```
+//! use std::time::Duration;
+//! use gruvberry::capture::{SampleCapture, SampleObserver};
+//! use rodio::{Source, source::SineWave};
+//!
+//! // Tracks the loudest sample that has gone past
+//! struct Peak(f32);
+//!
+//! impl SampleObserver for Peak {
+//!     fn on_samples(&mut self, samples: &[f32], _channels: u16, _sample_rate: u32) {
+//!         self.0 = samples.iter().fold(self.0, |peak, s| peak.max(s.abs()));
+//!     }
+//! }
+//!
+//! let source = SineWave::new(440.0).take_duration(Duration::from_millis(100));
+//! let mut tap = SampleCapture::new(source, Peak(0.0));
+//! assert_eq!(tap.sample_rate(), 48000);
+//!
+//! // Normally the tap goes to a Sink; here we pull the samples ourselves
+//! let played: Vec<f32> = tap.by_ref().collect();
+//! assert_eq!(played.len(), 4800);
+//! assert!(tap.observer().0 > 0.99);
+//! ```
+//!
+//! Observers are called from the audio output thread, so they should be
+//! quick. Samples are passed on in small batches (at most [`BATCH_LEN`]) with
+//! no allocation; a batch holds whole frames and never spans a change of
+//! channel count or sample rate. Share an observer with other threads through
+//! `Arc<Mutex<_>>`, which implements [`SampleObserver`] by locking once per
+//! batch.
+//!
+//! Every rodio source yields `rodio::Sample` (`f32`): decoders convert integer
+//! formats such as 16-bit WAV themselves, so the tap sits directly on the
+//! decoder and needs no conversion of its own, neither for the observer nor
+//! on the way to the sink.
+//!
+//! Observers only ever see finite samples. A NaN or infinite one (a broken
+//! decoder, a raw file that isn't what it was said to be) reaches them as
+//! 0.0, since a single one would poison every sum it goes into from then on;
+//! [`SampleObserver::on_non_finite`] tells them how many were replaced.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use rodio::Source;
+use crate::stereo::Channel;
+
+/// Most samples handed to an observer at once.
+pub const BATCH_LEN: usize = 64;
+
+/// Receives the samples flowing through a [`SampleCapture`].
+pub trait SampleObserver: Send {
+    /// Called with consecutive, interleaved samples as they are played.
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32);
+
+    /// Called after the source jumped to `position`: the next samples start
+    /// there and don't follow on from the previous ones.
+    fn on_seek(&mut self, _position: Duration) {}
+
+    /// Called after a batch in which `count` samples weren't finite numbers
+    /// and were passed on as 0.0.
+    fn on_non_finite(&mut self, _count: usize) {}
+}
+
+impl<O: SampleObserver> SampleObserver for Arc<Mutex<O>> {
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32) {
+        // A poisoned lock means the reader panicked; keep the audio going
+        if let Ok(mut observer) = self.lock() {
+            observer.on_samples(samples, channels, sample_rate);
+        }
+    }
+
+    fn on_seek(&mut self, position: Duration) {
+        if let Ok(mut observer) = self.lock() {
+            observer.on_seek(position);
+        }
+    }
+
+    fn on_non_finite(&mut self, count: usize) {
+        if let Ok(mut observer) = self.lock() {
+            observer.on_non_finite(count);
+        }
+    }
+}
+
+/// Two observers watching the same tap, each seeing every batch.
+impl<A: SampleObserver, B: SampleObserver> SampleObserver for (A, B) {
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32) {
+        self.0.on_samples(samples, channels, sample_rate);
+        self.1.on_samples(samples, channels, sample_rate);
+    }
+
+    fn on_seek(&mut self, position: Duration) {
+        self.0.on_seek(position);
+        self.1.on_seek(position);
+    }
+
+    fn on_non_finite(&mut self, count: usize) {
+        self.0.on_non_finite(count);
+        self.1.on_non_finite(count);
+    }
+}
+
+/// An observer that may be absent, such as an optional recording.
+impl<O: SampleObserver> SampleObserver for Option<O> {
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32) {
+        if let Some(observer) = self {
+            observer.on_samples(samples, channels, sample_rate);
+        }
+    }
+
+    fn on_seek(&mut self, position: Duration) {
+        if let Some(observer) = self {
+            observer.on_seek(position);
+        }
+    }
+
+    fn on_non_finite(&mut self, count: usize) {
+        if let Some(observer) = self {
+            observer.on_non_finite(count);
+        }
+    }
+}
+
+/// Shared handle the visualizer reads captured samples from.
+pub type SharedRing = Arc<Mutex<SampleRing>>;
+
+/// Fixed-capacity history of the most recently played samples (oldest first),
+/// mixed down to mono. For stereo (and wider) audio the first two channels
+/// are also kept as they are, for comparing left and right.
+///
+/// The ring also counts the frames that went through it, so it knows the
+/// audio time of what it holds: the position in the track, from the frames
+/// played since the start or the last seek. That time only moves as samples
+/// are played, so unlike a clock it stands still through an underrun.
+///
+/// A pause is a break in the audio much like a seek, except that what the
+/// ring held stays worth showing until playback goes on. The player marks
+/// it with [`SampleRing::pause`] and [`SampleRing::resume`]: resuming drops
+/// the samples from before the pause, and [`SampleRing::holding`] tells the
+/// analysis to keep what it last showed until fresh samples fill a window,
+/// so no window ever mixes the two sides of the pause.
+pub struct SampleRing {
+    samples: VecDeque<f32>,
+    stereo: VecDeque<[f32; 2]>,
+    capacity: usize,
+    // Rate the samples were captured at, as declared by the tap
+    sample_rate: Option<u32>,
+    // Samples that arrived as NaN or infinite and were replaced by silence
+    non_finite: u64,
+    // Audio time (seconds) the count starts from, at the start, a seek or a
+    // change of rate, and frames played since
+    origin: f64,
+    frames: u64,
+    // Marked paused by the player, and resumed since, with every sample in
+    // the ring played after that
+    paused: bool,
+    resumed: bool,
+}
+
+impl SampleRing {
+    pub fn new(capacity: usize) -> Self {
+        SampleRing {
+            samples: VecDeque::with_capacity(capacity),
+            stereo: VecDeque::new(),
+            capacity,
+            sample_rate: None,
+            non_finite: 0,
+            origin: 0.0,
+            frames: 0,
+            paused: false,
+            resumed: false,
+        }
+    }
+
+    /// A new ring behind a [`SharedRing`] handle, ready to pass to [`SampleCapture::new`].
+    pub fn shared(capacity: usize) -> SharedRing {
+        Arc::new(Mutex::new(SampleRing::new(capacity)))
+    }
+
+    pub fn push(&mut self, sample: f32) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Sample rate of the samples in the ring, None before the first batch.
+    /// This is the rate to analyze them at (see [`crate::rate`]).
+    pub fn sample_rate(&self) -> Option<u32> {
+        self.sample_rate
+    }
+
+    /// Copy `len` samples ending `offset` samples before the newest one.
+    ///
+    /// The offset is clamped to the history we actually have, so a large offset
+    /// early in playback reads the oldest full window instead of nothing.
+    pub fn window(&self, len: usize, offset: usize) -> Option<Vec<f32>> {
+        copy_window(&self.samples, len, offset)
+    }
+
+    /// Audio time in seconds at the end of the newest sample: where in the
+    /// track playback has got to, as far as samples have been played.
+    pub fn time(&self) -> f32 {
+        self.seconds(0) as f32
+    }
+
+    /// Audio time at the end of the window [`SampleRing::window`] gives for
+    /// the same `len` and `offset`: the time of the newest sample it was
+    /// made from. None when there is no such window.
+    pub fn window_time(&self, len: usize, offset: usize) -> Option<f32> {
+        if self.samples.len() < len {
+            return None;
+        }
+        let offset = offset.min(self.samples.len() - len);
+        Some(self.seconds(offset) as f32)
+    }
+
+    // Audio time `back` frames before the end of the newest sample
+    fn seconds(&self, back: usize) -> f64 {
+        let rate = self.sample_rate.unwrap_or(1).max(1) as f64;
+        self.origin + self.frames.saturating_sub(back as u64) as f64 / rate
+    }
+
+    /// Playback paused: keep the samples for now, as the last ones heard.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Playback goes on after [`SampleRing::pause`]. The samples from before
+    /// the pause are dropped, the output having pulled some of them ahead of
+    /// what was heard, so windows come from fresh samples only; the audio
+    /// time carries on from where it was.
+    pub fn resume(&mut self) {
+        if !self.paused {
+            return;
+        }
+        self.paused = false;
+        self.resumed = true;
+        self.samples.clear();
+        self.stereo.clear();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Whether the analysis of `len`-sample windows should hold still, its
+    /// smoothing and levels as they were: while paused with samples left to
+    /// show, and after resuming until `len` fresh samples have come in.
+    /// Once a seek has emptied the ring there is nothing to hold on to.
+    pub fn holding(&self, len: usize) -> bool {
+        (self.paused && !self.samples.is_empty()) || (self.resumed && self.samples.len() < len)
+    }
+
+    /// Samples replaced by 0.0 so far because they weren't finite numbers.
+    pub fn non_finite(&self) -> u64 {
+        self.non_finite
+    }
+
+    /// Like [`SampleRing::window`], for left/right frames; None unless the
+    /// latest audio has at least two channels.
+    pub fn stereo_window(&self, len: usize, offset: usize) -> Option<Vec<[f32; 2]>> {
+        copy_window(&self.stereo, len, offset)
+    }
+
+    /// Like [`SampleRing::window`], for one signal of the audio (see
+    /// [`Channel`]). The downmix is the ring's mono history; the others are
+    /// derived from the left/right frames, so they are None unless the latest
+    /// audio has at least two channels.
+    pub fn channel_window(&self, channel: Channel, len: usize, offset: usize) -> Option<Vec<f32>> {
+        match channel {
+            Channel::Downmix => self.window(len, offset),
+            _ => Some(self.stereo_window(len, offset)?.into_iter().map(|frame| channel.of(frame)).collect()),
+        }
+    }
+}
+
+fn copy_window<T: Copy>(history: &VecDeque<T>, len: usize, offset: usize) -> Option<Vec<T>> {
+    if history.len() < len {
+        return None;
+    }
+    let offset = offset.min(history.len() - len);
+    let end = history.len() - offset;
+    Some(history.range(end - len..end).copied().collect())
+}
+
+impl SampleObserver for SampleRing {
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32) {
+        // A window mixing two rates would have no single time scale
+        if self.sample_rate != Some(sample_rate) {
+            self.samples.clear();
+            self.stereo.clear();
+            // The count goes on at the new rate from where it was
+            self.origin = self.seconds(0);
+            self.frames = 0;
+            self.sample_rate = Some(sample_rate);
+            self.resumed = false;
+        }
+        let channels = channels.max(1) as usize;
+        self.frames += samples.len().div_ceil(channels) as u64;
+        // Left and right of earlier audio don't belong next to mono
+        if channels < 2 {
+            self.stereo.clear();
+        }
+        for frame in samples.chunks(channels) {
+            self.push(frame.iter().sum::<f32>() / channels as f32);
+            if let [left, right, ..] = *frame {
+                if self.stereo.len() == self.capacity {
+                    self.stereo.pop_front();
+                }
+                self.stereo.push_back([left, right]);
+            }
+        }
+    }
+
+    // A window across the jump would mix two stretches of the track
+    fn on_seek(&mut self, position: Duration) {
+        self.samples.clear();
+        self.stereo.clear();
+        self.origin = position.as_secs_f64();
+        self.frames = 0;
+        self.resumed = false;
+    }
+
+    fn on_non_finite(&mut self, count: usize) {
+        self.non_finite += count as u64;
+    }
+}
+
+/// Ring size needed to analyze `window_len` samples at up to `max_offset_ms` in the past.
+/// The second window's worth is headroom: the output pulls samples in
+/// bursts, so the newest window is rarely exactly at the end.
+pub fn ring_capacity(window_len: usize, sample_rate: u32, max_offset_ms: i32) -> usize {
+    window_len * 2 + offset_samples(max_offset_ms, sample_rate)
+}
+
+/// Whether a ring of `capacity` samples can serve windows of `window_len`;
+/// a smaller one never fills up, and the analysis would never get a frame.
+pub fn check_capacity(capacity: usize, window_len: usize) -> Result<(), String> {
+    if capacity < window_len {
+        return Err(format!(
+            "a capture buffer of {} samples can never fill a {}-sample analysis window",
+            capacity, window_len
+        ));
+    }
+    Ok(())
+}
+
+/// How far back (in milliseconds) a ring of `capacity` samples can read a
+/// window of `window_len` at `sample_rate`: offsets past this are clamped to
+/// the oldest full window.
+pub fn reach_ms(capacity: usize, window_len: usize, sample_rate: u32) -> i32 {
+    (capacity.saturating_sub(window_len) as u64 * 1000 / sample_rate.max(1) as u64).min(i32::MAX as u64) as i32
+}
+
+/// Largest manual latency offset either way, in milliseconds (the
+/// `latency_offset_ms` setting and `--latency-offset`).
+pub const MAX_OFFSET_MS: i32 = 1000;
+
+/// Convert a latency offset in milliseconds into a read offset in samples.
+///
+/// Positive offsets delay the visuals; we can't read audio that hasn't been
+/// captured yet, so negative offsets resolve to the newest window.
+pub fn offset_samples(offset_ms: i32, sample_rate: u32) -> usize {
+    if offset_ms <= 0 {
+        return 0;
+    }
+    (offset_ms as u64 * sample_rate as u64 / 1000) as usize
+}
+
+/// The lowest manual offset that still moves the window, on a device
+/// `device_latency_ms` behind the capture. The read offset is the device
+/// latency plus the manual one, and once that is zero the window is already
+/// the newest audio captured; a lower offset would have to read ahead of
+/// the capture.
+pub fn min_offset_ms(device_latency_ms: i32) -> i32 {
+    -device_latency_ms.max(0)
+}
+
+/// Source adapter that plays `source` unchanged while showing its samples to an observer.
+pub struct SampleCapture<I, O: SampleObserver> {
+    source: I,
+    observer: O,
+    // Samples not yet handed to the observer, all in the `format` (channels,
+    // sample rate) they were played in
+    batch: [f32; BATCH_LEN],
+    batch_len: usize,
+    format: (u16, u32),
+    // Samples in the batch that were replaced because they weren't finite
+    non_finite: usize,
+}
+
+impl<I: Source, O: SampleObserver> SampleCapture<I, O> {
+    pub fn new(source: I, observer: O) -> Self {
+        let format = (source.channels(), source.sample_rate());
+        SampleCapture {
+            source,
+            observer,
+            batch: [0.0; BATCH_LEN],
+            batch_len: 0,
+            format,
+            non_finite: 0,
+        }
+    }
+}
+
+impl<I, O: SampleObserver> SampleCapture<I, O> {
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// Pass on samples still waiting in the batch.
+    pub fn flush(&mut self) {
+        if self.batch_len > 0 {
+            let (channels, sample_rate) = self.format;
+            self.observer.on_samples(&self.batch[..self.batch_len], channels, sample_rate);
+            self.batch_len = 0;
+        }
+        if self.non_finite > 0 {
+            self.observer.on_non_finite(self.non_finite);
+            self.non_finite = 0;
+        }
+    }
+}
+
+impl<I: Source, O: SampleObserver> Iterator for SampleCapture<I, O> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The format can change between spans; don't mix two in one batch
+        let format = (self.source.channels(), self.source.sample_rate());
+        let Some(sample) = self.source.next() else {
+            self.flush();
+            return None;
+        };
+        if format != self.format {
+            self.flush();
+            self.format = format;
+        }
+        self.batch[self.batch_len] = if sample.is_finite() {
+            sample
+        } else {
+            self.non_finite += 1;
+            0.0
+        };
+        self.batch_len += 1;
+        // Flush at the last frame boundary that fits, so frames aren't split
+        let channels = (self.format.0 as usize).clamp(1, BATCH_LEN);
+        if self.batch_len + channels > BATCH_LEN && self.batch_len.is_multiple_of(channels) {
+            self.flush();
+        }
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.source.size_hint()
+    }
+}
+
+// A source dropped before its end (skipped, or the sink stopped) still
+// delivers what it played
+impl<I, O: SampleObserver> Drop for SampleCapture<I, O> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl<I: Source, O: SampleObserver> Source for SampleCapture<I, O> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.source.current_span_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        self.source.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: std::time::Duration) -> Result<(), rodio::source::SeekError> {
+        // What played before the jump still goes out as it was
+        self.flush();
+        self.source.try_seek(pos)?;
+        self.observer.on_seek(pos);
+        Ok(())
+    }
+}
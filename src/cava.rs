@@ -0,0 +1,166 @@
+//! Output in cava's raw format, for the tools built around it.
+//!
+//! [cava](https://github.com/karlstav/cava)'s `raw` output with
+//! `data_format = binary` is a stream of frames of one value per bar and
+//! nothing in between: a `u8` (0 to 255) per bar with `8bit`, or a `u16` in
+//! the machine's byte order (0 to 65535) with `16bit`. Bar displays, LED
+//! strips and status bar widgets read it from a FIFO; [`start`] writes the
+//! same bytes, so they work on gruvberry's analysis unchanged.
+//!
+//! ```
+//! use gruvberry::cava::{self, BitFormat};
+//!
+//! // Two bars, the first half way up, in 8-bit
+//! assert_eq!(cava::encode(&[50.0, 100.0], 2, BitFormat::Bit8), vec![128, 255]);
+//! ```
+//!
+//! A reader going away (the write fails with `EPIPE`) isn't the end: the
+//! output waits for the next one to open the FIFO. Frames that come while
+//! nobody reads are dropped rather than queued, so a new reader starts on
+//! current ones.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::time::Duration;
+use crate::reference;
+
+/// Bars when none are asked for, and the most there can be.
+pub const DEFAULT_BARS: usize = 64;
+pub const MAX_BARS: usize = 512;
+
+// Frames held for a reader that is slow to take them; past that they are dropped
+const QUEUE_FRAMES: usize = 2;
+// Wait before opening the output again after it couldn't be written
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Size of each bar's value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BitFormat {
+    Bit8,
+    #[default]
+    Bit16,
+}
+
+impl BitFormat {
+    pub const NAMES: [&str; 2] = ["8bit", "16bit"];
+
+    pub fn parse(value: &str) -> Option<BitFormat> {
+        match value {
+            "8bit" => Some(BitFormat::Bit8),
+            "16bit" => Some(BitFormat::Bit16),
+            _ => None,
+        }
+    }
+
+    /// Value of a full bar.
+    pub fn max(self) -> u16 {
+        match self {
+            BitFormat::Bit8 => u8::MAX as u16,
+            BitFormat::Bit16 => u16::MAX,
+        }
+    }
+
+    /// Bytes per bar.
+    pub fn bytes(self) -> usize {
+        match self {
+            BitFormat::Bit8 => 1,
+            BitFormat::Bit16 => 2,
+        }
+    }
+}
+
+/// One frame: `bands` (0.0 to 100.0, as drawn) resampled to `bars` and
+/// scaled to the format's range. Levels outside 0 to 100 are clamped, and
+/// ones that aren't numbers count as 0.
+pub fn encode(bands: &[f32], bars: usize, format: BitFormat) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(bars * format.bytes());
+    for level in reference::resample(bands, bars) {
+        let level = if level.is_nan() { 0.0 } else { (level / 100.0).clamp(0.0, 1.0) };
+        let value = (level * format.max() as f32).round() as u16;
+        match format {
+            BitFormat::Bit8 => frame.push(value as u8),
+            BitFormat::Bit16 => frame.extend_from_slice(&value.to_ne_bytes()),
+        }
+    }
+    frame
+}
+
+/// The visualizer's end of a raw output.
+pub struct CavaOutput {
+    sender: SyncSender<Vec<u8>>,
+    bars: usize,
+    format: BitFormat,
+}
+
+/// Start writing frames of `bars` bars to `path`. A path that doesn't exist
+/// is made a FIFO (on Unix; elsewhere a plain file); an existing FIFO or
+/// file is written to as it is. Opening a FIFO waits for a reader, which a
+/// writer thread does so the caller never waits.
+pub fn start(path: &Path, bars: usize, format: BitFormat) -> Result<CavaOutput, String> {
+    if !path.exists() {
+        make_fifo(path).map_err(|e| format!("can't create {}: {}", path.display(), e))?;
+    }
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_FRAMES);
+    let path = path.to_path_buf();
+    std::thread::spawn(move || write(&path, &receiver));
+    Ok(CavaOutput {
+        sender,
+        bars: bars.clamp(1, MAX_BARS),
+        format,
+    })
+}
+
+impl CavaOutput {
+    /// Hand over an analysis frame; dropped when no reader is keeping up.
+    pub fn send(&self, bands: &[f32]) {
+        let _ = self.sender.try_send(encode(bands, self.bars, self.format));
+    }
+}
+
+// The writer thread: opens the output, writes frames until the reader goes
+// away, and opens it again for the next one
+fn write(path: &PathBuf, receiver: &Receiver<Vec<u8>>) {
+    loop {
+        let mut output = match OpenOptions::new().write(true).open(path) {
+            Ok(output) => output,
+            Err(_) => {
+                std::thread::sleep(RETRY_INTERVAL);
+                continue;
+            }
+        };
+        // What came while nobody was reading is stale
+        receiver.try_iter().for_each(drop);
+        loop {
+            let Ok(frame) = receiver.recv() else {
+                return;
+            };
+            match output.write_all(&frame) {
+                Ok(()) => {}
+                // The reader closed its end; wait for the next one
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => break,
+                Err(_) => {
+                    std::thread::sleep(RETRY_INTERVAL);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn make_fifo(path: &Path) -> io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).map_err(io::Error::other)?;
+    // SAFETY: `path` is a valid NUL-terminated string for the duration of the call
+    match unsafe { libc::mkfifo(path.as_ptr(), 0o644) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(unix))]
+fn make_fifo(path: &Path) -> io::Result<()> {
+    std::fs::File::create(path).map(drop)
+}
@@ -0,0 +1,251 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use clap::{CommandFactory, Parser, error::ErrorKind};
+use gruvberry::alert::AlertStyle;
+use gruvberry::orientation::Orientation;
+use gruvberry::capture;
+use gruvberry::cava::{self, BitFormat};
+use gruvberry::config::Flags;
+#[cfg(unix)]
+use gruvberry::control;
+use gruvberry::gradient::Anchor;
+use gruvberry::raw::RawFormat;
+use gruvberry::segment::{self, Segment};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "gruvberry",
+    version,
+    about = "Real-time audio frequency spectrum visualizer",
+    after_help = "Every config file setting can also be set with an environment variable named \
+                  GRUVBERRY_ plus the key in upper case, e.g. GRUVBERRY_FPS=30 or \
+                  GRUVBERRY_FFT_SIZE=2048.\nPrecedence: command-line flags, then environment \
+                  variables, then the config file, then built-in defaults."
+)]
+pub struct Args {
+    /// WAV files to play one after another (exactly two with --compare)
+    #[arg(value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+
+    /// Read the files as headerless PCM in this format: sample layout
+    /// (s16le, s24le, s32le or f32le), sample rate and channels (1-8)
+    #[arg(long, value_name = "LAYOUT:RATE:CHANNELS", value_parser = RawFormat::parse)]
+    pub raw_pcm: Option<RawFormat>,
+
+    /// Start playing this far into each file: seconds, mm:ss or hh:mm:ss
+    #[arg(long, value_name = "TIME", value_parser = segment::parse_time, conflicts_with = "calibrate")]
+    pub start: Option<Duration>,
+
+    /// Stop playing each file this far into it (seconds, mm:ss or hh:mm:ss)
+    #[arg(long, value_name = "TIME", value_parser = segment::parse_time, conflicts_with = "calibrate")]
+    pub end: Option<Duration>,
+
+    /// A/B two files: both play in sync, one audible at a time ('x' switches),
+    /// with their spectra stacked
+    #[arg(long)]
+    pub compare: bool,
+
+    /// With --compare, match the files' RMS loudness so neither wins by being louder
+    #[arg(long, requires = "compare")]
+    pub level_match: bool,
+
+    /// Shift the analyzed audio window by this many milliseconds so the bars line up
+    /// with what you hear (positive delays the visuals, -1000..=1000)
+    #[arg(
+        long,
+        value_name = "MS",
+        allow_hyphen_values = true,
+        value_parser = clap::value_parser!(i32).range(-(capture::MAX_OFFSET_MS as i64)..=capture::MAX_OFFSET_MS as i64)
+    )]
+    pub latency_offset: Option<i32>,
+
+    /// Print file details to stdout before starting the visualizer
+    #[arg(short, long, conflicts_with = "quiet")]
+    pub verbose: bool,
+
+    /// Print nothing except fatal errors (reported after the terminal is restored)
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Play a click track instead of the WAV file and nudge the latency offset with
+    /// '+'/'-' until the flash coincides with the click
+    #[arg(long)]
+    pub calibrate: bool,
+
+    /// Seconds of silence before the idle animation replaces the bars [default: 10]
+    #[arg(long, value_name = "SECS", value_parser = parse_seconds)]
+    pub idle_timeout: Option<f32>,
+
+    /// Never switch to the idle animation during silence
+    #[arg(long)]
+    pub no_screensaver: bool,
+
+    /// Kick all bars up briefly on each detected onset (purely visual)
+    #[arg(long)]
+    pub bounce: bool,
+
+    /// Draw exactly what the analyzer produced: no bars interpolated between
+    /// analysis frames when analysis_fps is below fps
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Show the spectrum as measured, whatever the config says: Hann window,
+    /// no tilt, gain or smoothing, a fixed dB scale down to the config's
+    /// measurement_floor_db, and the peaks labeled
+    #[arg(long)]
+    pub measurement: bool,
+
+    /// Ring the terminal bell, flash the spectrum border, or both on clipping
+    /// (and every Nth beat with the config's alert_every_beats); "off" disables
+    #[arg(long, value_name = "STYLE", value_parser = ["bell", "flash", "both", "off"])]
+    pub alert: Option<String>,
+
+    /// Write the audio of the first file (and the playlist after it) to this
+    /// WAV file as it plays, as 32-bit float at its own rate and channels
+    #[arg(long, value_name = "PATH")]
+    pub record_audio: Option<PathBuf>,
+
+    /// Where 'S' saves the spectrum on screen as an SVG image (default:
+    /// gruvberry-<time>.svg in the current directory)
+    #[arg(long, value_name = "PATH")]
+    pub export_svg: Option<PathBuf>,
+
+    /// Exit as soon as playback is over instead of holding the finished
+    /// display until 'q' (for scripts)
+    #[arg(long)]
+    pub auto_exit: bool,
+
+    /// Print the session's statistics (each track's length, loudness,
+    /// tempo, key and clipping, and the total listening time) on exit
+    #[arg(long)]
+    pub stats: bool,
+
+    /// Write the session's statistics to this file as JSON on exit
+    #[arg(long, value_name = "PATH")]
+    pub stats_json: Option<PathBuf>,
+
+    /// Also write the bars to this FIFO (made if missing) in cava's raw
+    /// binary format, for tools that read cava's output
+    #[arg(long, value_name = "PATH")]
+    pub cava_raw: Option<PathBuf>,
+
+    /// Bars in each --cava-raw frame
+    #[arg(long, value_name = "N", default_value_t = cava::DEFAULT_BARS, value_parser = parse_bars, requires = "cava_raw")]
+    pub cava_bars: usize,
+
+    /// Size of each bar's value in --cava-raw frames: 0-255 or 0-65535
+    #[arg(long, value_name = "FORMAT", default_value = "16bit", value_parser = BitFormat::NAMES, requires = "cava_raw")]
+    pub cava_bit_format: String,
+
+    /// Which way the bars grow: up from the bottom, down from the top, or
+    /// sideways with the bands stacked in rows (for wide, short panes)
+    #[arg(long, value_name = "DIRECTION", value_parser = Orientation::NAMES)]
+    pub orientation: Option<String>,
+
+    /// Color the bars by frequency (the same color for the same range at any
+    /// width) or spread the gradient over the bands on screen
+    #[arg(long, value_name = "ANCHOR", value_parser = Anchor::NAMES)]
+    pub gradient: Option<String>,
+
+    /// Draw without any color: plain bars, default-colored borders and text
+    /// (also when the NO_COLOR environment variable is set)
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// Draw in the current screen instead of the alternate screen, so the
+    /// last frame stays in the scrollback after exit
+    #[arg(long)]
+    pub no_alt_screen: bool,
+
+    /// Show a single row instead of the whole screen: a small spectrum and
+    /// the time, redrawn in place, for a narrow tmux pane or a status bar
+    #[arg(long, conflicts_with_all = ["calibrate", "compare"])]
+    pub oneline: bool,
+
+    /// Read settings from this TOML file instead of
+    /// $XDG_CONFIG_HOME/gruvberry/config.toml (command-line flags still win)
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Apply the config file's [profile.NAME] section on top of its base settings
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Take requests on this Unix socket while playing (e.g.
+    /// unix:/tmp/gruvberry.sock): "add PATH" queues a file, "pause", "seek
+    /// SECS", "next" and "previous" work as the keys, and "follow" streams
+    /// the frames to another gruvberry (see --follow)
+    #[cfg(unix)]
+    #[arg(long, value_name = "unix:PATH", value_parser = control::parse_address, conflicts_with = "oneline")]
+    pub control: Option<PathBuf>,
+
+    /// Mirror the gruvberry taking requests on this socket (e.g.
+    /// unix:/tmp/gruvberry.sock): draw what it plays instead of playing
+    /// anything here; space, the arrows and 'n'/'p' are sent to it
+    #[cfg(unix)]
+    #[arg(
+        long,
+        value_name = "unix:PATH",
+        value_parser = control::parse_address,
+        conflicts_with_all = ["files", "control", "calibrate", "compare", "oneline"]
+    )]
+    pub follow: Option<PathBuf>,
+
+    // Stall every frame this many milliseconds, to check how a terminal too
+    // slow for the frame rate is handled
+    #[arg(long, value_name = "MS", hide = true)]
+    pub render_delay: Option<u64>,
+}
+
+// Non-negative, finite number of seconds
+fn parse_seconds(value: &str) -> Result<f32, String> {
+    let secs: f32 = value.parse().map_err(|_| format!("'{}' is not a number", value))?;
+    if secs.is_finite() && secs >= 0.0 {
+        Ok(secs)
+    } else {
+        Err(format!("'{}' must be zero or more seconds", value))
+    }
+}
+
+// Bar count for --cava-raw
+fn parse_bars(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(bars) if (1..=cava::MAX_BARS).contains(&bars) => Ok(bars),
+        _ => Err(format!("'{}' must be a whole number from 1 to {}", value, cava::MAX_BARS)),
+    }
+}
+
+// Parse the command line and check combinations clap can't express
+pub fn parse() -> Args {
+    let args = Args::parse();
+    if args.compare && args.files.len() != 2 {
+        Args::command()
+            .error(ErrorKind::WrongNumberOfValues, "--compare needs exactly two files")
+            .exit();
+    }
+    if let Err(e) = args.segment() {
+        Args::command().error(ErrorKind::ValueValidation, format!("--end: {}", e)).exit();
+    }
+    args
+}
+
+impl Args {
+    // The part of each file that plays (--start/--end)
+    pub fn segment(&self) -> Result<Segment, String> {
+        Segment::new(self.start, self.end)
+    }
+
+    // The settings given here, to go over the config
+    pub fn flags(&self) -> Flags {
+        Flags {
+            latency_offset_ms: self.latency_offset,
+            idle_timeout: self.idle_timeout,
+            no_screensaver: self.no_screensaver,
+            bounce: self.bounce,
+            raw: self.raw,
+            alert: self.alert.as_deref().map(AlertStyle::parse),
+            orientation: self.orientation.as_deref().and_then(Orientation::parse),
+            gradient: self.gradient.as_deref().and_then(Anchor::parse),
+        }
+    }
+}
@@ -0,0 +1,1068 @@
+//! The visualizer's settings.
+//!
+//! [`load`] starts from the defaults and lays over them the config file, the
+//! profile picked from it (`[profile.<name>]`) and then `GRUVBERRY_*`
+//! environment variables; command-line flags go over all of that. Every
+//! setting is checked as it is read, and a bad one is reported with the
+//! file and line it is on. A [`Watcher`] re-reads the file while the
+//! visualizer runs.
+
+use std::ffi::OsString;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use toml::Value;
+use crate::alert::{AlertSettings, AlertStyle};
+use crate::orientation::Orientation;
+use crate::playlist::Repeat;
+use crate::device::RetryPolicy;
+use crate::downsample::Aggregate;
+use crate::echo::{self, EchoSettings, Tap};
+use crate::gradient::{Anchor, Gradient};
+use crate::measurement::{self, Shaping};
+use crate::modes::Overrides;
+use crate::palette;
+use crate::smoothing::SmootherKind;
+use crate::tilt::Tilt;
+
+/// Settings from the config file and `GRUVBERRY_*` environment variables;
+/// command-line flags override both.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub fft_size: usize,
+    /// Samples of history kept for the analysis; 0 sizes it from fft_size
+    /// and the largest latency compensation.
+    pub capture_buffer: usize,
+    pub smoothing: f32,
+    /// Which smoother the bars get, and the attack-release one's time
+    /// constants (milliseconds).
+    pub smoother: SmootherKind,
+    pub attack_ms: f32,
+    pub release_ms: f32,
+    pub fps: u32,
+    /// Analyses per second, 0 for one per drawn frame; the frames in between
+    /// are interpolated unless `raw`.
+    pub analysis_fps: u32,
+    /// Draw exactly what the analyzer produced, never in-between frames.
+    pub raw: bool,
+    pub latency_offset_ms: i32,
+    pub idle_timeout: f32,
+    pub screensaver: bool,
+    pub shuffle: bool,
+    pub repeat: Repeat,
+    pub tilt: Tilt,
+    /// How far back the replay scrub can go.
+    pub history_secs: f32,
+    /// Bars kick up on onsets (display only).
+    pub bounce: bool,
+    /// Bell or border flash on clipping or beats.
+    pub alert: AlertSettings,
+    /// The metronome ('m') also clicks along in the audio.
+    pub metronome_click: bool,
+    /// How opening the audio output at startup is retried.
+    pub device_retry: RetryPolicy,
+    /// The echo on the playback ('E') and which side of it is analyzed.
+    pub echo: EchoSettings,
+    /// Which way the bars grow.
+    pub orientation: Orientation,
+    /// Whether the bars' colors follow frequency or position on screen.
+    pub gradient: Anchor,
+    /// Discord application the presence is published under (the `discord`
+    /// feature); None leaves the presence off.
+    pub discord_client_id: Option<String>,
+    /// Show what is playing in the terminal window's title.
+    pub terminal_title: bool,
+    /// Decimal separator of the numbers on screen.
+    pub decimal_separator: char,
+    /// How a column over several bands takes them together.
+    pub band_aggregate: Aggregate,
+    /// Display-only gain on the normalized bars (dB, Shift+Up/Down).
+    pub visual_gain_db: f32,
+    /// Top of the frequency axis (Hz); 0 picks it by the file's rate.
+    pub max_freq: f32,
+    /// Bottom of --measurement's fixed dB scale (dBFS).
+    pub measurement_floor_db: f32,
+    /// Name of the bars' gradient: [`palette::BUILT_IN`] or one of `themes`.
+    pub theme: String,
+    /// Themes Ctrl+1..5 switch to.
+    pub theme_slots: Vec<String>,
+    /// Gradients of the file's own, from `[themes.<name>]`.
+    pub themes: Vec<(String, Gradient)>,
+    /// Settings of their own for some orientations, from
+    /// `[mode.<orientation>]`.
+    pub modes: Vec<(Orientation, Overrides)>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            fft_size: crate::analysis::DEFAULT_FFT_SIZE,
+            capture_buffer: 0,
+            smoothing: crate::analysis::DEFAULT_SMOOTHING,
+            smoother: SmootherKind::default(),
+            attack_ms: crate::smoothing::DEFAULT_ATTACK * 1000.0,
+            release_ms: crate::smoothing::DEFAULT_RELEASE * 1000.0,
+            fps: 60,
+            analysis_fps: 0,
+            raw: false,
+            latency_offset_ms: 0,
+            idle_timeout: 10.0,
+            screensaver: true,
+            shuffle: false,
+            repeat: Repeat::Off,
+            tilt: Tilt::default(),
+            history_secs: 60.0,
+            bounce: false,
+            alert: AlertSettings::default(),
+            metronome_click: false,
+            device_retry: RetryPolicy::default(),
+            echo: EchoSettings::default(),
+            orientation: Orientation::default(),
+            gradient: Anchor::default(),
+            discord_client_id: None,
+            terminal_title: false,
+            decimal_separator: '.',
+            band_aggregate: Aggregate::default(),
+            visual_gain_db: 0.0,
+            max_freq: 0.0,
+            measurement_floor_db: measurement::DEFAULT_FLOOR_DB,
+            theme: palette::BUILT_IN.to_string(),
+            theme_slots: Vec::new(),
+            themes: Vec::new(),
+            modes: Vec::new(),
+        }
+    }
+}
+
+/// The settings command-line flags can give, which go over the file and the
+/// environment; None (or false) leaves the setting to them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Flags {
+    pub latency_offset_ms: Option<i32>,
+    pub idle_timeout: Option<f32>,
+    pub no_screensaver: bool,
+    pub bounce: bool,
+    pub raw: bool,
+    /// Some(None) is `--alert off`.
+    pub alert: Option<Option<AlertStyle>>,
+    pub orientation: Option<Orientation>,
+    pub gradient: Option<Anchor>,
+}
+
+/// Every key the config file understands; each can also be set through the
+/// environment as `GRUVBERRY_<KEY>`.
+pub const KEYS: [&str; 40] = [
+    "fft_size",
+    "capture_buffer",
+    "smoothing",
+    "smoother",
+    "attack_ms",
+    "release_ms",
+    "fps",
+    "analysis_fps",
+    "raw",
+    "latency_offset_ms",
+    "idle_timeout",
+    "screensaver",
+    "shuffle",
+    "repeat",
+    "tilt",
+    "history_secs",
+    "bounce",
+    "alert",
+    "alert_on_clip",
+    "alert_every_beats",
+    "alert_max_per_sec",
+    "metronome_click",
+    "device_attempts",
+    "device_retry_ms",
+    "echo",
+    "echo_delay_ms",
+    "echo_feedback",
+    "echo_mix",
+    "echo_tap",
+    "orientation",
+    "gradient",
+    "discord_client_id",
+    "terminal_title",
+    "decimal_separator",
+    "band_aggregate",
+    "visual_gain_db",
+    "max_freq",
+    "measurement_floor_db",
+    "theme",
+    "theme_slots",
+];
+
+// Largest capture_buffer: a minute and a half at 96 kHz
+const MAX_CAPTURE_BUFFER: i64 = 1 << 23;
+
+// How often the config file's modification time is checked for live reload
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Prefix of the environment variables that override config file settings.
+pub const ENV_PREFIX: &str = "GRUVBERRY_";
+
+impl Config {
+    // Check settings that depend on each other, once all are in
+    fn check(&self) -> Result<(), String> {
+        if self.capture_buffer > 0 {
+            crate::capture::check_capacity(self.capture_buffer, self.fft_size).map_err(|e| {
+                format!("capture_buffer must be at least fft_size ({}); {}", self.fft_size, e)
+            })?;
+        }
+        for name in std::iter::once(&self.theme).chain(&self.theme_slots) {
+            if self.palette(name).is_none() {
+                let themes = self.themes.iter().map(|(name, _)| name.as_str());
+                let defined: Vec<&str> = std::iter::once(palette::BUILT_IN).chain(themes).collect();
+                return Err(format!(
+                    "no theme named `{}` (add a [{}.{}] section); the themes are {}",
+                    name,
+                    palette::TABLE,
+                    name,
+                    defined.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The gradient of the theme `name`, None if there is no such theme.
+    pub fn palette(&self, name: &str) -> Option<Gradient> {
+        if name == palette::BUILT_IN {
+            return Some(Gradient::Vibgyor);
+        }
+        self.themes.iter().find(|(known, _)| known == name).map(|(_, gradient)| *gradient)
+    }
+
+    /// Remember a theme saved while running, as a reload would read it.
+    pub fn add_theme(&mut self, name: &str, gradient: Gradient) {
+        self.themes.retain(|(known, _)| known != name);
+        self.themes.push((name.to_string(), gradient));
+        self.theme = name.to_string();
+    }
+
+    /// The configured top of the frequency axis, None to pick it by the rate.
+    pub fn max_display_freq(&self) -> Option<f32> {
+        (self.max_freq > 0.0).then_some(self.max_freq)
+    }
+
+    /// Lay the command-line `flags` over the settings. Done at startup and
+    /// again over every reload, so editing the file never undoes a flag.
+    pub fn apply_flags(&mut self, flags: &Flags) {
+        if let Some(offset) = flags.latency_offset_ms {
+            self.latency_offset_ms = offset;
+        }
+        if let Some(timeout) = flags.idle_timeout {
+            self.idle_timeout = timeout;
+        }
+        if flags.no_screensaver {
+            self.screensaver = false;
+        }
+        if flags.bounce {
+            self.bounce = true;
+        }
+        if flags.raw {
+            self.raw = true;
+        }
+        if let Some(style) = flags.alert {
+            self.alert.style = style;
+        }
+        if let Some(orientation) = flags.orientation {
+            self.orientation = orientation;
+        }
+        if let Some(gradient) = flags.gradient {
+            self.gradient = gradient;
+        }
+    }
+
+    /// Lay `shaping` over the settings, the orientations' own included, so
+    /// nothing but it decides the stages it covers (--measurement, applied
+    /// after everything else).
+    pub fn overlay(&mut self, shaping: &Shaping) {
+        self.tilt = shaping.tilt.clone();
+        self.visual_gain_db = shaping.visual_gain_db;
+        self.band_aggregate = shaping.aggregate;
+        self.smoother = shaping.smoother;
+        self.smoothing = shaping.smoothing;
+        self.raw = !shaping.interpolate;
+        self.bounce = shaping.bounce;
+        for (_, overrides) in &mut self.modes {
+            overrides.tilt = None;
+            overrides.smoother = None;
+            overrides.smoothing = None;
+        }
+    }
+
+    // Validate one setting and store it
+    fn apply(&mut self, key: &str, value: &Value) -> Result<(), String> {
+        match key {
+            "fft_size" => {
+                let fft_size = value.as_integer().filter(|n| (256..=16384).contains(n) && n.count_ones() == 1);
+                self.fft_size = fft_size.ok_or_else(|| {
+                    format!("fft_size must be a power of two from 256 to 16384 (e.g. 1024, 2048, 4096), got {}", value)
+                })? as usize;
+            }
+            "capture_buffer" => {
+                let capture_buffer = value.as_integer().filter(|n| *n == 0 || (256..=MAX_CAPTURE_BUFFER).contains(n));
+                self.capture_buffer = capture_buffer.ok_or_else(|| {
+                    format!(
+                        "capture_buffer must be 0 (sized automatically) or a sample count from 256 to {}, got {}",
+                        MAX_CAPTURE_BUFFER, value
+                    )
+                })? as usize;
+            }
+            "smoothing" => {
+                let smoothing = as_float(value).filter(|&s| s > 0.0 && s <= 1.0);
+                self.smoothing = smoothing.ok_or_else(|| {
+                    format!("smoothing must be above 0.0 and at most 1.0 (0.3 = smooth, 1.0 = off), got {}", value)
+                })? as f32;
+            }
+            "smoother" => {
+                let smoother = value.as_str().and_then(SmootherKind::parse);
+                self.smoother = smoother.ok_or_else(|| {
+                    format!("smoother must be \"exponential\", \"attack-release\" or \"none\", got {}", value)
+                })?;
+            }
+            "attack_ms" | "release_ms" => {
+                let ms = as_float(value).filter(|ms| (0.0..=5000.0).contains(ms)).map(|ms| ms as f32);
+                let ms = ms.ok_or_else(|| format!("{} must be from 0 to 5000 milliseconds, got {}", key, value))?;
+                if key == "attack_ms" {
+                    self.attack_ms = ms;
+                } else {
+                    self.release_ms = ms;
+                }
+            }
+            "fps" => {
+                let fps = value.as_integer().filter(|n| (1..=240).contains(n));
+                self.fps = fps.ok_or_else(|| format!("fps must be a whole number from 1 to 240, got {}", value))? as u32;
+            }
+            "analysis_fps" => {
+                let fps = value.as_integer().filter(|n| (0..=240).contains(n));
+                self.analysis_fps = fps.ok_or_else(|| {
+                    format!("analysis_fps must be a whole number from 1 to 240, or 0 for every frame, got {}", value)
+                })? as u32;
+            }
+            "raw" => {
+                self.raw = value.as_bool().ok_or_else(|| format!("raw must be true or false, got {}", value))?;
+            }
+            "latency_offset_ms" => {
+                let limit = crate::capture::MAX_OFFSET_MS as i64;
+                let offset = value.as_integer().filter(|n| (-limit..=limit).contains(n));
+                self.latency_offset_ms = offset.ok_or_else(|| {
+                    format!("latency_offset_ms must be a whole number from -{} to {}, got {}", limit, limit, value)
+                })? as i32;
+            }
+            "idle_timeout" => {
+                let timeout = as_float(value).filter(|t| t.is_finite() && *t >= 0.0);
+                self.idle_timeout =
+                    timeout.ok_or_else(|| format!("idle_timeout must be zero or more seconds, got {}", value))? as f32;
+            }
+            "screensaver" => {
+                self.screensaver =
+                    value.as_bool().ok_or_else(|| format!("screensaver must be true or false, got {}", value))?;
+            }
+            "bounce" => {
+                self.bounce = value.as_bool().ok_or_else(|| format!("bounce must be true or false, got {}", value))?;
+            }
+            "alert" => {
+                let style = value.as_str().and_then(|v| (v == "off").then_some(None).or(AlertStyle::parse(v).map(Some)));
+                self.alert.style = style.ok_or_else(|| {
+                    format!("alert must be \"off\", \"bell\", \"flash\" or \"both\", got {}", value)
+                })?;
+            }
+            "alert_on_clip" => {
+                self.alert.on_clip =
+                    value.as_bool().ok_or_else(|| format!("alert_on_clip must be true or false, got {}", value))?;
+            }
+            "alert_every_beats" => {
+                let beats = value.as_integer().filter(|n| (0..=64).contains(n));
+                self.alert.every_beats = beats.ok_or_else(|| {
+                    format!("alert_every_beats must be a whole number from 0 (never) to 64, got {}", value)
+                })? as u32;
+            }
+            "alert_max_per_sec" => {
+                let rate = as_float(value).filter(|r| (0.1..=20.0).contains(r));
+                self.alert.max_per_sec = rate.ok_or_else(|| {
+                    format!("alert_max_per_sec must be from 0.1 to 20, got {}", value)
+                })? as f32;
+            }
+            "metronome_click" => {
+                self.metronome_click =
+                    value.as_bool().ok_or_else(|| format!("metronome_click must be true or false, got {}", value))?;
+            }
+            "device_attempts" => {
+                let attempts = value.as_integer().filter(|n| (1..=20).contains(n));
+                self.device_retry.attempts = attempts
+                    .ok_or_else(|| format!("device_attempts must be a whole number from 1 to 20, got {}", value))?
+                    as u32;
+            }
+            "device_retry_ms" => {
+                let ms = value.as_integer().filter(|n| (0..=10_000).contains(n));
+                let ms = ms.ok_or_else(|| format!("device_retry_ms must be from 0 to 10000 milliseconds, got {}", value))?;
+                self.device_retry.first_delay = Duration::from_millis(ms as u64);
+            }
+            "echo" => {
+                self.echo.enabled = value.as_bool().ok_or_else(|| format!("echo must be true or false, got {}", value))?;
+            }
+            "echo_delay_ms" => {
+                let range = echo::MIN_DELAY_MS as f64..=echo::MAX_DELAY_MS as f64;
+                let ms = as_float(value).filter(|ms| range.contains(ms));
+                self.echo.delay_ms = ms.ok_or_else(|| {
+                    format!("echo_delay_ms must be from {} to {} milliseconds, got {}", range.start(), range.end(), value)
+                })? as f32;
+            }
+            "echo_feedback" => {
+                let feedback = as_float(value).filter(|f| (0.0..=echo::MAX_FEEDBACK as f64).contains(f));
+                self.echo.feedback = feedback.ok_or_else(|| {
+                    format!("echo_feedback must be from 0 to {}, got {}", echo::MAX_FEEDBACK, value)
+                })? as f32;
+            }
+            "echo_mix" => {
+                let mix = as_float(value).filter(|m| (0.0..=1.0).contains(m));
+                self.echo.mix = mix.ok_or_else(|| format!("echo_mix must be from 0 to 1, got {}", value))? as f32;
+            }
+            "echo_tap" => {
+                let tap = value.as_str().and_then(Tap::parse);
+                self.echo.tap = tap.ok_or_else(|| format!("echo_tap must be \"dry\" or \"wet\", got {}", value))?;
+            }
+            "orientation" => {
+                let orientation = value.as_str().and_then(Orientation::parse);
+                self.orientation = orientation.ok_or_else(|| {
+                    format!(
+                        "orientation must be \"bottom-up\", \"top-down\", \"left-right\" or \"right-left\", got {}",
+                        value
+                    )
+                })?;
+            }
+            "gradient" => {
+                let gradient = value.as_str().and_then(Anchor::parse);
+                self.gradient =
+                    gradient.ok_or_else(|| format!("gradient must be \"frequency\" or \"index\", got {}", value))?;
+            }
+            "terminal_title" => {
+                self.terminal_title =
+                    value.as_bool().ok_or_else(|| format!("terminal_title must be true or false, got {}", value))?;
+            }
+            "decimal_separator" => {
+                let mut chars = value.as_str().map(str::chars);
+                let separator = chars
+                    .as_mut()
+                    .and_then(|chars| chars.next().filter(|_| chars.next().is_none()))
+                    .filter(|c| crate::format::SEPARATORS.contains(c));
+                self.decimal_separator =
+                    separator.ok_or_else(|| format!("decimal_separator must be \".\" or \",\", got {}", value))?;
+            }
+            "band_aggregate" => {
+                let aggregate = value.as_str().and_then(Aggregate::parse);
+                self.band_aggregate =
+                    aggregate.ok_or_else(|| format!("band_aggregate must be \"max\" or \"energy\", got {}", value))?;
+            }
+            "visual_gain_db" => {
+                let max = crate::analysis::MAX_VISUAL_GAIN_DB as f64;
+                let gain = as_float(value).filter(|gain| (-max..=max).contains(gain));
+                self.visual_gain_db = gain.ok_or_else(|| {
+                    format!("visual_gain_db must be from -{} to {} dB, got {}", max, max, value)
+                })? as f32;
+            }
+            "max_freq" => {
+                let freq = as_float(value).filter(|freq| *freq == 0.0 || (1000.0..=96000.0).contains(freq));
+                self.max_freq = freq.ok_or_else(|| {
+                    format!("max_freq must be 0 (picked by the file's sample rate) or from 1000 to 96000 Hz, got {}", value)
+                })? as f32;
+            }
+            "theme" => {
+                let name = value.as_str().filter(|name| *name == palette::BUILT_IN || palette::check_name(name).is_ok());
+                let name = name.ok_or_else(|| {
+                    format!(
+                        "theme must be \"{}\" or the name of a [{}.<name>] section, got {}",
+                        palette::BUILT_IN,
+                        palette::TABLE,
+                        value
+                    )
+                })?;
+                self.theme = name.to_string();
+            }
+            "theme_slots" => {
+                let names = value.as_array().and_then(|names| {
+                    names.iter().map(|name| name.as_str().map(str::to_string)).collect::<Option<Vec<String>>>()
+                });
+                self.theme_slots = names.filter(|names| names.len() <= palette::MAX_SLOTS).ok_or_else(|| {
+                    format!("theme_slots must be a list of up to {} theme names, got {}", palette::MAX_SLOTS, value)
+                })?;
+            }
+            "measurement_floor_db" => {
+                let floor = as_float(value).filter(|floor| (-160.0..=-20.0).contains(floor));
+                self.measurement_floor_db = floor.ok_or_else(|| {
+                    format!("measurement_floor_db must be from -160 to -20 dB, got {}", value)
+                })? as f32;
+            }
+            "discord_client_id" => {
+                // A bare number too, as that is what the environment variable parses as
+                let id = match value {
+                    Value::Integer(n) if *n > 0 => Some(n.to_string()),
+                    _ => value.as_str().filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit())).map(str::to_string),
+                };
+                self.discord_client_id = Some(id.ok_or_else(|| {
+                    format!("discord_client_id must be a Discord application ID (digits only), got {}", value)
+                })?);
+            }
+            "shuffle" => {
+                self.shuffle = value.as_bool().ok_or_else(|| format!("shuffle must be true or false, got {}", value))?;
+            }
+            "repeat" => {
+                let repeat = value.as_str().and_then(Repeat::parse);
+                self.repeat = repeat.ok_or_else(|| format!("repeat must be \"off\", \"one\" or \"all\", got {}", value))?;
+            }
+            "tilt" => {
+                // A bare number is a slope in dB per octave
+                self.tilt = match (value.as_str(), as_float(value)) {
+                    (Some(text), _) => Tilt::parse(text),
+                    (None, Some(slope)) if slope.is_finite() => Ok(Tilt::Slope(slope as f32)),
+                    _ => Err(format!("got {}", value)),
+                }
+                .map_err(|e| {
+                    format!(
+                        "tilt must be \"flat\", a slope like \"+3dB/oct\" or breakpoints like \"100:0, 1k:3, 10k:6\" (Hz:dB); {}",
+                        e
+                    )
+                })?;
+            }
+            "history_secs" => {
+                let secs = as_float(value).filter(|s| (1.0..=3600.0).contains(s));
+                self.history_secs = secs.ok_or_else(|| {
+                    format!("history_secs must be from 1 to 3600 seconds, got {}", value)
+                })? as f32;
+            }
+            _ => unreachable!("apply called with unknown key {}", key),
+        }
+        Ok(())
+    }
+}
+
+/// Watches the config file for edits and SIGUSR1 so settings can be re-read
+/// while the visualizer runs.
+pub struct Watcher {
+    explicit: Option<PathBuf>,
+    profile: Option<String>,
+    modified: Option<SystemTime>,
+    next_check: Instant,
+    // Set by the SIGUSR1 handler
+    signaled: Arc<AtomicBool>,
+}
+
+impl Watcher {
+    pub fn new(explicit: Option<&Path>, profile: Option<&str>) -> Self {
+        let signaled = Arc::new(AtomicBool::new(false));
+        // Without the handler only file edits trigger a reload
+        #[cfg(unix)]
+        let _ = signal_hook::flag::register(signal_hook::consts::SIGUSR1, signaled.clone());
+
+        let mut watcher = Watcher {
+            explicit: explicit.map(Path::to_path_buf),
+            profile: profile.map(str::to_string),
+            modified: None,
+            next_check: Instant::now() + RELOAD_POLL_INTERVAL,
+            signaled,
+        };
+        watcher.modified = watcher.modified_time();
+        watcher
+    }
+
+    // The file that load() would read, which may not exist yet
+    fn path(&self) -> Option<PathBuf> {
+        self.explicit.clone().or_else(default_path)
+    }
+
+    fn modified_time(&self) -> Option<SystemTime> {
+        std::fs::metadata(self.path()?).and_then(|meta| meta.modified()).ok()
+    }
+
+    /// Accept the file as it is now, e.g. after writing to it ourselves, so
+    /// that doesn't count as an edit.
+    pub fn refresh(&mut self) {
+        self.modified = self.modified_time();
+    }
+
+    /// Re-read the config if SIGUSR1 arrived or the file changed since the
+    /// last check; None when nothing happened.
+    pub fn poll(&mut self, now: Instant) -> Option<Result<(Config, Vec<String>), ConfigError>> {
+        let signaled = self.signaled.swap(false, Ordering::Relaxed);
+        if !signaled && now < self.next_check {
+            return None;
+        }
+        self.next_check = now + RELOAD_POLL_INTERVAL;
+
+        let modified = self.modified_time();
+        if !signaled && modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(load(self.explicit.as_deref(), self.profile.as_deref()))
+    }
+}
+
+// Integers are accepted wherever a fractional number is
+fn as_float(value: &Value) -> Option<f64> {
+    value.as_float().or_else(|| value.as_integer().map(|n| n as f64))
+}
+
+/// A setting that can't be used, pointing at where it came from.
+#[derive(Debug)]
+pub struct ConfigError {
+    /// e.g. "config file ~/.config/gruvberry/config.toml at line 4"
+    pub location: String,
+    /// The offending line of the file, when there is one.
+    pub line: Option<String>,
+    pub message: String,
+    /// Unknown-key warnings found before the error, worth showing alongside
+    /// it.
+    pub warnings: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error in {}:", self.location)?;
+        if let Some(line) = &self.line {
+            write!(f, "\n    {}", line.trim())?;
+        }
+        write!(f, "\n  {}", self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Default location: `$XDG_CONFIG_HOME/gruvberry/config.toml`, else
+/// `~/.config/gruvberry/config.toml`.
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("gruvberry").join("config.toml"))
+}
+
+/// Write one setting into the config file (created if needed), keeping the
+/// rest of the file, comments included, as it was. Used for state the UI
+/// toggles, like shuffle and repeat.
+pub fn save_setting(explicit: Option<&Path>, key: &str, value: impl Into<toml_edit::Value>) -> Result<PathBuf, String> {
+    let value = value.into();
+    edit_file(explicit, |document| {
+        document[key] = toml_edit::value(value);
+        Ok(())
+    })
+}
+
+// Make one edit to the config file (created if needed) and write it back
+fn edit_file(
+    explicit: Option<&Path>,
+    edit: impl FnOnce(&mut toml_edit::DocumentMut) -> Result<(), String>,
+) -> Result<PathBuf, String> {
+    let path = explicit
+        .map(Path::to_path_buf)
+        .or_else(default_path)
+        .ok_or("no config file location (HOME is not set)")?;
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("could not read {}: {}", path.display(), e)),
+    };
+    let mut document: toml_edit::DocumentMut =
+        text.parse().map_err(|e| format!("could not update {}: {}", path.display(), e))?;
+    edit(&mut document).map_err(|e| format!("could not update {}: {}", path.display(), e))?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("could not create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&path, document.to_string()).map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Save `gradient` as the theme `name` (`[themes.<name>]`) and make it the
+/// one in use, keeping the rest of the file as it was.
+pub fn save_theme(explicit: Option<&Path>, name: &str, gradient: &Gradient) -> Result<PathBuf, String> {
+    edit_file(explicit, |document| palette::write(document, name, gradient))
+}
+
+/// Save the echo's settings as the 'E' panel left them, in one write. The
+/// levels are kept to the panel's steps so the file reads 0.4 rather than
+/// the f32's 0.4000000059604645.
+pub fn save_echo(explicit: Option<&Path>, echo: &EchoSettings) -> Result<PathBuf, String> {
+    let level = |value: f32| (value as f64 * 100.0).round() / 100.0;
+    edit_file(explicit, |document| {
+        document["echo"] = toml_edit::value(echo.enabled);
+        document["echo_delay_ms"] = toml_edit::value(echo.delay_ms.round() as i64);
+        document["echo_feedback"] = toml_edit::value(level(echo.feedback));
+        document["echo_mix"] = toml_edit::value(level(echo.mix));
+        document["echo_tap"] = toml_edit::value(echo.tap.name());
+        Ok(())
+    })
+}
+
+/// Set or, with None, remove one setting of an orientation's own in the
+/// config file (`[mode.<orientation>]`), keeping the rest of the file as it
+/// was.
+pub fn save_mode_setting(
+    explicit: Option<&Path>,
+    mode: &str,
+    key: &str,
+    value: Option<toml_edit::Value>,
+) -> Result<PathBuf, String> {
+    edit_file(explicit, |document| {
+        let modes = document.entry(MODE_TABLE).or_insert_with(|| {
+            let mut table = toml_edit::Table::new();
+            table.set_implicit(true);
+            toml_edit::Item::Table(table)
+        });
+        let Some(modes) = modes.as_table_like_mut() else {
+            return Err(format!("`{}` in the config file isn't a table", MODE_TABLE));
+        };
+        match value {
+            Some(value) => {
+                let section = modes.entry(mode).or_insert(toml_edit::table());
+                let Some(section) = section.as_table_like_mut() else {
+                    return Err(format!("[{}.{}] in the config file isn't a table", MODE_TABLE, mode));
+                };
+                section.insert(key, toml_edit::value(value));
+            }
+            None => {
+                if let Some(section) = modes.get_mut(mode).and_then(toml_edit::Item::as_table_like_mut) {
+                    section.remove(key);
+                    if section.is_empty() {
+                        modes.remove(mode);
+                    }
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+// Table holding the named profiles: [profile.<name>]
+const PROFILE_TABLE: &str = "profile";
+/// Table holding each orientation's own settings: `[mode.<orientation>]`.
+pub const MODE_TABLE: &str = "mode";
+/// The settings an orientation can have its own of.
+pub const MODE_KEYS: [&str; 4] = ["smoothing", "smoother", "tilt", "gradient"];
+
+/// Load and validate the config: defaults, then the file, then the selected
+/// profile's section of it, then the environment. An explicitly requested
+/// file must exist; the default one is optional. Returns warnings (unknown
+/// keys) alongside the config.
+pub fn load(explicit: Option<&Path>, profile: Option<&str>) -> Result<(Config, Vec<String>), ConfigError> {
+    load_with_env(explicit, profile, std::env::vars_os())
+}
+
+/// [`load`], with `env` standing in for the process's environment.
+pub fn load_with_env(
+    explicit: Option<&Path>,
+    profile: Option<&str>,
+    env: impl IntoIterator<Item = (OsString, OsString)>,
+) -> Result<(Config, Vec<String>), ConfigError> {
+    let path = match explicit {
+        Some(path) => Some(path.to_path_buf()),
+        None => default_path().filter(|path| path.exists()),
+    };
+
+    let mut config = Config::default();
+    let mut warnings = Vec::new();
+
+    match path {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).map_err(|e| ConfigError {
+                location: format!("config file {}", path.display()),
+                line: None,
+                message: format!("could not read file: {}", e),
+                warnings: Vec::new(),
+            })?;
+            apply_file(&mut config, &path, &text, profile, &mut warnings)?;
+        }
+        None if profile.is_some() => {
+            return Err(ConfigError {
+                location: "--profile".to_string(),
+                line: None,
+                message: format!(
+                    "no config file to read profiles from (create {} or pass --config)",
+                    default_path().map_or("a config file".to_string(), |path| path.display().to_string())
+                ),
+                warnings,
+            });
+        }
+        None => {}
+    }
+    apply_env(&mut config, env, &mut warnings)?;
+    config.check().map_err(|message| ConfigError {
+        location: "the settings".to_string(),
+        line: None,
+        message,
+        warnings: warnings.clone(),
+    })?;
+
+    Ok((config, warnings))
+}
+
+fn apply_file(
+    config: &mut Config,
+    path: &Path,
+    text: &str,
+    profile: Option<&str>,
+    warnings: &mut Vec<String>,
+) -> Result<(), ConfigError> {
+    // One parse gives the values and the positions of the keys, to quote the
+    // bad line; syntax errors already carry line/column context
+    let syntax_error = |message: String| ConfigError {
+        location: format!("config file {}", path.display()),
+        line: None,
+        message,
+        warnings: warnings.clone(),
+    };
+    let document = toml_edit::ImDocument::parse(text.to_string()).map_err(|e| syntax_error(e.to_string()))?;
+    let mut table: toml::Table =
+        toml_edit::de::from_document(document.clone()).map_err(|e| syntax_error(e.to_string()))?;
+    let span_of = |keys: &[&str]| {
+        let mut item = document.as_item();
+        for key in keys {
+            item = item.get(key)?;
+        }
+        item.span()
+    };
+
+    let profiles = match table.remove(PROFILE_TABLE) {
+        Some(Value::Table(profiles)) => profiles,
+        _ => toml::Table::new(),
+    };
+    let modes = match table.remove(MODE_TABLE) {
+        Some(Value::Table(modes)) => modes,
+        _ => toml::Table::new(),
+    };
+    let themes = match table.remove(palette::TABLE) {
+        Some(Value::Table(themes)) => themes,
+        _ => toml::Table::new(),
+    };
+    for (name, section) in &themes {
+        let error = |message: String| ConfigError {
+            location: format!("config file {}", path.display()),
+            line: span_of(&[palette::TABLE, name]).map(|span| line_at(text, span.start).1),
+            message: format!("[{}.{}]: {}", palette::TABLE, name, message),
+            warnings: warnings.clone(),
+        };
+        palette::check_name(name).map_err(error)?;
+        let Value::Table(section) = section else {
+            return Err(error("must be a table of low_hue, low_saturation, high_hue and high_saturation".to_string()));
+        };
+        let gradient = palette::parse(section).map_err(error)?;
+        config.themes.retain(|(known, _)| known != name);
+        config.themes.push((name.clone(), gradient));
+    }
+
+    let entries = table
+        .into_iter()
+        .map(|(key, value)| {
+            let span = span_of(&[&key]);
+            (key, value, span)
+        })
+        .collect();
+    apply_entries(config, path, text, &path.display().to_string(), entries, warnings)?;
+
+    for (name, section) in &modes {
+        let error = |message: String| ConfigError {
+            location: format!("config file {}", path.display()),
+            line: span_of(&[MODE_TABLE, name]).map(|span| line_at(text, span.start).1),
+            message,
+            warnings: warnings.clone(),
+        };
+        let orientation = Orientation::parse(name).ok_or_else(|| {
+            error(format!("no mode named `{}`; modes are the orientations: {}", name, Orientation::NAMES.join(", ")))
+        })?;
+        let Value::Table(section) = section else {
+            return Err(error(format!("[{}.{}] must be a table of settings", MODE_TABLE, name)));
+        };
+        let context = format!("[{}.{}] of {}", MODE_TABLE, name, path.display());
+        for key in section.keys().filter(|key| KEYS.contains(&key.as_str()) && !MODE_KEYS.contains(&key.as_str())) {
+            warnings.push(format!(
+                "warning: `{}` in {} is ignored (a mode can only have its own {})",
+                key,
+                context,
+                MODE_KEYS.join(", ")
+            ));
+        }
+        // Checked like any other setting, on a copy the mode's values are read back from
+        let mut scratch = Config::default();
+        let entries = section
+            .iter()
+            .filter(|(key, _)| !KEYS.contains(&key.as_str()) || MODE_KEYS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone(), span_of(&[MODE_TABLE, name, key])))
+            .collect();
+        apply_entries(&mut scratch, path, text, &context, entries, warnings)?;
+        let overrides = Overrides {
+            smoothing: section.contains_key("smoothing").then_some(scratch.smoothing),
+            smoother: section.contains_key("smoother").then_some(scratch.smoother),
+            tilt: section.contains_key("tilt").then_some(scratch.tilt),
+            gradient: section.contains_key("gradient").then_some(scratch.gradient),
+        };
+        config.modes.retain(|(known, _)| *known != orientation);
+        config.modes.push((orientation, overrides));
+    }
+
+    let Some(name) = profile else {
+        return Ok(());
+    };
+    let Some(Value::Table(section)) = profiles.get(name) else {
+        let available: Vec<&str> = profiles.keys().map(String::as_str).collect();
+        return Err(ConfigError {
+            location: format!("config file {}", path.display()),
+            line: None,
+            message: if available.is_empty() {
+                format!("no profile named `{}` (the file defines no [profile.<name>] sections)", name)
+            } else {
+                format!("no profile named `{}`; available profiles: {}", name, available.join(", "))
+            },
+            warnings: warnings.clone(),
+        });
+    };
+    let entries = section
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone(), span_of(&[PROFILE_TABLE, name, key])))
+        .collect();
+    apply_entries(
+        config,
+        path,
+        text,
+        &format!("[profile.{}] of {}", name, path.display()),
+        entries,
+        warnings,
+    )
+}
+
+// Validate and apply one table of settings from the file, given as
+// (key, value, position in the file); `context` names the table in
+// unknown-key warnings
+fn apply_entries(
+    config: &mut Config,
+    path: &Path,
+    text: &str,
+    context: &str,
+    mut entries: Vec<(String, Value, Option<Range<usize>>)>,
+    warnings: &mut Vec<String>,
+) -> Result<(), ConfigError> {
+    // Apply in file order so the first mistake in the file is the one reported
+    entries.sort_by_key(|(_, _, span)| span.as_ref().map(|span| span.start));
+
+    // Unknown keys are probably typos: warn, and suggest the closest real key
+    for (key, _, _) in entries.iter().filter(|(key, _, _)| !KEYS.contains(&key.as_str())) {
+        warnings.push(unknown_warning(&format!("key `{}` in {}", key, context), key, str::to_string));
+    }
+
+    for (key, value, span) in entries.iter().filter(|(key, _, _)| KEYS.contains(&key.as_str())) {
+        config.apply(key, value).map_err(|message| {
+            let (location, line) = match span {
+                Some(span) => {
+                    let (number, line) = line_at(text, span.start);
+                    (format!("config file {} at line {}", path.display(), number), Some(line))
+                }
+                None => (format!("config file {}", path.display()), None),
+            };
+            ConfigError {
+                location,
+                line,
+                message,
+                warnings: warnings.clone(),
+            }
+        })?;
+    }
+    Ok(())
+}
+
+// GRUVBERRY_<KEY> overrides the file. Values use TOML syntax (30, 0.5, true);
+// anything that doesn't parse is taken as a plain string. A variable of ours
+// that isn't valid UTF-8 can't be a setting and is skipped with a warning;
+// anyone else's is none of our business.
+fn apply_env(
+    config: &mut Config,
+    env: impl IntoIterator<Item = (OsString, OsString)>,
+    warnings: &mut Vec<String>,
+) -> Result<(), ConfigError> {
+    for (name, raw) in env {
+        if !name.to_string_lossy().starts_with(ENV_PREFIX) {
+            continue;
+        }
+        let (name, raw) = match (name.into_string(), raw.into_string()) {
+            (Ok(name), Ok(raw)) => (name, raw),
+            (name, _) => {
+                let name = name.unwrap_or_else(|name| name.to_string_lossy().into_owned());
+                warnings.push(format!("warning: environment variable {} is ignored (not valid UTF-8)", name));
+                continue;
+            }
+        };
+        let suffix = &name[ENV_PREFIX.len()..];
+        let key = suffix.to_lowercase();
+        if !KEYS.contains(&key.as_str()) {
+            warnings.push(unknown_warning(&format!("environment variable {}", name), &key, env_var));
+            continue;
+        }
+
+        let value = toml::from_str::<toml::Table>(&format!("value = {}", raw))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or(Value::String(raw.clone()));
+        config.apply(&key, &value).map_err(|message| ConfigError {
+            location: format!("environment variable {}", name),
+            line: None,
+            message,
+            warnings: warnings.clone(),
+        })?;
+    }
+    Ok(())
+}
+
+/// Environment variable that overrides `key`, e.g. `GRUVBERRY_FFT_SIZE`.
+pub fn env_var(key: &str) -> String {
+    format!("{}{}", ENV_PREFIX, key.to_uppercase())
+}
+
+// "warning: unknown <what> (did you mean ...?)", with suggestions spelled by `spell`
+fn unknown_warning(what: &str, key: &str, spell: impl Fn(&str) -> String) -> String {
+    let mut warning = format!("warning: unknown {}", what);
+    let suggestions = near_misses(key);
+    if !suggestions.is_empty() {
+        let listed: Vec<String> = suggestions.iter().map(|s| format!("`{}`", spell(s))).collect();
+        warning.push_str(&format!(" (did you mean {}?)", listed.join(" or ")));
+    }
+    warning
+}
+
+// 1-based line number and text of the line containing byte `offset`
+fn line_at(text: &str, offset: usize) -> (usize, String) {
+    let offset = offset.min(text.len());
+    let number = text[..offset].matches('\n').count() + 1;
+    let line = text.lines().nth(number - 1).unwrap_or_default().to_string();
+    (number, line)
+}
+
+// Known keys the unknown one is plausibly a typo or abbreviation of, best first
+fn near_misses(key: &str) -> Vec<&'static str> {
+    let mut candidates: Vec<(bool, usize, &'static str)> = KEYS
+        .iter()
+        .map(|&known| (!known.starts_with(key), edit_distance(key, known), known))
+        .filter(|&(not_prefix, distance, _)| !not_prefix || distance <= 2)
+        .collect();
+    candidates.sort();
+    candidates.into_iter().map(|(_, _, known)| known).collect()
+}
+
+// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,367 @@
+//! The control socket.
+//!
+//! With `--control unix:PATH` a playing gruvberry takes requests from other
+//! programs over a Unix socket, a line of text each, and answers every one
+//! with a line starting `ok` or `error`:
+//!
+//! ```text
+//! $ echo "add ~/music/next.wav" | nc -U /tmp/gruvberry.sock
+//! ok Queued next.wav (#4)
+//! ```
+//!
+//! [`Server`] is the visualizer's side. It listens on a thread of its own
+//! and hands over what comes in as [`Request`]s between frames, each with the
+//! [`Reply`] to answer it by.
+//!
+//! A client that sends `follow` is streamed what is playing from then on, a
+//! line per [`Update`]: the track, whether it is playing, paused or finished,
+//! and every frame as it is drawn. That is what `--follow` draws a second
+//! instance from, and the frame lines are [`AnalysisFrame::to_line`]'s, so
+//! anything else can read them too. The follower's keys come back as
+//! requests over the same connection, and their answers come in among the
+//! updates.
+//!
+//! ```
+//! use std::io::{BufRead, BufReader, Write};
+//! use std::os::unix::net::UnixStream;
+//! use std::time::Duration;
+//! use gruvberry::control::{Request, Server};
+//!
+//! let path = std::env::temp_dir().join(format!("gruvberry-doc-{}.sock", std::process::id()));
+//! let server = Server::bind(&path).unwrap();
+//! let mut client = UnixStream::connect(&path).unwrap();
+//! client.write_all(b"add song.wav\n").unwrap();
+//!
+//! let (request, reply) = server.poll(Duration::from_secs(5)).unwrap();
+//! assert_eq!(request, Request::Add("song.wav".to_string()));
+//! reply.send(Ok("Queued song.wav (#2)".to_string()));
+//! let mut answer = String::new();
+//! BufReader::new(client).read_line(&mut answer).unwrap();
+//! assert_eq!(answer, "ok Queued song.wav (#2)\n");
+//! ```
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+use crate::history::AnalysisFrame;
+
+// How long a write to a follower may take before it is dropped, so one that
+// stopped reading can't hold up the frames
+const FOLLOWER_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// What a line sent to the socket asks for.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Request {
+    /// `add PATH`: queue a file, as typed into the 'a' prompt (`~` included).
+    Add(String),
+    /// `pause`: pause, or go on if paused, as space does.
+    Pause,
+    /// `seek SECS`: move this many seconds forward, or back if negative, as
+    /// the arrow keys do.
+    Seek(f32),
+    /// `next`: skip to the next track, as 'n' does.
+    Next,
+    /// `previous`: go back to the track before, as 'p' does.
+    Previous,
+    /// `follow`: stream [`Update`]s to this client from now on. The server
+    /// answers it itself, so it never comes out of [`Server::poll`].
+    Follow,
+}
+
+impl Request {
+    /// Read one line of the protocol.
+    pub fn parse(line: &str) -> Result<Request, String> {
+        let line = line.trim();
+        let (command, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let argument = argument.trim();
+        match command {
+            "add" if argument.is_empty() => Err("add needs a path".to_string()),
+            "add" => Ok(Request::Add(argument.to_string())),
+            "seek" => match argument.parse::<f32>() {
+                Ok(secs) if secs.is_finite() => Ok(Request::Seek(secs)),
+                _ => Err(format!("seek needs a number of seconds, not '{}'", argument)),
+            },
+            "pause" | "next" | "previous" | "follow" if !argument.is_empty() => {
+                Err(format!("{} takes nothing after it", command))
+            }
+            "pause" => Ok(Request::Pause),
+            "next" => Ok(Request::Next),
+            "previous" => Ok(Request::Previous),
+            "follow" => Ok(Request::Follow),
+            _ => Err(format!(
+                "unknown request '{}' (expected add, pause, seek, next, previous or follow)",
+                command
+            )),
+        }
+    }
+
+    /// The request as a line of the protocol, without the newline.
+    pub fn to_line(&self) -> String {
+        match self {
+            Request::Add(path) => format!("add {}", path),
+            Request::Pause => "pause".to_string(),
+            Request::Seek(secs) => format!("seek {}", secs),
+            Request::Next => "next".to_string(),
+            Request::Previous => "previous".to_string(),
+            Request::Follow => "follow".to_string(),
+        }
+    }
+}
+
+/// Whether the primary is playing, as a follower shows it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayState {
+    Playing,
+    Paused,
+    /// The last track has ended.
+    Finished,
+}
+
+impl PlayState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlayState::Playing => "playing",
+            PlayState::Paused => "paused",
+            PlayState::Finished => "finished",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<PlayState> {
+        [PlayState::Playing, PlayState::Paused, PlayState::Finished].into_iter().find(|state| state.name() == name)
+    }
+}
+
+/// What followers are sent, a line each.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Update {
+    /// `track SECS NAME`: the track now playing, its length and name.
+    Track { name: String, duration: f32 },
+    /// `state playing`, `state paused` or `state finished`.
+    State(PlayState),
+    /// A frame as it was drawn, in [`AnalysisFrame::to_line`]'s form.
+    Frame(AnalysisFrame),
+}
+
+impl Update {
+    /// The update as a line, without the newline.
+    pub fn to_line(&self) -> String {
+        match self {
+            // Names can hold anything; the line ends at the first newline
+            Update::Track { name, duration } => format!("track {} {}", duration, name.replace(['\n', '\r'], " ")),
+            Update::State(state) => format!("state {}", state.name()),
+            Update::Frame(frame) => frame.to_line(),
+        }
+    }
+
+    /// Read an update from a line [`to_line`](Update::to_line) wrote.
+    pub fn parse(line: &str) -> Result<Update, String> {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match kind {
+            "track" => {
+                let (duration, name) = rest.split_once(' ').unwrap_or((rest, ""));
+                let duration = duration.parse().map_err(|_| format!("track has a bad length: '{}'", duration))?;
+                Ok(Update::Track {
+                    name: name.to_string(),
+                    duration,
+                })
+            }
+            "state" => PlayState::parse(rest).map(Update::State).ok_or_else(|| format!("unknown state '{}'", rest)),
+            "frame" => AnalysisFrame::from_line(line).map(Update::Frame),
+            _ => Err(format!("unknown update '{}'", kind)),
+        }
+    }
+}
+
+/// The socket path of an address given as `unix:PATH`.
+pub fn parse_address(address: &str) -> Result<PathBuf, String> {
+    match address.strip_prefix("unix:") {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Err(format!("'{}' isn't a socket address; give it as unix:PATH", address)),
+    }
+}
+
+/// Where the answer to a request goes.
+pub struct Reply {
+    stream: UnixStream,
+}
+
+impl Reply {
+    /// Answer with `ok` and the message, or `error` and the reason. A client
+    /// that has gone away misses it.
+    pub fn send(mut self, result: Result<String, String>) {
+        let (status, message) = match result {
+            Ok(message) => ("ok", message),
+            Err(message) => ("error", message),
+        };
+        // One line per answer, whatever the message holds
+        let message = message.replace(['\n', '\r'], " ");
+        let line = if message.is_empty() { format!("{}\n", status) } else { format!("{} {}\n", status, message) };
+        let _ = self.stream.write_all(line.as_bytes());
+    }
+}
+
+/// A listening control socket. The socket file goes when it is dropped.
+pub struct Server {
+    path: PathBuf,
+    requests: mpsc::Receiver<(Request, Reply)>,
+    closed: Arc<AtomicBool>,
+    followers: Arc<Mutex<Followers>>,
+}
+
+// Clients that sent `follow`, and the latest track and state lines for the
+// ones that join later
+#[derive(Default)]
+struct Followers {
+    streams: Vec<UnixStream>,
+    track: Option<String>,
+    state: Option<String>,
+}
+
+impl Server {
+    /// Listen at `path`. A socket file left behind by a gruvberry that is
+    /// gone is replaced; one that another is still listening on is not.
+    pub fn bind(path: &Path) -> io::Result<Server> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(io::ErrorKind::AddrInUse, "another program is listening there"));
+            }
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        let (sender, requests) = mpsc::channel();
+        let closed = Arc::new(AtomicBool::new(false));
+        let stop = closed.clone();
+        let followers = Arc::new(Mutex::new(Followers::default()));
+        let joining = followers.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let sender = sender.clone();
+                let followers = joining.clone();
+                std::thread::spawn(move || serve(stream, sender, followers));
+            }
+        });
+        Ok(Server {
+            path: path.to_path_buf(),
+            requests,
+            closed,
+            followers,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The next request, waiting up to `timeout` for one (not at all with
+    /// zero, as between frames).
+    pub fn poll(&self, timeout: Duration) -> Option<(Request, Reply)> {
+        if timeout.is_zero() {
+            self.requests.try_recv().ok()
+        } else {
+            self.requests.recv_timeout(timeout).ok()
+        }
+    }
+
+    /// Whether anyone is following, so frames are only written out for
+    /// someone.
+    pub fn is_followed(&self) -> bool {
+        self.followers.lock().is_ok_and(|followers| !followers.streams.is_empty())
+    }
+
+    /// Send `update` to every follower, dropping those that are gone or
+    /// stopped reading. A track or state is remembered for followers that
+    /// join later and only sent when it changes, so it can be published every
+    /// frame.
+    pub fn publish(&self, update: &Update) {
+        let Ok(mut followers) = self.followers.lock() else {
+            return;
+        };
+        let line = format!("{}\n", update.to_line());
+        let latest = match update {
+            Update::Track { .. } => Some(&mut followers.track),
+            Update::State(_) => Some(&mut followers.state),
+            Update::Frame(_) => None,
+        };
+        if let Some(latest) = latest {
+            if latest.as_ref() == Some(&line) {
+                return;
+            }
+            *latest = Some(line.clone());
+        }
+        followers.streams.retain_mut(|stream| stream.write_all(line.as_bytes()).is_ok());
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+        // Followers see the end of the stream rather than it going quiet
+        if let Ok(followers) = self.followers.lock() {
+            for stream in &followers.streams {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+            }
+        }
+        // Wake the listening thread so it sees it's done
+        let _ = UnixStream::connect(&self.path);
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// Read a client's requests a line at a time until it hangs up. Lines that
+// aren't requests are answered here, and so is `follow`; the rest go to the
+// visualizer.
+fn serve(stream: UnixStream, requests: mpsc::Sender<(Request, Reply)>, followers: Arc<Mutex<Followers>>) {
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(writer) = stream.try_clone() else {
+            return;
+        };
+        let reply = Reply { stream: writer };
+        match Request::parse(&line) {
+            Ok(Request::Follow) => follow(reply.stream, &followers),
+            Ok(request) => {
+                if requests.send((request, reply)).is_err() {
+                    return;
+                }
+            }
+            Err(e) => reply.send(Err(e)),
+        }
+    }
+}
+
+// Take on a follower: the answer, then the latest track and state, then
+// whatever is published. All under the lock, so nothing published in the
+// meantime comes in before them or is missed.
+fn follow(mut stream: UnixStream, followers: &Mutex<Followers>) {
+    let Ok(mut followers) = followers.lock() else {
+        return;
+    };
+    if stream.set_write_timeout(Some(FOLLOWER_TIMEOUT)).is_err() {
+        return;
+    }
+    let mut lines = String::from("ok Following\n");
+    for latest in [&followers.track, &followers.state].into_iter().flatten() {
+        lines.push_str(latest);
+    }
+    if stream.write_all(lines.as_bytes()).is_ok() {
+        followers.streams.push(stream);
+    }
+}
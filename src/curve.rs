@@ -0,0 +1,104 @@
+//! A per-band curve in dB, plotted in text.
+//!
+//! Used to show the shaping the display applies on top of the signal: the
+//! gain of each band as the analyzer's [`BandTable`](crate::analysis::BandTable)
+//! holds it (the tilt, for now). [`plot`] draws one column per band (resampled
+//! to the width) as a line of thin strokes, `▔`, `─` or `▁` by where within
+//! its row the value falls, over a dotted 0 dB line when that is in range.
+//! The frequency axis underneath is the main view's
+//! [`axis_line`](crate::legend::axis_line), so the curve lines up with the
+//! legend's numbered segments.
+//!
+//! ```
+//! use gruvberry::curve;
+//!
+//! let plot = curve::plot(&[0.0, 3.0, 6.0], 6, 3, 2);
+//! assert_eq!((plot.top_db, plot.bottom_db), (6.0, 0.0));
+//! assert_eq!(plot.rows, ["    ─▔", "  ▁▔  ", "▁─┄┄┄┄"]);
+//! assert_eq!(plot.axis, gruvberry::legend::axis_line(6, 2));
+//! ```
+
+use crate::legend;
+use crate::reference;
+
+/// Smallest span of the plot's scale, so a nearly flat curve doesn't fill
+/// the whole height.
+pub const MIN_SPAN_DB: f32 = 6.0;
+/// The scale's ends are rounded out to multiples of this.
+pub const STEP_DB: f32 = 3.0;
+
+// Strokes from the top of a row to the bottom
+const STROKES: [char; 3] = ['▔', '─', '▁'];
+// The 0 dB line where the curve isn't
+const ZERO: char = '┄';
+
+/// A plotted curve: `rows` top to bottom and the axis under them, all the
+/// same width, and the values the top and bottom rows stand for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plot {
+    pub rows: Vec<String>,
+    pub axis: String,
+    pub top_db: f32,
+    pub bottom_db: f32,
+}
+
+/// The dB values of `bands` (resampled to `width` columns) as a line
+/// `height` rows high, over an axis of `segments` legend segments. The
+/// scale covers the values, rounded out to [`STEP_DB`], and spans at least
+/// [`MIN_SPAN_DB`]. Values that aren't finite numbers are left out.
+pub fn plot(bands: &[f32], width: usize, height: usize, segments: usize) -> Plot {
+    let finite: Vec<f32> = bands.iter().cloned().filter(|db| db.is_finite()).collect();
+    let low = finite.iter().cloned().fold(f32::INFINITY, f32::min);
+    let high = finite.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let (mut bottom, mut top) = match finite.is_empty() {
+        true => (0.0, MIN_SPAN_DB),
+        false => ((low / STEP_DB).floor() * STEP_DB, (high / STEP_DB).ceil() * STEP_DB),
+    };
+    // Widen evenly around the middle, staying on the steps
+    while top - bottom < MIN_SPAN_DB {
+        top += STEP_DB;
+        if top - bottom < MIN_SPAN_DB {
+            bottom -= STEP_DB;
+        }
+    }
+
+    let height = height.max(1);
+    // Sub-rows from the top: each row has one per stroke
+    let levels = height * STROKES.len();
+    let level_of = |db: f32| {
+        let position = (top - db) / (top - bottom) * (levels - 1) as f32;
+        (position.round().max(0.0) as usize).min(levels - 1)
+    };
+    let zero_row = (bottom <= 0.0 && top >= 0.0).then(|| level_of(0.0) / STROKES.len());
+
+    let columns = if bands.is_empty() { Vec::new() } else { resample_finite(bands, width) };
+    let mut grid = vec![vec![' '; width]; height];
+    if let Some(row) = zero_row {
+        grid[row].fill(ZERO);
+    }
+    for (column, db) in columns.iter().enumerate() {
+        if let Some(db) = db {
+            let level = level_of(*db);
+            grid[level / STROKES.len()][column] = STROKES[level % STROKES.len()];
+        }
+    }
+
+    Plot {
+        rows: grid.into_iter().map(|row| row.into_iter().collect()).collect(),
+        axis: legend::axis_line(width, segments),
+        top_db: top,
+        bottom_db: bottom,
+    }
+}
+
+// `bands` resampled to `width`, with the columns that fall on a band that
+// isn't a number left empty
+fn resample_finite(bands: &[f32], width: usize) -> Vec<Option<f32>> {
+    let filled: Vec<f32> = bands.iter().map(|&db| if db.is_finite() { db } else { 0.0 }).collect();
+    let valid: Vec<f32> = bands.iter().map(|db| if db.is_finite() { 1.0 } else { 0.0 }).collect();
+    reference::resample(&filled, width)
+        .into_iter()
+        .zip(reference::resample(&valid, width))
+        .map(|(db, valid)| (valid >= 0.999).then_some(db))
+        .collect()
+}
@@ -0,0 +1,118 @@
+//! Opening the audio output, retried while the device comes back.
+//!
+//! Right after a resume from suspend the sound server can take a few
+//! seconds to come back, and opening the default output fails until it
+//! has. [`open`] tries a [`DeviceOpener`] up to [`RetryPolicy::attempts`]
+//! times, waiting twice as long before each retry as before the last, and
+//! only gives up with the last error. The opener is a trait (closures
+//! returning a `Result` implement it) so the retries can be tested without
+//! a device, and the waiting is left to the caller's `wait`, which is also
+//! where it says what is going on.
+//!
+//! ```
+//! use std::time::Duration;
+//! use gruvberry::device::{self, RetryPolicy};
+//!
+//! // A device that is there on the third try
+//! let mut tries = 0;
+//! let mut opener = || {
+//!     tries += 1;
+//!     if tries < 3 { Err("device busy".to_string()) } else { Ok("output") }
+//! };
+//! let policy = RetryPolicy { attempts: 5, first_delay: Duration::from_millis(200) };
+//! let mut waited = Vec::new();
+//! let opened = device::open(&mut opener, &policy, |retry| waited.push(retry.delay));
+//! assert_eq!(opened, Ok("output"));
+//! assert_eq!(waited, [Duration::from_millis(200), Duration::from_millis(400)]);
+//! ```
+
+use std::time::Duration;
+
+/// Something that opens an audio device, such as the default output.
+pub trait DeviceOpener {
+    type Device;
+
+    /// One attempt at opening the device.
+    fn open(&mut self) -> Result<Self::Device, String>;
+}
+
+impl<D, F: FnMut() -> Result<D, String>> DeviceOpener for F {
+    type Device = D;
+
+    fn open(&mut self) -> Result<D, String> {
+        self()
+    }
+}
+
+/// How many times to try, and how long to wait before the first retry.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in all, the first one included; 1 never retries.
+    pub attempts: u32,
+    /// Wait before the second attempt, doubled before each one after.
+    pub first_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Five attempts over three seconds (0.2 + 0.4 + 0.8 + 1.6 s of waiting).
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: 5,
+            first_delay: Duration::from_millis(200),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The waits between attempts, in order.
+    pub fn delays(&self) -> impl Iterator<Item = Duration> {
+        let first = self.first_delay;
+        (0..self.attempts.saturating_sub(1)).map(move |n| first.saturating_mul(1 << n.min(16)))
+    }
+
+    /// How long all the waiting takes when every attempt fails.
+    pub fn total_wait(&self) -> Duration {
+        self.delays().sum()
+    }
+}
+
+/// A failed attempt about to be retried.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Retry {
+    /// The attempt coming up, from 2 to `attempts`.
+    pub attempt: u32,
+    pub attempts: u32,
+    /// How long to wait before it.
+    pub delay: Duration,
+    /// Why the last attempt failed.
+    pub error: String,
+}
+
+/// Open the device, retrying as `policy` says. Before each retry `wait` is
+/// told about it and is expected to wait `retry.delay` (a test can skip
+/// that). The error is the last attempt's, with the number of attempts when
+/// there was more than one.
+pub fn open<O: DeviceOpener>(
+    opener: &mut O,
+    policy: &RetryPolicy,
+    mut wait: impl FnMut(&Retry),
+) -> Result<O::Device, String> {
+    let attempts = policy.attempts.max(1);
+    // One fewer than the attempts: there is no waiting after the last
+    let mut delays = policy.delays();
+    let mut attempt = 1;
+    loop {
+        let error = match opener.open() {
+            Ok(device) => return Ok(device),
+            Err(error) => error,
+        };
+        match delays.next() {
+            Some(delay) => {
+                attempt += 1;
+                wait(&Retry { attempt, attempts, delay, error });
+            }
+            None if attempts > 1 => return Err(format!("{} (gave up after {} attempts)", error, attempts)),
+            None => return Err(error),
+        }
+    }
+}
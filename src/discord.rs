@@ -0,0 +1,222 @@
+use std::io::{self, Read, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use gruvberry::presence::{self, Activity};
+
+// Discord Rich Presence (the `discord` feature): the track, where playback is
+// and a mini spectrum, sent to the Discord client over its local IPC socket.
+// A worker thread does the talking, so a slow or missing Discord never holds
+// up a frame.
+
+// Least time between two updates; Discord drops faster ones anyway
+pub const UPDATE_INTERVAL: Duration = Duration::from_secs(5);
+
+// How long a read or write on the socket may take before the connection is
+// given up on
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+// How long the exit waits for the presence to be cleared
+const CLEAR_TIMEOUT: Duration = Duration::from_secs(1);
+
+// Opcodes of the IPC frames
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+
+enum Update {
+    Set(Activity),
+    Clear,
+}
+
+// The visualizer's end of the presence
+pub struct Presence {
+    updates: Sender<Update>,
+    // The one line logged when Discord can't be reached
+    log: Receiver<String>,
+    done: Receiver<()>,
+    last_update: Option<Instant>,
+}
+
+impl Presence {
+    // Start the worker for the Discord application `client_id`; without one
+    // the worker logs why there is no presence and stops
+    pub fn start(client_id: Option<String>) -> Presence {
+        let (updates, receiver) = mpsc::channel();
+        let (log, log_receiver) = mpsc::channel();
+        let (done, done_receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            match client_id {
+                Some(client_id) => run(&client_id, receiver, &log),
+                None => {
+                    let _ = log.send("Discord presence is off: set discord_client_id in the config".to_string());
+                }
+            }
+            let _ = done.send(());
+        });
+        Presence {
+            updates,
+            log: log_receiver,
+            done: done_receiver,
+            last_update: None,
+        }
+    }
+
+    // Publish the activity `make` builds, unless the last update went out
+    // less than UPDATE_INTERVAL ago
+    pub fn update(&mut self, make: impl FnOnce() -> Activity) {
+        if self.last_update.is_some_and(|at| at.elapsed() < UPDATE_INTERVAL) {
+            return;
+        }
+        self.last_update = Some(Instant::now());
+        let _ = self.updates.send(Update::Set(make()));
+    }
+
+    // The line to show, once, when Discord couldn't be reached
+    pub fn log_line(&self) -> Option<String> {
+        self.log.try_recv().ok()
+    }
+
+    // Clear the presence, waiting a moment for that to go out
+    pub fn finish(self) {
+        let _ = self.updates.send(Update::Clear);
+        // Past that, Discord clears it anyway once the socket closes with the process
+        let _ = self.done.recv_timeout(CLEAR_TIMEOUT);
+    }
+}
+
+// The worker: connects when there is something to show and not before, and
+// again at the next update after losing the connection. Only the first
+// failure is logged.
+fn run(client_id: &str, updates: Receiver<Update>, log: &Sender<String>) {
+    let mut connection: Option<Connection> = None;
+    let mut logged = false;
+    while let Ok(update) = updates.recv() {
+        // Only the newest update matters
+        let update = updates.try_iter().last().unwrap_or(update);
+        let activity = match &update {
+            Update::Set(activity) => Some(activity),
+            Update::Clear => None,
+        };
+        if connection.is_none() && activity.is_some() {
+            connection = match Connection::open(client_id) {
+                Ok(connection) => Some(connection),
+                Err(e) => {
+                    if !std::mem::replace(&mut logged, true) {
+                        let _ = log.send(format!("Discord presence unavailable ({}); retrying quietly", e));
+                    }
+                    None
+                }
+            };
+        }
+        if let Some(open) = connection.as_mut()
+            && let Err(e) = open.set_activity(activity)
+        {
+            connection = None;
+            if !std::mem::replace(&mut logged, true) {
+                let _ = log.send(format!("Discord presence lost ({}); retrying quietly", e));
+            }
+        }
+        if activity.is_none() {
+            break;
+        }
+    }
+}
+
+trait Socket: Read + Write + Send {}
+impl<T: Read + Write + Send> Socket for T {}
+
+struct Connection {
+    socket: Box<dyn Socket>,
+    nonce: u64,
+}
+
+impl Connection {
+    // Connect to the first Discord socket that answers and introduce ourselves
+    fn open(client_id: &str) -> io::Result<Connection> {
+        let mut connection = Connection {
+            socket: connect()?,
+            nonce: 0,
+        };
+        let handshake = format!("{{\"v\":1,\"client_id\":{}}}", presence::json_string(client_id));
+        connection.send(OP_HANDSHAKE, &handshake)?;
+        Ok(connection)
+    }
+
+    // Show `activity`, or nothing
+    fn set_activity(&mut self, activity: Option<&Activity>) -> io::Result<()> {
+        let activity = match activity {
+            Some(activity) => activity.to_json(unix_ms()),
+            None => "null".to_string(),
+        };
+        self.nonce += 1;
+        let command = format!(
+            "{{\"cmd\":\"SET_ACTIVITY\",\"args\":{{\"pid\":{},\"activity\":{}}},\"nonce\":\"{}\"}}",
+            std::process::id(),
+            activity,
+            self.nonce
+        );
+        self.send(OP_FRAME, &command)
+    }
+
+    // Send a frame and read Discord's reply to it
+    fn send(&mut self, op: u32, payload: &str) -> io::Result<()> {
+        let mut frame = Vec::with_capacity(payload.len() + 8);
+        frame.extend_from_slice(&op.to_le_bytes());
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(payload.as_bytes());
+        self.socket.write_all(&frame)?;
+        self.socket.flush()?;
+
+        let mut header = [0; 8];
+        self.socket.read_exact(&mut header)?;
+        let reply_op = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let mut reply = vec![0; len as usize];
+        self.socket.read_exact(&mut reply)?;
+        let reply = String::from_utf8_lossy(&reply);
+        if reply_op == OP_CLOSE || reply.contains("\"evt\":\"ERROR\"") {
+            return Err(io::Error::other(format!("Discord refused: {}", reply)));
+        }
+        Ok(())
+    }
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_millis() as u64)
+}
+
+// Discord listens on the first free one of discord-ipc-0 to -9
+#[cfg(unix)]
+fn connect() -> io::Result<Box<dyn Socket>> {
+    use std::os::unix::net::UnixStream;
+    let dir = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .find_map(std::env::var_os)
+        .unwrap_or_else(|| "/tmp".into());
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "Discord isn't running");
+    for i in 0..10 {
+        match UnixStream::connect(std::path::Path::new(&dir).join(format!("discord-ipc-{}", i))) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(IO_TIMEOUT))?;
+                stream.set_write_timeout(Some(IO_TIMEOUT))?;
+                return Ok(Box::new(stream));
+            }
+            Err(e) if e.kind() != io::ErrorKind::NotFound => last_error = e,
+            Err(_) => {}
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(windows)]
+fn connect() -> io::Result<Box<dyn Socket>> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "Discord isn't running");
+    for i in 0..10 {
+        let pipe = format!(r"\\.\pipe\discord-ipc-{}", i);
+        match std::fs::OpenOptions::new().read(true).write(true).open(pipe) {
+            Ok(file) => return Ok(Box::new(file)),
+            Err(e) if e.kind() != io::ErrorKind::NotFound => last_error = e,
+            Err(_) => {}
+        }
+    }
+    Err(last_error)
+}
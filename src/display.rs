@@ -0,0 +1,71 @@
+//! Text from outside, made safe to draw.
+//!
+//! File names and other text from outside can hold anything: bytes that
+//! aren't UTF-8, escape sequences, newlines, wide CJK characters. Everything
+//! user-controlled goes through here before it reaches the UI.
+//!
+//! ```
+//! use gruvberry::display;
+//!
+//! assert_eq!(display::clean("a\x1b[2Jb\n"), "a[2Jb");
+//! assert_eq!(display::fit("交響曲第九番.wav", 7), "交響曲…");
+//! ```
+
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+/// Drop control characters (newlines, tabs, ESC of escape sequences...) that
+/// would break the layout or be interpreted by the terminal, and the bidi
+/// controls that would reorder the rest of the line. Right-to-left text itself
+/// is kept.
+pub fn clean(text: &str) -> String {
+    text.chars().filter(|&c| !c.is_control() && !is_bidi_control(c)).collect()
+}
+
+// Embeddings, overrides, isolates and marks: invisible, but they change the
+// order whatever follows them is drawn in
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{200e}' | '\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
+
+/// A path for display; invalid UTF-8 shows as U+FFFD.
+pub fn path(path: &Path) -> String {
+    clean(&path.to_string_lossy())
+}
+
+/// The last component of a path for display, or the whole path if it has
+/// none.
+pub fn file_name(path: &Path) -> String {
+    match path.file_name() {
+        Some(name) => clean(&name.to_string_lossy()),
+        None => self::path(path),
+    }
+}
+
+/// Terminal cells `text` takes up (2 for most CJK characters).
+pub fn width(text: &str) -> usize {
+    text.width()
+}
+
+/// Shorten `text` to at most `max` cells, ending in "…" when cut.
+pub fn fit(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return text.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut fitted = String::new();
+    for c in text.chars() {
+        fitted.push(c);
+        // Measured as a whole: a variation selector or joiner can make the
+        // characters before it wider than they are one by one. Keep one cell
+        // for the ellipsis.
+        if fitted.width() > max - 1 {
+            fitted.pop();
+            break;
+        }
+    }
+    fitted.push('…');
+    fitted
+}
@@ -0,0 +1,103 @@
+//! Drawing more bands than there are cells to draw them in.
+//!
+//! A frame can hold more bands than the spectrum has columns (or rows, for
+//! horizontal bars): one replayed from history after the terminal shrank,
+//! say. Picking one band per cell would skip the rest, and with them any
+//! peak that falls between, so each cell instead takes all the bands it
+//! covers together, by their [`Aggregate`]: the loudest of them, or their
+//! energy summed. Either way a lone tone shows at any width.
+//!
+//! ```
+//! use gruvberry::downsample::{self, Aggregate};
+//!
+//! let mut bands = vec![0.0; 256];
+//! bands[100] = 80.0;
+//! let cells = downsample::downsample(&bands, 40, Aggregate::Max);
+//! assert_eq!(cells.len(), 40);
+//! assert_eq!(cells[downsample::cell_of(100, 40, 256)], 80.0);
+//! ```
+
+use std::ops::Range;
+
+/// How the bands a cell covers make up its level.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Aggregate {
+    /// The loudest of them.
+    #[default]
+    Max,
+    /// Their energy summed (the root of the summed squares), up to full
+    /// scale; a cell over a broad hump stands taller than one over a lone
+    /// band of the same level.
+    Energy,
+}
+
+impl Aggregate {
+    pub const NAMES: [&str; 2] = ["max", "energy"];
+
+    pub fn parse(value: &str) -> Option<Aggregate> {
+        match value {
+            "max" => Some(Aggregate::Max),
+            "energy" => Some(Aggregate::Energy),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Aggregate::Max => "max",
+            Aggregate::Energy => "energy",
+        }
+    }
+}
+
+/// The bands out of `num_bands` that `cell` out of `cells` covers: every
+/// band goes to exactly one cell, and every cell gets at least one when
+/// there are as many bands as cells or more.
+pub fn span(cell: usize, cells: usize, num_bands: usize) -> Range<usize> {
+    let cells = cells.max(1);
+    let start = cell * num_bands / cells;
+    let end = ((cell + 1) * num_bands / cells).max(start + 1).min(num_bands);
+    start..end
+}
+
+/// The cell `band` out of `num_bands` is drawn in when fitted to `cells`
+/// by [`downsample`]: the one whose [`span`] holds it, or the band itself
+/// when there are no more bands than cells.
+pub fn cell_of(band: usize, cells: usize, num_bands: usize) -> usize {
+    if num_bands <= cells {
+        return band;
+    }
+    // The last cell whose span starts at or before the band
+    ((band + 1) * cells).div_ceil(num_bands).saturating_sub(1).min(cells.saturating_sub(1))
+}
+
+/// `levels` (0-100, one per band) fitted to `cells`; as they are when
+/// there are no more of them than cells.
+pub fn downsample(levels: &[f32], cells: usize, aggregate: Aggregate) -> Vec<f32> {
+    if levels.len() <= cells {
+        return levels.to_vec();
+    }
+    (0..cells)
+        .map(|cell| {
+            let covered = &levels[span(cell, cells, levels.len())];
+            match aggregate {
+                Aggregate::Max => covered.iter().fold(0.0, |max: f32, &level| max.max(level)),
+                Aggregate::Energy => covered.iter().fold(0.0, |sum, &level| sum + level * level).sqrt().min(100.0),
+            }
+        })
+        .collect()
+}
+
+/// The band standing for each of `cells` when `levels` are fitted to them,
+/// for its color: the loudest it covers, the first of equals.
+pub fn representatives(levels: &[f32], cells: usize) -> Vec<usize> {
+    if levels.len() <= cells {
+        return (0..levels.len()).collect();
+    }
+    (0..cells)
+        .map(|cell| {
+            let covered = span(cell, cells, levels.len());
+            covered.clone().fold(covered.start, |best, band| if levels[band] > levels[best] { band } else { best })
+        })
+        .collect()
+}
@@ -0,0 +1,232 @@
+//! Crest factor and DR-style dynamic range.
+//!
+//! The crest factor is how far the peak sits above the RMS level, in dB: a
+//! sine has 3.01 dB, a heavily limited master a few dB more, a dynamic
+//! recording twice that or more. [`DynamicsMeter`] follows it live over the
+//! last [`WINDOW_SECS`] and sums up a whole track the way the common "DR"
+//! meters do:
+//!
+//! - the audio is cut into blocks of [`BLOCK_SECS`],
+//! - each block's RMS is taken as `sqrt(2 × mean square)`, so a full-scale
+//!   sine measures 0 dB like its peak,
+//! - the figure is the second highest block peak over the RMS of the loudest
+//!   20% of blocks, in dB.
+//!
+//! A sine therefore measures DR 0; loud modern masters land around 4-7 and
+//! dynamic recordings at 12 and above.
+//!
+//! The blocks aren't kept: their levels are counted in 0.1 dB steps, and
+//! the two highest peaks remembered, so the meter takes the same room after
+//! days of audio as after a minute.
+//!
+//! The meter is a [`SampleObserver`], so it sees every sample a
+//! [`SampleCapture`](crate::capture::SampleCapture) plays, independent of how
+//! often anyone reads it:
+//!
+//! ```
+//! use gruvberry::capture::SampleObserver;
+//! use gruvberry::dynamics::DynamicsMeter;
+//!
+//! let sine: Vec<f32> = (0..44100 * 4).map(|i| (i as f32 * 0.05).sin()).collect();
+//! let mut meter = DynamicsMeter::new();
+//! meter.on_samples(&sine, 1, 44100);
+//! assert!((meter.crest_db().unwrap() - 3.01).abs() < 0.05);
+//! assert_eq!(meter.dr().map(f32::round), Some(0.0));
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use crate::capture::SampleObserver;
+
+/// Seconds of audio the live crest factor covers.
+pub const WINDOW_SECS: f32 = 3.0;
+/// Length of the blocks the whole-track figure is measured in.
+pub const BLOCK_SECS: f32 = 3.0;
+
+// The live window moves in steps of this, so it never has to keep samples
+const STEP_SECS: f32 = 0.1;
+// Share of the loudest blocks whose RMS the DR figure uses
+const LOUDEST_BLOCKS: f32 = 0.2;
+// Mean squares below this (about -80 dBFS) are silence, which has no crest
+const SILENCE: f64 = 1e-8;
+// Block levels are counted in steps of this many dB from the floor up to
+// 20 dB over full scale; quieter blocks count at the floor
+const LEVEL_STEP_DB: f64 = 0.1;
+const LEVEL_FLOOR_DB: f64 = -100.0;
+const LEVEL_STEPS: usize = 1200;
+
+/// Crest factor of `samples` in dB, or None for silence.
+pub fn crest_factor_db(samples: &[f32]) -> Option<f32> {
+    let mut stats = Stats::default();
+    stats.add(samples);
+    stats.crest_db()
+}
+
+/// Meter shared between the audio thread and its readers.
+pub type SharedDynamics = Arc<Mutex<DynamicsMeter>>;
+
+// Peak and energy of a stretch of samples
+#[derive(Clone, Copy, Default)]
+struct Stats {
+    peak: f32,
+    sum_squares: f64,
+    count: usize,
+}
+
+impl Stats {
+    fn add(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            self.peak = self.peak.max(sample.abs());
+            self.sum_squares += sample as f64 * sample as f64;
+        }
+        self.count += samples.len();
+    }
+
+    fn merge(&mut self, other: &Stats) {
+        self.peak = self.peak.max(other.peak);
+        self.sum_squares += other.sum_squares;
+        self.count += other.count;
+    }
+
+    fn mean_square(&self) -> f64 {
+        self.sum_squares / self.count.max(1) as f64
+    }
+
+    fn crest_db(&self) -> Option<f32> {
+        let mean_square = self.mean_square();
+        if self.count == 0 || mean_square < SILENCE {
+            return None;
+        }
+        Some((20.0 * (self.peak as f64 / mean_square.sqrt()).log10()) as f32)
+    }
+}
+
+/// Live crest factor and whole-track dynamic range of the samples it observes.
+pub struct DynamicsMeter {
+    // The step being filled and the completed ones inside the live window
+    step: Stats,
+    steps: VecDeque<Stats>,
+    // The block being filled; for the completed ones the two highest peaks,
+    // and by level step how many there were and their summed mean squares
+    block: Stats,
+    blocks: u64,
+    peaks: [f32; 2],
+    levels: Vec<(u64, f64)>,
+}
+
+impl DynamicsMeter {
+    pub fn new() -> Self {
+        DynamicsMeter {
+            step: Stats::default(),
+            steps: VecDeque::new(),
+            block: Stats::default(),
+            blocks: 0,
+            peaks: [0.0; 2],
+            levels: Vec::new(),
+        }
+    }
+
+    /// A new meter behind a [`SharedDynamics`] handle.
+    pub fn shared() -> SharedDynamics {
+        Arc::new(Mutex::new(DynamicsMeter::new()))
+    }
+
+    /// Crest factor over the last [`WINDOW_SECS`] of audio, None while silent.
+    pub fn crest_db(&self) -> Option<f32> {
+        let mut window = self.step;
+        for step in &self.steps {
+            window.merge(step);
+        }
+        window.crest_db()
+    }
+
+    /// DR-style dynamic range of everything observed so far, None before the
+    /// first block is complete or when all of it was silent.
+    pub fn dr(&self) -> Option<f32> {
+        if self.blocks == 0 {
+            return None;
+        }
+        // Second highest peak, so one stray sample doesn't decide
+        let peak = if self.blocks > 1 { self.peaks[1] } else { self.peaks[0] };
+
+        // The loudest blocks, from the top step down; a step only partly
+        // needed gives its mean
+        let loudest = ((self.blocks as f32 * LOUDEST_BLOCKS).round() as u64).max(1);
+        let (mut taken, mut sum) = (0, 0.0);
+        for &(count, sum_squares) in self.levels.iter().rev().filter(|&&(count, _)| count > 0) {
+            let take = count.min(loudest - taken);
+            sum += sum_squares * take as f64 / count as f64;
+            taken += take;
+            if taken == loudest {
+                break;
+            }
+        }
+        let mean_square = sum / loudest as f64;
+        if mean_square < SILENCE {
+            return None;
+        }
+        Some((20.0 * (peak as f64 / mean_square.sqrt()).log10()) as f32)
+    }
+
+    // Count the block just completed
+    fn add_block(&mut self) {
+        let block = std::mem::take(&mut self.block);
+        let (peak, mean_square) = (block.peak, 2.0 * block.mean_square());
+        self.blocks += 1;
+        if peak > self.peaks[0] {
+            self.peaks = [peak, self.peaks[0]];
+        } else if peak > self.peaks[1] {
+            self.peaks[1] = peak;
+        }
+        if self.levels.is_empty() {
+            self.levels = vec![(0, 0.0); LEVEL_STEPS];
+        }
+        let db = 10.0 * mean_square.max(f64::MIN_POSITIVE).log10();
+        let step = (((db - LEVEL_FLOOR_DB) / LEVEL_STEP_DB).max(0.0) as usize).min(LEVEL_STEPS - 1);
+        self.levels[step].0 += 1;
+        self.levels[step].1 += mean_square;
+    }
+
+    /// Forget everything, e.g. for a new track.
+    pub fn reset(&mut self) {
+        *self = DynamicsMeter::new();
+    }
+}
+
+impl Default for DynamicsMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SampleObserver for DynamicsMeter {
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32) {
+        // Lengths in interleaved samples at this batch's format
+        let per_sec = sample_rate.max(1) as f32 * channels.max(1) as f32;
+        let step_len = ((STEP_SECS * per_sec) as usize).max(1);
+        let block_len = ((BLOCK_SECS * per_sec) as usize).max(1);
+        let window_steps = (WINDOW_SECS / STEP_SECS).round() as usize;
+
+        let mut rest = samples;
+        while !rest.is_empty() {
+            let take = (step_len - self.step.count.min(step_len))
+                .min(block_len - self.block.count.min(block_len))
+                .clamp(1, rest.len());
+            let (chunk, remaining) = rest.split_at(take);
+            self.step.add(chunk);
+            self.block.add(chunk);
+            rest = remaining;
+
+            if self.step.count >= step_len {
+                self.steps.push_back(std::mem::take(&mut self.step));
+                // The step being filled counts toward the window too
+                while self.steps.len() >= window_steps {
+                    self.steps.pop_front();
+                }
+            }
+            if self.block.count >= block_len {
+                self.add_block();
+            }
+        }
+    }
+}
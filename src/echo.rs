@@ -0,0 +1,392 @@
+//! A feedback echo on the playback, for playing along.
+//!
+//! [`Echo`] is a `Source` adapter around a single [`DelayLine`]: what it
+//! plays is the input plus the line's output at `mix`, and the line is fed
+//! the input plus its own output at `feedback`. An impulse therefore comes
+//! back after the delay at `mix`, then at `mix × feedback`, `mix ×
+//! feedback²`... Settings are shared ([`SharedEcho`]) and taken up while it
+//! plays, a few times a millisecond.
+//!
+//! Turning it on or off never clicks: the echo's level ramps over
+//! [`RAMP_SECS`] rather than jumping, and only once it is silent does the
+//! line stop (and empty, so turning it back on doesn't bring back an old
+//! tail). A new delay waits for the same ramp down and comes back in.
+//!
+//! The analysis can look at either side of it. The tap before the echo and
+//! the one after each wrap their observer in a [`Tapped`], which passes the
+//! samples on only while the settings pick its [`Tap`]:
+//!
+//! ```
+//! use gruvberry::capture::SampleCapture;
+//! use gruvberry::echo::{Echo, EchoSettings, Tap, Tapped};
+//! use rodio::{Source, source::SineWave};
+//! use std::time::Duration;
+//!
+//! let settings = EchoSettings { enabled: true, tap: Tap::Dry, ..EchoSettings::default() }.shared();
+//! let ring = gruvberry::capture::SampleRing::shared(4096);
+//! let tone = SineWave::new(440.0).take_duration(Duration::from_millis(50));
+//! // Before the echo, then the echo, then after it
+//! let dry = SampleCapture::new(tone, Tapped::new(Tap::Dry, settings.clone(), ring.clone()));
+//! let wet = SampleCapture::new(Echo::new(dry, settings.clone()), Tapped::new(Tap::Wet, settings, ring.clone()));
+//! assert_eq!(wet.count(), 2400);
+//! // The ring saw the 50 ms once, from the dry side
+//! assert!((ring.lock().unwrap().time() - 0.05).abs() < 1e-6);
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use rodio::Source;
+use rodio::source::SeekError;
+use crate::capture::SampleObserver;
+use crate::format::Locale;
+
+/// Shortest and longest delay, in milliseconds.
+pub const MIN_DELAY_MS: f32 = 20.0;
+pub const MAX_DELAY_MS: f32 = 2000.0;
+
+/// Most feedback: at 1.0 the echoes would never die away.
+pub const MAX_FEEDBACK: f32 = 0.9;
+
+/// How long the echo takes to fade in or out.
+pub const RAMP_SECS: f32 = 0.01;
+
+// Samples between looks at the shared settings
+const REFRESH_SAMPLES: usize = 256;
+
+/// Which side of the echo the analysis looks at.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tap {
+    /// The audio as it comes in, before the echo.
+    Dry,
+    /// What is heard, echo included.
+    #[default]
+    Wet,
+}
+
+impl Tap {
+    pub fn name(self) -> &'static str {
+        match self {
+            Tap::Dry => "dry",
+            Tap::Wet => "wet",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Tap> {
+        match value {
+            "dry" => Some(Tap::Dry),
+            "wet" => Some(Tap::Wet),
+            _ => None,
+        }
+    }
+
+    pub fn other(self) -> Tap {
+        match self {
+            Tap::Dry => Tap::Wet,
+            Tap::Wet => Tap::Dry,
+        }
+    }
+}
+
+/// The echo as set: on or off, its delay, feedback and mix, and where the
+/// analysis taps the playback.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EchoSettings {
+    pub enabled: bool,
+    /// [`MIN_DELAY_MS`] to [`MAX_DELAY_MS`].
+    pub delay_ms: f32,
+    /// How much of each echo comes back again, 0.0 to [`MAX_FEEDBACK`].
+    pub feedback: f32,
+    /// Level of the echo against the input, 0.0 to 1.0.
+    pub mix: f32,
+    pub tap: Tap,
+}
+
+impl Default for EchoSettings {
+    fn default() -> Self {
+        EchoSettings {
+            enabled: false,
+            delay_ms: 350.0,
+            feedback: 0.4,
+            mix: 0.3,
+            tap: Tap::default(),
+        }
+    }
+}
+
+impl EchoSettings {
+    /// These settings behind a [`SharedEcho`] handle.
+    pub fn shared(self) -> SharedEcho {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Move `field` by `steps`, onto a whole step and within its range; the
+    /// switches flip on any step.
+    pub fn adjust(&mut self, field: Field, steps: i32) {
+        let stepped = |value: f32, step: f32, min: f32, max: f32| {
+            // Divided rather than multiplied out, so 0.05 steps land on 0.3 and not 0.30000001
+            let per_unit = 1.0 / step;
+            (((value * per_unit).round() + steps as f32) / per_unit).clamp(min, max)
+        };
+        match field {
+            Field::Enabled => self.enabled = !self.enabled,
+            Field::Delay => self.delay_ms = stepped(self.delay_ms, DELAY_STEP_MS, MIN_DELAY_MS, MAX_DELAY_MS),
+            Field::Feedback => self.feedback = stepped(self.feedback, LEVEL_STEP, 0.0, MAX_FEEDBACK),
+            Field::Mix => self.mix = stepped(self.mix, LEVEL_STEP, 0.0, 1.0),
+            Field::Tap => self.tap = self.tap.other(),
+        }
+    }
+
+    /// `field`'s value as shown, with `locale`'s numbers.
+    pub fn value_text(&self, field: Field, locale: Locale) -> String {
+        match field {
+            Field::Enabled => (if self.enabled { "on" } else { "off" }).to_string(),
+            Field::Delay => locale.ms(self.delay_ms, 0),
+            Field::Feedback => locale.percent(self.feedback),
+            Field::Mix => locale.percent(self.mix),
+            Field::Tap => self.tap.name().to_string(),
+        }
+    }
+
+    /// The same settings with every number within its range.
+    pub fn clamped(self) -> EchoSettings {
+        EchoSettings {
+            delay_ms: self.delay_ms.clamp(MIN_DELAY_MS, MAX_DELAY_MS),
+            feedback: self.feedback.clamp(0.0, MAX_FEEDBACK),
+            mix: self.mix.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+}
+
+/// Settings changed by the user and read by the audio thread.
+pub type SharedEcho = Arc<Mutex<EchoSettings>>;
+
+/// Steps the settings move by when adjusted: 10 ms of delay, 5% of
+/// feedback or mix.
+pub const DELAY_STEP_MS: f32 = 10.0;
+pub const LEVEL_STEP: f32 = 0.05;
+
+/// One line of the echo's settings as they are adjusted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Enabled,
+    Delay,
+    Feedback,
+    Mix,
+    Tap,
+}
+
+impl Field {
+    pub const ALL: [Field; 5] = [Field::Enabled, Field::Delay, Field::Feedback, Field::Mix, Field::Tap];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Enabled => "Echo",
+            Field::Delay => "Delay",
+            Field::Feedback => "Feedback",
+            Field::Mix => "Mix",
+            Field::Tap => "Analyze",
+        }
+    }
+
+    /// The field `delta` lines away, stopping at the first and last.
+    pub fn moved(self, delta: isize) -> Field {
+        let index = Field::ALL.iter().position(|&field| field == self).unwrap_or(0);
+        Field::ALL[index.saturating_add_signed(delta).min(Field::ALL.len() - 1)]
+    }
+}
+
+/// A delay of a fixed number of samples whose output is fed back into it.
+pub struct DelayLine {
+    buffer: Vec<f32>,
+    position: usize,
+    feedback: f32,
+}
+
+impl DelayLine {
+    /// A silent line of `len` samples (interleaved, so frames × channels);
+    /// an empty one passes nothing.
+    pub fn new(len: usize, feedback: f32) -> Self {
+        DelayLine { buffer: vec![0.0; len], position: 0, feedback }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback;
+    }
+
+    /// The sample that went in `len` samples ago (with what came back of
+    /// it), while `input` goes in along with `feedback` of that.
+    pub fn process(&mut self, input: f32) -> f32 {
+        let Some(slot) = self.buffer.get_mut(self.position) else {
+            return 0.0;
+        };
+        let delayed = *slot;
+        *slot = input + delayed * self.feedback;
+        self.position = (self.position + 1) % self.buffer.len();
+        delayed
+    }
+
+    /// Silence everything in the line.
+    pub fn clear(&mut self) {
+        self.buffer.fill(0.0);
+    }
+}
+
+/// Plays `input` with the echo of [`SharedEcho`] settings mixed in.
+pub struct Echo<I> {
+    input: I,
+    shared: SharedEcho,
+    settings: EchoSettings,
+    line: DelayLine,
+    // Length the line should have at the input's rate and channels
+    wanted_len: usize,
+    // The echo's level now, ramping towards the mix or to nothing
+    level: f32,
+    // How far the level moves per sample
+    step: f32,
+    // Whether the line has been emptied since the echo went silent
+    cleared: bool,
+    until_refresh: usize,
+}
+
+impl<I: Source> Echo<I> {
+    pub fn new(input: I, shared: SharedEcho) -> Self {
+        let settings = shared.lock().map(|settings| settings.clamped()).unwrap_or_default();
+        let mut echo = Echo {
+            input,
+            shared,
+            settings,
+            line: DelayLine::new(0, settings.feedback),
+            wanted_len: 0,
+            level: 0.0,
+            step: 0.0,
+            cleared: true,
+            until_refresh: 0,
+        };
+        echo.refresh();
+        echo
+    }
+
+    // The level the echo is heading for: the mix, unless it is off or the
+    // line is about to change length
+    fn target(&self) -> f32 {
+        if self.settings.enabled && self.line.len() == self.wanted_len { self.settings.mix } else { 0.0 }
+    }
+
+    fn refresh(&mut self) {
+        // The audio thread doesn't wait: settings being changed are taken up next time
+        if let Ok(settings) = self.shared.try_lock() {
+            self.settings = settings.clamped();
+        }
+        self.line.set_feedback(self.settings.feedback);
+        let (rate, channels) = (self.input.sample_rate() as f32, self.input.channels().max(1) as usize);
+        self.wanted_len = ((self.settings.delay_ms / 1000.0 * rate).round() as usize).max(1) * channels;
+        self.step = 1.0 / (RAMP_SECS * rate * channels as f32).max(1.0);
+        if self.level == 0.0 {
+            if self.line.len() != self.wanted_len {
+                self.line = DelayLine::new(self.wanted_len, self.settings.feedback);
+                self.cleared = true;
+            } else if !self.settings.enabled && !self.cleared {
+                self.line.clear();
+                self.cleared = true;
+            }
+        }
+    }
+}
+
+impl<I: Source> Iterator for Echo<I> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?;
+        if self.until_refresh == 0 {
+            self.refresh();
+            self.until_refresh = REFRESH_SAMPLES;
+        }
+        self.until_refresh -= 1;
+
+        let target = self.target();
+        if self.level == 0.0 && target == 0.0 && self.cleared {
+            return Some(sample);
+        }
+        self.cleared = false;
+        self.level = if self.level < target {
+            (self.level + self.step).min(target)
+        } else {
+            (self.level - self.step).max(target)
+        };
+        Some(sample + self.line.process(sample) * self.level)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I: Source> Source for Echo<I> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    // The echoes of what played before the seek ring on, as they would
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)
+    }
+}
+
+/// An observer that only sees the samples while the analysis taps `at`.
+pub struct Tapped<O> {
+    at: Tap,
+    shared: SharedEcho,
+    observer: O,
+}
+
+impl<O: SampleObserver> Tapped<O> {
+    pub fn new(at: Tap, shared: SharedEcho, observer: O) -> Self {
+        Tapped { at, shared, observer }
+    }
+
+    fn selected(&self) -> bool {
+        self.shared.lock().map(|settings| settings.tap == self.at).unwrap_or(self.at == Tap::default())
+    }
+}
+
+impl<O: SampleObserver> SampleObserver for Tapped<O> {
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32) {
+        if self.selected() {
+            self.observer.on_samples(samples, channels, sample_rate);
+        }
+    }
+
+    fn on_seek(&mut self, position: Duration) {
+        if self.selected() {
+            self.observer.on_seek(position);
+        }
+    }
+
+    fn on_non_finite(&mut self, count: usize) {
+        if self.selected() {
+            self.observer.on_non_finite(count);
+        }
+    }
+}
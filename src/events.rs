@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, mpsc};
+
+// Fans player events out to everyone interested (the visualizer, the
+// --verbose session log, ...), each subscriber getting its own copy, so
+// nothing has to poll the player to notice a track change
+pub struct EventBus<E> {
+    subscribers: Vec<mpsc::Sender<E>>,
+    recorders: Vec<Recorder<E>>,
+}
+
+type Recorder<E> = Box<dyn FnMut(&E) + Send>;
+
+// What a recorder keeps: the latest records, oldest first
+pub type Records<T> = Arc<Mutex<VecDeque<T>>>;
+
+impl<E: Clone> EventBus<E> {
+    pub fn new() -> Self {
+        EventBus {
+            subscribers: Vec::new(),
+            recorders: Vec::new(),
+        }
+    }
+
+    // Receive every event published from now on
+    pub fn subscribe(&mut self) -> mpsc::Receiver<E> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    // Keep the last `capacity` events as `record` makes them out, for reading
+    // only at the end: a channel nobody drains would hold every event (and
+    // whatever they keep alive) for as long as the session runs
+    pub fn record<T: Send + 'static>(&mut self, capacity: usize, record: fn(&E) -> T) -> Records<T>
+    where
+        E: 'static,
+    {
+        let records = Arc::new(Mutex::new(VecDeque::with_capacity(capacity)));
+        let kept = records.clone();
+        self.recorders.push(Box::new(move |event| {
+            if let Ok(mut kept) = kept.lock() {
+                if kept.len() >= capacity.max(1) {
+                    kept.pop_front();
+                }
+                kept.push_back(record(event));
+            }
+        }));
+        records
+    }
+
+    // Deliver to each subscriber; ones that have gone away are forgotten
+    pub fn publish(&mut self, event: E) {
+        for recorder in &mut self.recorders {
+            recorder(&event);
+        }
+        self.subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
+impl<E: Clone> Default for EventBus<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,103 @@
+//! What the display does once playback is over.
+//!
+//! The analysis keeps running on silence when the last track ends, so the
+//! bars fall the way they always do, and on top of that they are faded out
+//! over [`FADE`] by the clock rather than by frames: they reach zero at the
+//! same moment at any frame rate or smoothing. Then the display either
+//! holds a finished state until the user quits or plays again, or, for
+//! scripted use, exits on its own once the output device has played out its
+//! buffer ([`AUTO_EXIT_AFTER`]).
+//!
+//! Playback can end while something that takes the keys is open over the
+//! display (the add-to-queue prompt, the playlist panel). The finished state
+//! then comes in behind it, fade included, and stays there: the display
+//! doesn't close under the user. While such a modal is [held](Finish::hold)
+//! open the auto-exit wait stands still, and it goes on from where it was
+//! once the modal closes.
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//! use gruvberry::finish::{Finish, FADE};
+//!
+//! let at = Instant::now();
+//! let finish = Finish::new(at, false);
+//! assert_eq!(finish.fade(at), 1.0);
+//! assert_eq!(finish.fade(at + FADE / 2), 0.5);
+//! assert!(finish.is_faded(at + FADE));
+//! // Interactive: still there a minute later
+//! assert!(!finish.should_exit(at + Duration::from_secs(60)));
+//! ```
+
+use std::time::{Duration, Instant};
+
+/// How long the bars take to fade out after the end.
+pub const FADE: Duration = Duration::from_secs(1);
+/// When an auto-exiting display closes, counted from the end: after the
+/// fade, with time for the device to play out what it has buffered.
+pub const AUTO_EXIT_AFTER: Duration = Duration::from_millis(1500);
+
+/// The finished state, from the moment playback ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Finish {
+    at: Instant,
+    auto_exit: bool,
+    // Time spent with a modal open since the end, not counting the current
+    // stretch, and when that began if one is open now
+    held: Duration,
+    held_since: Option<Instant>,
+}
+
+impl Finish {
+    /// Playback ended at `at`; with `auto_exit` the display closes by
+    /// itself, otherwise it waits for the user.
+    pub fn new(at: Instant, auto_exit: bool) -> Self {
+        Finish {
+            at,
+            auto_exit,
+            held: Duration::ZERO,
+            held_since: None,
+        }
+    }
+
+    pub fn at(&self) -> Instant {
+        self.at
+    }
+
+    /// Factor for the bars at `now`: 1.0 at the end, falling in a straight
+    /// line to 0.0 at [`FADE`] after it.
+    pub fn fade(&self, now: Instant) -> f32 {
+        let since = now.saturating_duration_since(self.at).as_secs_f32();
+        (1.0 - since / FADE.as_secs_f32()).clamp(0.0, 1.0)
+    }
+
+    /// Whether the fade is over, and the finished state is what shows.
+    pub fn is_faded(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.at) >= FADE
+    }
+
+    /// Whether a modal is open over the display at `now`; the auto-exit
+    /// wait only counts time without one. Called every frame, so only a
+    /// change does anything.
+    pub fn hold(&mut self, modal: bool, now: Instant) {
+        match (modal, self.held_since) {
+            (true, None) => self.held_since = Some(now.max(self.at)),
+            (false, Some(since)) => {
+                self.held += now.saturating_duration_since(since);
+                self.held_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.held_since.is_some()
+    }
+
+    /// Whether the display should close by itself at `now`; never when it
+    /// waits for the user, or while a modal is open.
+    pub fn should_exit(&self, now: Instant) -> bool {
+        self.auto_exit
+            && !self.is_held()
+            && now.saturating_duration_since(self.at).saturating_sub(self.held) >= AUTO_EXIT_AFTER
+    }
+}
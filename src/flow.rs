@@ -0,0 +1,150 @@
+//! Buffer fill and underruns.
+//!
+//! The output pulls samples through a [`SampleCapture`](crate::capture::SampleCapture)
+//! tap a buffer at a time, a little ahead of what is being heard.
+//! [`FlowMeter`] compares the audio pulled so far with the time that has
+//! passed: the difference is how much is buffered ahead of the speakers.
+//! When the source can't keep up (a slow disk, a network stream), that runs
+//! out and the device has nothing to play: an underrun. The meter counts
+//! them and keeps the time they took (past [`UNDERRUN_MARGIN`], which is
+//! taken as jitter), so a position that leaves that time out doesn't move on
+//! while nothing is heard, and never steps back.
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//! use gruvberry::flow::FlowMeter;
+//!
+//! let start = Instant::now();
+//! let at = |ms| start + Duration::from_millis(ms);
+//! let mut meter = FlowMeter::new();
+//! // The device takes 100 ms up front, then the source stalls for 300 ms
+//! meter.pulled(0.1, at(0));
+//! assert_eq!(meter.fill(at(40)), Duration::from_millis(60));
+//! assert_eq!(meter.underruns(at(400)), 1);
+//! assert_eq!(meter.lost(at(400)), Duration::from_millis(280));
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::capture::SampleObserver;
+
+/// How far the audio may fall behind the clock before it counts as an
+/// underrun, for the jitter of when the device asks for more.
+pub const UNDERRUN_MARGIN: Duration = Duration::from_millis(20);
+
+/// Meter shared between the audio thread and its readers.
+pub type SharedFlow = Arc<Mutex<FlowMeter>>;
+
+/// Buffer fill and underruns of one stream, from when its samples are pulled.
+pub struct FlowMeter {
+    // When the first pulled sample was due to be heard, moved on by pauses
+    // and underruns (None before the first samples)
+    origin: Option<Instant>,
+    // Seconds of audio pulled so far
+    pulled: f64,
+    paused_at: Option<Instant>,
+    underruns: u32,
+    // Time lost to underruns that have ended
+    lost: Duration,
+    // When audio was last pulled, for the watchdog
+    last_pulled: Option<Instant>,
+}
+
+impl FlowMeter {
+    pub fn new() -> Self {
+        FlowMeter {
+            origin: None,
+            pulled: 0.0,
+            paused_at: None,
+            underruns: 0,
+            lost: Duration::ZERO,
+            last_pulled: None,
+        }
+    }
+
+    /// A new meter behind a [`SharedFlow`] handle.
+    pub fn shared() -> SharedFlow {
+        Arc::new(Mutex::new(FlowMeter::new()))
+    }
+
+    /// Record `secs` of audio pulled by the output at `now`.
+    pub fn pulled(&mut self, secs: f64, now: Instant) {
+        match self.origin {
+            None => self.origin = Some(now),
+            Some(origin) => {
+                // The device went without for as long as the audio was late
+                let late = self.late(now);
+                if late > UNDERRUN_MARGIN {
+                    self.underruns += 1;
+                    self.lost += late - UNDERRUN_MARGIN;
+                    self.origin = Some(origin + late);
+                }
+            }
+        }
+        self.pulled += secs;
+        self.last_pulled = Some(now);
+    }
+
+    /// When audio was last pulled; None before the first.
+    pub fn last_pulled(&self) -> Option<Instant> {
+        self.last_pulled
+    }
+
+    /// Audio buffered ahead of what is heard at `now`; zero while starved.
+    pub fn fill(&self, now: Instant) -> Duration {
+        match self.origin {
+            Some(_) => Duration::from_secs_f64(self.pulled).saturating_sub(self.clock(now)),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Underruns up to `now`, one still going on included.
+    pub fn underruns(&self, now: Instant) -> u32 {
+        self.underruns + u32::from(self.late(now) > UNDERRUN_MARGIN)
+    }
+
+    /// Time nothing could be played up to `now`, less the margin of each
+    /// underrun.
+    pub fn lost(&self, now: Instant) -> Duration {
+        self.lost + self.late(now).saturating_sub(UNDERRUN_MARGIN)
+    }
+
+    /// Stop the clock: a paused output pulls nothing, and that is no underrun.
+    pub fn pause(&mut self, now: Instant) {
+        self.paused_at.get_or_insert(now);
+    }
+
+    /// Run the clock on from `now`.
+    pub fn resume(&mut self, now: Instant) {
+        if let (Some(paused_at), Some(origin)) = (self.paused_at.take(), self.origin) {
+            self.origin = Some(origin + now.saturating_duration_since(paused_at));
+        }
+    }
+
+    // Time the audio pulled so far would have lasted, at `now`
+    fn clock(&self, now: Instant) -> Duration {
+        let now = self.paused_at.unwrap_or(now);
+        self.origin.map_or(Duration::ZERO, |origin| now.saturating_duration_since(origin))
+    }
+
+    // How far the pulled audio has fallen behind the clock
+    fn late(&self, now: Instant) -> Duration {
+        match self.origin {
+            Some(_) => self.clock(now).saturating_sub(Duration::from_secs_f64(self.pulled)),
+            None => Duration::ZERO,
+        }
+    }
+}
+
+impl Default for FlowMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SampleObserver for FlowMeter {
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32) {
+        let frames = samples.len() / channels.max(1) as usize;
+        self.pulled(frames as f64 / sample_rate.max(1) as f64, Instant::now());
+    }
+}
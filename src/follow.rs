@@ -0,0 +1,190 @@
+// --follow: draw what a gruvberry playing elsewhere streams over its control
+// socket instead of playing and analyzing anything here, for a second
+// monitor. The frames come as they were drawn there, with the track and
+// whether it is paused. Space, the arrows and 'n'/'p' are sent back as
+// requests, so the keys work on either side; 'q', Esc or Ctrl+C stop
+// following and leave the primary playing.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Layout},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Terminal,
+};
+use gruvberry::control::{PlayState, Request, Update};
+use gruvberry::display;
+use gruvberry::format;
+use gruvberry::history::AnalysisFrame;
+use gruvberry::input::{self, Steps};
+use gruvberry::orientation::Orientation;
+use gruvberry::spectrum::SpectrumWidget;
+use gruvberry::theme::Theme;
+
+use crate::terminal;
+
+// How long the primary has to take the follower on
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct FollowOptions {
+    pub orientation: Orientation,
+    pub frame_interval: Duration,
+    pub alternate_screen: bool,
+    pub theme: Theme,
+}
+
+// Why following stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ended {
+    // 'q', Esc, Ctrl+C or a signal
+    Quit,
+    // The primary closed the connection, most likely on its way out
+    Gone,
+}
+
+// Connect to the primary at `path` and ask to follow it. What goes wrong is
+// reported before the terminal is taken over.
+pub fn connect(path: &Path) -> io::Result<(UnixStream, BufReader<UnixStream>)> {
+    let mut stream = UnixStream::connect(path)?;
+    stream.write_all(format!("{}\n", Request::Follow.to_line()).as_bytes())?;
+    stream.set_read_timeout(Some(ANSWER_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut answer = String::new();
+    reader.read_line(&mut answer)?;
+    stream.set_read_timeout(None)?;
+    match answer.trim_end().split_once(' ').unwrap_or((answer.trim_end(), "")) {
+        ("ok", _) => Ok((stream, reader)),
+        ("error", reason) => Err(io::Error::other(reason.to_string())),
+        _ => Err(io::Error::other("no answer from a gruvberry")),
+    }
+}
+
+// Draw what the primary sends until it goes or 'q'
+pub fn run(
+    mut stream: UnixStream,
+    reader: BufReader<UnixStream>,
+    options: FollowOptions,
+) -> Result<Ended, Box<dyn std::error::Error>> {
+    // Lines are read on a thread of their own, so a frame never waits for
+    // the socket; the channel closing says the primary went
+    let (sender, lines) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in reader.lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let signals = terminal::Signals::install();
+    let _guard = terminal::TerminalGuard::enter(terminal::Setup {
+        alternate_screen: options.alternate_screen,
+        ..Default::default()
+    })?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    let theme = &options.theme;
+
+    let mut track: Option<(String, f32)> = None;
+    let mut state = PlayState::Playing;
+    let mut frame: Option<AnalysisFrame> = None;
+    // An answer the primary refused a request with, until the deadline
+    let mut refused: Option<(String, Instant)> = None;
+
+    let mut drawn_at = Instant::now();
+    loop {
+        if signals.quit() {
+            return Ok(Ended::Quit);
+        }
+
+        // Keys are read while waiting for the next frame
+        let mut seek_steps = Steps::default();
+        let mut requests = Vec::new();
+        for event in input::drain(&mut input::Terminal, options.frame_interval.saturating_sub(drawn_at.elapsed()))? {
+            let Event::Key(key) = event else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(Ended::Quit),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(Ended::Quit),
+                KeyCode::Char(' ') => requests.push(Request::Pause),
+                KeyCode::Char('n') => requests.push(Request::Next),
+                KeyCode::Char('p') => requests.push(Request::Previous),
+                KeyCode::Left | KeyCode::Right => seek_steps.add(key, crate::SEEK_STEP_SECS, crate::SEEK_BIG_STEP_SECS),
+                _ => {}
+            }
+        }
+        if seek_steps.total() != 0.0 {
+            requests.push(Request::Seek(seek_steps.total()));
+        }
+        for request in requests {
+            if stream.write_all(format!("{}\n", request.to_line()).as_bytes()).is_err() {
+                return Ok(Ended::Gone);
+            }
+        }
+
+        loop {
+            let line = match lines.try_recv() {
+                Ok(line) => line,
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => return Ok(Ended::Gone),
+            };
+            if let Some(reason) = line.strip_prefix("error") {
+                refused = Some((reason.trim().to_string(), Instant::now() + crate::STATUS_DURATION));
+                continue;
+            }
+            // Answers to the requests above, and updates of kinds this
+            // side doesn't know, change nothing
+            match Update::parse(&line) {
+                Ok(Update::Track { name, duration }) => {
+                    track = Some((display::clean(&name), duration));
+                    frame = None;
+                }
+                Ok(Update::State(playing)) => state = playing,
+                Ok(Update::Frame(latest)) => frame = Some(latest),
+                Err(_) => {}
+            }
+        }
+        if refused.as_ref().is_some_and(|(_, until)| Instant::now() >= *until) {
+            refused = None;
+        }
+
+        terminal.draw(|f| {
+            let [heading, bars, footer] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)]).areas(f.area());
+            let (name, duration) = track.clone().unwrap_or_default();
+            let time = frame.as_ref().map_or(0.0, |frame| frame.time).min(duration);
+            let mut title = vec![
+                Span::styled("Following ", theme.dim),
+                Span::styled(name, theme.label),
+                Span::raw(format!("  {} / {}", format::time(time), format::time(duration))),
+            ];
+            match state {
+                PlayState::Playing => {}
+                PlayState::Paused => title.push(Span::styled(" (paused)", theme.warn)),
+                PlayState::Finished => title.push(Span::styled(" (finished)", theme.dim)),
+            }
+            f.render_widget(Paragraph::new(Line::from(title)), heading);
+            if let Some(frame) = &frame {
+                f.render_widget(SpectrumWidget::from_frame(frame, theme).orientation(options.orientation), bars);
+            }
+            let help = match &refused {
+                Some((reason, _)) => Line::styled(display::fit(reason, footer.width as usize), theme.bad),
+                None => Line::styled("Space pause | ←/→ seek | n/p next/previous | q stop following", theme.dim),
+            };
+            f.render_widget(Paragraph::new(help), footer);
+        })?;
+        drawn_at = Instant::now();
+    }
+}
@@ -0,0 +1,156 @@
+//! How numbers read on screen.
+//!
+//! Every frequency, time and level the display shows goes through here, so
+//! the rules are in one place: frequencies in Hz below 1 kHz and in kHz from
+//! there (a decimal below 10 kHz), playback times as `m:ss` (`h:mm:ss` from
+//! an hour), short durations in seconds, how far back the replay scrub is to
+//! the tenth of a second, levels in dB with their sign, and the sample rates,
+//! milliseconds, percentages and sizes the readouts show.
+//! Values are rounded to what is shown before the unit is picked, so 999.7 Hz
+//! reads as "1.0 kHz" rather than "1000 Hz".
+//!
+//! A [`Locale`] carries the decimal separator (the `decimal_separator`
+//! setting). Machine-readable output, like the statistics JSON, doesn't go
+//! through it.
+//!
+//! ```
+//! use gruvberry::format::{self, Locale};
+//!
+//! let locale = Locale::default();
+//! assert_eq!(locale.hz(440.0), "440 Hz");
+//! assert_eq!(locale.hz(3200.0), "3.2 kHz");
+//! assert_eq!(locale.db(-6.02, 1), "-6.0 dB");
+//! assert_eq!(format::time(83.41), "1:23");
+//! assert_eq!(Locale::new(',').duration(2.5), "2,5 s");
+//! ```
+
+/// Decimal separators the `decimal_separator` setting can pick.
+pub const SEPARATORS: [char; 2] = ['.', ','];
+
+/// Number conventions for what is shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Locale {
+    decimal: char,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale { decimal: '.' }
+    }
+}
+
+impl Locale {
+    pub fn new(decimal: char) -> Self {
+        Locale { decimal }
+    }
+
+    pub fn decimal_separator(&self) -> char {
+        self.decimal
+    }
+
+    /// `value` with `decimals` places.
+    pub fn number(&self, value: f32, decimals: usize) -> String {
+        self.localize(format!("{:.*}", decimals, value))
+    }
+
+    /// `value` with `decimals` places and always a sign.
+    pub fn signed(&self, value: f32, decimals: usize) -> String {
+        self.localize(format!("{:+.*}", decimals, value))
+    }
+
+    /// A level or gain, e.g. "-6.0 dB" or "+3 dB".
+    pub fn db(&self, value: f32, decimals: usize) -> String {
+        format!("{} dB", self.signed(value, decimals))
+    }
+
+    /// A frequency: "999 Hz", then "1.0 kHz" to "9.9 kHz", then "10 kHz" up.
+    pub fn hz(&self, freq: f32) -> String {
+        if !freq.is_finite() {
+            return "-".to_string();
+        }
+        if freq.round() < 1000.0 {
+            format!("{:.0} Hz", freq)
+        } else if (freq / 100.0).round() < 100.0 {
+            format!("{} kHz", self.number(freq / 1000.0, 1))
+        } else {
+            format!("{:.0} kHz", freq / 1000.0)
+        }
+    }
+
+    /// A sample rate as it is, e.g. "44100 Hz"; unlike [`hz`](Locale::hz)
+    /// never in kHz.
+    pub fn rate(&self, rate: u32) -> String {
+        format!("{} Hz", rate)
+    }
+
+    /// A short time in milliseconds, e.g. "12 ms".
+    pub fn ms(&self, ms: f32, decimals: usize) -> String {
+        format!("{} ms", self.number(ms, decimals))
+    }
+
+    /// A shift in whole milliseconds with its sign, e.g. "+40 ms" (the
+    /// latency offset).
+    pub fn signed_ms(&self, ms: f32) -> String {
+        format!("{} ms", self.signed(ms, 0))
+    }
+
+    /// A whole percentage of `fraction` (1.0 is 100%), e.g. "35%".
+    pub fn percent(&self, fraction: f32) -> String {
+        format!("{}%", self.number(fraction * 100.0, 0))
+    }
+
+    /// An angle in whole degrees, e.g. "210°".
+    pub fn degrees(&self, degrees: f32) -> String {
+        format!("{}°", self.number(degrees, 0))
+    }
+
+    /// A size in megabytes (a million bytes) to the tenth, e.g. "1.5 MB".
+    pub fn megabytes(&self, bytes: u64) -> String {
+        format!("{} MB", self.number((bytes as f64 / 1_000_000.0) as f32, 1))
+    }
+
+    /// A stretch of time: "59.9 s" under a minute, then as [`time`].
+    pub fn duration(&self, secs: f32) -> String {
+        if secs.is_finite() && (secs * 10.0).round() < 600.0 {
+            format!("{} s", self.number(secs.max(0.0), 1))
+        } else {
+            time(secs.round())
+        }
+    }
+
+    /// How far back a look at the past is, to the tenth of a second:
+    /// "-4.2s", then "-1:23.4" from a minute.
+    pub fn ago(&self, secs: f32) -> String {
+        if !secs.is_finite() {
+            return "--:--".to_string();
+        }
+        let tenths = (secs.max(0.0) * 10.0).round();
+        if tenths < 600.0 {
+            format!("-{}s", self.number(tenths / 10.0, 1))
+        } else {
+            format!("-{}{}{}", time(tenths / 10.0), self.decimal, tenths % 10.0)
+        }
+    }
+
+    fn localize(&self, text: String) -> String {
+        match self.decimal {
+            '.' => text,
+            decimal => text.replace('.', &decimal.to_string()),
+        }
+    }
+}
+
+/// A playback time as "m:ss", or "h:mm:ss" from an hour up, counting whole
+/// seconds only so it never runs ahead; "--:--" when unbounded.
+pub fn time(secs: f32) -> String {
+    if !secs.is_finite() {
+        return "--:--".to_string();
+    }
+    let total = secs.max(0.0) as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
@@ -0,0 +1,166 @@
+//! The gradient the bars are colored with: VIBGYOR unless a theme sweeps
+//! between two colors of its own ([`Gradient`]).
+//!
+//! With [`Anchor::Frequency`] (the default) a band's color comes from where
+//! it sits on a fixed 20 Hz–20 kHz log scale, in third-octave steps, so a
+//! region of the spectrum keeps its color however wide the terminal is or
+//! whatever range is shown. [`Anchor::Index`] spreads the whole gradient over
+//! the bands on screen instead, red at the first and violet at the last.
+//!
+//! ```
+//! use gruvberry::gradient::{self, Anchor};
+//!
+//! // The band holding 1 kHz is the same color at any width
+//! let band = |num_bands| gruvberry::analysis::band_of(1000.0, num_bands, 22050.0);
+//! assert_eq!(
+//!     gradient::band_rgb(band(60), 60, 22050.0, Anchor::Frequency),
+//!     gradient::band_rgb(band(150), 150, 22050.0, Anchor::Frequency),
+//! );
+//! ```
+
+use crate::analysis::FreqRange;
+
+/// Lowest and highest frequencies of the fixed scale (Hz).
+pub const LOW_HZ: f32 = 20.0;
+pub const HIGH_HZ: f32 = 20_000.0;
+
+/// What a band's place in the gradient follows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    /// The band's center frequency on the fixed scale.
+    #[default]
+    Frequency,
+    /// The band's position among the bands on screen.
+    Index,
+}
+
+impl Anchor {
+    pub const NAMES: [&str; 2] = ["frequency", "index"];
+
+    pub fn parse(value: &str) -> Option<Anchor> {
+        match value {
+            "frequency" => Some(Anchor::Frequency),
+            "index" => Some(Anchor::Index),
+            _ => None,
+        }
+    }
+}
+
+/// Color of `band` out of `num_bands` log-spaced bands up to `max_freq`.
+pub fn band_rgb(band: usize, num_bands: usize, max_freq: f32, anchor: Anchor) -> (u8, u8, u8) {
+    band_rgb_in(band, num_bands, FreqRange::full(max_freq), anchor)
+}
+
+/// Color of `band` out of `num_bands` log-spaced bands over `range`, e.g.
+/// zoomed in: anchored to frequency, the bands keep the colors they have in
+/// the whole range.
+pub fn band_rgb_in(band: usize, num_bands: usize, range: FreqRange, anchor: Anchor) -> (u8, u8, u8) {
+    Gradient::Vibgyor.band_rgb(band, num_bands, range, anchor)
+}
+
+/// One end of a [`Gradient::Sweep`]: a hue in degrees (0 red, 120 green,
+/// 240 blue) and a saturation from 0.0 (white) to 1.0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Endpoint {
+    pub hue: f32,
+    pub saturation: f32,
+}
+
+/// What the bars are colored with, from the lowest band to the highest.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Gradient {
+    /// Red, orange, yellow, green, cyan, blue, violet ([`vibgyor`]).
+    #[default]
+    Vibgyor,
+    /// Hue and saturation running straight from `low` to `high` at full
+    /// brightness; the hue goes the way the numbers do, so 0 to 280 passes
+    /// through green and 280 to 360 doesn't.
+    Sweep { low: Endpoint, high: Endpoint },
+}
+
+impl Gradient {
+    /// The gradient at `ratio` (0.0 to 1.0, clamped; NaN is 0.0).
+    pub fn rgb(&self, ratio: f32) -> (u8, u8, u8) {
+        match self {
+            Gradient::Vibgyor => vibgyor(ratio),
+            Gradient::Sweep { low, high } => {
+                let t = if ratio.is_nan() { 0.0 } else { ratio.clamp(0.0, 1.0) };
+                let hue = low.hue + (high.hue - low.hue) * t;
+                let saturation = low.saturation + (high.saturation - low.saturation) * t;
+                hsv(hue, saturation, 1.0)
+            }
+        }
+    }
+
+    /// Color of `band` out of `num_bands` log-spaced bands over `range`, as
+    /// [`band_rgb_in`] places it in this gradient.
+    pub fn band_rgb(&self, band: usize, num_bands: usize, range: FreqRange, anchor: Anchor) -> (u8, u8, u8) {
+        match anchor {
+            Anchor::Frequency => {
+                let (low, high) = range.band_edges(band, num_bands.max(1));
+                self.rgb(frequency_ratio((low * high).sqrt()))
+            }
+            Anchor::Index => self.rgb(band as f32 / num_bands.saturating_sub(1).max(1) as f32),
+        }
+    }
+}
+
+/// An HSV color as RGB: `hue` in degrees (any, taken round the circle),
+/// `saturation` and `value` from 0.0 to 1.0 (clamped).
+pub fn hsv(hue: f32, saturation: f32, value: f32) -> (u8, u8, u8) {
+    let hue = if hue.is_finite() { hue.rem_euclid(360.0) } else { 0.0 };
+    let (saturation, value) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |v: f32| ((v + value - chroma) * 255.0).round() as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// Where `freq` falls on the fixed scale, 0.0 at [`LOW_HZ`] to 1.0 at
+/// [`HIGH_HZ`], rounded to the nearest third-octave center (1 kHz being
+/// one) so every band within a third-octave shares a color.
+pub fn frequency_ratio(freq: f32) -> f32 {
+    let third = (3.0 * (freq.max(1.0) / 1000.0).log2()).round();
+    let center = 1000.0 * (third / 3.0).exp2();
+    ((center / LOW_HZ).ln() / (HIGH_HZ / LOW_HZ).ln()).clamp(0.0, 1.0)
+}
+
+/// The gradient at `ratio` (0.0 to 1.0): red, orange, yellow, green, cyan,
+/// blue, violet. Ratios outside that range are clamped to it and a NaN one
+/// is taken as 0.0, so every channel is always a real value.
+pub fn vibgyor(ratio: f32) -> (u8, u8, u8) {
+    let ratio = if ratio.is_nan() { 0.0 } else { ratio.clamp(0.0, 1.0) };
+    if ratio < 0.167 {
+        // Red (0%) -> Orange (16.7%)
+        let t = ratio / 0.167;
+        (255, (165.0 * t) as u8, 0)
+    } else if ratio < 0.333 {
+        // Orange (16.7%) -> Yellow (33.3%)
+        let t = (ratio - 0.167) / 0.166;
+        (255, (165.0 + 90.0 * t) as u8, 0)
+    } else if ratio < 0.5 {
+        // Yellow (33.3%) -> Green (50%)
+        let t = (ratio - 0.333) / 0.167;
+        ((255.0 * (1.0 - t)) as u8, 255, 0)
+    } else if ratio < 0.667 {
+        // Green (50%) -> Cyan (66.7%)
+        let t = (ratio - 0.5) / 0.167;
+        (0, 255, (255.0 * t) as u8)
+    } else if ratio < 0.833 {
+        // Cyan (66.7%) -> Blue (83.3%)
+        let t = (ratio - 0.667) / 0.166;
+        (0, (255.0 * (1.0 - t)) as u8, 255)
+    } else {
+        // Blue (83.3%) -> Violet (100%)
+        let t = (ratio - 0.833) / 0.167;
+        ((148.0 * t) as u8, 0, (255.0 - 44.0 * t) as u8)
+    }
+}
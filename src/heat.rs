@@ -0,0 +1,89 @@
+//! Heat trails: where the bars have just been.
+//!
+//! Between plain bars and a full waterfall: every cell a bar reaches gets
+//! hot, and cools over [`TRAIL_SECS`] once the bar drops below it, so a
+//! falling bar leaves a fading trail of the heights it just had. A
+//! [`HeatMap`] keeps one byte per cell of the bars' area (bars × bar
+//! length) counting down, by time rather than frames so a trail lasts as
+//! long at any frame rate.
+//!
+//! ```
+//! use gruvberry::heat::{HeatMap, TRAIL_SECS};
+//!
+//! let mut heat = HeatMap::new(TRAIL_SECS);
+//! // One bar, 10 cells long at most: up to 8, then down to 2
+//! heat.update(&[8], 10, 0.0);
+//! heat.update(&[2], 10, 0.5);
+//! assert_eq!(heat.level(0, 1), 1.0);
+//! assert!((heat.level(0, 7) - 0.5).abs() < 0.01);
+//! assert_eq!(heat.level(0, 9), 0.0);
+//! ```
+
+/// How long a cell takes to cool down completely (seconds).
+pub const TRAIL_SECS: f32 = 1.0;
+
+// Heat of a cell a bar covers
+const HOT: u8 = u8::MAX;
+
+/// Heat of each cell of the bars' area.
+#[derive(Clone, Debug, Default)]
+pub struct HeatMap {
+    trail_secs: f32,
+    bars: usize,
+    bar_length: usize,
+    // Bar by bar, from the base out
+    cells: Vec<u8>,
+    // Cooling not taken off yet for being less than a step
+    carry: f32,
+}
+
+impl HeatMap {
+    /// Cells cooling down over `trail_secs`.
+    pub fn new(trail_secs: f32) -> Self {
+        HeatMap {
+            trail_secs: trail_secs.max(f32::EPSILON),
+            ..Default::default()
+        }
+    }
+
+    /// Cool every cell for `dt` seconds, then heat the ones up to each bar's
+    /// length (in cells, `lengths` one per bar, up to `bar_length`). A
+    /// different number of bars or bar length starts over cold.
+    pub fn update(&mut self, lengths: &[usize], bar_length: usize, dt: f32) {
+        if lengths.len() != self.bars || bar_length != self.bar_length {
+            self.bars = lengths.len();
+            self.bar_length = bar_length;
+            self.cells = vec![0; self.bars * bar_length];
+            self.carry = 0.0;
+        }
+        let cooling = self.carry + dt.max(0.0) / self.trail_secs * HOT as f32;
+        let steps = cooling.floor();
+        self.carry = cooling - steps;
+        let steps = steps.min(HOT as f32) as u8;
+        if steps > 0 {
+            for cell in &mut self.cells {
+                *cell = cell.saturating_sub(steps);
+            }
+        }
+        for (bar, &length) in lengths.iter().enumerate() {
+            let start = bar * bar_length;
+            self.cells[start..start + length.min(bar_length)].fill(HOT);
+        }
+    }
+
+    /// Heat of the cell `along` cells out from the base of `bar`: 1.0 while
+    /// a bar covers it, down to 0.0 once it has cooled (or for a cell
+    /// outside the area).
+    pub fn level(&self, bar: usize, along: usize) -> f32 {
+        if bar >= self.bars || along >= self.bar_length {
+            return 0.0;
+        }
+        self.cells[bar * self.bar_length + along] as f32 / HOT as f32
+    }
+
+    /// Everything cold again.
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+        self.carry = 0.0;
+    }
+}
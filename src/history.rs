@@ -0,0 +1,215 @@
+//! A bounded record of recent analysis frames.
+//!
+//! The visualizer keeps what it drew for the last few seconds so features
+//! that look back (the replay scrub, summaries) don't redo the analysis.
+//! [`FrameHistory`] holds at most a fixed number of frames covering at most a
+//! fixed span of playback time, whichever is reached first.
+//!
+//! # Memory
+//!
+//! A frame stores two `f32` values per band (the drawn level and the level in
+//! dB), so a full history takes about `bands × 2 × 4 × max_frames` bytes plus
+//! a small fixed overhead per frame: 30 seconds at 60 fps with 200 bands is
+//! 1800 frames, about 2.9 MB.
+//!
+//! # Sharing
+//!
+//! Frames are reference-counted. A reader on another thread can take a
+//! [`FrameHistory::snapshot`] while holding a [`SharedHistory`] lock only for
+//! the copy of the pointers, then iterate at leisure while the writer keeps
+//! pushing.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use crate::analysis::FreqRange;
+
+/// One analyzed frame as it was shown on screen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnalysisFrame {
+    /// Audio time in seconds at the end of the window the frame was
+    /// analyzed from (see [`SampleRing::window_time`](crate::capture::SampleRing::window_time)):
+    /// where in the track its newest sample is, rather than when the
+    /// analysis happened to run.
+    pub time: f32,
+    /// Normalized band levels (0-100), one per band at the time of analysis.
+    pub bands: Vec<f32>,
+    /// The same bands in dB before tilt and normalization.
+    pub levels_db: Vec<f32>,
+    /// Overall RMS level of the analyzed samples (1.0 = full scale).
+    pub rms: f32,
+    /// Sample rate the samples were analyzed at, which places the bands in
+    /// frequency (see [`crate::rate`]).
+    pub sample_rate: u32,
+    /// Frequencies the bands were spread over, the whole range up to
+    /// Nyquist unless zoomed in.
+    pub range: FreqRange,
+}
+
+impl AnalysisFrame {
+    /// The frame as one line of text, the form the control socket streams to
+    /// followers: `frame`, then the time, sample rate, range (low and high
+    /// Hz), RMS and band count, then the bands and after them their levels in
+    /// dB, all separated by spaces. Numbers are written as short as they can
+    /// be and still read back exactly (`-inf` for silence in dB).
+    ///
+    /// ```
+    /// use gruvberry::analysis::FreqRange;
+    /// use gruvberry::history::AnalysisFrame;
+    ///
+    /// let frame = AnalysisFrame {
+    ///     time: 1.5,
+    ///     bands: vec![80.0, 12.5],
+    ///     levels_db: vec![-6.0, f32::NEG_INFINITY],
+    ///     rms: 0.25,
+    ///     sample_rate: 44100,
+    ///     range: FreqRange { low: 20.0, high: 22050.0 },
+    /// };
+    /// assert_eq!(frame.to_line(), "frame 1.5 44100 20 22050 0.25 2 80 12.5 -6 -inf");
+    /// assert_eq!(AnalysisFrame::from_line(&frame.to_line()), Ok(frame));
+    /// ```
+    pub fn to_line(&self) -> String {
+        let mut line = format!(
+            "frame {} {} {} {} {} {}",
+            self.time,
+            self.sample_rate,
+            self.range.low,
+            self.range.high,
+            self.rms,
+            self.bands.len()
+        );
+        for value in self.bands.iter().chain(&self.levels_db) {
+            line.push_str(&format!(" {}", value));
+        }
+        line
+    }
+
+    /// Read a frame back from a line [`to_line`](AnalysisFrame::to_line) wrote.
+    pub fn from_line(line: &str) -> Result<AnalysisFrame, String> {
+        let mut fields = line.split_whitespace();
+        if fields.next() != Some("frame") {
+            return Err("not a frame".to_string());
+        }
+        let mut next = |what: &str| fields.next().ok_or_else(|| format!("frame is missing its {}", what));
+        let number = |field: &str, what: &str| field.parse::<f32>().map_err(|_| format!("frame has a bad {}: '{}'", what, field));
+        let time = number(next("time")?, "time")?;
+        let rate = next("sample rate")?;
+        let sample_rate = rate.parse().map_err(|_| format!("frame has a bad sample rate: '{}'", rate))?;
+        let low = number(next("range")?, "range")?;
+        let high = number(next("range")?, "range")?;
+        let rms = number(next("RMS")?, "RMS")?;
+        let count = next("band count")?;
+        let count: usize = count.parse().map_err(|_| format!("frame has a bad band count: '{}'", count))?;
+        let mut levels = Vec::with_capacity(count * 2);
+        for field in fields {
+            levels.push(number(field, "band")?);
+        }
+        if levels.len() != count * 2 {
+            return Err(format!("frame has {} values for {} bands", levels.len(), count));
+        }
+        let levels_db = levels.split_off(count);
+        Ok(AnalysisFrame {
+            time,
+            bands: levels,
+            levels_db,
+            rms,
+            sample_rate,
+            range: FreqRange { low, high },
+        })
+    }
+}
+
+/// History written by one thread and read by others.
+pub type SharedHistory = Arc<Mutex<FrameHistory>>;
+
+/// Recent analysis frames, oldest first.
+pub struct FrameHistory {
+    frames: VecDeque<Arc<AnalysisFrame>>,
+    window_secs: f32,
+    max_frames: usize,
+}
+
+impl FrameHistory {
+    /// Keep frames from the last `window_secs` of playback, `max_frames` at most.
+    pub fn new(window_secs: f32, max_frames: usize) -> Self {
+        FrameHistory {
+            frames: VecDeque::new(),
+            window_secs,
+            max_frames: max_frames.max(1),
+        }
+    }
+
+    /// A history for `window_secs` of frames arriving `fps` times a second.
+    pub fn for_rate(window_secs: f32, fps: u32) -> Self {
+        FrameHistory::new(window_secs, frames_for(window_secs, fps))
+    }
+
+    /// Change the bounds, dropping the oldest frames that no longer fit.
+    pub fn set_limits(&mut self, window_secs: f32, max_frames: usize) {
+        self.window_secs = window_secs;
+        self.max_frames = max_frames.max(1);
+        self.trim();
+    }
+
+    pub fn window_secs(&self) -> f32 {
+        self.window_secs
+    }
+
+    pub fn max_frames(&self) -> usize {
+        self.max_frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Append the newest frame and drop what falls outside the bounds.
+    pub fn push(&mut self, frame: AnalysisFrame) {
+        self.frames.push_back(Arc::new(frame));
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        let Some(newest) = self.frames.back().map(|f| f.time) else {
+            return;
+        };
+        let cutoff = newest - self.window_secs;
+        while self.frames.len() > self.max_frames || self.frames.front().is_some_and(|f| f.time < cutoff) {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn latest(&self) -> Option<&AnalysisFrame> {
+        self.frames.back().map(|f| &**f)
+    }
+
+    pub fn oldest(&self) -> Option<&AnalysisFrame> {
+        self.frames.front().map(|f| &**f)
+    }
+
+    /// The newest frame analyzed at or before `time` (the oldest one if `time`
+    /// predates the history).
+    pub fn at(&self, time: f32) -> Option<&AnalysisFrame> {
+        let index = self.frames.partition_point(|f| f.time <= time);
+        self.frames.get(index.saturating_sub(1)).map(|f| &**f)
+    }
+
+    /// The frames, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &AnalysisFrame> {
+        self.frames.iter().map(|f| &**f)
+    }
+
+    /// The frames as they are now, oldest first, sharing their data with the
+    /// history; later pushes don't affect the snapshot.
+    pub fn snapshot(&self) -> Vec<Arc<AnalysisFrame>> {
+        self.frames.iter().cloned().collect()
+    }
+}
+
+/// Frames needed to cover `window_secs` at `fps` frames a second.
+pub fn frames_for(window_secs: f32, fps: u32) -> usize {
+    (window_secs.max(0.0) * fps as f32).ceil() as usize + 1
+}
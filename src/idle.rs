@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+use gruvberry::theme::{frequency_to_color, Theme};
+
+// RMS below this (about -60 dBFS) counts as silence
+const SILENCE_RMS: f32 = 0.001;
+
+// The animation fades in over this long once idle, rather than popping up
+// where the bars just were
+const FADE_IN: Duration = Duration::from_secs(2);
+
+// Tracks how long the input has been silent and decides when to go idle
+pub struct SilenceDetector {
+    timeout: Duration,
+    silent_since: Option<Instant>,
+}
+
+impl SilenceDetector {
+    pub fn new(timeout: Duration) -> Self {
+        SilenceDetector {
+            timeout,
+            silent_since: None,
+        }
+    }
+
+    // Feed the RMS of the latest analysis window; returns true while idle.
+    // Any signal above the threshold resets the timer, so live bars come
+    // back on the very next frame.
+    pub fn update(&mut self, rms: f32, now: Instant) -> bool {
+        if rms > SILENCE_RMS {
+            self.silent_since = None;
+            return false;
+        }
+        let since = *self.silent_since.get_or_insert(now);
+        now.duration_since(since) >= self.timeout
+    }
+
+    // How far the animation has faded in, 0 when it starts to 1 after
+    // FADE_IN; 0 while not idle
+    pub fn fade(&self, now: Instant) -> f32 {
+        let Some(since) = self.silent_since else {
+            return 0.0;
+        };
+        let idle_for = now.duration_since(since).saturating_sub(self.timeout);
+        (idle_for.as_secs_f32() / FADE_IN.as_secs_f32()).min(1.0)
+    }
+}
+
+// Root mean square level of a block of samples
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+// Idle animation: a slowly drifting sine whose gradient cycles across the
+// screen. Driven purely by time so it keeps moving with no audio at all.
+// `fade` (0 to 1) brings it up from the background: RGB colors are dimmed,
+// and colors that can't be (plain, or the terminal's palette) start out as
+// smaller dots.
+pub fn idle_lines(t: f32, width: usize, height: usize, fade: f32, theme: &Theme) -> Vec<Line<'static>> {
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let middle = (height as f32 - 1.0) / 2.0;
+    let amplitude = middle * 0.6;
+    let color_shift = (t * 4.0) as usize; // columns per second of color drift
+
+    // Row the sine passes through in each column
+    let wave_rows: Vec<usize> = (0..width)
+        .map(|col| {
+            let phase = col as f32 / width as f32 * std::f32::consts::TAU * 2.0 + t * 0.8;
+            ((middle + phase.sin() * amplitude).round() as usize).min(height - 1)
+        })
+        .collect();
+
+    (0..height)
+        .map(|row| {
+            let spans: Vec<Span> = wave_rows
+                .iter()
+                .enumerate()
+                .map(|(col, &wave_row)| {
+                    if wave_row == row {
+                        let color = theme.gradient(frequency_to_color((col + color_shift) % width, width));
+                        match color {
+                            Color::Rgb(r, g, b) => {
+                                let dim = |v: u8| (v as f32 * fade).round() as u8;
+                                Span::styled("•", Style::default().fg(Color::Rgb(dim(r), dim(g), dim(b))))
+                            }
+                            _ if fade < 0.5 => Span::styled("·", Style::default().fg(color)),
+                            _ => Span::styled("•", Style::default().fg(color)),
+                        }
+                    } else {
+                        Span::raw(" ")
+                    }
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
@@ -0,0 +1,121 @@
+//! Terminal input, a frame at a time.
+//!
+//! The visualizer reads events between frames. Holding an arrow key or
+//! pasting queues many at once, so a frame takes [`drain`] of everything
+//! queued rather than one event, and repeated steps add up in [`Steps`] to be
+//! applied once. Events come from an [`EventSource`]: the terminal, or a
+//! [`Scripted`] list of them in tests.
+//!
+//! ```
+//! use std::time::Duration;
+//! use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+//! use gruvberry::input::{self, Scripted, Steps};
+//!
+//! let right = Event::Key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE));
+//! let mut source = Scripted::new(vec![right.clone(), right.clone(), right]);
+//! let mut steps = Steps::default();
+//! for event in input::drain(&mut source, Duration::ZERO).unwrap() {
+//!     if let Event::Key(key) = event {
+//!         steps.add(key, 0.5, 5.0);
+//!     }
+//! }
+//! assert_eq!(steps.total(), 1.5);
+//! ```
+
+use std::collections::VecDeque;
+use std::io;
+use std::time::Duration;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+
+/// Where events come from.
+pub trait EventSource {
+    /// Whether an event is ready, waiting up to `timeout` for one.
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool>;
+    /// The next event, waiting for one if need be.
+    fn read(&mut self) -> io::Result<Event>;
+}
+
+/// The terminal's events, as crossterm reads them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Terminal;
+
+impl EventSource for Terminal {
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        event::read()
+    }
+}
+
+/// Events given up front, all ready at once; none once they run out.
+#[derive(Clone, Debug, Default)]
+pub struct Scripted {
+    events: VecDeque<Event>,
+}
+
+impl Scripted {
+    pub fn new(events: impl IntoIterator<Item = Event>) -> Self {
+        Scripted {
+            events: events.into_iter().collect(),
+        }
+    }
+
+    /// Events not read yet.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl EventSource for Scripted {
+    fn poll(&mut self, _timeout: Duration) -> io::Result<bool> {
+        Ok(!self.events.is_empty())
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        self.events.pop_front().ok_or_else(|| io::Error::new(io::ErrorKind::WouldBlock, "no more events"))
+    }
+}
+
+/// Wait up to `timeout` for an event, then take it and every other one
+/// already queued, oldest first. Empty if none came in time.
+pub fn drain<S: EventSource>(source: &mut S, timeout: Duration) -> io::Result<Vec<Event>> {
+    let mut events = Vec::new();
+    let mut timeout = timeout;
+    while source.poll(timeout)? {
+        timeout = Duration::ZERO;
+        events.push(source.read()?);
+    }
+    Ok(events)
+}
+
+/// Left/right arrow steps added up over a frame, so a held key moves once
+/// per frame by all its repeats instead of once per repeat.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Steps {
+    total: f32,
+}
+
+impl Steps {
+    /// Count `key`: `step` back for Left and forward for Right, or
+    /// `big_step` with Shift. Other keys count for nothing.
+    pub fn add(&mut self, key: KeyEvent, step: f32, big_step: f32) {
+        let step = if key.modifiers.contains(KeyModifiers::SHIFT) { big_step } else { step };
+        match key.code {
+            KeyCode::Left => self.total -= step,
+            KeyCode::Right => self.total += step,
+            _ => {}
+        }
+    }
+
+    /// The steps added up, negative going back; 0 with none (or as many each
+    /// way).
+    pub fn total(&self) -> f32 {
+        self.total
+    }
+}
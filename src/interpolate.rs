@@ -0,0 +1,73 @@
+//! Smooth bar motion between analysis frames.
+//!
+//! When the spectrum is analyzed less often than it is drawn (a large FFT, a
+//! slow machine, a low `analysis_fps`), drawing each analysis frame as it
+//! comes makes the bars move in steps. [`FrameInterpolator`] keeps the last
+//! two frames with the times they were analyzed at and blends from the older
+//! to the newer one over the interval that follows, so the bars glide from
+//! frame to frame. The price is one analysis interval of delay.
+//!
+//! ```
+//! use gruvberry::interpolate::FrameInterpolator;
+//!
+//! let mut frames = FrameInterpolator::new();
+//! frames.push(0.0, vec![0.0, 10.0]);
+//! frames.push(0.5, vec![10.0, 20.0]);
+//! // Halfway through the interval after the newest frame
+//! assert_eq!(frames.at(0.75), Some(vec![5.0, 15.0]));
+//! ```
+
+/// `from` blended toward `to` by `alpha` (0.0 = `from`, 1.0 = `to`).
+pub fn lerp(from: &[f32], to: &[f32], alpha: f32) -> Vec<f32> {
+    from.iter().zip(to).map(|(&a, &b)| a + (b - a) * alpha).collect()
+}
+
+/// The last two analysis frames, for drawing in between them.
+#[derive(Clone, Debug, Default)]
+pub struct FrameInterpolator {
+    previous: Option<(f64, Vec<f32>)>,
+    current: Option<(f64, Vec<f32>)>,
+}
+
+impl FrameInterpolator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the bands analyzed at `time` (seconds, increasing).
+    pub fn push(&mut self, time: f64, bands: Vec<f32>) {
+        self.previous = self.current.replace((time, bands));
+    }
+
+    /// The newest frame as analyzed.
+    pub fn latest(&self) -> Option<&[f32]> {
+        self.current.as_ref().map(|(_, bands)| bands.as_slice())
+    }
+
+    /// How far from the previous frame toward the newest one the bands drawn
+    /// at `time` are: 0.0 when the newest frame has just arrived, 1.0 once a
+    /// whole interval (the time between the two frames) has passed since.
+    pub fn alpha(&self, time: f64) -> f32 {
+        match (&self.previous, &self.current) {
+            (Some((previous, _)), Some((current, _))) if current > previous => {
+                ((time - current) / (current - previous)).clamp(0.0, 1.0) as f32
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Bands to draw at `time`, None before the first frame. Frames with
+    /// different band counts (the terminal was resized) aren't blended.
+    pub fn at(&self, time: f64) -> Option<Vec<f32>> {
+        let (_, current) = self.current.as_ref()?;
+        match &self.previous {
+            Some((_, previous)) if previous.len() == current.len() => Some(lerp(previous, current, self.alpha(time))),
+            _ => Some(current.clone()),
+        }
+    }
+
+    /// Forget both frames, e.g. after a jump in the audio.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
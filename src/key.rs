@@ -0,0 +1,159 @@
+//! Musical key estimation.
+//!
+//! A chromagram folds the spectrum onto the twelve pitch classes (C, C#, ...
+//! B, any octave). Accumulated over a while, it is correlated with the
+//! Krumhansl–Schmuckler key profiles (how prominent each pitch class is in
+//! major and minor keys) rotated to all twelve tonics; the best match is the
+//! estimated key.
+//!
+//! ```
+//! use gruvberry::key;
+//!
+//! // Energy on A, C and E, with A strongest
+//! let mut chroma = [0.0; 12];
+//! chroma[9] = 2.0;
+//! chroma[0] = 1.0;
+//! chroma[4] = 1.0;
+//! let estimate = key::estimate(&chroma).unwrap();
+//! assert_eq!(estimate.name(), "A minor");
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Pitch class names, C first.
+pub const PITCH_CLASSES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+// Krumhansl–Kessler probe-tone ratings, tonic first
+const MAJOR_PROFILE: [f32; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const MINOR_PROFILE: [f32; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+/// Frequencies the chromagram looks at. Below this a semitone is narrower
+/// than an FFT bin at common sizes, above it harmonics dominate.
+pub const CHROMA_MIN_FREQ: f32 = 100.0;
+pub const CHROMA_MAX_FREQ: f32 = 4000.0;
+
+/// An estimated key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Key {
+    /// Pitch class of the tonic, 0 = C.
+    pub tonic: usize,
+    pub minor: bool,
+    /// Correlation of the chromagram with the key's profile, -1.0 to 1.0.
+    pub correlation: f32,
+}
+
+impl Key {
+    /// The key's name, e.g. "A minor".
+    pub fn name(&self) -> String {
+        format!("{} {}", PITCH_CLASSES[self.tonic], if self.minor { "minor" } else { "major" })
+    }
+}
+
+/// "A minor (78%)", the percentage being the profile correlation.
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({:.0}%)", self.name(), (self.correlation * 100.0).max(0.0))
+    }
+}
+
+/// Fold a magnitude spectrum (bin `k` at `k * freq_per_bin` Hz) onto the
+/// twelve pitch classes, summing the energy of each bin into its nearest one.
+pub fn chroma(magnitudes: &[f32], freq_per_bin: f32) -> [f32; 12] {
+    let mut chroma = [0.0; 12];
+    for (bin, &magnitude) in magnitudes.iter().enumerate().skip(1) {
+        let freq = bin as f32 * freq_per_bin;
+        if !(CHROMA_MIN_FREQ..=CHROMA_MAX_FREQ).contains(&freq) {
+            continue;
+        }
+        // MIDI note number; 69 is A4 = 440 Hz
+        let note = (12.0 * (freq / 440.0).log2() + 69.0).round() as i32;
+        chroma[note.rem_euclid(12) as usize] += magnitude * magnitude;
+    }
+    chroma
+}
+
+/// The key whose profile best matches `chroma`, or None without any energy.
+pub fn estimate(chroma: &[f32; 12]) -> Option<Key> {
+    if chroma.iter().all(|&c| c <= 0.0) {
+        return None;
+    }
+    let mut best: Option<Key> = None;
+    for tonic in 0..12 {
+        for (profile, minor) in [(&MAJOR_PROFILE, false), (&MINOR_PROFILE, true)] {
+            // The profile with its tonic moved to `tonic`
+            let rotated: Vec<f32> = (0..12).map(|pc| profile[(pc + 12 - tonic) % 12]).collect();
+            let correlation = pearson(chroma, &rotated);
+            if best.is_none_or(|b| correlation > b.correlation) {
+                best = Some(Key { tonic, minor, correlation });
+            }
+        }
+    }
+    best
+}
+
+fn pearson(a: &[f32], b: &[f32]) -> f32 {
+    let n = a.len() as f32;
+    let (mean_a, mean_b) = (a.iter().sum::<f32>() / n, b.iter().sum::<f32>() / n);
+    let mut covariance = 0.0;
+    let (mut var_a, mut var_b) = (0.0, 0.0);
+    for (&x, &y) in a.iter().zip(b) {
+        covariance += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a) * (x - mean_a);
+        var_b += (y - mean_b) * (y - mean_b);
+    }
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return 0.0;
+    }
+    covariance / (var_a * var_b).sqrt()
+}
+
+/// Accumulates chromagrams over a rolling window of time and estimates the
+/// key from their sum.
+pub struct KeyEstimator {
+    window_secs: f32,
+    frames: VecDeque<(f64, [f32; 12])>,
+}
+
+impl KeyEstimator {
+    pub fn new(window_secs: f32) -> Self {
+        KeyEstimator {
+            window_secs,
+            frames: VecDeque::new(),
+        }
+    }
+
+    /// Add the chromagram analyzed at `time` (seconds, increasing).
+    pub fn push(&mut self, time: f64, chroma: [f32; 12]) {
+        self.frames.push_back((time, chroma));
+        while self.frames.front().is_some_and(|&(t, _)| t < time - self.window_secs as f64) {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Seconds of audio in the window.
+    pub fn span(&self) -> f32 {
+        match (self.frames.front(), self.frames.back()) {
+            (Some(first), Some(last)) => (last.0 - first.0) as f32,
+            _ => 0.0,
+        }
+    }
+
+    /// The key over the window, once it spans at least `min_secs`.
+    pub fn estimate(&self, min_secs: f32) -> Option<Key> {
+        if self.span() < min_secs {
+            return None;
+        }
+        let mut total = [0.0; 12];
+        for (_, chroma) in &self.frames {
+            for (sum, value) in total.iter_mut().zip(chroma) {
+                *sum += value;
+            }
+        }
+        estimate(&total)
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
@@ -0,0 +1,277 @@
+//! How the display is laid out for a terminal size.
+//!
+//! [`LayoutPlan::compute`] decides, from the terminal's size and what is on
+//! show, how many bands and legend segments there are, how tall each block is
+//! and whether the display fits at all. The frame loop sizes the analysis
+//! from the plan and the renderer splits the screen with it, so the two can't
+//! disagree.
+//!
+//! While a terminal corner is dragged the size changes many times a second.
+//! [`BandSettle`] keeps the analysis on the band count it has until a new
+//! one has held for [`SETTLE_TIME`], so the band table is built once for
+//! where the drag ends rather than for every column on the way; meanwhile
+//! the bars of the old count are stretched or squeezed to the width.
+//!
+//! ```
+//! use gruvberry::layout::{Fit, LayoutConfig, LayoutPlan};
+//!
+//! let plan = LayoutPlan::compute(120, 40, &LayoutConfig::default());
+//! assert_eq!(plan.fit, Fit::Full);
+//! assert_eq!(plan.num_bands, 116);
+//! assert_eq!(plan.num_legend_bands, 11);
+//!
+//! assert_eq!(LayoutPlan::compute(79, 24, &LayoutConfig::default()).fit, Fit::TooSmall);
+//! ```
+
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+/// Smallest terminal the display fits in.
+pub const MIN_WIDTH: u16 = 80;
+pub const MIN_HEIGHT: u16 = 20;
+/// Widest the display gets; wider terminals leave the rest empty.
+pub const MAX_WIDTH: u16 = 160;
+/// Rows the spectrum gets at least, borders included.
+pub const MIN_SPECTRUM_HEIGHT: u16 = 10;
+/// Columns of the display that hold no band (borders and a margin), when
+/// the bands run across.
+pub const BAND_MARGIN: u16 = 4;
+/// One legend segment per this many columns, within [`LEGEND_SEGMENTS`].
+pub const COLUMNS_PER_LEGEND: u16 = 10;
+pub const LEGEND_SEGMENTS: RangeInclusive<usize> = 8..=16;
+/// Legend entries down the side of horizontal bars, one per two rows.
+pub const ROW_LEGEND_SEGMENTS: RangeInclusive<usize> = 2..=16;
+/// Height of the one-line blocks (legend strip, progress).
+pub const STRIP_HEIGHT: u16 = 3;
+/// Height of the overview, whose envelope takes a row above its middle for
+/// the highest samples and one below for the lowest.
+pub const OVERVIEW_HEIGHT: u16 = 4;
+/// How long a new band count must hold before the analysis takes it up.
+pub const SETTLE_TIME: Duration = Duration::from_millis(150);
+/// A count further than this from the one in use is taken up at once: a
+/// window maximized, or a switch between vertical and horizontal bars.
+pub const SETTLE_MAX_LAG: usize = 16;
+
+/// How much of the display fits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fit {
+    Full,
+    /// Below [`MIN_WIDTH`] x [`MIN_HEIGHT`]: only a warning is drawn, and the
+    /// band count should stay as it was.
+    TooSmall,
+}
+
+/// What is on show, besides the terminal size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutConfig {
+    /// Bands in rows (one per row) instead of columns.
+    pub horizontal: bool,
+    /// Spectra stacked above each other (two when comparing files).
+    pub streams: usize,
+    /// The whole-track overview strip.
+    pub overview: bool,
+    /// Rows of the playlist panel, when it is open.
+    pub playlist_rows: Option<u16>,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig {
+            horizontal: false,
+            streams: 1,
+            overview: false,
+            playlist_rows: None,
+        }
+    }
+}
+
+/// The blocks of the display, top to bottom.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Areas {
+    /// All the spectra together, and each stream's.
+    pub spectrum: Rect,
+    pub spectra: Vec<Rect>,
+    /// Legend strip ("Frequency Ranges", or the level scale).
+    pub legend: Rect,
+    /// "Band Details".
+    pub details: Rect,
+    pub overview: Option<Rect>,
+    pub playlist: Option<Rect>,
+    pub progress: Rect,
+}
+
+/// The layout for one terminal size.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutPlan {
+    pub fit: Fit,
+    /// Width the display takes, left-aligned in the terminal.
+    pub width: u16,
+    pub height: u16,
+    pub num_bands: usize,
+    pub num_legend_bands: usize,
+    /// Heights of the blocks, top to bottom, as in [`Areas`].
+    pub constraints: Vec<Constraint>,
+    config: LayoutConfig,
+}
+
+impl LayoutPlan {
+    /// The layout of a `width` x `height` terminal. A terminal too small
+    /// still gets a plan, laid out as at the smallest size that fits.
+    pub fn compute(width: u16, height: u16, config: &LayoutConfig) -> Self {
+        let fit = if width < MIN_WIDTH || height < MIN_HEIGHT { Fit::TooSmall } else { Fit::Full };
+        let width = width.clamp(MIN_WIDTH, MAX_WIDTH);
+        let height = height.max(MIN_HEIGHT);
+        let mut plan = LayoutPlan {
+            fit,
+            width,
+            height,
+            num_bands: 0,
+            num_legend_bands: *LEGEND_SEGMENTS.end(),
+            constraints: Vec::new(),
+            config: config.clone(),
+        };
+        if config.horizontal {
+            // One band per spectrum row, and one legend entry per two rows.
+            // More entries make the details block taller, leaving fewer rows,
+            // so take the most entries that the rows they leave still call for.
+            let (rows, legends) = ROW_LEGEND_SEGMENTS
+                .rev()
+                .map(|legends| (plan.spectrum_rows(legends).max(1), legends))
+                .find(|&(rows, legends)| rows / 2 >= legends || legends == *ROW_LEGEND_SEGMENTS.start())
+                .unwrap_or((1, *ROW_LEGEND_SEGMENTS.start()));
+            plan.num_bands = rows;
+            plan.num_legend_bands = legends;
+        } else {
+            let usable = width - BAND_MARGIN;
+            plan.num_bands = usable as usize;
+            plan.num_legend_bands =
+                ((usable / COLUMNS_PER_LEGEND) as usize).clamp(*LEGEND_SEGMENTS.start(), *LEGEND_SEGMENTS.end());
+        }
+        plan.constraints = plan.constraints_for(plan.num_legend_bands);
+        plan
+    }
+
+    pub fn fits(&self) -> bool {
+        self.fit == Fit::Full
+    }
+
+    /// The blocks of the display within `area` (the terminal).
+    pub fn areas(&self, area: Rect) -> Areas {
+        let display = Rect {
+            width: area.width.min(MAX_WIDTH),
+            ..area
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(self.constraints.clone())
+            .split(display);
+        let streams = self.config.streams.max(1);
+        let spectra = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Ratio(1, streams as u32); streams])
+            .split(chunks[0]);
+        let mut rest = chunks[3..].iter().copied();
+        Areas {
+            spectrum: chunks[0],
+            spectra: spectra.to_vec(),
+            legend: chunks[1],
+            details: chunks[2],
+            overview: self.config.overview.then(|| rest.next()).flatten(),
+            playlist: self.config.playlist_rows.and_then(|_| rest.next()),
+            progress: chunks[chunks.len() - 1],
+        }
+    }
+
+    // Block heights with `legends` legend entries in the details block
+    fn constraints_for(&self, legends: usize) -> Vec<Constraint> {
+        let mut constraints = vec![
+            Constraint::Min(MIN_SPECTRUM_HEIGHT),
+            Constraint::Length(STRIP_HEIGHT),
+            // Taller with more legend entries, up to 8 rows
+            Constraint::Length(legends.min(10) as u16 / 2 + 3),
+        ];
+        if self.config.overview {
+            constraints.push(Constraint::Length(OVERVIEW_HEIGHT));
+        }
+        if let Some(rows) = self.config.playlist_rows {
+            constraints.push(Constraint::Length(rows + 2));
+        }
+        constraints.push(Constraint::Length(STRIP_HEIGHT));
+        constraints
+    }
+
+    // Rows inside the borders of the shortest spectrum with `legends` legend
+    // entries in the details block
+    fn spectrum_rows(&self, legends: usize) -> usize {
+        let plan = LayoutPlan {
+            constraints: self.constraints_for(legends),
+            ..self.clone()
+        };
+        let areas = plan.areas(Rect::new(0, 0, self.width, self.height));
+        areas.spectra.iter().map(|area| area.height.saturating_sub(2) as usize).min().unwrap_or(0)
+    }
+}
+
+/// The band count the analysis runs at, following [`LayoutPlan::num_bands`]
+/// once a new count has settled. A plan that doesn't fit leaves the count
+/// as it is.
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use gruvberry::layout::{BandSettle, LayoutConfig, LayoutPlan, SETTLE_TIME};
+///
+/// let plan = |width| LayoutPlan::compute(width, 40, &LayoutConfig::default());
+/// let start = Instant::now();
+/// let mut settle = BandSettle::default();
+/// assert_eq!(settle.update(&plan(120), start), 116);
+/// // Dragged a few columns narrower: the old count holds for a while
+/// assert_eq!(settle.update(&plan(116), start + Duration::from_millis(20)), 116);
+/// assert_eq!(settle.update(&plan(114), start + Duration::from_millis(40)), 116);
+/// assert_eq!(settle.update(&plan(114), start + Duration::from_millis(40) + SETTLE_TIME), 110);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BandSettle {
+    applied: Option<usize>,
+    // The count the layout last asked for, and since when
+    wanted: Option<(usize, Instant)>,
+    changes: u32,
+}
+
+impl BandSettle {
+    /// The count to analyze at `now` with `plan` on screen. The first
+    /// count, and one more than [`SETTLE_MAX_LAG`] bands away, is taken at
+    /// once; any other change waits until the plans have asked for the same
+    /// count for [`SETTLE_TIME`].
+    pub fn update(&mut self, plan: &LayoutPlan, now: Instant) -> usize {
+        let wanted = plan.num_bands;
+        if !plan.fits() {
+            return self.applied.unwrap_or(wanted);
+        }
+        let since = match self.wanted {
+            Some((count, since)) if count == wanted => since,
+            _ => now,
+        };
+        self.wanted = Some((wanted, since));
+        let take = match self.applied {
+            None => true,
+            Some(applied) if applied == wanted => false,
+            Some(applied) => applied.abs_diff(wanted) > SETTLE_MAX_LAG || now.duration_since(since) >= SETTLE_TIME,
+        };
+        if take {
+            self.applied = Some(wanted);
+            self.changes += 1;
+        }
+        self.applied.unwrap_or(wanted)
+    }
+
+    /// Whether the layout asks for another count than the one in use.
+    pub fn settling(&self) -> bool {
+        matches!((self.applied, self.wanted), (Some(applied), Some((wanted, _))) if applied != wanted)
+    }
+
+    /// How many times the count has been taken up, the first included.
+    pub fn changes(&self) -> u32 {
+        self.changes
+    }
+}
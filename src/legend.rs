@@ -0,0 +1,65 @@
+//! The "Frequency Ranges" axis under the spectrum.
+//!
+//! The axis is cut into numbered segments, `|──1──|──2──|...`, one per legend
+//! band, each as wide as the bars its "Band Details" entry covers. When the
+//! width doesn't divide evenly, the leftover cells form one more, partial
+//! segment at the end. The line always fills exactly the width it is given,
+//! closing pipe included, so it lines up with the bars above it.
+//!
+//! ```
+//! use gruvberry::legend;
+//!
+//! assert_eq!(legend::axis_line(13, 2), "|──1──|──2──|");
+//! assert_eq!(legend::axis_line(15, 3), "|─1─|─2─|─3─|4|");
+//! ```
+
+/// The axis line for `segments` legend bands, exactly `width` cells wide.
+pub fn axis_line(width: usize, segments: usize) -> String {
+    if width == 0 {
+        return String::new();
+    }
+    // The closing pipe takes the last cell
+    let inner = width - 1;
+    let (segment_width, remainder) = split(inner, segments);
+    let mut line = String::new();
+    for i in 0..segments {
+        line.push_str(&segment(i + 1, segment_width));
+    }
+    if remainder > 0 {
+        line.push_str(&segment(segments + 1, remainder));
+    }
+    line.push('|');
+    line
+}
+
+/// Whether [`axis_line`] ends with a partial segment, numbered
+/// `segments + 1`, that the legend details should list too.
+pub fn has_partial_segment(width: usize, segments: usize) -> bool {
+    split(width.saturating_sub(1), segments).1 > 0
+}
+
+// Width of each full segment and of the partial one
+fn split(inner: usize, segments: usize) -> (usize, usize) {
+    match segments {
+        0 => (0, inner),
+        _ => (inner / segments, inner % segments),
+    }
+}
+
+// One segment `cells` wide: a pipe, then the number centered in ─, or a "*"
+// when the number doesn't fit
+fn segment(number: usize, cells: usize) -> String {
+    if cells == 0 {
+        return String::new();
+    }
+    let room = cells - 1;
+    let mut label = number.to_string();
+    if label.len() > room {
+        label = "*".to_string();
+    }
+    let label = if room == 0 { String::new() } else { label };
+    let padding = room - label.len();
+    let left_pad = padding / 2;
+    let right_pad = padding - left_pad;
+    format!("|{}{}{}", "─".repeat(left_pad), label, "─".repeat(right_pad))
+}
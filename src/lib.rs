@@ -0,0 +1,53 @@
+//! Gruvberry's reusable pieces. The visualizer itself is the `gruvberry`
+//! binary; this library holds what other rodio users can pick up.
+
+pub mod agc;
+pub mod alert;
+pub mod analysis;
+pub mod capture;
+pub mod cava;
+pub mod config;
+#[cfg(unix)]
+pub mod control;
+pub mod curve;
+pub mod device;
+pub mod display;
+pub mod downsample;
+pub mod dynamics;
+pub mod echo;
+pub mod finish;
+pub mod flow;
+pub mod format;
+pub mod gradient;
+pub mod heat;
+pub mod history;
+pub mod input;
+pub mod interpolate;
+pub mod key;
+pub mod layout;
+pub mod legend;
+pub mod measurement;
+pub mod modes;
+pub mod oneline;
+pub mod onset;
+pub mod orientation;
+pub mod palette;
+pub mod playlist;
+pub mod presence;
+pub mod rate;
+pub mod raw;
+pub mod record;
+pub mod reference;
+pub mod segment;
+pub mod smoothing;
+pub mod sparkline;
+pub mod spectrum;
+pub mod stats;
+pub mod stereo;
+pub mod svg;
+pub mod theme;
+pub mod tilt;
+pub mod transport;
+pub mod watchdog;
+pub mod wav;
+pub mod zoom;
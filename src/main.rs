@@ -1,534 +1,3730 @@
+mod calibrate;
+mod cli;
+#[cfg(feature = "discord")]
+mod discord;
+mod events;
+#[cfg(unix)]
+mod follow;
+mod idle;
+mod metronome;
+mod overview;
+mod prompt;
+mod terminal;
+
 use std::fs::File;
 use std::io::BufReader;
-use std::sync::{Arc, Mutex};
+use std::path::PathBuf;
+use std::sync::{Arc, mpsc};
 use rodio::{Decoder, OutputStreamBuilder, Sink, Source};
-use rustfft::{FftPlanner, num_complex::Complex};
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-    widgets::{Block, Borders, Paragraph},
+    layout::Rect,
+    widgets::{Block, Borders, Clear, Paragraph},
     style::{Color, Style},
     text::{Line, Span},
     Terminal,
 };
-use crossterm::{
-    event::{poll, read, Event, KeyCode, KeyModifiers},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use std::sync::atomic::{AtomicBool, Ordering};
+use crossterm::event::{poll, read, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use gruvberry::alert;
+use gruvberry::analysis::{self, FreqRange};
+use gruvberry::capture::{self, SampleCapture, SampleRing, SharedRing};
+use gruvberry::cava;
+use gruvberry::config;
+#[cfg(unix)]
+use gruvberry::control;
+use gruvberry::curve;
+use gruvberry::display;
+use gruvberry::dynamics;
+use gruvberry::echo;
+use gruvberry::finish;
+use gruvberry::flow;
+use gruvberry::format::{self, Locale};
+use gruvberry::gradient;
+use gruvberry::heat::{self, HeatMap};
+use gruvberry::history;
+use gruvberry::input;
+use gruvberry::interpolate;
+use gruvberry::key;
+use gruvberry::layout;
+use gruvberry::legend;
+use gruvberry::measurement::{self, Shaping};
+use gruvberry::modes;
+use gruvberry::onset;
+use gruvberry::orientation::Orientation;
+use gruvberry::palette;
+use gruvberry::playlist;
+use gruvberry::record;
+use gruvberry::reference;
+use gruvberry::segment::{Segment, SegmentSource};
+use gruvberry::smoothing::{self, Smoother};
+use gruvberry::spectrum::{self, SpectrumWidget};
+use gruvberry::rate;
+use gruvberry::raw::{self, RawFormat};
+use gruvberry::stats;
+use gruvberry::stereo;
+use gruvberry::theme::Theme;
+use gruvberry::tilt;
+use gruvberry::transport::{self, Player, Transport};
+use gruvberry::watchdog::{self, Watchdog};
+use gruvberry::wav;
+use gruvberry::zoom;
+
+// Audio file to play when none is given on the command line
+const DEFAULT_AUDIO_PATH: &str = "src/sound4.wav";
+
+// Step used by the '+'/'-' latency nudge keys
+const LATENCY_STEP_MS: i32 = 5;
+
+// Output latency assumed when the device doesn't report its buffer size,
+// about what desktop sound servers buffer by default
+const FALLBACK_DEVICE_LATENCY_MS: i32 = 40;
+// Largest device latency compensated for; the ring is sized to match
+const MAX_DEVICE_LATENCY_MS: i32 = 500;
+
+// Arrow-key steps of the replay scrub
+const SCRUB_STEP_SECS: f32 = 0.1;
+const SCRUB_BIG_STEP_SECS: f32 = 1.0;
+// Seconds the arrow keys jump playback by (Shift for the big step)
+const SEEK_STEP_SECS: f32 = 5.0;
+const SEEK_BIG_STEP_SECS: f32 = 30.0;
+// Shift+Up/Down step of the visual gain (dB)
+const VISUAL_GAIN_STEP_DB: f32 = 1.0;
+
+// The overall level sparkline next to the time: one cell per second over
+// the last minute, drawn from this level up to full scale
+const LEVEL_SPARKLINE_SECS: usize = 60;
+const LEVEL_SPARKLINE_FLOOR_DB: f32 = -60.0;
+
+// How far bars jump on an onset with bounce on, and how long they take to settle
+const BOUNCE_KICK: f32 = 1.15;
+const BOUNCE_DECAY_SECS: f32 = 0.1;
+
+// Key estimate in the header: the audio it's taken from, how much of it is
+// needed first, and how often the shown estimate may change
+const KEY_WINDOW_SECS: f32 = 30.0;
+const KEY_MIN_SECS: f32 = 5.0;
+const KEY_UPDATE_SECS: f64 = 3.0;
+
+// Events the --verbose session log keeps for printing on exit
+const SESSION_LOG_LINES: usize = 1000;
+
+// How long the metronome dot stays lit after each beat, as a fraction of the beat
+const METRONOME_PULSE: f32 = 0.2;
+
+// Samples at or above this level count as clipped
+const CLIP_LEVEL: f32 = 0.999;
+// How long the spectrum border stays lit for a flash alert
+const ALERT_FLASH: std::time::Duration = std::time::Duration::from_millis(120);
+
+// Cells of the stereo phase meter between -1 and +1, and how far the shown
+// correlation moves toward each new reading
+const PHASE_METER_CELLS: usize = 21;
+const PHASE_SMOOTHING: f32 = 0.2;
+
+// Rows of the 'D' gain curve, above its axis
+const GAIN_CURVE_ROWS: u16 = 8;
+// Width of the statistics table without the track names
+const STATS_COLUMNS_WIDTH: usize = 65;
+// Width of the theme editor ('T'), borders included
+const THEME_EDITOR_WIDTH: u16 = 40;
+
+// The crest factor meter spans 0 to CREST_METER_MAX_DB in this many cells;
+// below CREST_SQUASHED_DB the audio is heavily limited, above CREST_DYNAMIC_DB
+// it breathes
+const CREST_METER_CELLS: usize = 8;
+const CREST_METER_MAX_DB: f32 = 20.0;
+const CREST_SQUASHED_DB: f32 = 6.0;
+const CREST_DYNAMIC_DB: f32 = 10.0;
+// Cells of the spectral flux ("activity") meter
+const ACTIVITY_METER_CELLS: usize = 8;
+
+// The debug overlay's buffer gauge spans 0 to BUFFER_GAUGE_MAX_MS of audio
+// buffered ahead of the speakers in this many cells
+const BUFFER_GAUGE_CELLS: usize = 10;
+const BUFFER_GAUGE_MAX_MS: f32 = 250.0;
+
+// --oneline redraws its row this often, plenty for 16 cells and easy on a
+// status bar reading every line; piped, the row is this many columns
+const ONELINE_FPS: u32 = 20;
+const ONELINE_PIPED_WIDTH: usize = 40;
+
+// Longest a suspend waits for the player to report the pause it asked for;
+// the player thread takes commands every 20 ms
+const SUSPEND_PAUSE_WAIT: std::time::Duration = std::time::Duration::from_millis(200);
+
+// Frames a late frame may be behind before the ones it missed count as
+// dropped; one frame of jitter is normal scheduling, not overload
+const MAX_LATE_FRAMES: f32 = 1.0;
+
+// How long a transient message (config reload, queued file) stays in the progress block
+const STATUS_DURATION: std::time::Duration = std::time::Duration::from_secs(4);
+
+// Peaks labeled with 'P'
+const PEAK_LABELS: usize = 4;
+
+// Marks of the frozen reference curves ('F'), one per slot
+const REFERENCE_MARKS: [&str; reference::SLOTS] = ["•", "◦", "×"];
+
+// Columns horizontal bars give up for the legend numbers beside them
+const AXIS_GUTTER: usize = 4;
+
+// Most playlist entries shown at once in the playlist panel
+const PLAYLIST_PANEL_ROWS: usize = 8;
+
+// Band of the bars in `area` under the cell at `column`, `row`; None
+// outside them
+fn band_at(area: Rect, column: u16, row: u16, num_bands: usize, orientation: Orientation) -> Option<usize> {
+    if !area.contains(ratatui::layout::Position { x: column, y: row }) {
+        return None;
+    }
+    let (col, row) = ((column - area.x) as usize, (row - area.y) as usize);
+    orientation
+        .cell(col, row, area.width as usize, area.height as usize, num_bands)
+        .map(|(band, _)| band)
+}
 
-// Custom wrapper that captures audio samples while playing
-struct SampleCapture<I> {
-    source: I,
-    buffer: Arc<Mutex<Vec<f32>>>,
-    sample_rate: u32,
+// Legend numbers down the base side of horizontal bars, one per row of a
+// `rows` high spectrum: each legend's number at the row of the band its
+// "Band Details" range starts at, a tick line elsewhere. AXIS_GUTTER wide.
+fn axis_labels(num_bands: usize, num_legend_bands: usize, rows: usize, orientation: Orientation) -> Vec<String> {
+    let digits = AXIS_GUTTER - 1;
+    let mut labels: Vec<String> = (0..rows)
+        .map(|_| match orientation {
+            Orientation::RightLeft => format!("│{}", " ".repeat(digits)),
+            _ => format!("{}│", " ".repeat(digits)),
+        })
+        .collect();
+    for i in 0..num_legend_bands {
+        let band = (i * num_bands) / num_legend_bands.max(1);
+        // First row showing that band (the same mapping as the bars)
+        let Some(row) = (0..rows).find(|&row| row * num_bands.max(1) / rows >= band) else {
+            continue;
+        };
+        labels[row] = match orientation {
+            Orientation::RightLeft => format!("├{:<digits$}", i + 1),
+            _ => format!("{:>digits$}┤", i + 1),
+        };
+    }
+    labels
 }
 
-impl<I> SampleCapture<I> {
-    fn new(source: I, sample_rate: u32) -> (Self, Arc<Mutex<Vec<f32>>>) {
-        let buffer = Arc::new(Mutex::new(Vec::new()));
-        let capture = SampleCapture {
-            source,
-            buffer: buffer.clone(),
-            sample_rate,
+// "|0%──────|25%─────|50% ..." along the bars of a horizontal spectrum,
+// `gutter` columns in from the side the bars start at, for the legend strip
+fn level_scale(width: usize, gutter: usize, orientation: Orientation) -> Line<'static> {
+    let length = width.saturating_sub(gutter);
+    let mut scale = vec!['─'; length];
+    for percent in [0, 25, 50, 75, 100] {
+        let label: Vec<char> = match orientation {
+            Orientation::RightLeft => format!("{}%|", percent),
+            _ => format!("|{}%", percent),
+        }
+        .chars()
+        .collect();
+        let tick = (percent * length.saturating_sub(1) + 50) / 100;
+        let start = match orientation {
+            Orientation::RightLeft => (length - 1 - tick).saturating_sub(label.len() - 1),
+            _ => tick.min(length.saturating_sub(label.len())),
+        };
+        for (cell, c) in scale.iter_mut().skip(start).zip(label) {
+            *cell = c;
+        }
+    }
+    let scale: String = scale.into_iter().collect();
+    let padding = " ".repeat(gutter.min(width));
+    match orientation {
+        Orientation::RightLeft => Line::from(format!("{}{}", scale, padding)),
+        _ => Line::from(format!("{}{}", padding, scale)),
+    }
+}
+
+// Place a frequency label at the end of each peak's bar, loudest first. A
+// label that would overlap one already placed moves a row further out
+// (vertical bars) or is left out, as is one that runs out of room.
+fn peak_labels(
+    peaks: &[analysis::Peak],
+    bands: &[f32],
+    range: FreqRange,
+    inner: Rect,
+    orientation: Orientation,
+    locale: Locale,
+) -> Vec<(Rect, String)> {
+    let num_bands = bands.len();
+    let (width, height) = (inner.width as usize, inner.height as usize);
+    let mut placed: Vec<(Rect, String)> = Vec::new();
+    if num_bands == 0 || width == 0 || height == 0 {
+        return placed;
+    }
+
+    for peak in peaks {
+        let band = range.band_of(peak.freq, num_bands);
+        let text = locale.hz(peak.freq);
+        let len = display::width(&text) as u16;
+        if len > inner.width {
+            continue;
+        }
+        let bar_length = orientation.bar_length(width, height);
+        let bar = spectrum::length(bands[band], bar_length).min(bar_length) as u16;
+        let overlaps = |x: u16, y: u16| {
+            placed.iter().any(|(rect, _)| rect.y == y && x < rect.right() + 1 && rect.x < x + len + 1)
+        };
+
+        if orientation.horizontal() {
+            // On the band's row, just past the end of the bar
+            let row = (band * height + height / 2) / num_bands;
+            let y = inner.y + row as u16;
+            let x = match orientation {
+                Orientation::RightLeft => inner.right().checked_sub(bar + 1 + len).filter(|&x| x >= inner.x),
+                _ => Some(inner.x + bar + 1).filter(|&x| x + len <= inner.right()),
+            };
+            if let Some(x) = x.filter(|&x| !overlaps(x, y)) {
+                placed.push((Rect::new(x, y, len, 1), text));
+            }
+            continue;
+        }
+
+        // Centered over the band's columns, kept inside the borders
+        let column = (band * width + width / 2) / num_bands;
+        let x = (inner.x + column as u16).saturating_sub(len / 2).clamp(inner.x, inner.right() - len);
+
+        // Just past the bar's tip, moving further out while it overlaps
+        let (mut y, outward): (u16, i32) = match orientation {
+            Orientation::TopDown => ((inner.y + bar).min(inner.bottom() - 1), 1),
+            _ => (inner.bottom().saturating_sub(bar + 1).max(inner.y), -1),
         };
-        (capture, buffer)
+        while overlaps(x, y) {
+            let next = y as i32 + outward;
+            if next < inner.y as i32 || next >= inner.bottom() as i32 {
+                break;
+            }
+            y = next as u16;
+        }
+        if !overlaps(x, y) {
+            placed.push((Rect::new(x, y, len, 1), text));
+        }
+    }
+    placed
+}
+
+// User-selected behavior for the visualizer thread
+struct VisualizerOptions {
+    // Settings from the config file/environment, compared against on reload
+    config: config::Config,
+    // Re-reads the config when the file changes or on SIGUSR1
+    watcher: config::Watcher,
+    // Command-line flags, laid over every reloaded config as over the first
+    flags: config::Flags,
+    // Config profile selected with --profile, shown in the header
+    profile: Option<String>,
+    // --config, where toggled settings like shuffle are saved (None for the default file)
+    config_path: Option<PathBuf>,
+    fft_size: usize,
+    smoothing: f32,
+    // Pause between frames (1 / fps)
+    frame_interval: std::time::Duration,
+    // Manual offset, added to the device latency
+    latency_offset_ms: i32,
+    // Compensation for the output device's buffer, and whether the device
+    // reported it (false: FALLBACK_DEVICE_LATENCY_MS)
+    device_latency_ms: i32,
+    device_latency_reported: bool,
+    // Rate the output device runs at; files at another rate are resampled
+    // for playback only
+    device_sample_rate: u32,
+    calibrate: bool,
+    // Kick the bars on onsets (--bounce or the config's bounce)
+    bounce: bool,
+    // Never interpolate between analysis frames (--raw or the config's raw)
+    raw: bool,
+    // Where the metronome publishes its beat grid for the audible click
+    // (None unless metronome_click was on at startup)
+    metronome_grid: Option<metronome::SharedGrid>,
+    // Hidden --render-delay: extra time spent on every frame
+    render_delay: Option<std::time::Duration>,
+    // None disables the idle animation
+    idle_timeout: Option<std::time::Duration>,
+    // The queue when playing files one after another (None when comparing
+    // or calibrating)
+    playlist: Option<playlist::SharedPlaylist>,
+    // How queued files are read
+    reading: Reading,
+    // The --record-audio recording, for its progress
+    recorder: Option<record::Recorder>,
+    // The echo on the playback, set from the 'E' panel
+    echo: echo::SharedEcho,
+    // Where --cava-raw frames go
+    cava: Option<cava::CavaOutput>,
+    // Requests from other programs (--control)
+    #[cfg(unix)]
+    control: Option<control::Server>,
+    // Track changes from the player thread
+    player_events: mpsc::Receiver<PlayerEvent>,
+    // Index of the stream being heard, switched with 'x' when comparing files
+    audible: Arc<AtomicUsize>,
+    // Pause and seek requests for the player thread
+    player_commands: mpsc::Sender<PlayerCommand>,
+    // Shown in the progress block, e.g. when compared files differ in length
+    notice: Option<String>,
+    // Return once playback is over instead of waiting for 'q' (--auto-exit)
+    auto_exit: bool,
+    // Where 'S' saves the spectrum (--export-svg; None names a file by the time)
+    export_svg: Option<PathBuf>,
+    // Styles of everything on screen, adapted to the terminal's colors
+    theme: Theme,
+    // Draw on the alternate screen (false with --no-alt-screen)
+    alternate_screen: bool,
+    // --measurement: every display shaping stage off, laid over the config
+    // again on each reload
+    measurement: Option<Shaping>,
+    // Discord Rich Presence (None when calibrating)
+    #[cfg(feature = "discord")]
+    presence: Option<discord::Presence>,
+}
+
+// Where one spectrum view gets its audio from
+#[derive(Clone)]
+struct StreamInput {
+    name: String,
+    // File details shown along the bottom of the spectrum
+    info: String,
+    buffer: SharedRing,
+    // Crest factor and dynamic range of everything captured from the file
+    dynamics: dynamics::SharedDynamics,
+    // Buffer fill and underruns while the file is being pulled
+    flow: flow::SharedFlow,
+    sample_rate: u32,
+    // Channels in the file, for the layout in the spectrum title
+    channels: u16,
+    // Seconds (infinite for the click track); a segment's length when
+    // playing one
+    duration: f32,
+    // Where in the file a segment (--start/--end) starts, in seconds
+    start: Option<f32>,
+    // Whole-track envelope for the overview strip (None when there is no file)
+    overview: Option<overview::SharedEnvelope>,
+    // Shown when the track starts: the file is cut short
+    warning: Option<String>,
+}
+
+// Published by the player thread as it works through the playlist
+#[derive(Clone)]
+enum PlayerEvent {
+    // Playlist entry `index` started playing; for the visualizer it replaces
+    // the first stream's input. `seamless` when it follows the previous entry
+    // without a jump (false after next/previous or removing the playing entry).
+    TrackStarted { index: usize, input: StreamInput, seamless: bool },
+    // Playlist entry `index` played to its end or was cut short
+    TrackEnded { index: usize },
+    // An entry couldn't be opened and was skipped
+    TrackFailed(String),
+    // Everything has played (end of the playlist or of the shorter compared file)
+    Finished,
+    // Playback paused or resumed at a position in the track
+    Paused(std::time::Duration),
+    Resumed(std::time::Duration),
+    // Playback jumped to a position in the track
+    Seeked(std::time::Duration),
+    // A seek couldn't be done (e.g. the click track can't seek)
+    SeekFailed(String),
+    // The output stopped pulling samples while playing; the device is being
+    // reopened
+    OutputStalled,
+    // The device was reopened and the first stream's track goes on from
+    // `position` in `input`, which replaces its input
+    OutputReopened { input: StreamInput, position: std::time::Duration },
+    // The output stalled for good: playback stops and the visualizer closes,
+    // with this for the terminal once it's back
+    OutputLost(String),
+}
+
+// Sent by the visualizer to the player thread
+enum PlayerCommand {
+    TogglePause,
+    // Jump by this many seconds, back when negative
+    Seek(f32),
+}
+
+// What the visualizer reports once it has closed
+struct VisualizerSummary {
+    // Latency offset in effect at exit (it can be nudged live)
+    latency_offset_ms: i32,
+    // Frames skipped because analysis and drawing couldn't keep up
+    dropped_frames: u64,
+    // Name and DR figure of each file shown, in order (None when too short)
+    dynamic_range: Vec<(String, Option<f32>)>,
+    // What each track of the first stream amounted to (--stats, --stats-json)
+    stats: stats::Session,
+    // Why playback had to stop, when it wasn't the user's doing
+    error: Option<String>,
+    // Closed because the terminal went away (SIGHUP)
+    hung_up: bool,
+}
+
+// Ctrl+Z or SIGTSTP while playing: playback is paused first, so the output
+// isn't taken for stalled when everything starts again, then the terminal
+// handed back and the process stopped. Once continued (fg) the terminal is
+// set up again and playback goes on if it was playing before.
+#[derive(Default)]
+struct Suspend {
+    // Asked for, to happen once the player has paused or by then regardless
+    pending: Option<std::time::Instant>,
+    // Playback was paused for the suspend and resumes after it
+    resume: bool,
+}
+
+impl Suspend {
+    fn request(&mut self, playing: bool, commands: &mpsc::Sender<PlayerCommand>) {
+        if self.pending.is_some() {
+            return;
+        }
+        let mut deadline = std::time::Instant::now();
+        if playing {
+            let _ = commands.send(PlayerCommand::TogglePause);
+            deadline += SUSPEND_PAUSE_WAIT;
+        }
+        self.pending = Some(deadline);
+        self.resume = playing;
+    }
+
+    // Suspend when it is due, or set the terminal up again after a stop
+    // from outside (SIGSTOP); true when the screen has to be redrawn
+    // from scratch
+    fn run(
+        &mut self,
+        paused: bool,
+        guard: &mut terminal::TerminalGuard,
+        signals: &terminal::Signals,
+        commands: &mpsc::Sender<PlayerCommand>,
+    ) -> std::io::Result<bool> {
+        if self.pending.is_some_and(|deadline| paused || std::time::Instant::now() >= deadline) {
+            self.pending = None;
+            guard.suspend()?;
+            // Continued by now, and the terminal already set up for it
+            signals.take_continued();
+            if std::mem::take(&mut self.resume) {
+                let _ = commands.send(PlayerCommand::TogglePause);
+            }
+            return Ok(true);
+        }
+        if signals.take_continued() {
+            guard.reset()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+// Per-stream analysis state inside the visualizer
+struct StreamView {
+    input: StreamInput,
+    analyzer: analysis::Analyzer,
+    history: history::FrameHistory,
+    peaks: analysis::PeakTracker,
+    onsets: onset::OnsetDetector,
+    // The last two analysis frames, drawn in between when analysis runs
+    // slower than drawing
+    frames: interpolate::FrameInterpolator,
+    // Audio time at the end of the window last taken from the ring, the
+    // time of what is being heard; None while there was none
+    window_time: Option<f32>,
+    // The signal picked with 'C' over the same stretch, analyzed in place of
+    // the window unless it is the downmix (or the audio is mono)
+    channel_window: Option<Vec<f32>>,
+}
+
+// The terminal's colors (none with --no-color) with the configured palette
+fn theme_for(config: &config::Config, no_color: bool) -> Theme {
+    let mut theme = Theme::detect(no_color).map_colors(terminal::adapt);
+    theme.palette = config.palette(&config.theme).unwrap_or_default();
+    theme
+}
+
+// The settings an orientation can have its own of, as configured
+fn base_settings(config: &config::Config) -> modes::Settings {
+    modes::Settings {
+        smoothing: config.smoothing,
+        smoother: config.smoother,
+        tilt: config.tilt.clone(),
+        gradient: config.gradient,
     }
 }
 
-impl<I> Iterator for SampleCapture<I>
-where
-    I: Source<Item = f32>,
-{
-    type Item = f32;
+// The smoother `settings` call for, with the configured attack and release
+fn smoother_for(settings: &modes::Settings, config: &config::Config) -> Box<dyn Smoother> {
+    settings.smoother.build(settings.smoothing, config.attack_ms / 1000.0, config.release_ms / 1000.0)
+}
+
+// Perform FFT and visualize frequencies with ratatui
+fn visualize_frequencies(
+    inputs: Vec<StreamInput>,
+    mut options: VisualizerOptions,
+) -> Result<VisualizerSummary, Box<dyn std::error::Error>> {
+    use std::time::Instant;
+
+    // Setup terminal
+    let signals = terminal::Signals::install();
+    let mut guard = terminal::TerminalGuard::enter(terminal::Setup {
+        alternate_screen: options.alternate_screen,
+        ..Default::default()
+    })?;
+    let mut suspend = Suspend::default();
+    let backend = CrosstermBackend::new(std::io::stdout());
+    let mut terminal = Terminal::new(backend)?;
+
+    // Where playback is, following what the player reports; restarted
+    // whenever the playlist moves on to the next track
+    let mut playhead = transport::Playhead::start(Instant::now(), false);
+    // Underrun time of the first stream's track already taken off the playhead
+    let mut lost_seen = std::time::Duration::ZERO;
+    // Buffer fill, underruns and analysis details ('d')
+    let mut show_debug = false;
+    let mut show_gain_curve = false;
+    let calibrate = options.calibrate;
+    let mut latency_offset_ms = options.latency_offset_ms;
+    let mut silence = options.idle_timeout.map(idle::SilenceDetector::new);
+    let mut frame_interval = options.frame_interval;
+    // Time between analyses (zero: every frame) and when the next is due
+    let mut analysis_interval = analysis_interval_for(options.config.analysis_fps);
+    let mut next_analysis = Instant::now();
+    let mut raw = options.raw;
+    // Each orientation ('o' cycles them) can have its own smoothing, tilt
+    // and gradient; `settings` are the ones in effect. A preset picked with
+    // 't' is the current orientation's tilt until the cycle comes back round
+    // to the configured one.
+    let mut modes = modes::ModeMemory::new(options.config.orientation, options.config.modes.iter().cloned());
+    let mut settings = modes.settings(&base_settings(&options.config));
+    options.smoothing = settings.smoothing;
+    let mut smoother_timing = (options.config.attack_ms, options.config.release_ms);
+    let mut tilt_preset = modes.overrides(modes.mode()).tilt.as_ref().and_then(tilt::Preset::of);
+    let mut display_tilt = settings.tilt.clone();
+
+    // Message in the progress block, e.g. after a config reload; errors stay
+    // until the next reload, anything else disappears at the deadline. A
+    // file cut short says so from the start
+    let mut status: Option<(String, Option<Instant>)> = inputs
+        .iter()
+        .find_map(|input| input.warning.clone())
+        .map(|warning| (warning, Some(Instant::now() + STATUS_DURATION)));
+
+    // One analysis pipeline per input, each remembering recent frames for
+    // the replay scrub; `scrub_time` is the playback time being inspected
+    // while frozen, None while live
+    let mut streams: Vec<StreamView> = inputs
+        .into_iter()
+        .map(|input| StreamView {
+            analyzer: analysis::Analyzer::new(
+                input.sample_rate,
+                options.fft_size,
+                options.smoothing,
+                display_tilt.clone(),
+            ),
+            history: history::FrameHistory::for_rate(options.config.history_secs, options.config.fps),
+            peaks: analysis::PeakTracker::default(),
+            onsets: onset::OnsetDetector::new(),
+            frames: interpolate::FrameInterpolator::new(),
+            window_time: None,
+            channel_window: None,
+            input,
+        })
+        .collect();
+    for stream in &mut streams {
+        stream.analyzer.set_smoother(smoother_for(&settings, &options.config));
+        stream.analyzer.set_window(options.measurement.as_ref().map_or_else(Default::default, |shaping| shaping.window));
+        stream.analyzer.set_max_display_freq(options.config.max_display_freq());
+    }
+    // When the last analysis ran, for how far the smoothing moves the bars
+    let mut analyzed_at: Option<Instant> = None;
+    let mut scrub_time: Option<f32> = None;
+    // Band picked out with Ctrl/Alt+arrows for an exact frequency and level readout
+    let mut freq_cursor: Option<usize> = None;
+    // Part of the frequency range the bars are spread over ('[', ']' or a
+    // mouse drag), the bars of each spectrum as last drawn, and the drag
+    // going on: the spectrum it's in and the bands it started and is at
+    let mut zoom = zoom::Zoom::default();
+    let mut bars_areas = vec![Rect::default(); streams.len()];
+    let mut drag: Option<(usize, usize, usize)> = None;
+
+    // Dynamic number of bands based on terminal width (will be updated each
+    // frame), held while a resize is still going on
+    let mut num_bands = 60;
+    let mut band_settle = layout::BandSettle::default();
+
+    // Playlist panel ('l'), the "add to queue" prompt ('a') and the first
+    // 'd' of a "dd" removal
+    let mut show_playlist = false;
+    // Frequency labels on the strongest peaks ('P')
+    let mut show_peaks = options.measurement.as_ref().is_some_and(|shaping| shaping.peaks);
+    // Heat trails behind the bars ('w'), one heat map per spectrum, and
+    // when the bars were last drawn, for how far the trails have cooled
+    let mut trails: Option<Vec<HeatMap>> = None;
+    let mut drawn_at: Option<Instant> = None;
+    // Spectra frozen with 'F' to compare the live one against
+    let mut references = reference::References::new();
+    let mut prompt: Option<(PromptFor, prompt::LineEditor)> = None;
+    let mut pending_delete = false;
+    // The gradient being edited ('T'), previewed on the bars as it changes
+    let mut theme_editor: Option<palette::Editor> = None;
+    // The echo panel ('E'): the row picked, and the settings it opened on,
+    // to save only what changed. Changes are heard as they're made
+    let mut echo_panel: Option<(echo::Field, echo::EchoSettings)> = None;
+
+    // When the next frame is due
+    let mut next_frame = Instant::now();
+    // Frames that were due while the previous one was still being drawn
+    let mut dropped_frames: u64 = 0;
+    // Onset kick on the drawn bars, timed on its own clock so track changes don't disturb it
+    let clock = Instant::now();
+    let mut bounce = options.bounce.then(|| onset::Bounce::new(BOUNCE_KICK, BOUNCE_DECAY_SECS));
+    // Tempo and beat grid of what is being heard, shown by the metronome ('m')
+    let mut beats = onset::BeatTracker::new();
+    // How much the spectrum being heard is changing, from the onset
+    // detector's flux, for the activity meter
+    let mut activity = onset::Activity::new();
+    // Key of what is being heard, and the estimate on screen with when it was taken
+    let mut key_estimator = key::KeyEstimator::new(KEY_WINDOW_SECS);
+    let mut shown_key: Option<(key::Key, f64)> = None;
+    let mut show_metronome = false;
+    // Which signal of the audio the spectrum shows ('C'); playback is left alone
+    let mut channel = stereo::Channel::default();
+    // Clipping and beats go out on a bus; the alerts (bell, border flash)
+    // are one subscriber
+    let mut analysis_events = events::EventBus::new();
+    let alert_events = analysis_events.subscribe();
+    let mut alerts = alert::Alerts::new(options.config.alert.clone());
+    let mut alert_flash_until: Option<Instant> = None;
+    // Per-second levels for the progress sparkline, refreshed once a second
+    let mut level_series: Vec<f32> = Vec::new();
+    let mut level_series_at: Option<Instant> = None;
+    // Left/right correlation of what is being heard: None for mono input,
+    // Some(None) while a stereo one is silent
+    let mut phase: Option<Option<f32>> = None;
+    // DR figures of the tracks the first stream has moved on from
+    let mut dynamic_range = Vec::new();
+    // Set when the player reports that playback is over, until a track
+    // starts again ('n' from the finished state)
+    let mut finished: Option<finish::Finish> = None;
+    // Statistics of each track the first stream plays, shown once playback
+    // is over; clipping comes in on the analysis bus
+    let mut session = stats::Session::default();
+    if !calibrate {
+        session.start(&streams[0].input.name, streams[0].input.duration);
+    }
+    let stats_events = analysis_events.subscribe();
+    let mut listened_at = Instant::now();
+    // The statistics screen's selected row, and whether it shows (Esc hides it)
+    let mut stats_selected: usize = 0;
+    let mut show_stats = true;
+    // Why playback stopped, when the player had to give up
+    let mut error = None;
+    // What is playing, in the terminal window's title (terminal_title)
+    let mut window_title = terminal::WindowTitle::default();
+
+    // Styles by role; plain with NO_COLOR or --no-color. The bars' gradient
+    // changes with Ctrl+1..5 and the theme editor
+    let mut theme = options.theme;
+
+    'frames: loop {
+        // SIGTERM and SIGHUP quit as 'q' does
+        if signals.quit() {
+            break 'frames;
+        }
+        if signals.take_stop() {
+            suspend.request(finished.is_none() && !playhead.is_paused(), &options.player_commands);
+        }
+        // How numbers read this frame (decimal_separator changes live)
+        let locale = Locale::new(options.config.decimal_separator);
+        // Check for Ctrl+C or 'q' key, '+'/'-' to nudge the latency offset,
+        // 'h' / arrows / Esc for the replay scrub, and 'x' to switch compared files
+        let scrub_history = &streams[0].history;
+        // Wait for input until the next frame is due: a key wakes the loop at
+        // once, and everything queued is handled before analyzing and drawing
+        let timeout = next_frame.saturating_duration_since(Instant::now());
+        // Scrub and seek steps from a held arrow key add up and are applied
+        // once below
+        let mut scrub_steps = input::Steps::default();
+        let mut seek_steps = input::Steps::default();
+        // 'F' freezes the bands on screen once they are known below, and 'S'
+        // saves them as an image
+        let mut freeze = false;
+        let mut export = false;
+        for event in input::drain(&mut input::Terminal, timeout)? {
+            // Windows also reports key releases; only presses (and repeats
+            // of a held key) are actions
+            let key = match event {
+                Event::Key(key) => key,
+                // Dragging across the bars picks a range to zoom to
+                Event::Mouse(mouse) if prompt.is_none() => {
+                    let range = streams[0].analyzer.range();
+                    match mouse.kind {
+                        MouseEventKind::Down(MouseButton::Left) => {
+                            drag = bars_areas.iter().enumerate().find_map(|(i, area)| {
+                                let band = band_at(*area, mouse.column, mouse.row, num_bands, options.config.orientation)?;
+                                Some((i, band, band))
+                            });
+                        }
+                        MouseEventKind::Drag(MouseButton::Left) => {
+                            if let Some((i, start, end)) = drag.as_mut() {
+                                // Past the edge counts as the last band that way
+                                let area = bars_areas[*i];
+                                let column = mouse.column.clamp(area.x, area.right().saturating_sub(1));
+                                let row = mouse.row.clamp(area.y, area.bottom().saturating_sub(1));
+                                *end = band_at(area, column, row, num_bands, options.config.orientation).unwrap_or(*end);
+                                let selected = range.bands(*start, *end, num_bands);
+                                status = Some((
+                                    format!("Zoom to {} (release to zoom)", zoom::label(selected, locale)),
+                                    Some(Instant::now() + STATUS_DURATION),
+                                ));
+                            }
+                        }
+                        MouseEventKind::Up(MouseButton::Left) => {
+                            if let Some((_, start, end)) = drag.take()
+                                && start != end
+                            {
+                                let selected = range.bands(start, end, num_bands);
+                                let full = FreqRange::full(streams[0].analyzer.max_freq());
+                                zoom.select(selected.low, selected.high, full, Instant::now());
+                                freq_cursor = None;
+                                status = None;
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            // Ctrl+C quits from anywhere, 'q' from anywhere but the prompt
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+                || key.code == KeyCode::Char('q') && prompt.is_none()
+            {
+                break 'frames;
+            }
+            // Raw mode delivers Ctrl+Z as a key rather than SIGTSTP
+            if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                suspend.request(finished.is_none() && !playhead.is_paused(), &options.player_commands);
+                continue;
+            }
+            // The prompt takes every other key while it's open
+            if let Some((purpose, editor)) = prompt.as_mut() {
+                match (editor.handle_key(key), *purpose) {
+                    (prompt::PromptAction::Editing, _) => {}
+                    (prompt::PromptAction::Cancel, _) => prompt = None,
+                    (prompt::PromptAction::Submit(text), PromptFor::Queue) => {
+                        prompt = None;
+                        status = (!text.trim().is_empty()).then(|| {
+                            let (Ok(message) | Err(message)) = queue_file(options.playlist.as_ref(), options.reading, &text);
+                            (message, Some(Instant::now() + STATUS_DURATION))
+                        });
+                    }
+                    (prompt::PromptAction::Submit(name), PromptFor::ThemeName) => {
+                        let name = name.trim();
+                        let message = match (palette::check_name(name), &theme_editor) {
+                            (Err(e), _) => e,
+                            (Ok(()), None) => continue,
+                            (Ok(()), Some(editor)) => {
+                                match config::save_theme(options.config_path.as_deref(), name, &editor.gradient()) {
+                                    Ok(path) => {
+                                        options.watcher.refresh();
+                                        options.config.add_theme(name, editor.gradient());
+                                        theme_editor = None;
+                                        prompt = None;
+                                        format!("Theme \"{}\" saved to {}", name, path.display())
+                                    }
+                                    Err(e) => format!("Theme not saved: {}", e),
+                                }
+                            }
+                        };
+                        status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+                    }
+                }
+                continue;
+            }
+            // The theme editor takes every other key while it's open; Esc
+            // puts the gradient back as it was
+            if let Some(editor) = theme_editor.as_mut() {
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => editor.move_selection(-1),
+                    KeyCode::Down | KeyCode::Char('j') => editor.move_selection(1),
+                    KeyCode::Left | KeyCode::Char('h') => editor.adjust(-1),
+                    KeyCode::Right | KeyCode::Char('l') => editor.adjust(1),
+                    KeyCode::Enter => prompt = Some((PromptFor::ThemeName, prompt::LineEditor::default())),
+                    KeyCode::Esc | KeyCode::Char('T') => {
+                        theme.palette = editor.original();
+                        theme_editor = None;
+                        continue;
+                    }
+                    _ => {}
+                }
+                theme.palette = editor.gradient();
+                continue;
+            }
+            // So does the echo panel; closing it keeps what was set
+            if let Some((field, opened)) = echo_panel.as_mut() {
+                let steps = match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        *field = field.moved(-1);
+                        0
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        *field = field.moved(1);
+                        0
+                    }
+                    KeyCode::Left | KeyCode::Char('h') => -1,
+                    KeyCode::Right | KeyCode::Char('l') => 1,
+                    KeyCode::Esc | KeyCode::Char('E') => {
+                        let settings = options.echo.lock().map_or(*opened, |echo| *echo);
+                        if settings != *opened {
+                            options.config.echo = settings;
+                            let message = format!("Echo {}", settings.value_text(echo::Field::Enabled, locale));
+                            let message = match config::save_echo(options.config_path.as_deref(), &settings) {
+                                Ok(_) => {
+                                    options.watcher.refresh();
+                                    message
+                                }
+                                Err(e) => format!("{} (not saved: {})", message, e),
+                            };
+                            status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+                        }
+                        echo_panel = None;
+                        continue;
+                    }
+                    _ => 0,
+                };
+                if steps != 0
+                    && let Ok(mut echo) = options.echo.lock()
+                {
+                    echo.adjust(*field, steps);
+                }
+                continue;
+            }
+            let delete_armed = std::mem::take(&mut pending_delete);
+
+            match key.code {
+                KeyCode::Char('+') | KeyCode::Char('=') => {
+                    latency_offset_ms = (latency_offset_ms + LATENCY_STEP_MS).min(capture::MAX_OFFSET_MS);
+                }
+                KeyCode::Char('-') | KeyCode::Char('_') => {
+                    // Past minus the device latency the window is already the newest audio
+                    let floor = capture::min_offset_ms(options.device_latency_ms).max(-capture::MAX_OFFSET_MS);
+                    if latency_offset_ms - LATENCY_STEP_MS < floor {
+                        status = Some((
+                            format!("The visuals can't run any earlier: at {} they show the newest audio", locale.signed_ms(floor as f32)),
+                            Some(Instant::now() + STATUS_DURATION),
+                        ));
+                    }
+                    latency_offset_ms = (latency_offset_ms - LATENCY_STEP_MS).max(floor);
+                }
+                KeyCode::Char('h') => {
+                    // Freeze on the newest frame, or snap back to live
+                    scrub_time = match scrub_time {
+                        Some(_) => None,
+                        None => scrub_history.latest().map(|frame| frame.time),
+                    };
+                }
+                KeyCode::Esc if stats_open(finished, show_stats && !show_playlist, &session, options.auto_exit) => {
+                    show_stats = false
+                }
+                KeyCode::Esc => {
+                    scrub_time = None;
+                    freq_cursor = None;
+                }
+                KeyCode::Char(' ') => {
+                    let _ = options.player_commands.send(PlayerCommand::TogglePause);
+                }
+                KeyCode::Char('a') if options.playlist.is_some() => {
+                    prompt = Some((PromptFor::Queue, prompt::LineEditor::default()))
+                }
+                KeyCode::Char('l') if options.playlist.is_some() => show_playlist = !show_playlist,
+                // Measuring, what would shape the display stays as it is
+                KeyCode::Char('P' | 't' | 'e') if options.measurement.is_some() => {
+                    let message = "Measurement mode: the peak labels, tilt and smoothing stay as they are";
+                    status = Some((message.to_string(), Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Up | KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) && options.measurement.is_some() => {
+                    status = Some(("Measurement mode: no visual gain".to_string(), Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Char('P') => show_peaks = !show_peaks,
+                KeyCode::Char('w') => {
+                    trails = match trails {
+                        Some(_) => None,
+                        None => Some(vec![HeatMap::new(heat::TRAIL_SECS); streams.len()]),
+                    };
+                    let message = format!("Heat trails {}", if trails.is_some() { "on" } else { "off" });
+                    status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Char('F') => freeze = true,
+                KeyCode::Char('S') => export = true,
+                // Shift+1..3, reported as the shifted character by most terminals
+                KeyCode::Char(c @ ('!' | '@' | '#')) => {
+                    references.select(['!', '@', '#'].iter().position(|&shifted| shifted == c).unwrap_or(0));
+                    status = Some((
+                        format!("Reference slot {} ('F' freezes into it)", references.selected() + 1),
+                        Some(Instant::now() + STATUS_DURATION),
+                    ));
+                }
+                // Theme slots (theme_slots); not every terminal sends Ctrl+digits
+                KeyCode::Char(c @ '1'..='5') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    let slot = c as usize - '1' as usize;
+                    let message = match options.config.theme_slots.get(slot) {
+                        Some(name) if !theme.is_colored() => {
+                            format!("Theme {}: {} (but colors are off)", slot + 1, name)
+                        }
+                        Some(name) => match options.config.palette(name) {
+                            Some(gradient) => {
+                                theme.palette = gradient;
+                                format!("Theme {}: {}", slot + 1, name)
+                            }
+                            None => format!("No theme named {} (slot {})", name, slot + 1),
+                        },
+                        None => format!("No theme in slot {} (theme_slots in the config file)", slot + 1),
+                    };
+                    status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Char('T') if !theme.is_colored() => {
+                    let message = "Colors are off (NO_COLOR or --no-color): no gradient to edit";
+                    status = Some((message.to_string(), Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Char('T') => {
+                    theme_editor = Some(palette::Editor::new(theme.palette));
+                    status = None;
+                }
+                KeyCode::Char('E') => {
+                    let settings = options.echo.lock().map_or(options.config.echo, |echo| *echo);
+                    echo_panel = Some((echo::Field::Enabled, settings));
+                    status = None;
+                }
+                KeyCode::Char(c @ '1'..='3') if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                    references.select(c as usize - '1' as usize);
+                    status = Some((
+                        format!("Reference slot {} ('F' freezes into it)", references.selected() + 1),
+                        Some(Instant::now() + STATUS_DURATION),
+                    ));
+                }
+                KeyCode::Char('X') if !references.is_empty() => {
+                    references.clear();
+                    status = Some(("References cleared".to_string(), Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Char('C') => {
+                    let audible = options.audible.load(Ordering::Relaxed).min(streams.len() - 1);
+                    let message = if streams[audible].input.channels < 2 {
+                        "Mono audio: there is only the one channel to show".to_string()
+                    } else {
+                        channel = channel.next();
+                        format!("Showing the {} signal", channel.name())
+                    };
+                    status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Char('m') => show_metronome = !show_metronome,
+                KeyCode::Char('t') => {
+                    tilt_preset = tilt::Preset::next(tilt_preset);
+                    modes.set_tilt(tilt_preset.map(tilt::Preset::tilt));
+                    // Remembered for this orientation in the config file
+                    let value = tilt_preset.map(|preset| toml_edit::Value::from(preset.name()));
+                    if let Err(e) = config::save_mode_setting(options.config_path.as_deref(), modes.mode().name(), "tilt", value) {
+                        status = Some((format!("Tilt not saved: {}", e), Some(Instant::now() + STATUS_DURATION)));
+                    } else {
+                        options.watcher.refresh();
+                    }
+                }
+                KeyCode::Char('e') => {
+                    let smoother = settings.smoother.next();
+                    modes.set_smoother(Some(smoother));
+                    // Remembered for this orientation in the config file
+                    let value = Some(toml_edit::Value::from(smoother.name()));
+                    let message = format!("Smoothing {}", smoother.name());
+                    let message = match config::save_mode_setting(options.config_path.as_deref(), modes.mode().name(), "smoother", value) {
+                        Ok(_) => {
+                            options.watcher.refresh();
+                            message
+                        }
+                        Err(e) => format!("{} (not saved: {})", message, e),
+                    };
+                    status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Char('o') => {
+                    let orientation = options.config.orientation.next();
+                    options.config.orientation = orientation;
+                    modes.switch(orientation, &base_settings(&options.config));
+                    let message = format!("Orientation {}", orientation.name());
+                    let message = match config::save_setting(options.config_path.as_deref(), "orientation", orientation.name()) {
+                        Ok(_) => {
+                            options.watcher.refresh();
+                            message
+                        }
+                        Err(e) => format!("{} (not saved: {})", message, e),
+                    };
+                    status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Char('n') | KeyCode::Char('p') => {
+                    let step = if key.code == KeyCode::Char('n') { playlist::Step::Next } else { playlist::Step::Previous };
+                    if let Some(playlist) = &options.playlist
+                        && let Ok(mut playlist) = playlist.lock()
+                    {
+                        playlist.skip(step);
+                    }
+                }
+                KeyCode::Char('z') | KeyCode::Char('R') => {
+                    let toggled = options.playlist.as_ref().and_then(|playlist| {
+                        let mut playlist = playlist.lock().ok()?;
+                        Some(if key.code == KeyCode::Char('z') {
+                            let shuffle = !playlist.shuffle();
+                            playlist.set_shuffle(shuffle);
+                            ("shuffle", toml_edit::Value::from(shuffle), if shuffle { "Shuffle on" } else { "Shuffle off" }.to_string())
+                        } else {
+                            let repeat = playlist.repeat().cycle();
+                            playlist.set_repeat(repeat);
+                            ("repeat", toml_edit::Value::from(repeat.as_str()), format!("Repeat {}", repeat.as_str()))
+                        })
+                    });
+                    // Remembered in the config file for next time
+                    if let Some((key, value, message)) = toggled {
+                        let message = match config::save_setting(options.config_path.as_deref(), key, value) {
+                            Ok(_) => {
+                                options.watcher.refresh();
+                                message
+                            }
+                            Err(e) => format!("{} (not saved: {})", message, e),
+                        };
+                        status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+                    }
+                }
+                KeyCode::Up | KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                    let step = if key.code == KeyCode::Up { VISUAL_GAIN_STEP_DB } else { -VISUAL_GAIN_STEP_DB };
+                    let max = analysis::MAX_VISUAL_GAIN_DB;
+                    let gain = (options.config.visual_gain_db + step).clamp(-max, max);
+                    options.config.visual_gain_db = gain;
+                    let message = format!("Visual gain {}", locale.db(gain, 0));
+                    let message = match config::save_setting(options.config_path.as_deref(), "visual_gain_db", gain as f64) {
+                        Ok(_) => {
+                            options.watcher.refresh();
+                            message
+                        }
+                        Err(e) => format!("{} (not saved: {})", message, e),
+                    };
+                    status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+                }
+                KeyCode::Up | KeyCode::Down | KeyCode::Char('k') | KeyCode::Char('j')
+                    if stats_open(finished, show_stats && !show_playlist, &session, options.auto_exit) =>
+                {
+                    stats_selected = match matches!(key.code, KeyCode::Up | KeyCode::Char('k')) {
+                        true => stats_selected.saturating_sub(1),
+                        false => (stats_selected + 1).min(session.tracks().len() - 1),
+                    };
+                }
+                KeyCode::Char('s') if finished.is_some() => show_stats = true,
+                KeyCode::Up | KeyCode::Down | KeyCode::Char('k') | KeyCode::Char('j') if show_playlist => {
+                    let delta = if matches!(key.code, KeyCode::Up | KeyCode::Char('k')) { -1 } else { 1 };
+                    if let Some(playlist) = &options.playlist
+                        && let Ok(mut playlist) = playlist.lock()
+                    {
+                        playlist.move_selection(delta);
+                    }
+                }
+                KeyCode::Char('d') if show_playlist => {
+                    if !delete_armed {
+                        pending_delete = true;
+                    } else if let Some(playlist) = &options.playlist
+                        && let Ok(mut playlist) = playlist.lock()
+                    {
+                        let selected = playlist.selected();
+                        if let Some(removed) = playlist.remove(selected) {
+                            status = Some((
+                                format!("Removed {} from the queue", display::file_name(&removed)),
+                                Some(Instant::now() + STATUS_DURATION),
+                            ));
+                        }
+                    }
+                }
+                KeyCode::Char('d') => show_debug = !show_debug,
+                KeyCode::Char('D') => show_gain_curve = !show_gain_curve,
+                KeyCode::Char('x') if streams.len() > 1 => {
+                    let next = (options.audible.load(Ordering::Relaxed) + 1) % streams.len();
+                    options.audible.store(next, Ordering::Relaxed);
+                }
+                KeyCode::Left | KeyCode::Right if key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                    let band = freq_cursor.unwrap_or(num_bands / 2);
+                    freq_cursor = Some(if key.code == KeyCode::Left {
+                        band.saturating_sub(1)
+                    } else {
+                        (band + 1).min(num_bands - 1)
+                    });
+                }
+                // Zoom in or out around the cursor's band, or the middle one
+                KeyCode::Char('[') | KeyCode::Char(']') => {
+                    let full = FreqRange::full(streams[0].analyzer.max_freq());
+                    let (low, high) = streams[0].analyzer.range().band_edges(freq_cursor.unwrap_or(num_bands / 2), num_bands);
+                    let center = (low * high).sqrt();
+                    if key.code == KeyCode::Char('[') {
+                        zoom.narrow(center, full, Instant::now());
+                    } else {
+                        zoom.widen(center, full, Instant::now());
+                    }
+                    freq_cursor = freq_cursor.map(|_| zoom.target(full).band_of(center, num_bands));
+                }
+                KeyCode::Char('0') if zoom.is_zoomed() => {
+                    zoom.reset(FreqRange::full(streams[0].analyzer.max_freq()), Instant::now());
+                    freq_cursor = None;
+                }
+                KeyCode::Left | KeyCode::Right if scrub_time.is_some() => {
+                    scrub_steps.add(key, SCRUB_STEP_SECS, SCRUB_BIG_STEP_SECS);
+                }
+                KeyCode::Left | KeyCode::Right => seek_steps.add(key, SEEK_STEP_SECS, SEEK_BIG_STEP_SECS),
+                _ => {}
+            }
+        }
+        // Requests over the control socket, answered as they are carried out
+        #[cfg(unix)]
+        while let Some((request, reply)) = options.control.as_ref().and_then(|server| server.poll(std::time::Duration::ZERO)) {
+            match request {
+                control::Request::Add(text) => {
+                    let result = queue_file(options.playlist.as_ref(), options.reading, &text);
+                    let (Ok(message) | Err(message)) = &result;
+                    status = Some((message.clone(), Some(Instant::now() + STATUS_DURATION)));
+                    reply.send(result);
+                }
+                // What the keys do, answered once asked for; the player
+                // reports how it went as it does for the keys
+                control::Request::Pause => {
+                    let _ = options.player_commands.send(PlayerCommand::TogglePause);
+                    reply.send(Ok(String::new()));
+                }
+                control::Request::Seek(secs) => {
+                    let _ = options.player_commands.send(PlayerCommand::Seek(secs));
+                    reply.send(Ok(String::new()));
+                }
+                control::Request::Next | control::Request::Previous => {
+                    let step = if request == control::Request::Next { playlist::Step::Next } else { playlist::Step::Previous };
+                    reply.send(match &options.playlist {
+                        Some(playlist) => {
+                            if let Ok(mut playlist) = playlist.lock() {
+                                playlist.skip(step);
+                            }
+                            Ok(String::new())
+                        }
+                        None => Err("There is no queue to skip in while comparing or calibrating".to_string()),
+                    });
+                }
+                // Taken care of by the server
+                control::Request::Follow => {}
+            }
+        }
+        if scrub_steps.total() != 0.0
+            && let (Some(time), Some(oldest), Some(latest)) = (scrub_time, scrub_history.oldest(), scrub_history.latest())
+        {
+            scrub_time = Some((time + scrub_steps.total()).clamp(oldest.time, latest.time));
+        }
+        if seek_steps.total() != 0.0 {
+            let _ = options.player_commands.send(PlayerCommand::Seek(seek_steps.total()));
+        }
+
+        // Re-apply settings that changed in the config; only the ones that
+        // actually changed, so untouched ones keep the values they were given
+        // while running. The environment goes over the file on every load,
+        // and the flags go over both here, so a setting either of them gives
+        // stays as it is whatever the file says.
+        if let Some(result) = options.watcher.poll(Instant::now()) {
+            status = Some(match result {
+                Ok((mut config, _)) => {
+                    config.apply_flags(&options.flags);
+                    if let Some(shaping) = &mut options.measurement {
+                        *shaping = Shaping::measurement(config.measurement_floor_db);
+                        config.overlay(shaping);
+                    }
+                    let old = std::mem::replace(&mut options.config, config);
+                    let new = &options.config;
+                    if new.modes != old.modes || new.orientation != old.orientation {
+                        modes = modes::ModeMemory::new(new.orientation, new.modes.iter().cloned());
+                    }
+                    if new.fps != old.fps {
+                        frame_interval = std::time::Duration::from_secs_f32(1.0 / new.fps as f32);
+                    }
+                    if new.analysis_fps != old.analysis_fps {
+                        analysis_interval = analysis_interval_for(new.analysis_fps);
+                    }
+                    if new.raw != old.raw {
+                        raw = new.raw;
+                    }
+                    if new.alert != old.alert {
+                        alerts = alert::Alerts::new(new.alert.clone());
+                    }
+                    if new.bounce != old.bounce {
+                        bounce = new.bounce.then(|| onset::Bounce::new(BOUNCE_KICK, BOUNCE_DECAY_SECS));
+                    }
+                    if new.fps != old.fps || new.history_secs != old.history_secs {
+                        let max_frames = history::frames_for(new.history_secs, new.fps);
+                        for stream in &mut streams {
+                            stream.history.set_limits(new.history_secs, max_frames);
+                        }
+                    }
+                    if new.max_freq != old.max_freq {
+                        for stream in &mut streams {
+                            stream.analyzer.set_max_display_freq(new.max_display_freq());
+                        }
+                        zoom.reset(FreqRange::full(streams[0].analyzer.max_freq()), Instant::now());
+                    }
+                    if new.latency_offset_ms != old.latency_offset_ms {
+                        latency_offset_ms = new.latency_offset_ms.max(capture::min_offset_ms(options.device_latency_ms));
+                    }
+                    if let Some(playlist) = &options.playlist
+                        && let Ok(mut playlist) = playlist.lock()
+                    {
+                        if new.shuffle != old.shuffle {
+                            playlist.set_shuffle(new.shuffle);
+                        }
+                        if new.repeat != old.repeat {
+                            playlist.set_repeat(new.repeat);
+                        }
+                    }
+                    if (new.theme != old.theme || new.themes != old.themes)
+                        && theme_editor.is_none()
+                        && let Some(gradient) = new.palette(&new.theme)
+                    {
+                        theme.palette = gradient;
+                    }
+                    if new.echo != old.echo
+                        && echo_panel.is_none()
+                        && let Ok(mut echo) = options.echo.lock()
+                    {
+                        *echo = new.echo;
+                    }
+                    if new.idle_timeout != old.idle_timeout || new.screensaver != old.screensaver {
+                        silence = new
+                            .screensaver
+                            .then(|| idle::SilenceDetector::new(std::time::Duration::from_secs_f32(new.idle_timeout)));
+                    }
+                    let message = if new.fft_size != old.fft_size {
+                        format!(
+                            "Config reloaded; fft_size stays {} until restart",
+                            options.fft_size
+                        )
+                    } else {
+                        "Config reloaded".to_string()
+                    };
+                    (message, Some(Instant::now() + STATUS_DURATION))
+                }
+                Err(e) => (format!("Config not reloaded ({}): {}", e.location, e.message), None),
+            });
+        }
+        // Settings of the orientation in effect, after a switch, a new tilt
+        // preset or a reload; only what changed is applied
+        let current = modes.settings(&base_settings(&options.config));
+        let timing = (options.config.attack_ms, options.config.release_ms);
+        if current.smoothing != settings.smoothing || current.smoother != settings.smoother || timing != smoother_timing {
+            options.smoothing = current.smoothing;
+            smoother_timing = timing;
+            for stream in &mut streams {
+                stream.analyzer.set_smoother(smoother_for(&current, &options.config));
+            }
+        }
+        if current != settings {
+            if current.tilt != settings.tilt {
+                display_tilt = current.tilt.clone();
+            }
+            settings = current;
+        }
+        tilt_preset = modes.overrides(modes.mode()).tilt.as_ref().and_then(tilt::Preset::of);
+        if status.as_ref().is_some_and(|(_, until)| until.is_some_and(|until| Instant::now() >= until)) {
+            status = None;
+        }
+
+        // Follow the player onto the next track with a fresh analysis history
+        while let Ok(event) = options.player_events.try_recv() {
+            // Where playback jumped to, for the analysis to start over there
+            let mut jumped = None;
+            match event {
+                // The capture ring carries over between tracks so the bars keep
+                // flowing; only the smoothing starts over, so the new track's
+                // first frame isn't dragged toward the old one's levels
+                PlayerEvent::TrackStarted { input, seamless, .. } => {
+                    let stream = &mut streams[0];
+                    if !Arc::ptr_eq(&input.dynamics, &stream.input.dynamics) {
+                        // As many as the session keeps statistics for
+                        if dynamic_range.len() >= stats::MAX_TRACKS {
+                            dynamic_range.remove(0);
+                        }
+                        dynamic_range.push(track_dynamic_range(&stream.input));
+                        session.start(&input.name, input.duration);
+                    }
+                    show_stats = true;
+                    stream.analyzer.set_sample_rate(input.sample_rate);
+                    if seamless {
+                        stream.analyzer.reset();
+                    } else {
+                        stream.analyzer.flush();
+                        stream.peaks.clear();
+                        stream.onsets.reset();
+                        beats.reset();
+                        activity.reset();
+                        key_estimator.clear();
+                        shown_key = None;
+                        phase = None;
+                    }
+                    stream.history = history::FrameHistory::for_rate(options.config.history_secs, options.config.fps);
+                    level_series_at = None;
+                    stream.input = input;
+                    scrub_time = None;
+                    playhead = transport::Playhead::start(Instant::now(), playhead.is_paused());
+                    lost_seen = std::time::Duration::ZERO;
+                    finished = None;
+                    if let Some(warning) = &stream.input.warning {
+                        status = Some((warning.clone(), Some(Instant::now() + STATUS_DURATION)));
+                    }
+                }
+                PlayerEvent::TrackEnded { .. } => session.end(),
+                PlayerEvent::TrackFailed(message) | PlayerEvent::SeekFailed(message) => {
+                    status = Some((message, Some(Instant::now() + STATUS_DURATION)))
+                }
+                PlayerEvent::Finished => {
+                    session.end();
+                    stats_selected = 0;
+                    finished = Some(finish::Finish::new(Instant::now(), options.auto_exit));
+                }
+                PlayerEvent::Paused(position) => playhead.pause(position),
+                PlayerEvent::Resumed(position) => playhead.resume(position, Instant::now()),
+                PlayerEvent::Seeked(position) => jumped = Some(position),
+                // The stall is over on a new device; what the old one still
+                // had is gone, so this is a jump like a seek's
+                PlayerEvent::OutputReopened { input, position } => {
+                    streams[0].input = input;
+                    lost_seen = std::time::Duration::ZERO;
+                    status = Some(("Audio output reopened after it stalled".to_string(), Some(Instant::now() + STATUS_DURATION)));
+                    jumped = Some(position);
+                }
+                PlayerEvent::OutputStalled => {
+                    status = Some(("Audio output stalled; reopening the device".to_string(), None));
+                }
+                PlayerEvent::OutputLost(message) => {
+                    error = Some(message);
+                    break 'frames;
+                }
+            }
+            // The rings start over at the new position; like after a jump to
+            // another track, nothing from before carries over
+            if let Some(position) = jumped {
+                for stream in &mut streams {
+                    stream.analyzer.flush();
+                    stream.peaks.clear();
+                    stream.onsets.reset();
+                    stream.frames.clear();
+                    stream.history = history::FrameHistory::for_rate(options.config.history_secs, options.config.fps);
+                }
+                beats.reset();
+                activity.reset();
+                key_estimator.clear();
+                shown_key = None;
+                phase = None;
+                level_series_at = None;
+                scrub_time = None;
+                playhead.seek(position, Instant::now());
+            }
+        }
+
+        // Back from a suspend everything is drawn afresh, and the frames
+        // that would have been drawn while stopped weren't dropped
+        if suspend.run(playhead.is_paused(), &mut guard, &signals, &options.player_commands)? {
+            terminal.clear()?;
+            window_title.clear();
+            next_frame = Instant::now();
+        }
+
+        // While the device went without audio the position stands still
+        if let Ok(meter) = streams[0].input.flow.lock() {
+            let lost = meter.lost(Instant::now());
+            playhead.delay(lost.saturating_sub(lost_seen));
+            lost_seen = lost;
+        }
+
+        // The player decides when playback is over; the visualizer fades the
+        // bars out and then waits for 'q', or with --auto-exit returns once
+        // the device has played out. The prompt and the playlist panel stay
+        // open over the finished state, and hold off the auto-exit meanwhile
+        if let Some(finished) = &mut finished {
+            finished.hold(
+                prompt.is_some() || theme_editor.is_some() || echo_panel.is_some() || show_playlist,
+                Instant::now(),
+            );
+            if finished.should_exit(Instant::now()) {
+                break;
+            }
+        }
+        // Pace from when this frame started, so slow analysis or drawing
+        // eats into the wait instead of adding to it. A frame that comes
+        // late doesn't make up for the ones it missed: it analyzes the newest
+        // audio and the position follows the clock, so those are only counted.
+        let now = Instant::now();
+        let behind = now.saturating_duration_since(next_frame).as_secs_f32() / frame_interval.as_secs_f32();
+        if behind >= MAX_LATE_FRAMES {
+            dropped_frames += behind as u64;
+        }
+        next_frame = now + frame_interval;
+
+        // Analysis runs at analysis_fps when that is below fps; the frames in
+        // between draw the last analysis, blended toward it unless raw. A new
+        // band count (resize) can't wait.
+        let resized = streams.iter().any(|stream| stream.frames.latest().is_none_or(|bands| bands.len() != num_bands));
+        let analyze = now + frame_interval / 2 >= next_analysis || resized;
+        if analyze {
+            next_analysis = now + analysis_interval;
+        }
+        let interpolate = !raw && analysis_interval > frame_interval;
+
+        // Get samples from each buffer, shifted back by the latency offset
+        // (silence once finished, so the bars fall instead of freezing)
+        let mut holding = false;
+        let windows: Option<Vec<Vec<f32>>> = streams
+            .iter_mut()
+            .map(|stream| {
+                stream.window_time = None;
+                stream.channel_window = None;
+                if finished.is_some() {
+                    return Some(vec![0.0; stream.analyzer.state().fft_size()]);
+                }
+                let ring = stream.input.buffer.lock().ok()?;
+                // The samples are analyzed at the rate the ring says they
+                // were captured at, whatever the file header claimed, in a
+                // window as long as that rate needs
+                if let Some(rate) = ring.sample_rate() {
+                    stream.analyzer.set_sample_rate(rate);
+                }
+                let fft_size = stream.analyzer.state().fft_size();
+                let offset = capture::offset_samples(
+                    options.device_latency_ms + latency_offset_ms,
+                    ring.sample_rate().unwrap_or(stream.input.sample_rate),
+                );
+                // After a jump the ring starts empty; the bars fall meanwhile
+                let window = ring.window(fft_size, offset);
+                stream.window_time = ring.window_time(fft_size, offset);
+                if channel != stereo::Channel::Downmix {
+                    stream.channel_window = ring.channel_window(channel, fft_size, offset);
+                }
+                // Paused, or resumed with no fresh window yet: nothing is
+                // analyzed, so the bars and their smoothing stay as they were
+                if ring.holding(fft_size) {
+                    holding = true;
+                    return Some(window.unwrap_or_else(|| vec![0.0; fft_size]));
+                }
+                window.or_else(|| stream.analyzer.priming().then(|| vec![0.0; fft_size]))
+            })
+            .collect();
+        let Some(windows) = windows else {
+            continue;
+        };
+        let audible = options.audible.load(Ordering::Relaxed).min(streams.len() - 1);
+        let samples = &windows[audible];
+
+        // Where playback is: the audio time of the window being shown, so
+        // the position, the history and what is drawn keep to the samples
+        // (through underruns and seeks alike); the playhead's clock stands
+        // in while the ring has no window, as right after a jump
+        let total_duration = streams.iter().map(|stream| stream.input.duration).fold(f32::INFINITY, f32::min);
+        let elapsed = match (finished, streams[audible].window_time) {
+            (Some(_), _) => total_duration,
+            (None, Some(time)) => time.min(total_duration),
+            (None, None) => playhead.position(Instant::now()).as_secs_f32().min(total_duration),
+        };
+
+        if finished.is_none() && samples.iter().any(|s| s.abs() >= CLIP_LEVEL) {
+            analysis_events.publish(alert::AnalysisEvent::Clipped);
+        }
+
+        // Listening time and levels of the first stream's track while it plays
+        let listened_now = Instant::now();
+        if finished.is_none()
+            && !playhead.is_paused()
+            && let Some(track) = session.current_mut()
+        {
+            track.listen(listened_now.duration_since(listened_at).as_secs_f32());
+            if !holding {
+                track.frame(&windows[0]);
+            }
+        }
+        listened_at = listened_now;
+
+        // Phase correlation over the same stretch of audio
+        if finished.is_none() && !holding {
+            let stream = &streams[audible];
+            let offset = capture::offset_samples(options.device_latency_ms + latency_offset_ms, stream.analyzer.sample_rate());
+            let frames = stream.input.buffer.lock().ok().and_then(|ring| ring.stereo_window(stream.analyzer.state().fft_size(), offset));
+            phase = frames.map(|frames| match (stereo::correlation(&frames), phase.flatten()) {
+                (Some(reading), Some(shown)) => Some(shown + (reading - shown) * PHASE_SMOOTHING),
+                (reading, _) => reading,
+            });
+        }
+
+        // Swap in the idle animation after a long enough stretch of silence
+        let idle = match silence.as_mut() {
+            Some(detector) => detector.update(idle::rms(samples), Instant::now()),
+            None => false,
+        };
+        let idle_fade = silence.as_ref().map_or(0.0, |detector| detector.fade(Instant::now()));
+
+        // Analyze every stream into smoothed bands
+        for stream in streams.iter_mut().filter(|stream| *stream.analyzer.tilt() != display_tilt) {
+            stream.analyzer.set_tilt(display_tilt.clone());
+        }
+        // Each stream zooms within its own range; during the glide the band
+        // tables are rebuilt every frame
+        let zoomed_at = Instant::now();
+        for stream in streams.iter_mut() {
+            let full = FreqRange::full(stream.analyzer.max_freq());
+            stream.analyzer.set_range(zoom.current(full, zoomed_at));
+        }
+        let range = streams[0].analyzer.range();
+        let now = clock.elapsed().as_secs_f64();
+        // The first analysis after a hold goes on from the frame before it
+        // as if one frame had passed, not the whole pause
+        if holding {
+            analyzed_at = None;
+        }
+        if analyze && (!holding || resized) {
+            let analyzed_now = Instant::now();
+            let dt = analyzed_at.map_or(1.0 / smoothing::REFERENCE_FPS, |at| (analyzed_now - at).as_secs_f32());
+            analyzed_at = Some(analyzed_now);
+            let smoothed: Vec<Vec<f32>> = streams
+                .iter_mut()
+                .zip(windows.iter())
+                .map(|(stream, window)| {
+                    let signal = stream.channel_window.as_deref().unwrap_or(window);
+                    stream.analyzer.process_after(signal, num_bands, dt).to_vec()
+                })
+                .collect();
+
+            // Normalize bands against a shared peak so compared files stay comparable
+            let max_amplitude = analysis::max_amplitude(smoothed.iter().flatten());
+
+            // Normalize to 0-100 range for visualization, then the visual
+            // gain; measuring, the levels in dBFS go on a fixed scale instead
+            let floor_db = options.measurement.as_ref().and_then(|shaping| shaping.floor_db);
+            let normalized: Vec<Vec<f32>> = streams
+                .iter()
+                .zip(&smoothed)
+                .map(|(stream, bands)| match floor_db {
+                    Some(floor_db) => measurement::fixed_levels(&stream.analyzer.levels_db(), floor_db),
+                    None => {
+                        let mut levels = analysis::normalize(bands, max_amplitude);
+                        analysis::apply_visual_gain(&mut levels, options.config.visual_gain_db);
+                        levels
+                    }
+                })
+                .collect();
+
+            for ((stream, bands), window) in streams.iter_mut().zip(normalized.iter()).zip(windows.iter()) {
+                let levels_db = stream.analyzer.levels_db();
+                stream.history.push(history::AnalysisFrame {
+                    time: stream.window_time.unwrap_or(elapsed),
+                    bands: bands.clone(),
+                    levels_db,
+                    rms: idle::rms(window),
+                    sample_rate: stream.analyzer.sample_rate(),
+                    range: stream.analyzer.range(),
+                });
+                stream.frames.push(now, bands.clone());
+            }
+            if let Some(output) = &options.cava {
+                output.send(&normalized[audible]);
+            }
+            // Followers draw what is being heard
+            #[cfg(unix)]
+            if let Some(server) = options.control.as_ref().filter(|server| server.is_followed())
+                && let Some(frame) = streams[audible].history.latest()
+            {
+                server.publish(&control::Update::Frame(frame.clone()));
+            }
+
+            // Onsets in what is being heard kick the drawn bars; the history
+            // above keeps the levels as analyzed
+            for (i, stream) in streams.iter_mut().enumerate() {
+                let magnitudes = stream.analyzer.magnitudes();
+                let onset = stream.onsets.update(&magnitudes, now);
+                if i == audible {
+                    activity.update(stream.onsets.flux(), now);
+                }
+                if i == audible && !idle {
+                    let freq_per_bin = rate::freq_per_bin(stream.analyzer.sample_rate(), stream.analyzer.state().fft_size());
+                    key_estimator.push(now, key::chroma(&magnitudes, freq_per_bin));
+                }
+                if onset && i == audible {
+                    beats.onset(now);
+                    analysis_events.publish(alert::AnalysisEvent::Beat);
+                    if let Some(bounce) = bounce.as_mut() {
+                        bounce.kick(now);
+                    }
+                }
+            }
+        }
+        let mut normalized: Vec<Vec<f32>> = streams
+            .iter()
+            .map(|stream| match interpolate {
+                true => stream.frames.at(now),
+                false => stream.frames.latest().map(<[f32]>::to_vec),
+            })
+            .map(Option::unwrap_or_default)
+            .collect();
+
+        if shown_key.is_none_or(|(_, at)| now - at >= KEY_UPDATE_SECS) {
+            shown_key = key_estimator.estimate(KEY_MIN_SECS).map(|estimate| (estimate, now));
+        }
+        if finished.is_none()
+            && let Some(track) = session.current_mut()
+        {
+            track.detect_bpm(beats.bpm());
+            track.detect_key(shown_key.as_ref().map(|(key, _)| key.to_string()));
+        }
+        for event in stats_events.try_iter() {
+            if event == alert::AnalysisEvent::Clipped
+                && let Some(track) = session.current_mut()
+            {
+                track.clipped(clock.elapsed().as_secs_f64());
+            }
+        }
+
+        // The audible click follows the grid only while the metronome shows
+        if let Some(grid) = &options.metronome_grid
+            && let Ok(mut grid) = grid.lock()
+        {
+            *grid = beats.next_beat(now).filter(|_| show_metronome).zip(beats.bpm()).map(|(next, bpm)| {
+                metronome::BeatGrid {
+                    next: clock + std::time::Duration::from_secs_f64(next),
+                    period: std::time::Duration::from_secs_f32(60.0 / bpm),
+                }
+            });
+        }
+
+        let mut ring_bell = false;
+        for event in alert_events.try_iter() {
+            if let Some(style) = alerts.on_event(event, Instant::now()) {
+                ring_bell |= style.bell();
+                if style.flash() {
+                    alert_flash_until = Some(Instant::now() + ALERT_FLASH);
+                }
+            }
+        }
+        // In calibration mode the display flashes while a click is being analyzed
+        let flash = calibrate && samples.iter().any(|s| s.abs() > calibrate::FLASH_THRESHOLD)
+            || alert_flash_until.is_some_and(|until| Instant::now() < until);
+        if let Some(bounce) = &bounce {
+            for bands in &mut normalized {
+                bounce.apply(bands, now);
+            }
+        }
+        // After the end the bars fade out by the clock, whatever the smoothing
+        if let Some(finished) = &finished {
+            let fade = finished.fade(Instant::now());
+            for level in normalized.iter_mut().flatten() {
+                *level *= fade;
+            }
+        }
+
+        // What is being heard, for Discord and the window title; both are
+        // rate limited
+        let now_playing = || {
+            let (artist, title) = gruvberry::presence::split_name(&streams[audible].input.name);
+            gruvberry::presence::Activity {
+                title: title.to_string(),
+                artist: artist.map(str::to_string),
+                elapsed,
+                total: total_duration,
+                paused: playhead.is_paused(),
+                spectrum: gruvberry::presence::mini_spectrum(&normalized[audible]),
+            }
+        };
+        #[cfg(feature = "discord")]
+        if let Some(presence) = options.presence.as_mut() {
+            if let Some(message) = presence.log_line() {
+                status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+            }
+            presence.update(now_playing);
+        }
+        if options.config.terminal_title && !options.calibrate {
+            window_title.update(|| now_playing().window_title());
+        } else {
+            window_title.clear();
+        }
+        // Sent to followers only when they change
+        #[cfg(unix)]
+        if let Some(server) = &options.control {
+            server.publish(&control::Update::Track {
+                name: streams[audible].input.name.clone(),
+                duration: total_duration,
+            });
+            server.publish(&control::Update::State(match (finished, playhead.is_paused()) {
+                (Some(_), _) => control::PlayState::Finished,
+                (None, true) => control::PlayState::Paused,
+                (None, false) => control::PlayState::Playing,
+            }));
+        }
+
+        if level_series_at.is_none_or(|at| at.elapsed() >= std::time::Duration::from_secs(1)) {
+            level_series = level_series_of(&streams[audible].history);
+            level_series_at = Some(Instant::now());
+        }
+
+        // Strongest peaks of the live spectrum (scrubbed frames don't keep the
+        // bins they would need)
+        let peaks: Vec<Vec<analysis::Peak>> = streams
+            .iter_mut()
+            .map(|stream| {
+                if show_peaks && scrub_time.is_none() && !idle && !stream.analyzer.priming() {
+                    let found = stream.analyzer.peaks(PEAK_LABELS);
+                    stream.peaks.update(found).to_vec()
+                } else {
+                    stream.peaks.clear();
+                    Vec::new()
+                }
+            })
+            .collect();
+
+        // While scrubbing, show the remembered frames instead of the live ones
+        let scrubbed_frames: Option<Vec<&history::AnalysisFrame>> = scrub_time.and_then(|time| {
+            streams.iter().map(|stream| stream.history.at(time)).collect()
+        });
+
+        // The audible stream's bands as they are on screen become the reference
+        if freeze {
+            let bands = match &scrubbed_frames {
+                Some(frames) => &frames[audible].bands,
+                None => &normalized[audible],
+            };
+            references.freeze(bands);
+            status = Some((
+                format!("Reference {} frozen ({})", references.selected() + 1, REFERENCE_MARKS[references.selected()]),
+                Some(Instant::now() + STATUS_DURATION),
+            ));
+        }
+
+        if export {
+            let frame = match &scrubbed_frames {
+                Some(frames) => Some(frames[audible]),
+                None => streams[audible].history.latest(),
+            };
+            let message = match frame {
+                Some(frame) => export_svg(frame, settings.gradient, options.export_svg.as_deref()),
+                None => "Nothing to save yet".to_string(),
+            };
+            status = Some((message, Some(Instant::now() + STATUS_DURATION)));
+        }
+
+        // Exact range and level of the band under the cursor, from the frame on screen
+        let cursor_readout = freq_cursor.and_then(|band| {
+            let frame = match &scrubbed_frames {
+                Some(frames) => frames[audible],
+                None => streams[audible].history.latest()?,
+            };
+            let level = frame.levels_db.get(band)?;
+            let (start, end) = frame.range.band_edges(band, frame.levels_db.len());
+            Some(format!("Band {}: {} – {}, {}", band + 1, locale.hz(start), locale.hz(end), locale.db(*level, 1)))
+        });
+
+        // Buffer and analysis details of what is being heard for the 'd' overlay
+        let debug = show_debug.then(|| {
+            let now = Instant::now();
+            let flow = match streams[audible].input.flow.lock() {
+                Ok(meter) => (meter.fill(now), meter.underruns(now), meter.lost(now)),
+                Err(_) => Default::default(),
+            };
+            let non_finite = streams[audible].input.buffer.lock().map_or(0, |ring| ring.non_finite());
+            let mut lines = debug_lines(flow, dropped_frames, non_finite, &streams[audible].analyzer, locale, &theme);
+            lines.push(Line::from(format!("Visual gain: {}", locale.db(options.config.visual_gain_db, 0))));
+            lines
+        });
+
+        // Calculate num_bands BEFORE terminal.draw to update smoothed_bands size
+        let orientation = options.config.orientation;
+        let aggregate = options.config.band_aggregate;
+        let current_size = terminal.size().unwrap_or(ratatui::layout::Size { width: 80, height: 24 });
+        let playlist_rows = options
+            .playlist
+            .as_ref()
+            .filter(|_| show_playlist)
+            .and_then(|playlist| playlist.lock().ok())
+            .map(|playlist| playlist.entries().len().clamp(1, PLAYLIST_PANEL_ROWS) as u16);
+        let plan = layout::LayoutPlan::compute(
+            current_size.width,
+            current_size.height,
+            &layout::LayoutConfig {
+                horizontal: orientation.horizontal(),
+                streams: streams.len(),
+                overview: streams[0].input.overview.is_some(),
+                playlist_rows,
+            },
+        );
+        let num_legend_bands = plan.num_legend_bands;
+
+        // The analyzers pick up a changed band count on the next frame, once
+        // a resize has settled; a terminal too small to draw in keeps the
+        // count it had
+        num_bands = band_settle.update(&plan, Instant::now());
+        freq_cursor = freq_cursor.map(|band| band.min(num_bands - 1));
+
+        // Position in the queue for the progress block, and the profile and
+        // playback modes for the header
+        let mut header_tags = Vec::new();
+        if let Some(profile) = &options.profile {
+            header_tags.push(format!("profile: {}", profile));
+        }
+        if let Some(shaping) = &options.measurement {
+            header_tags.push(format!("measurement: {}", shaping.guarantees().join(", ")));
+        }
+        if !references.is_empty() {
+            let slots: Vec<String> =
+                references.frozen().map(|slot| format!("{}{}", REFERENCE_MARKS[slot], slot + 1)).collect();
+            header_tags.push(format!("Ref {}", slots.join(" ")));
+        }
+        // What is heard isn't the file alone; say which of the two is drawn
+        if let Ok(echo) = options.echo.lock()
+            && echo.enabled
+        {
+            let drawn = if echo.tap == echo::Tap::Dry { "bars dry" } else { "bars wet" };
+            header_tags.push(format!("Echo {} ({})", echo.value_text(echo::Field::Delay, locale), drawn));
+        }
+        header_tags.push(match &shown_key {
+            Some((estimate, _)) => format!("Key: {}", estimate),
+            None => "Key: —".to_string(),
+        });
+        // The dot lights up on each predicted beat
+        if show_metronome {
+            header_tags.push(match (beats.bpm(), beats.phase(now)) {
+                (Some(bpm), Some(phase)) => {
+                    format!("{} {} BPM", if phase < METRONOME_PULSE { "●" } else { "○" }, locale.number(bpm, 0))
+                }
+                _ => "○ -- BPM".to_string(),
+            });
+        }
+        if zoom.is_zoomed() {
+            header_tags.push("zoom ('0' resets)".to_string());
+        }
+        let playlist_position = options.playlist.as_ref().and_then(|playlist| {
+            let playlist = playlist.lock().ok()?;
+            if playlist.shuffle() {
+                header_tags.push("⤮ shuffle".to_string());
+            }
+            if playlist.repeat() != playlist::Repeat::Off {
+                header_tags.push(format!("↻ repeat {}", playlist.repeat().as_str()));
+            }
+            let current = playlist.current()?;
+            Some(format!("Track {}/{} ('l' playlist)", current + 1, playlist.entries().len()))
+        });
+
+        let drawn_now = Instant::now();
+        let draw_dt = drawn_at.map_or(0.0, |at| (drawn_now - at).as_secs_f32());
+        drawn_at = Some(drawn_now);
+
+        // Render UI
+        let drawn = terminal.draw(|f| {
+            if !plan.fits() {
+                let warning_text = format!(
+                    "Terminal too small!\n\n\
+                     Current size: {}x{}\n\
+                     Minimum required: {}x{}\n\n\
+                     Please resize your terminal window.",
+                    f.area().width,
+                    f.area().height,
+                    layout::MIN_WIDTH,
+                    layout::MIN_HEIGHT
+                );
+                let warning_widget = Paragraph::new(warning_text)
+                    .block(Block::default().borders(Borders::ALL).title("Error"))
+                    .style(theme.bad);
+                f.render_widget(warning_widget, f.area());
+                return;
+            }
+
+            // One spectrum per stream (two when comparing files) above the
+            // legend, details, overview, playlist and progress blocks
+            let areas = plan.areas(f.area());
+            let overview = streams[0].input.overview.as_ref();
+            let playlist = options.playlist.as_ref().filter(|_| show_playlist).and_then(|p| p.lock().ok());
+            let progress_area = areas.progress;
+
+            for (i, stream) in streams.iter().enumerate() {
+                let area = areas.spectra[i];
+
+                // Calculate actual usable width for spectrum (exclude borders)
+                let spectrum_width = area.width.saturating_sub(2) as usize; // Subtract borders
+                let spectrum_height = area.height.saturating_sub(2) as usize;
+
+                // Horizontal bars give up a few columns on their base side
+                // for the legend numbers
+                let gutter = if orientation.horizontal() && !idle { AXIS_GUTTER.min(spectrum_width) } else { 0 };
+                let bars_width = spectrum_width - gutter;
+
+                let bars_x = if orientation == Orientation::LeftRight { gutter } else { 0 };
+                let inner = Rect::new(area.x + 1 + bars_x as u16, area.y + 1, bars_width as u16, spectrum_height as u16);
+                let scrubbed_frame = scrubbed_frames.as_ref().map(|frames| frames[i]);
+                let (bands, colors) = match scrubbed_frame {
+                    Some(frame) => (&frame.bands[..], spectrum::band_colors(frame.bands.len(), frame.range, settings.gradient, &theme)),
+                    None => (&normalized[i][..], spectrum::band_colors(num_bands, stream.analyzer.range(), settings.gradient, &theme)),
+                };
+
+                let heading = if streams.len() > 1 {
+                    let marker = if i == audible { "▶" } else { " " };
+                    format!("{} {}: {}", marker, (b'A' + i as u8) as char, stream.input.name)
+                } else if idle {
+                    "Gruvberry - Idle (waiting for signal)".to_string()
+                } else {
+                    format!("Gruvberry - Frequency Spectrum ({}) VIBGYOR", zoom::label(range, locale))
+                };
+                // The channel layout, and the signal shown when there is a choice
+                let heading = match stream.input.channels {
+                    channels @ 2.. if !idle => format!("{} [{}: {}]", heading, stereo::layout(channels), channel.name()),
+                    channels => format!("{} [{}]", heading, stereo::layout(channels)),
+                };
+                // The tilt in effect, when it is one of the presets
+                let heading = match tilt::Preset::of(&display_tilt) {
+                    Some(preset) if i == 0 => format!("{} [{}]", heading, preset.name()),
+                    _ => heading,
+                };
+                let title = match scrubbed_frame {
+                    Some(frame) => format!(
+                        "{} [HISTORY {}] ←/→ scrub, 'h' or Esc for live",
+                        heading,
+                        locale.ago(elapsed - frame.time)
+                    ),
+                    None => format!("{} [LIVE]", heading),
+                };
+                // Titles share the top border with the tags, so file names
+                // can't push either past the corner
+                let tags = (i == 0 && !header_tags.is_empty()).then(|| format!(" {} ", header_tags.join(" · ")));
+                let title_width = spectrum_width.saturating_sub(tags.as_deref().map_or(0, |tags| display::width(tags) + 1));
+                let mut info = stream.input.info.clone();
+                if stream.input.sample_rate != options.device_sample_rate {
+                    info.push_str(&format!(" · plays at {}", locale.rate(options.device_sample_rate)));
+                }
+                let info = display::fit(&info, spectrum_width.saturating_sub(2));
+                let mut spectrum_block = Block::default()
+                    .title(display::fit(&title, title_width))
+                    .title_bottom(Line::from(format!(" {} ", info)).right_aligned())
+                    .borders(Borders::ALL);
+                if let Some(tags) = tags {
+                    spectrum_block = spectrum_block.title(Line::from(tags).right_aligned());
+                }
+                if flash {
+                    spectrum_block = spectrum_block.border_style(theme.flash);
+                }
+                f.render_widget(spectrum_block, area);
+
+                if idle && scrubbed_frame.is_none() {
+                    let idle_area = Rect::new(area.x + 1, area.y + 1, spectrum_width as u16, spectrum_height as u16);
+                    f.render_widget(Paragraph::new(idle::idle_lines(elapsed, spectrum_width, spectrum_height, idle_fade, &theme)), idle_area);
+                } else {
+                    let mut bars = SpectrumWidget::new(bands, &theme)
+                        .colors(&colors)
+                        .orientation(orientation)
+                        .aggregate(aggregate)
+                        .adapt(terminal::adapt)
+                        .cursor(freq_cursor);
+                    // Idle, a scrubbed frame shows without the marks
+                    for (slot, curve) in references.curves(bands.len()).filter(|_| !idle) {
+                        bars = bars.reference(REFERENCE_MARKS[slot], &curve);
+                    }
+                    // Trails follow the live bars only, not a scrubbed frame
+                    let heat = trails.as_mut().and_then(|trails| trails.get_mut(i));
+                    match heat.filter(|_| scrubbed_frame.is_none()) {
+                        Some(heat) => f.render_stateful_widget(bars.elapsed(draw_dt), inner, heat),
+                        None => f.render_widget(bars, inner),
+                    }
+                }
+                if gutter > 0 {
+                    let labels: Vec<Line> = axis_labels(num_bands, num_legend_bands, spectrum_height, orientation)
+                        .into_iter()
+                        .map(Line::from)
+                        .collect();
+                    let gutter_x = if orientation == Orientation::RightLeft { inner.right() } else { area.x + 1 };
+                    let gutter_area = Rect::new(gutter_x, inner.y, gutter as u16, inner.height);
+                    f.render_widget(Paragraph::new(labels).style(theme.dim), gutter_area);
+                }
+
+                bars_areas[i] = inner;
+                for (rect, text) in peak_labels(&peaks[i], &normalized[i], stream.analyzer.range(), inner, orientation, locale) {
+                    f.render_widget(Paragraph::new(text).style(theme.label), rect);
+                }
+            }
+
+            // Legend indicators (|---1---|---2---|...) - must match spectrum_width exactly
+            let legend_width = areas.legend.width.saturating_sub(2) as usize; // Match legend box width
+            // Horizontal bars number the bands down their side instead, and
+            // this strip becomes the scale the bars extend along
+            let partial_segment = !orientation.horizontal() && legend::has_partial_segment(legend_width, num_legend_bands);
+
+            let (legend_line, legend_title) = if orientation.horizontal() {
+                (level_scale(legend_width, AXIS_GUTTER, orientation), "Level")
+            } else {
+                (Line::from(legend::axis_line(legend_width, num_legend_bands)), "Frequency Ranges")
+            };
+            let legend_indicators = Paragraph::new(legend_line)
+                .block(Block::default().borders(Borders::ALL).title(legend_title));
+            f.render_widget(legend_indicators, areas.legend);
+
+            // Legend details (frequency ranges with colors)
+            let mut legend_details = String::new();
+            let total_legend_items = if partial_segment { num_legend_bands + 1 } else { num_legend_bands };
+
+            for i in 0..total_legend_items {
+                let band_index = (i * num_bands) / num_legend_bands.max(1);
+
+                // Where this legend band starts, in the range shown
+                let (freq_start, _) = range.band_edges(band_index, num_bands);
+
+                let freq_label = format!("{}:{}", i + 1, locale.hz(freq_start));
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(sample) = self.source.next() {
-            // Store sample in buffer for FFT
-            if let Ok(mut buf) = self.buffer.lock() {
-                buf.push(sample);
-                // Keep buffer size manageable (1024 samples for FFT)
-                if buf.len() > 2048 {
-                    buf.drain(0..1024);
+                legend_details.push_str(&format!("{:12}", freq_label));
+
+                // Add newline every 6 items for better layout
+                if (i + 1) % 6 == 0 {
+                    legend_details.push('\n');
+                }
+            }
+
+            let legend_widget = Paragraph::new(legend_details)
+                .block(Block::default().borders(Borders::ALL).title("Band Details"));
+            f.render_widget(legend_widget, areas.details);
+
+            // Overview strip of the whole track with the playback cursor
+            if let (Some(envelope), Some(area)) = (overview, areas.overview) {
+                let strip_width = area.width.saturating_sub(2) as usize;
+                let columns = match envelope.lock() {
+                    Ok(env) => env.downsample(strip_width),
+                    Err(_) => vec![None; strip_width],
+                };
+                let strip = Paragraph::new(overview::overview_lines(&columns, elapsed / total_duration, &theme))
+                    .block(Block::default().borders(Borders::ALL).title("Overview"));
+                f.render_widget(strip, area);
+            }
+
+            // Queue with the playing entry marked and the highlighted one inverted
+            if let (Some(playlist), Some(area)) = (&playlist, areas.playlist) {
+                let rows = area.height.saturating_sub(2) as usize;
+                let width = area.width.saturating_sub(2) as usize;
+                let first = playlist.selected().saturating_sub(rows.saturating_sub(1));
+                let lines: Vec<Line> = playlist
+                    .entries()
+                    .iter()
+                    .enumerate()
+                    .skip(first)
+                    .take(rows)
+                    .map(|(i, path)| {
+                        let marker = if playlist.current() == Some(i) { "▶ " } else { "  " };
+                        let style = if i == playlist.selected() {
+                            theme.selected
+                        } else {
+                            Style::default()
+                        };
+                        let entry = format!("{}{:>3}. {}", marker, i + 1, display::file_name(path));
+                        Line::styled(display::fit(&entry, width), style)
+                    })
+                    .collect();
+                let panel = Paragraph::new(lines).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(format!(
+                            "Playlist ({}) ↑/↓ select, 'dd' remove, 'a' add, 'l' hide",
+                            playlist.entries().len()
+                        )),
+                );
+                f.render_widget(panel, area);
+            }
+
+            // Debug overlay in the top right corner of the spectrum
+            if let Some(lines) = &debug {
+                let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2;
+                let height = lines.len() as u16 + 2;
+                let area = areas.spectrum;
+                if area.width > width + 2 && area.height > height + 1 {
+                    let rect = Rect::new(area.right() - width - 1, area.y + 1, width, height);
+                    f.render_widget(Clear, rect);
+                    f.render_widget(
+                        Paragraph::new(lines.clone()).block(Block::default().borders(Borders::ALL).title("Debug ('d')")),
+                        rect,
+                    );
+                }
+            }
+
+            // Gain curve along the bottom of the spectrum, over the same axis
+            // as the legend so the bands line up
+            if show_gain_curve {
+                let area = areas.spectrum;
+                let width = area.width.saturating_sub(2);
+                let rows = GAIN_CURVE_ROWS.min(area.height.saturating_sub(4));
+                if width > 0 && rows > 0 {
+                    let gains = streams[audible].analyzer.band_gains_db();
+                    let plot = curve::plot(&gains, width as usize, rows as usize, num_legend_bands);
+                    let mut lines: Vec<Line> = plot.rows.into_iter().map(Line::from).collect();
+                    lines.push(Line::from(plot.axis));
+                    let rect = Rect::new(area.x, area.bottom() - rows - 3, area.width, rows + 3);
+                    f.render_widget(Clear, rect);
+                    f.render_widget(
+                        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(
+                            "Gain curve ('D') {} to {}",
+                            locale.db(plot.bottom_db, 0),
+                            locale.db(plot.top_db, 0)
+                        ))),
+                        rect,
+                    );
+                }
+            }
+
+            // The theme editor in the top left corner of the spectrum, with
+            // the gradient it makes under its numbers
+            if let Some(editor) = &theme_editor {
+                let area = areas.spectrum;
+                let width = THEME_EDITOR_WIDTH.min(area.width.saturating_sub(2));
+                let height = (palette::Field::ALL.len() as u16 + 3).min(area.height.saturating_sub(1));
+                if width > 2 && height > 2 {
+                    let rect = Rect::new(area.x + 1, area.y + 1, width, height);
+                    let inner = width as usize - 2;
+                    let mut lines: Vec<Line> = palette::Field::ALL
+                        .iter()
+                        .map(|&field| {
+                            let value = editor.value_text(field, locale);
+                            let gap = inner.saturating_sub(field.label().len() + value.chars().count() + 2);
+                            let text = format!(" {}{}{} ", field.label(), " ".repeat(gap), value);
+                            let style = if field == editor.field() { theme.selected } else { Style::default() };
+                            Line::styled(display::fit(&text, inner), style)
+                        })
+                        .collect();
+                    let preview = editor.gradient();
+                    lines.push(Line::from(
+                        (0..inner)
+                            .map(|x| {
+                                let (r, g, b) = preview.rgb(x as f32 / inner.saturating_sub(1).max(1) as f32);
+                                Span::styled("█", Style::default().fg(terminal::adapt(Color::Rgb(r, g, b))))
+                            })
+                            .collect::<Vec<Span>>(),
+                    ));
+                    f.render_widget(Clear, rect);
+                    f.render_widget(
+                        Paragraph::new(lines)
+                            .block(Block::default().borders(Borders::ALL).title("Theme ↑/↓ ←/→ Enter saves, Esc cancels")),
+                        rect,
+                    );
+                }
+            }
+
+            // The echo panel in the same corner
+            if let Some((field, _)) = &echo_panel {
+                let area = areas.spectrum;
+                let width = THEME_EDITOR_WIDTH.min(area.width.saturating_sub(2));
+                let height = (echo::Field::ALL.len() as u16 + 2).min(area.height.saturating_sub(1));
+                if width > 2 && height > 2 {
+                    let rect = Rect::new(area.x + 1, area.y + 1, width, height);
+                    let inner = width as usize - 2;
+                    let settings = options.echo.lock().map_or(options.config.echo, |echo| *echo);
+                    let lines: Vec<Line> = echo::Field::ALL
+                        .iter()
+                        .map(|&row| {
+                            let value = settings.value_text(row, locale);
+                            let gap = inner.saturating_sub(row.label().len() + value.chars().count() + 2);
+                            let text = format!(" {}{}{} ", row.label(), " ".repeat(gap), value);
+                            let style = if row == *field { theme.selected } else { Style::default() };
+                            Line::styled(display::fit(&text, inner), style)
+                        })
+                        .collect();
+                    f.render_widget(Clear, rect);
+                    f.render_widget(
+                        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Echo ↑/↓ ←/→, Esc closes")),
+                        rect,
+                    );
+                }
+            }
+
+            // Once the bars have faded, what the session amounted to, over the spectrum
+            if stats_open(finished, show_stats && !show_playlist && prompt.is_none(), &session, options.auto_exit) {
+                let area = areas.spectrum;
+                let width = area.width.saturating_sub(2) as usize;
+                let rows = area.height.saturating_sub(3) as usize;
+                let name_width = width.saturating_sub(STATS_COLUMNS_WIDTH).max(8);
+                let mut table = stats_table(&session, name_width, locale).into_iter();
+                let header = table.next().unwrap_or_default();
+                let first = stats_selected.saturating_sub(rows.saturating_sub(1));
+                let mut lines = vec![Line::styled(display::fit(&header, width), theme.dim)];
+                lines.extend(table.enumerate().skip(first).take(rows).map(|(i, row)| {
+                    let style = if i == stats_selected {
+                        theme.selected
+                    } else {
+                        Style::default()
+                    };
+                    Line::styled(display::fit(&row, width), style)
+                }));
+                f.render_widget(Clear, area);
+                f.render_widget(
+                    Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(
+                        "Session: {} listened over {} ↑/↓ select, Esc hides",
+                        format::time(session.total_listened()),
+                        match session.earlier() + session.tracks().len() {
+                            1 => "1 track".to_string(),
+                            n => format!("{} tracks", n),
+                        }
+                    ))),
+                    area,
+                );
+            }
+
+            // The add-to-queue prompt takes over the progress block while open
+            if let Some((purpose, editor)) = &prompt {
+                let (label, title) = match purpose {
+                    PromptFor::Queue => ("Add: ", "Add to queue (Tab completes, Enter adds, Esc cancels)"),
+                    PromptFor::ThemeName => ("Name: ", "Save theme as (Enter saves to the config file, Esc cancels)"),
+                };
+                let block = Block::default().borders(Borders::ALL).title(title);
+                let widget = Paragraph::new(editor.line(label, &theme)).block(block);
+                f.render_widget(widget, progress_area);
+                return;
+            }
+
+            // Device compensation the manual offset is added to
+            let device_latency = format!(
+                "{} device{}",
+                locale.ms(options.device_latency_ms as f32, 0),
+                if options.device_latency_reported { "" } else { " (assumed)" }
+            );
+            // Time display (calibration has no end, so show the offset controls instead)
+            let time_text = if calibrate {
+                Line::from(format!(
+                    "Calibrating | Latency offset: {} on {} | '+'/'-' adjust by {} until the flash matches the click | 'q' to exit",
+                    locale.signed_ms(latency_offset_ms as f32),
+                    device_latency,
+                    locale.ms(LATENCY_STEP_MS as f32, 0)
+                ))
+            } else {
+                let mut time = format!(
+                    "{}: {} / {}",
+                    match (finished, playhead.is_paused()) {
+                        (Some(_), _) => "Finished",
+                        (None, true) => "Paused",
+                        (None, false) => "Playing",
+                    },
+                    format::time(elapsed),
+                    format::time(total_duration)
+                );
+                // A segment's time is its own; where that is in the file follows
+                if let Some(start) = streams[0].input.start {
+                    time.push_str(&format!(" ({})", format::time(start + elapsed)));
+                }
+                let mut parts = Vec::new();
+                // Once the bars have faded, what can be done from here
+                if finished.is_some_and(|finished| finished.is_faded(Instant::now())) && !options.auto_exit {
+                    if !show_stats && !session.tracks().is_empty() {
+                        parts.push("'s' shows the statistics".to_string());
+                    }
+                    parts.push(match options.playlist {
+                        Some(_) => "Press 'q' to quit or 'n' to play again".to_string(),
+                        None => "Press 'q' to quit".to_string(),
+                    });
+                }
+                parts.push(format!("Bands: {}", num_bands));
+                if latency_offset_ms != 0 {
+                    parts.push(format!("Offset: {} on {}", locale.signed_ms(latency_offset_ms as f32), device_latency));
+                }
+                if let Some(playlist) = &playlist_position {
+                    parts.push(playlist.clone());
+                }
+                if streams.len() > 1 {
+                    parts.push(format!("Hearing {} ('x' to switch)", (b'A' + audible as u8) as char));
+                }
+                if let Some(notice) = &options.notice {
+                    parts.push(notice.clone());
+                }
+                if let Some(readout) = &cursor_readout {
+                    parts.push(readout.clone());
+                }
+                if dropped_frames > 0 {
+                    parts.push(format!("Dropped: {} frames", dropped_frames));
                 }
+                if let Some(recorder) = &options.recorder {
+                    let recording = recorder.status();
+                    parts.push(match &recording.error {
+                        Some(e) => format!("Recording {}", e),
+                        None => format!(
+                            "● REC {} · {}",
+                            format::time(recording.duration().as_secs_f32()),
+                            locale.megabytes(recording.bytes())
+                        ),
+                    });
+                }
+                if let Some((message, _)) = &status {
+                    parts.push(message.clone());
+                }
+                parts.push("Press 'q' or Ctrl+C to exit".to_string());
+                let rest = parts.join(" | ");
+
+                // The level sparkline gets whatever room the text leaves, up to a cell per second
+                let room = (progress_area.width.saturating_sub(2) as usize)
+                    .saturating_sub(display::width(&time) + display::width(&rest) + 4)
+                    .min(LEVEL_SPARKLINE_SECS);
+                let mut spans = vec![Span::raw(time)];
+                if room >= 8 {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(
+                        gruvberry::sparkline::render(&level_series, room),
+                        theme.accent,
+                    ));
+                }
+                spans.push(Span::raw(format!(" | {}", rest)));
+                Line::from(spans)
+            };
+            let mut progress_block = Block::default().borders(Borders::ALL).title("Progress");
+            if !calibrate {
+                let crest = streams[audible].input.dynamics.lock().ok().and_then(|meter| meter.crest_db());
+                progress_block = progress_block.title(crest_meter(crest, locale, &theme).right_aligned());
+                progress_block = progress_block.title(activity_meter(activity.level(), &theme).right_aligned());
             }
-            Some(sample)
-        } else {
-            None
+            if let Some(correlation) = phase {
+                progress_block = progress_block.title(phase_meter(correlation, locale, &theme).right_aligned());
+            }
+            let time_widget = Paragraph::new(time_text).block(progress_block);
+            f.render_widget(time_widget, progress_area);
+        });
+        // The terminal can go away mid-frame; that is the hangup's quit,
+        // not an error
+        if drawn.is_err() && signals.hung_up() {
+            break 'frames;
         }
-    }
-}
+        drawn?;
 
-impl<I> Source for SampleCapture<I>
-where
-    I: Source<Item = f32>,
-{
-    fn current_span_len(&self) -> Option<usize> {
-        self.source.current_span_len()
-    }
+        if ring_bell {
+            use std::io::Write;
+            let mut stdout = std::io::stdout();
+            stdout.write_all(b"\x07")?;
+            stdout.flush()?;
+        }
 
-    fn channels(&self) -> u16 {
-        self.source.channels()
+        if let Some(delay) = options.render_delay {
+            std::thread::sleep(delay);
+        }
     }
 
-    fn sample_rate(&self) -> u32 {
-        self.sample_rate
+    // Every way out of the loop, 'q' or Ctrl+C from any screen or the
+    // auto-exit, ends up here
+    if !calibrate {
+        dynamic_range.extend(streams.iter().map(|stream| track_dynamic_range(&stream.input)));
     }
-
-    fn total_duration(&self) -> Option<std::time::Duration> {
-        self.source.total_duration()
+    #[cfg(feature = "discord")]
+    if let Some(presence) = options.presence.take() {
+        presence.finish();
     }
-}
-
-// Map frequency index to smooth VIBGYOR gradient (true color)
-fn frequency_to_color(index: usize, total: usize) -> Color {
-    // Ensure we don't divide by zero
-    let total = total.max(1);
-    let ratio = index as f32 / (total - 1) as f32; // 0.0 to 1.0
 
-    // Smooth gradient: Red -> Orange -> Yellow -> Green -> Cyan -> Blue -> Violet
-    // Using HSV-like interpolation for smooth transitions
-
-    if ratio < 0.167 {
-        // Red (0%) -> Orange (16.7%)
-        let t = ratio / 0.167;
-        Color::Rgb(255, (165.0 * t) as u8, 0)
-    } else if ratio < 0.333 {
-        // Orange (16.7%) -> Yellow (33.3%)
-        let t = (ratio - 0.167) / 0.166;
-        Color::Rgb(255, (165.0 + 90.0 * t) as u8, 0)
-    } else if ratio < 0.5 {
-        // Yellow (33.3%) -> Green (50%)
-        let t = (ratio - 0.333) / 0.167;
-        Color::Rgb((255.0 * (1.0 - t)) as u8, 255, 0)
-    } else if ratio < 0.667 {
-        // Green (50%) -> Cyan (66.7%)
-        let t = (ratio - 0.5) / 0.167;
-        Color::Rgb(0, 255, (255.0 * t) as u8)
-    } else if ratio < 0.833 {
-        // Cyan (66.7%) -> Blue (83.3%)
-        let t = (ratio - 0.667) / 0.166;
-        Color::Rgb(0, (255.0 * (1.0 - t)) as u8, 255)
-    } else {
-        // Blue (83.3%) -> Violet (100%)
-        let t = (ratio - 0.833) / 0.167;
-        Color::Rgb((148.0 * t) as u8, 0, (255.0 - 44.0 * t) as u8)
-    }
+    session.end();
+
+    // The guard restores the terminal on the way out
+    Ok(VisualizerSummary {
+        latency_offset_ms,
+        dropped_frames,
+        dynamic_range,
+        stats: session,
+        error,
+        hung_up: signals.hung_up(),
+    })
 }
 
-// Perform FFT and visualize frequencies with ratatui
-fn visualize_frequencies(
-    buffer: Arc<Mutex<Vec<f32>>>,
-    sample_rate: u32,
-    total_duration: f32,
-    should_stop: Arc<AtomicBool>,
-) -> Result<(), Box<dyn std::error::Error>> {
+// --oneline: the first stream's spectrum as a row of block characters with
+// the time, redrawn in place with a carriage return, or a line at a time when
+// piped. Keys are only read from a terminal: 'q', Esc or Ctrl+C quit, space
+// pauses and Ctrl+Z suspends. It returns once playback is over.
+fn show_oneline(
+    inputs: Vec<StreamInput>,
+    options: VisualizerOptions,
+) -> Result<VisualizerSummary, Box<dyn std::error::Error>> {
+    use std::io::{IsTerminal, Write};
     use std::time::Instant;
-
-    // Setup terminal
-    enable_raw_mode()?;
+    use crossterm::style::{Color as TermColor, Stylize};
+
+    let keys = std::io::stdin().is_terminal();
+    let signals = terminal::Signals::install();
+    let mut guard = terminal::TerminalGuard::enter(terminal::Setup {
+        raw_mode: keys,
+        screen: false,
+        ..Default::default()
+    })?;
+    let mut suspend = Suspend::default();
     let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    let mut planner = FftPlanner::new();
-    let fft = planner.plan_fft_forward(1024);
-    let start_time = Instant::now();
-
-    // Dynamic number of bands based on terminal width (will be updated each frame)
-    let mut num_bands = 60;
-    let mut smoothed_bands = vec![0.0f32; num_bands];
+    let in_place = stdout.is_terminal();
+    let colored = in_place && options.theme.is_colored();
+
+    let mut input = inputs.into_iter().next().expect("--oneline plays one stream");
+    let settings = base_settings(&options.config);
+    let mut analyzer = analysis::Analyzer::new(input.sample_rate, options.fft_size, options.smoothing, settings.tilt.clone());
+    analyzer.set_smoother(smoother_for(&settings, &options.config));
+    analyzer.set_max_display_freq(options.config.max_display_freq());
+    let mut levels: Vec<f32> = Vec::new();
+    let mut analyzed_at: Option<Instant> = None;
+    let mut playhead = transport::Playhead::start(Instant::now(), false);
+    let mut lost_seen = std::time::Duration::ZERO;
+    let mut session = stats::Session::default();
+    session.start(&input.name, input.duration);
+    let mut listened_at = Instant::now();
+    let mut dynamic_range = Vec::new();
+    let mut error = None;
+    let mut finished = false;
+    // Columns the last row took, blanked out when the next is shorter
+    let mut shown_width: usize = 0;
+    let frame_interval = std::time::Duration::from_secs_f32(1.0 / ONELINE_FPS as f32);
 
     loop {
-        // Check for Ctrl+C or 'q' key
-        if poll(std::time::Duration::from_millis(0))? {
-            if let Event::Key(key) = read()? {
-                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
-                    || key.code == KeyCode::Char('q')
-                {
-                    should_stop.store(true, Ordering::Relaxed);
-                    break;
+        let frame_start = Instant::now();
+        if signals.quit() {
+            break;
+        }
+        if signals.take_stop() {
+            suspend.request(!finished && !playhead.is_paused(), &options.player_commands);
+        }
+        while let Ok(event) = options.player_events.try_recv() {
+            match event {
+                PlayerEvent::TrackStarted { input: next, .. } => {
+                    if !Arc::ptr_eq(&next.dynamics, &input.dynamics) {
+                        if dynamic_range.len() >= stats::MAX_TRACKS {
+                            dynamic_range.remove(0);
+                        }
+                        dynamic_range.push(track_dynamic_range(&input));
+                        session.start(&next.name, next.duration);
+                    }
+                    analyzer.set_sample_rate(next.sample_rate);
+                    analyzer.reset();
+                    input = next;
+                    playhead = transport::Playhead::start(Instant::now(), playhead.is_paused());
+                    lost_seen = std::time::Duration::ZERO;
+                }
+                PlayerEvent::TrackEnded { .. } => session.end(),
+                PlayerEvent::Finished => finished = true,
+                PlayerEvent::Paused(position) => playhead.pause(position),
+                PlayerEvent::Resumed(position) => playhead.resume(position, Instant::now()),
+                PlayerEvent::Seeked(position) => {
+                    analyzer.flush();
+                    playhead.seek(position, Instant::now());
                 }
+                PlayerEvent::OutputReopened { input: reopened, position } => {
+                    input = reopened;
+                    lost_seen = std::time::Duration::ZERO;
+                    analyzer.flush();
+                    playhead.seek(position, Instant::now());
+                }
+                PlayerEvent::OutputLost(message) => {
+                    error = Some(message);
+                    finished = true;
+                }
+                // Nowhere to say so on one row
+                PlayerEvent::TrackFailed(_) | PlayerEvent::SeekFailed(_) | PlayerEvent::OutputStalled => {}
             }
         }
-
-        let elapsed = start_time.elapsed().as_secs_f32();
-        if elapsed >= total_duration || should_stop.load(Ordering::Relaxed) {
-            break;
+        // The row was ended on the way out; back, it starts on a new one
+        if suspend.run(playhead.is_paused(), &mut guard, &signals, &options.player_commands)? {
+            shown_width = 0;
+        }
+        if let Ok(meter) = input.flow.lock() {
+            let lost = meter.lost(Instant::now());
+            playhead.delay(lost.saturating_sub(lost_seen));
+            lost_seen = lost;
         }
 
-        std::thread::sleep(std::time::Duration::from_millis(16)); // ~60 FPS
-
-        // Get samples from buffer
-        let samples = {
-            if let Ok(buf) = buffer.lock() {
-                if buf.len() < 1024 {
-                    continue;
-                }
-                buf.iter().rev().take(1024).rev().copied().collect::<Vec<f32>>()
-            } else {
-                continue;
+        // The newest window, as the full screen takes it; paused, or with no
+        // fresh window yet after a jump, the bars stay as they were
+        let mut window_time = None;
+        let window = input.buffer.lock().ok().and_then(|ring| {
+            if let Some(rate) = ring.sample_rate() {
+                analyzer.set_sample_rate(rate);
+            }
+            let fft_size = analyzer.state().fft_size();
+            let offset = capture::offset_samples(
+                options.device_latency_ms + options.latency_offset_ms,
+                ring.sample_rate().unwrap_or(input.sample_rate),
+            );
+            window_time = ring.window_time(fft_size, offset);
+            if ring.holding(fft_size) {
+                return None;
             }
+            ring.window(fft_size, offset).or_else(|| analyzer.priming().then(|| vec![0.0; fft_size]))
+        });
+        let elapsed = match (finished, window_time) {
+            (true, _) => input.duration,
+            (false, Some(time)) => time.min(input.duration),
+            (false, None) => playhead.position(Instant::now()).as_secs_f32().min(input.duration),
         };
 
-        // Convert to complex numbers for FFT
-        let mut complex_samples: Vec<Complex<f32>> = samples
-            .iter()
-            .map(|&s| Complex { re: s, im: 0.0 })
-            .collect();
-
-        // Perform FFT
-        fft.process(&mut complex_samples);
-
-        // Calculate magnitude for each frequency bin
-        let magnitudes: Vec<f32> = complex_samples
-            .iter()
-            .take(512) // Only first half (Nyquist)
-            .map(|c| (c.re * c.re + c.im * c.im).sqrt())
-            .collect();
-
-        // Will be calculated after we know terminal width
-        let mut bands = vec![0.0f32; num_bands];
-        let freq_per_bin = sample_rate as f32 / 1024.0;
-
-        // Define logarithmic frequency ranges (more bins for low freq, fewer for high)
-        let min_freq: f32 = 20.0; // Human hearing starts ~20 Hz
-        let max_freq: f32 = (sample_rate / 2) as f32; // Nyquist frequency (22.05kHz for 44.1kHz)
-        let log_min = min_freq.ln();
-        let log_max = max_freq.ln();
-
-        for (i, band) in bands.iter_mut().enumerate() {
-            // Calculate logarithmic frequency range for this band
-            let log_start = log_min + (i as f32 / num_bands as f32) * (log_max - log_min);
-            let log_end = log_min + ((i + 1) as f32 / num_bands as f32) * (log_max - log_min);
-
-            let freq_start = log_start.exp();
-            let freq_end = log_end.exp();
-
-            let bin_start = (freq_start / freq_per_bin) as usize;
-            let bin_end = (freq_end / freq_per_bin).min(512.0) as usize;
-
-            if bin_start < bin_end && bin_end <= magnitudes.len() {
-                // Average magnitude in this frequency range
-                *band = magnitudes[bin_start..bin_end].iter().sum::<f32>() / (bin_end - bin_start) as f32;
-
-                // Apply slight boost to higher frequencies for better visibility
-                let boost = 1.0 + (i as f32 / num_bands as f32) * 2.0;
-                *band *= boost;
+        let listened_now = Instant::now();
+        if !finished
+            && !playhead.is_paused()
+            && let Some(track) = session.current_mut()
+        {
+            track.listen(listened_now.duration_since(listened_at).as_secs_f32());
+            if let Some(samples) = &window {
+                track.frame(samples);
             }
         }
+        listened_at = listened_now;
 
-        // Apply exponential smoothing (0.3 = smooth, 0.7 = responsive)
-        let smoothing_factor = 0.3;
-        for (i, &new_value) in bands.iter().enumerate() {
-            smoothed_bands[i] = smoothed_bands[i] * (1.0 - smoothing_factor) + new_value * smoothing_factor;
+        let time = gruvberry::oneline::time(elapsed, input.duration);
+        let width = if in_place {
+            crossterm::terminal::size().map_or(ONELINE_PIPED_WIDTH, |(columns, _)| columns as usize)
+        } else {
+            ONELINE_PIPED_WIDTH
+        };
+        let layout = gruvberry::oneline::Layout::fit(width, time.chars().count());
+        let samples = if finished { Some(vec![0.0; analyzer.state().fft_size()]) } else { window };
+        if let Some(samples) = samples {
+            let now = Instant::now();
+            let dt = analyzed_at.map_or(1.0 / smoothing::REFERENCE_FPS, |at| (now - at).as_secs_f32());
+            analyzed_at = Some(now);
+            let bands = analyzer.process_after(&samples, layout.cells, dt);
+            levels = analysis::normalize(bands, analysis::max_amplitude(bands));
+            analysis::apply_visual_gain(&mut levels, options.config.visual_gain_db);
+        }
+        if levels.len() != layout.cells {
+            levels = vec![0.0; layout.cells];
         }
 
-        // Normalize bands
-        let max_amplitude = smoothed_bands.iter().cloned().fold(0.0f32, f32::max).max(1.0);
-
-        // Normalize to 0-100 range for visualization
-        let normalized_bands: Vec<f32> = smoothed_bands
-            .iter()
-            .map(|&band| (band / max_amplitude) * 100.0)
-            .collect();
-
-        // Calculate num_bands BEFORE terminal.draw to update smoothed_bands size
-        let current_size = terminal.size().unwrap_or_else(|_| ratatui::layout::Size { width: 80, height: 24 });
-        let current_width = current_size.width;
-
-        // Calculate based on terminal width (80-160 total columns including borders)
-        const MIN_WIDTH: u16 = 80;
-        const MAX_WIDTH: u16 = 160;
-
-        let (calculated_num_bands, num_legend_bands) = if current_width >= MIN_WIDTH {
-            let usable_width = current_width.min(MAX_WIDTH).saturating_sub(4); // Cap at 160 total, then exclude borders
-            let bands = usable_width as usize; // 1 column = 1 band
-
-            // Legend bands: 1 legend per 10 characters (8-16 legends total)
-            let legends = (usable_width / 10).max(8).min(16) as usize;
-
-            (bands, legends)
+        let bars = gruvberry::oneline::bars(&levels, layout.cells);
+        let mut row = if colored {
+            let colors = spectrum::band_colors(layout.cells, analyzer.range(), settings.gradient, &options.theme);
+            bars.chars().zip(colors).map(|(bar, color)| bar.with(TermColor::from(color)).to_string()).collect()
         } else {
-            // Terminal too small, keep current values and show warning
-            (num_bands, 8)
+            bars
         };
-
-        // Resize smoothed_bands if terminal width changed
-        if calculated_num_bands != num_bands {
-            num_bands = calculated_num_bands;
-            smoothed_bands.resize(num_bands, 0.0);
-            bands.resize(num_bands, 0.0);
+        if layout.time {
+            row.push(' ');
+            row.push_str(&time);
+        }
+        let row_width = layout.width(time.chars().count());
+        row.push_str(&" ".repeat(shown_width.saturating_sub(row_width)));
+        shown_width = row_width;
+        let written = if in_place { write!(stdout, "\r{}", row) } else { writeln!(stdout, "{}", row) };
+        match written.and_then(|_| stdout.flush()) {
+            // Whatever read the lines (a status bar, `head`) has gone
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => break,
+            Err(_) if signals.hung_up() => break,
+            written => written?,
+        }
+        if finished {
+            break;
         }
 
-        // Render UI
-        terminal.draw(|f| {
-            let terminal_width = f.area().width;
-            let terminal_height = f.area().height;
+        let wait = frame_interval.saturating_sub(frame_start.elapsed());
+        if !keys {
+            std::thread::sleep(wait);
+            continue;
+        }
+        if poll(wait)?
+            && let Event::Key(key) = read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    suspend.request(!finished && !playhead.is_paused(), &options.player_commands);
+                }
+                KeyCode::Char(' ') => {
+                    let _ = options.player_commands.send(PlayerCommand::TogglePause);
+                }
+                _ => {}
+            }
+        }
+    }
 
-            // Minimum terminal size requirement
-            const MIN_WIDTH: u16 = 80; // Total including borders
-            const MIN_HEIGHT: u16 = 20;
-            const MAX_DISPLAY_WIDTH: u16 = 160; // Max total width including borders
+    dynamic_range.push(track_dynamic_range(&input));
+    session.end();
+    Ok(VisualizerSummary {
+        latency_offset_ms: options.latency_offset_ms,
+        dropped_frames: 0,
+        dynamic_range,
+        stats: session,
+        error,
+        hung_up: signals.hung_up(),
+    })
+}
 
-            if terminal_width < MIN_WIDTH || terminal_height < MIN_HEIGHT {
-                // Terminal too small - show warning
-                let warning_text = format!(
-                    "Terminal too small!\n\n\
-                     Current size: {}x{}\n\
-                     Minimum required: {}x{}\n\n\
-                     Please resize your terminal window.",
-                    terminal_width, terminal_height, MIN_WIDTH, MIN_HEIGHT
-                );
-                let warning_widget = Paragraph::new(warning_text)
-                    .block(Block::default().borders(Borders::ALL).title("Error"))
-                    .style(ratatui::style::Style::default().fg(ratatui::style::Color::Red));
-                f.render_widget(warning_widget, f.area());
-                return;
-            }
+// What the line typed into the prompt is for
+#[derive(Clone, Copy)]
+enum PromptFor {
+    // A file to add to the queue ('a')
+    Queue,
+    // The name to save the theme editor's gradient as
+    ThemeName,
+}
 
-            // Constrain display area to max width, left-aligned
-            let display_width = terminal_width.min(MAX_DISPLAY_WIDTH);
-            let display_area = ratatui::layout::Rect {
-                x: f.area().x,
-                y: f.area().y,
-                width: display_width,
-                height: terminal_height,
-            };
+// Queue the file typed into the prompt or sent over the control socket,
+// returning the message to show. Anything that isn't a readable WAV file (or
+// raw file, with --raw-pcm) is refused with the reason.
+fn queue_file(playlist: Option<&playlist::SharedPlaylist>, reading: Reading, text: &str) -> Result<String, String> {
+    let playlist = playlist.ok_or("Not added: there is no queue while comparing or calibrating")?;
+    let path = prompt::expand_home(text.trim());
+    if let Err(e) = probe(&path, reading.raw).and_then(|probed| Ok(reading.segment.check(probed.duration())?)) {
+        return Err(format!("Not added: {}: {}", display::path(&path), e));
+    }
+    let mut playlist = playlist.lock().map_err(|_| "Not added: the queue is gone")?;
+    playlist.add(path.clone());
+    Ok(format!("Queued {} (#{})", display::file_name(&path), playlist.entries().len()))
+}
 
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Min(10),     // Frequency spectrum (main visualization)
-                    Constraint::Length(3),   // Legend indicators
-                    Constraint::Length(num_legend_bands.min(10) as u16 / 2 + 3), // Legend details (dynamic height)
-                    Constraint::Length(3),   // Time progress
-                ].as_ref())
-                .split(display_area);
-
-            // Calculate actual usable width for spectrum (exclude borders)
-            let spectrum_width = chunks[0].width.saturating_sub(2) as usize; // Subtract borders
-            let spectrum_height = chunks[0].height.saturating_sub(2) as usize;
-
-            // Build spectrum as text lines (row by row, from top to bottom)
-            let mut spectrum_lines: Vec<Line> = Vec::new();
-
-            for row in (0..spectrum_height).rev() {
-                let mut spans: Vec<Span> = Vec::new();
-
-                for col in 0..spectrum_width {
-                    // Map screen column to frequency band index
-                    let band_index = ((col as f64 / spectrum_width as f64) * num_bands as f64) as usize;
-                    if band_index >= normalized_bands.len() {
-                        spans.push(Span::raw(" "));
-                        continue;
-                    }
+// Save `frame` as an SVG image to `path`, or to a file named after the time
+// in the current directory, returning the message to show
+fn export_svg(frame: &history::AnalysisFrame, anchor: gradient::Anchor, path: Option<&std::path::Path>) -> String {
+    let path = path.map_or_else(
+        || {
+            let secs = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs());
+            PathBuf::from(format!("gruvberry-{}.svg", secs))
+        },
+        std::path::Path::to_path_buf,
+    );
+    match std::fs::write(&path, gruvberry::svg::render(frame, anchor)) {
+        Ok(()) => format!("Spectrum saved to {}", display::path(&path)),
+        Err(e) => format!("Couldn't save {}: {}", display::path(&path), e),
+    }
+}
 
-                    let amplitude = normalized_bands[band_index];
-                    let color = frequency_to_color(band_index, num_bands);
+// How large each stream's sample ring is: the config's capture_buffer, or
+// (0) enough history to read the FFT window at the largest latency offset on
+// top of the largest device latency. Either way it holds at least the
+// longer window a high sample rate gets.
+#[derive(Clone, Copy)]
+struct RingSize {
+    // The longest window read from the ring (the spectrum's and the phase
+    // meter's)
+    fft_size: usize,
+    capture_buffer: usize,
+}
 
-                    // Calculate how high this bar should be (1-spectrum_height, minimum 1)
-                    let bar_height = ((amplitude / 100.0) * spectrum_height as f32) as usize;
-                    let bar_height = bar_height.max(1); // Always show at least 1 character
+impl RingSize {
+    fn capacity(self, sample_rate: u32) -> usize {
+        let fft_size = rate::fft_size_for(self.fft_size, sample_rate);
+        match self.capture_buffer {
+            0 => capture::ring_capacity(fft_size, sample_rate, capture::MAX_OFFSET_MS + MAX_DEVICE_LATENCY_MS),
+            samples => samples.max(fft_size),
+        }
+    }
+}
 
-                    // If this row is below the bar height, draw a block
-                    if row < bar_height {
-                        spans.push(Span::styled("█", Style::default().fg(color)));
-                    } else {
-                        spans.push(Span::raw(" "));
-                    }
-                }
+// How long samples wait in the output buffer before reaching the speakers,
+// and whether the device told us. With the default buffer size the backend
+// picks one without reporting it, so FALLBACK_DEVICE_LATENCY_MS stands in.
+fn device_latency_ms(stream: &rodio::OutputStream) -> (i32, bool) {
+    let config = stream.config();
+    match config.buffer_size() {
+        rodio::cpal::BufferSize::Fixed(frames) => {
+            let ms = *frames as u64 * 1000 / config.sample_rate().max(1) as u64;
+            ((ms as i32).min(MAX_DEVICE_LATENCY_MS), true)
+        }
+        rodio::cpal::BufferSize::Default => (FALLBACK_DEVICE_LATENCY_MS, false),
+    }
+}
 
-                spectrum_lines.push(Line::from(spans));
-            }
+// How every file is read: the format of headerless ones (--raw-pcm) and the
+// part of each that plays (--start/--end)
+#[derive(Clone, Copy)]
+struct Reading {
+    raw: Option<RawFormat>,
+    segment: Segment,
+}
 
-            let spectrum = Paragraph::new(spectrum_lines)
-                .block(
-                    Block::default()
-                        .title(format!("Gruvberry - Frequency Spectrum (20Hz - {:.1}kHz) VIBGYOR", max_freq / 1000.0))
-                        .borders(Borders::ALL),
-                );
+// What each track's audio passes through on its way out besides the
+// analysis: the --record-audio file and the echo
+#[derive(Clone)]
+struct Chain {
+    recorder: Option<record::Recorder>,
+    echo: echo::SharedEcho,
+}
 
-            f.render_widget(spectrum, chunks[0]);
+// Decode a WAV file, or a raw one in `reading`'s format, (its segment only,
+// scaled by `gain`) through a sample capture, along with the details the
+// visualizer shows for it. `carry` is the ring and sample rate of the track
+// before it in a playlist.
+fn open_track(
+    path: &PathBuf,
+    reading: Reading,
+    gain: f32,
+    ring_size: RingSize,
+    carry: Option<(&SharedRing, u32)>,
+    chain: Chain,
+    locale: Locale,
+) -> Result<(StreamInput, Box<dyn Source + Send>), Box<dyn std::error::Error>> {
+    let Reading { raw, segment } = reading;
+    let probed = probe(path, raw)?;
+    let (spec, duration) = (probed.spec, probed.duration());
+    segment.check(duration)?;
+    let name = display::file_name(path);
+    let mut info = format!(
+        "{} · {} · {} ch · {}",
+        name,
+        locale.rate(spec.sample_rate),
+        spec.channels,
+        format::time(duration)
+    );
+    if let Some(format) = raw {
+        info.push_str(&format!(" · raw {}", format.layout.name()));
+    }
+    if !segment.is_whole() {
+        let end = segment.end.map_or(duration, |end| end.as_secs_f32().min(duration));
+        info.push_str(&format!(" · playing {}-{}", format::time(segment.start.as_secs_f32()), format::time(end)));
+    }
+    if gain != 1.0 {
+        info.push_str(&format!(" · level {}", locale.db(20.0 * gain.log10(), 1)));
+    }
 
-            // Legend indicators (|---1---|---2---|...) - must match spectrum_width exactly
-            let legend_width = chunks[1].width.saturating_sub(2) as usize; // Match legend box width
-            let mut legend_spans: Vec<Span> = Vec::new();
+    let sample_rate = spec.sample_rate;
+    let source: Box<dyn Source + Send> = match raw {
+        Some(format) => Box::new(raw::open(path, format)?),
+        None => Box::new(Decoder::new(BufReader::new(File::open(path)?))?),
+    };
+    // Cut to the segment before anything sees a sample, so the ring, the
+    // recording and the clock all start at its start
+    let source = SegmentSource::new(source, segment);
+    // --record-audio keeps the file's own samples, before any level matching
+    let source = SampleCapture::new(source, chain.recorder);
+    // Played as it is; only the analyzer's ring mixes it down to mono
+    let source = source.amplify(gain);
+    // Gapless playlists: keep capturing into the previous track's ring so the
+    // first windows of this track are filled with the end of the last one
+    // instead of the display stalling until a full window has played. A
+    // different sample rate can't share the ring (the window would mix two
+    // time scales), so that starts empty.
+    let buffer = match carry {
+        Some((ring, rate)) if rate == sample_rate => ring.clone(),
+        _ => SampleRing::shared(ring_size.capacity(sample_rate)),
+    };
+    let dynamics = dynamics::DynamicsMeter::shared();
+    let flow = flow::FlowMeter::shared();
+    // The ring is filled on whichever side of the echo the settings pick;
+    // the dynamics are the track's own, and the flow meter counts what the
+    // output takes
+    let dry = SampleCapture::new(
+        source,
+        (echo::Tapped::new(echo::Tap::Dry, chain.echo.clone(), buffer.clone()), dynamics.clone()),
+    );
+    let echoed = echo::Echo::new(dry, chain.echo.clone());
+    let wrapped_source =
+        SampleCapture::new(echoed, (echo::Tapped::new(echo::Tap::Wet, chain.echo, buffer.clone()), flow.clone()));
+
+    let input = StreamInput {
+        name,
+        info,
+        buffer,
+        dynamics,
+        flow,
+        sample_rate,
+        channels: spec.channels,
+        duration: segment.length(duration),
+        start: (!segment.is_whole()).then_some(segment.start.as_secs_f32()),
+        overview: Some(overview::spawn_envelope_scan(path.clone(), raw, segment)),
+        warning: probed.truncation_warning().map(|warning| format!("{}: {}", display::file_name(path), warning)),
+    };
+    Ok((input, Box::new(wrapped_source)))
+}
 
-            let segment_width = legend_width / num_legend_bands;
-            let remainder_width = legend_width % num_legend_bands; // Partial segment at the end
+// The player thread's side of playback: the streams' transports, the
+// playlist it works through and what it tells the visualizer
+struct PlayerLoop<T: Transport> {
+    player: Player<T>,
+    playlist: Option<playlist::SharedPlaylist>,
+    events: events::EventBus<PlayerEvent>,
+    commands: mpsc::Receiver<PlayerCommand>,
+    // Index of the stream to hear, as the visualizer last set it
+    audible: Arc<AtomicUsize>,
+    // How large each new track's ring is made
+    ring_size: RingSize,
+    // The playlist's ring, handed from track to track
+    carry: (SharedRing, u32),
+    // Where the playlist's audio is recorded to (--record-audio), and the
+    // echo it plays through
+    chain: Chain,
+    // How every file is read
+    reading: Reading,
+    // How numbers read in the details of the tracks it opens
+    locale: Locale,
+    // Flow meter of each stream's track, whose clock stops while paused
+    flows: Vec<flow::SharedFlow>,
+    // Capture ring of each stream's track, marked where playback pauses and
+    // resumes
+    rings: Vec<SharedRing>,
+    // A transport on a newly opened output device, for when the one in use
+    // stalls
+    open_output: Box<dyn FnMut() -> Result<T, String>>,
+    // Playlist entry on the first stream, and the seconds playback can
+    // seek within (the shortest compared file)
+    playing: Option<usize>,
+    duration: f32,
+}
 
-            for i in 0..num_legend_bands {
-                let num_str = (i + 1).to_string();
-                let num_len = num_str.len();
+impl<T: Transport<Track = Box<dyn Source + Send>>> PlayerLoop<T> {
+    // Route audio to the selected stream, carry out pause and seek requests
+    // and move through the playlist until told to stop or everything has
+    // played. True in the latter case. With `hold` the end of playback
+    // doesn't return: the finished state waits for 'n' to play again.
+    fn run(&mut self, should_stop: &AtomicBool, hold: bool) -> bool {
+        // False once the playing entry is cut short, until the next one starts
+        let mut seamless = true;
+        // Everything has played and the finished state holds
+        let mut finished = false;
+        let mut watchdog = Watchdog::new(watchdog::STALL_TIMEOUT, std::time::Instant::now());
+        while !should_stop.load(Ordering::Relaxed) {
+            let selected = self.audible.load(Ordering::Relaxed);
+            if selected != self.player.audible() {
+                self.player.hear(selected);
+            }
 
-                // Build segment: |---N---
-                legend_spans.push(Span::raw("|"));
+            while let Ok(command) = self.commands.try_recv() {
+                self.command(command);
+            }
 
-                // Account for variable digit length (1-9 = 1 char, 10-20 = 2 chars)
-                let padding = segment_width.saturating_sub(num_len + 1); // 1 for |
-                let left_pad = padding / 2;
-                let right_pad = padding - left_pad;
+            // Next/previous, or the playing entry was removed from the queue
+            if let Some(playlist) = &self.playlist
+                && playlist.lock().is_ok_and(|mut playlist| playlist.take_interrupt())
+            {
+                if finished {
+                    self.playing = self.play_again();
+                    finished = self.playing.is_none();
+                } else {
+                    self.player.skip();
+                    seamless = false;
+                }
+            }
 
-                if left_pad > 0 {
-                    legend_spans.push(Span::raw("─".repeat(left_pad)));
+            if !finished && self.player.track_ended() {
+                if let Some(index) = self.playing.take() {
+                    self.events.publish(PlayerEvent::TrackEnded { index });
                 }
-                legend_spans.push(Span::raw(num_str));
-                if right_pad > 0 {
-                    legend_spans.push(Span::raw("─".repeat(right_pad)));
+                self.playing = self.play_next(seamless);
+                seamless = true;
+                if self.playing.is_none() {
+                    if !hold {
+                        return true;
+                    }
+                    self.finish();
+                    finished = true;
                 }
             }
 
-            // Handle remainder/partial segment if exists
-            if remainder_width > 0 {
-                let num_str = (num_legend_bands + 1).to_string();
-                let num_len = num_str.len();
+            // The output should be pulling samples whenever something plays
+            let playing = !finished && !self.player.is_paused() && !self.player.track_ended();
+            let pulled = self.flows.iter().filter_map(|flow| flow.lock().ok()?.last_pulled()).max();
+            let stalled = match watchdog.check(pulled, playing, std::time::Instant::now()) {
+                watchdog::Verdict::Running => None,
+                watchdog::Verdict::Reopen => {
+                    self.events.publish(PlayerEvent::OutputStalled);
+                    let reopened = self.reopen_output();
+                    watchdog.reopened(std::time::Instant::now());
+                    reopened.err()
+                }
+                watchdog::Verdict::GiveUp => Some("it stalled again after reopening the device".to_string()),
+            };
+            if let Some(reason) = stalled {
+                self.events.publish(PlayerEvent::OutputLost(format!(
+                    "Audio output stopped taking samples for {} s and playback can't go on: {}",
+                    watchdog::STALL_TIMEOUT.as_secs(),
+                    reason
+                )));
+                return false;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        false
+    }
 
-                legend_spans.push(Span::raw("|"));
+    // Go on with the first stream's track from where it had got to, on a
+    // newly opened output device. Only a playlist's track can be opened
+    // again; compared files and the click track aren't kept by path.
+    fn reopen_output(&mut self) -> Result<(), String> {
+        if self.player.transports().len() > 1 {
+            return Err("compared files can't be reopened".to_string());
+        }
+        let (index, path) = self
+            .playlist
+            .as_ref()
+            .and_then(|playlist| {
+                let playlist = playlist.lock().ok()?;
+                let index = playlist.current()?;
+                Some((index, playlist.entries().get(index)?.clone()))
+            })
+            .ok_or("only a playlist's track can be reopened")?;
+        let position = self.player.position();
+        let mut transport = (self.open_output)()?;
+        let (input, mut source) = open_track(&path, self.reading, 1.0, self.ring_size, None, self.chain.clone(), self.locale)
+            .map_err(|e| e.to_string())?;
+        // Seeking the source itself, before the device has it, can't wait on
+        // a device that doesn't respond
+        source.try_seek(position).map_err(|e| e.to_string())?;
+        transport.append(source);
+        self.player.replace(0, transport);
+        self.carry = (input.buffer.clone(), input.sample_rate);
+        self.flows[0] = input.flow.clone();
+        self.set_ring(input.buffer.clone());
+        self.playing = Some(index);
+        self.events.publish(PlayerEvent::OutputReopened { input, position });
+        Ok(())
+    }
 
-                if remainder_width == 1 {
-                    // Only 1 char: show *
-                    legend_spans.push(Span::raw("*".to_string()));
-                } else if remainder_width == 2 {
-                    // 2 chars: show number directly
-                    if num_len == 1 {
-                        legend_spans.push(Span::raw(num_str));
-                    } else {
-                        legend_spans.push(Span::raw("*".to_string()));
+    fn command(&mut self, command: PlayerCommand) {
+        match command {
+            PlayerCommand::TogglePause => {
+                let position = self.player.position();
+                // The rings drop what was played before the pause just ahead
+                // of the streams going on, so fresh samples land after that
+                for ring in &self.rings {
+                    if let Ok(mut ring) = ring.lock() {
+                        ring.resume();
                     }
-                } else {
-                    // 3+ chars: show with padding
-                    let padding = remainder_width.saturating_sub(num_len + 1); // 1 for |
-                    let left_pad = padding / 2;
-                    let right_pad = padding - left_pad;
-
-                    if left_pad > 0 {
-                        legend_spans.push(Span::raw("─".repeat(left_pad)));
+                }
+                let paused = self.player.toggle_pause();
+                if paused {
+                    for ring in &self.rings {
+                        if let Ok(mut ring) = ring.lock() {
+                            ring.pause();
+                        }
                     }
-                    legend_spans.push(Span::raw(num_str.clone()));
-                    if right_pad > 0 {
-                        legend_spans.push(Span::raw("─".repeat(right_pad)));
+                }
+                for flow in &self.flows {
+                    if let Ok(mut meter) = flow.lock() {
+                        if paused {
+                            meter.pause(std::time::Instant::now());
+                        } else {
+                            meter.resume(std::time::Instant::now());
+                        }
                     }
                 }
+                self.events.publish(if paused {
+                    PlayerEvent::Paused(position)
+                } else {
+                    PlayerEvent::Resumed(position)
+                });
+            }
+            PlayerCommand::Seek(delta) => {
+                let limit = std::time::Duration::try_from_secs_f32(self.duration).unwrap_or(std::time::Duration::MAX);
+                self.events.publish(match self.player.seek_by(delta, limit) {
+                    Ok(position) => PlayerEvent::Seeked(position),
+                    Err(e) => PlayerEvent::SeekFailed(format!("Can't seek: {}", e)),
+                });
             }
+        }
+    }
 
-            // Add final closing pipe
-            legend_spans.push(Span::raw("|"));
+    // Capture into `ring` on the first stream from now on, paused if the
+    // player is
+    fn set_ring(&mut self, ring: SharedRing) {
+        if self.player.is_paused()
+            && let Ok(mut ring) = ring.lock()
+        {
+            ring.pause();
+        }
+        self.rings[0] = ring;
+    }
 
-            let legend_indicators = Paragraph::new(Line::from(legend_spans))
-                .block(Block::default().borders(Borders::ALL).title("Frequency Ranges"));
-            f.render_widget(legend_indicators, chunks[1]);
+    // Cut off the longer of two compared files, but let the device play out
+    // what it has buffered while the visualizer shows the end
+    fn finish(&mut self) {
+        self.player.stop();
+        self.events.publish(PlayerEvent::Finished);
+    }
 
-            // Legend details (frequency ranges with colors)
-            let mut legend_details = String::new();
-            let total_legend_items = if remainder_width > 0 { num_legend_bands + 1 } else { num_legend_bands };
+    // From the finished state: whatever was queued after the end, or else
+    // the playlist again from the top
+    fn play_again(&mut self) -> Option<usize> {
+        self.play_next(false).or_else(|| {
+            self.playlist.as_ref()?.lock().ok()?.rewind();
+            self.play_next(false)
+        })
+    }
 
-            for i in 0..total_legend_items {
-                let band_index = (i * num_bands) / num_legend_bands.max(1);
+    // Start the playlist's next playable entry on the first stream,
+    // announcing it (and any entry that had to be skipped). Returns its
+    // index, or None at the end. A `seamless` start continues in the
+    // previous entry's ring; otherwise the ring starts empty so no window
+    // mixes audio from both sides of the jump.
+    fn play_next(&mut self, seamless: bool) -> Option<usize> {
+        let playlist = self.playlist.as_ref()?;
+        loop {
+            let (index, path) = playlist.lock().ok().and_then(|mut playlist| {
+                let path = playlist.advance()?;
+                Some((playlist.current()?, path))
+            })?;
+            let previous = seamless.then_some((&self.carry.0, self.carry.1));
+            match open_track(&path, self.reading, 1.0, self.ring_size, previous, self.chain.clone(), self.locale) {
+                Ok((input, source)) => {
+                    self.carry = (input.buffer.clone(), input.sample_rate);
+                    self.flows[0] = input.flow.clone();
+                    self.set_ring(input.buffer.clone());
+                    self.duration = input.duration;
+                    self.player.append(source);
+                    self.events.publish(PlayerEvent::TrackStarted { index, input, seamless });
+                    return Some(index);
+                }
+                Err(e) => {
+                    self.events.publish(PlayerEvent::TrackFailed(format!("Skipped {}: {}", display::path(&path), e)));
+                }
+            }
+        }
+    }
+}
+
+// What --verbose prints after the visualizer exits: the tracks as they were
+// played, skipped or cut short, an event to a line
+fn session_log_line(event: &PlayerEvent) -> String {
+    match event {
+        PlayerEvent::TrackStarted { index, input, .. } => {
+            format!("Started #{}: {} ({})", index + 1, input.name, format::time(input.duration))
+        }
+        PlayerEvent::TrackEnded { index } => format!("Ended #{}", index + 1),
+        PlayerEvent::TrackFailed(message) => message.clone(),
+        PlayerEvent::Finished => "Finished".to_string(),
+        PlayerEvent::Paused(position) => format!("Paused at {}", format::time(position.as_secs_f32())),
+        PlayerEvent::Resumed(position) => format!("Resumed at {}", format::time(position.as_secs_f32())),
+        PlayerEvent::Seeked(position) => format!("Seeked to {}", format::time(position.as_secs_f32())),
+        PlayerEvent::SeekFailed(message) => message.clone(),
+        PlayerEvent::OutputStalled => "Output stalled".to_string(),
+        PlayerEvent::OutputReopened { position, .. } => {
+            format!("Reopened the output at {}", format::time(position.as_secs_f32()))
+        }
+        PlayerEvent::OutputLost(_) => "Output lost".to_string(),
+    }
+}
 
-                // Calculate frequency range for this legend band
-                let min_freq: f32 = 20.0;
-                let max_freq_val: f32 = (sample_rate / 2) as f32;
-                let log_min = min_freq.ln();
-                let log_max = max_freq_val.ln();
+// Whether the statistics screen is up: once the bars have faded at the end,
+// unless the visualizer is about to close anyway or it is hidden (with Esc,
+// or behind the prompt or the playlist panel until they close)
+fn stats_open(finished: Option<finish::Finish>, show: bool, session: &stats::Session, auto_exit: bool) -> bool {
+    show && !auto_exit && !session.tracks().is_empty() && finished.is_some_and(|finished| finished.is_faded(std::time::Instant::now()))
+}
 
-                let log_start = log_min + (band_index as f32 / num_bands as f32) * (log_max - log_min);
-                let freq_start = log_start.exp();
+// The session's statistics as a header and a row per track, the names cut
+// to `name_width`; the end screen and --stats both show it
+fn stats_table(session: &stats::Session, name_width: usize, locale: Locale) -> Vec<String> {
+    let row = |cells: [&str; 9]| {
+        format!(
+            "{:>3}  {:<name$}  {:>7}  {:>8}  {:>7}  {:>7}  {:>4}  {:<8}  {:>5}",
+            cells[0], cells[1], cells[2], cells[3], cells[4], cells[5], cells[6], cells[7], cells[8],
+            name = name_width
+        )
+    };
+    let mut lines = vec![row(["#", "Track", "Length", "Listened", "Avg dB", "Peak dB", "BPM", "Key", "Clips"])];
+    let value = |value: Option<f32>, decimals: usize| value.map_or("-".to_string(), |value| locale.number(value, decimals));
+    // Numbered through the whole session, past any tracks it no longer keeps
+    for (i, track) in session.tracks().iter().enumerate() {
+        lines.push(row([
+            &(session.earlier() + i + 1).to_string(),
+            &display::fit(&track.name, name_width),
+            &format::time(track.duration),
+            &format::time(track.listened),
+            &value(track.average_db, 1),
+            &value(track.peak_db, 1),
+            &value(track.bpm, 0),
+            track.key.as_deref().unwrap_or("-"),
+            &track.clips.to_string(),
+        ]));
+    }
+    lines
+}
 
-                let freq_label = if freq_start < 1000.0 {
-                    format!("{}:{:.0}Hz", i + 1, freq_start)
-                } else {
-                    format!("{}:{:.1}kHz", i + 1, freq_start / 1000.0)
-                };
+// Where a file's samples change rate on their way out: only in rodio's
+// mixer, after the capture tap, so the analysis always sees the file's rate
+fn resampling_note(file_rate: u32, device_rate: u32, locale: Locale) -> String {
+    if file_rate == device_rate {
+        format!("Resampling: none (file and device at {})", locale.rate(file_rate))
+    } else {
+        format!(
+            "Resampling: {} file to {} device in the output mixer; analysis runs at {} before it",
+            locale.rate(file_rate),
+            locale.rate(device_rate),
+            locale.rate(file_rate)
+        )
+    }
+}
 
-                legend_details.push_str(&format!("{:12}", freq_label));
+// Name and DR figure of a stream's file as far as it has played
+fn track_dynamic_range(input: &StreamInput) -> (String, Option<f32>) {
+    (input.name.clone(), input.dynamics.lock().ok().and_then(|meter| meter.dr()))
+}
 
-                // Add newline every 6 items for better layout
-                if (i + 1) % 6 == 0 {
-                    legend_details.push('\n');
-                }
-            }
+// "Crest ■■■■□□□□ 9.8 dB": peak over RMS of the last few seconds, red when
+// squashed, yellow in between, green when dynamic
+fn crest_meter(crest_db: Option<f32>, locale: Locale, theme: &Theme) -> Line<'static> {
+    let filled = crest_db.map_or(0, |db| {
+        ((db / CREST_METER_MAX_DB).clamp(0.0, 1.0) * CREST_METER_CELLS as f32).round() as usize
+    });
+    let style = match crest_db {
+        Some(db) if db < CREST_SQUASHED_DB => theme.bad,
+        Some(db) if db < CREST_DYNAMIC_DB => theme.warn,
+        Some(_) => theme.good,
+        None => theme.faint,
+    };
+    let reading = crest_db.map_or("  -  ".to_string(), |db| format!("{:>7}", locale.db(db, 1)));
+    Line::from(vec![
+        Span::raw(" Crest "),
+        Span::styled("■".repeat(filled), style),
+        Span::styled("□".repeat(CREST_METER_CELLS - filled), theme.faint),
+        Span::raw(format!(" {} ", reading)),
+    ])
+}
 
-            let legend_widget = Paragraph::new(legend_details)
-                .block(Block::default().borders(Borders::ALL).title("Band Details"));
-            f.render_widget(legend_widget, chunks[2]);
+// "Activity ▮▮▮▯▯▯▯▯": how much the spectrum is changing, up on drum hits
+// and edits and down through sustained tones
+fn activity_meter(level: f32, theme: &Theme) -> Line<'static> {
+    let filled = (level.clamp(0.0, 1.0) * ACTIVITY_METER_CELLS as f32).round() as usize;
+    Line::from(vec![
+        Span::raw(" Activity "),
+        Span::styled("▮".repeat(filled), theme.accent),
+        Span::styled("▯".repeat(ACTIVITY_METER_CELLS - filled), theme.faint),
+        Span::raw(" "),
+    ])
+}
 
-            // Time display
-            let time_text = format!(
-                "Playing: {:.2}s / {:.2}s | Bands: {} | Press 'q' or Ctrl+C to exit",
-                elapsed, total_duration, num_bands
-            );
-            let time_widget = Paragraph::new(time_text)
-                .block(Block::default().borders(Borders::ALL).title("Progress"));
-            f.render_widget(time_widget, chunks[3]);
-        })?;
+// "Phase -1 ───●─── +1 0.82": where the left/right correlation sits between
+// out of phase and mono, red when negative, yellow while wide, green when
+// close to mono. A silent stereo input shows the scale without a marker.
+fn phase_meter(correlation: Option<f32>, locale: Locale, theme: &Theme) -> Line<'static> {
+    let marker = correlation.map(|c| ((c.clamp(-1.0, 1.0) + 1.0) / 2.0 * (PHASE_METER_CELLS - 1) as f32).round() as usize);
+    let scale: String = (0..PHASE_METER_CELLS)
+        .map(|cell| match cell {
+            _ if Some(cell) == marker => '●',
+            _ if cell == PHASE_METER_CELLS / 2 => '┼',
+            _ => '─',
+        })
+        .collect();
+    let style = match correlation {
+        Some(c) if c < 0.0 => theme.bad,
+        Some(c) if c < 0.5 => theme.warn,
+        Some(_) => theme.good,
+        None => theme.faint,
+    };
+    let reading = correlation.map_or("  -  ".to_string(), |c| locale.signed(c, 2));
+    Line::from(vec![
+        Span::raw("Phase -1 "),
+        Span::styled(scale, style),
+        Span::raw(format!(" +1 {} ", reading)),
+    ])
+}
+
+// The 'd' overlay: how much audio is buffered ahead of the speakers (red
+// while starved), underruns so far with the time they cost, dropped frames,
+// samples that had to be replaced for not being numbers, what the analysis
+// runs at and how often its FFT plan, band table and buffers were rebuilt
+fn debug_lines(
+    (fill, underruns, lost): (std::time::Duration, u32, std::time::Duration),
+    dropped_frames: u64,
+    non_finite: u64,
+    analyzer: &analysis::Analyzer,
+    locale: Locale,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let state = analyzer.state();
+    let rebuilds = state.rebuilds();
+    let fill_ms = fill.as_secs_f32() * 1000.0;
+    let filled = ((fill_ms / BUFFER_GAUGE_MAX_MS).clamp(0.0, 1.0) * BUFFER_GAUGE_CELLS as f32).round() as usize;
+    let style = if fill.is_zero() { theme.bad } else { theme.good };
+    vec![
+        Line::from(vec![
+            Span::raw("Buffer "),
+            Span::styled("▮".repeat(filled), style),
+            Span::styled("▯".repeat(BUFFER_GAUGE_CELLS - filled), theme.faint),
+            Span::raw(format!(" {:>6}", locale.ms(fill_ms, 0))),
+        ]),
+        Line::from(format!("Underruns ×{} ({} lost)", underruns, locale.duration(lost.as_secs_f32()))),
+        Line::from(format!("Dropped frames: {}", dropped_frames)),
+        Line::from(format!("Bad samples (NaN/Inf): {}", non_finite)),
+        Line::from(format!("Analysis: {}, FFT {}", locale.rate(analyzer.sample_rate()), state.fft_size())),
+        Line::from(format!(
+            "Rebuilt: FFT ×{} · bands ×{} · buffers ×{}",
+            rebuilds.fft, rebuilds.band_table, rebuilds.bands
+        )),
+    ]
+}
+
+// Time between analyses for the config's analysis_fps (0: every frame)
+fn analysis_interval_for(analysis_fps: u32) -> std::time::Duration {
+    match analysis_fps {
+        0 => std::time::Duration::ZERO,
+        fps => std::time::Duration::from_secs_f32(1.0 / fps as f32),
     }
+}
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+// Mean level of each whole second in the history's last LEVEL_SPARKLINE_SECS
+// (skipping seconds no frame was analyzed in), oldest first, scaled from LEVEL_SPARKLINE_FLOOR_DB (0.0) to full scale (1.0)
+fn level_series_of(history: &history::FrameHistory) -> Vec<f32> {
+    let (Some(oldest), Some(latest)) = (history.oldest(), history.latest()) else {
+        return Vec::new();
+    };
+    let last = latest.time.max(0.0) as usize;
+    let first = (oldest.time.max(0.0) as usize).max((last + 1).saturating_sub(LEVEL_SPARKLINE_SECS));
+    let mut seconds = vec![(0.0f32, 0usize); last + 1 - first];
+    for frame in history.iter() {
+        if let Some(second) = (frame.time.max(0.0) as usize).checked_sub(first).and_then(|i| seconds.get_mut(i)) {
+            second.0 += frame.rms;
+            second.1 += 1;
+        }
+    }
+    seconds
+        .iter()
+        .filter(|&&(_, count)| count > 0)
+        .map(|&(sum, count)| {
+            let rms = sum / count as f32;
+            let db = 20.0 * rms.max(1e-6).log10();
+            (db - LEVEL_SPARKLINE_FLOOR_DB) / -LEVEL_SPARKLINE_FLOOR_DB
+        })
+        .collect()
+}
 
-    Ok(())
+// Format and length of a WAV file from its header, checked against the
+// file (an empty or cut-off file, a rate of 0 Hz), or of a raw file in the
+// --raw-pcm format from its size. Nothing is decoded or divided by before this.
+fn probe(path: &PathBuf, raw: Option<RawFormat>) -> Result<wav::Inspection, Box<dyn std::error::Error>> {
+    if let Some(format) = raw {
+        let frames = format.frames(std::fs::metadata(path)?.len());
+        if frames == 0 {
+            return Err("the file is too short for a single frame".into());
+        }
+        return Ok(wav::Inspection { spec: format.spec(), frames, declared_frames: frames });
+    }
+    Ok(wav::inspect(BufReader::new(File::open(path)?))?)
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Open the WAV file
-    let file = File::open("src/sound4.wav")?;
-    let reader = BufReader::new(file);
+    let args = cli::parse();
+    terminal::install_panic_hook();
+
+    // Settle the config before touching the terminal so mistakes are reported
+    // on the normal screen
+    let mut config = match config::load(args.config.as_deref(), args.profile.as_deref()) {
+        Ok((config, warnings)) => {
+            if !args.quiet {
+                for warning in warnings {
+                    eprintln!("{}", warning);
+                }
+            }
+            config
+        }
+        Err(e) => {
+            for warning in &e.warnings {
+                eprintln!("{}", warning);
+            }
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let flags = args.flags();
+    config.apply_flags(&flags);
+    // Over everything else, flags included
+    let measurement = args.measurement.then(|| Shaping::measurement(config.measurement_floor_db));
+    if let Some(shaping) = &measurement {
+        config.overlay(shaping);
+    }
+    // --follow draws what another gruvberry plays and plays nothing itself;
+    // a primary that isn't there is reported on the normal screen
+    #[cfg(unix)]
+    if let Some(path) = &args.follow {
+        let (stream, reader) = follow::connect(path).unwrap_or_else(|e| {
+            eprintln!("Can't follow {}: {}", display::path(path), e);
+            std::process::exit(1);
+        });
+        let options = follow::FollowOptions {
+            orientation: config.orientation,
+            frame_interval: std::time::Duration::from_secs_f32(1.0 / config.fps as f32),
+            alternate_screen: !args.no_alt_screen,
+            theme: theme_for(&config, args.no_color),
+        };
+        let ended = follow::run(stream, reader, options).map_err(|e| format!("Visualization error: {}", e))?;
+        if ended == follow::Ended::Gone && !args.quiet {
+            println!("Stopped following: the gruvberry at {} has exited", display::path(path));
+        }
+        return Ok(());
+    }
+    let locale = Locale::new(config.decimal_separator);
+    let reading = Reading {
+        raw: args.raw_pcm,
+        segment: args.segment()?,
+    };
+
+    // Check every file up front, before the audio device or the terminal is
+    // touched, so a bad one is reported on the normal screen
+    let paths = if args.files.is_empty() {
+        vec![PathBuf::from(DEFAULT_AUDIO_PATH)]
+    } else {
+        args.files.clone()
+    };
+    let mut probed = Vec::new();
+    if !args.calibrate {
+        for path in &paths {
+            let checked = probe(path, reading.raw).and_then(|inspection| {
+                reading.segment.check(inspection.duration())?;
+                Ok(inspection)
+            });
+            match checked {
+                Ok(inspection) => probed.push(inspection),
+                Err(e) => {
+                    eprintln!("{}: {}", display::path(path), e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
 
-    // Parse WAV metadata
-    let wav_reader = hound::WavReader::new(reader)?;
-    let spec = wav_reader.spec();
+    // Create audio output stream; the device can take a moment to come back
+    // (after a resume from suspend), so a failure is retried before giving up
+    let mut open_default = || OutputStreamBuilder::open_default_stream().map_err(|e| e.to_string());
+    let opened = gruvberry::device::open(&mut open_default, &config.device_retry, |retry| {
+        if !args.quiet {
+            eprintln!(
+                "Waiting for the audio device ({}); trying again in {} ({} of {})",
+                retry.error,
+                locale.duration(retry.delay.as_secs_f32()),
+                retry.attempt,
+                retry.attempts
+            );
+        }
+        std::thread::sleep(retry.delay);
+    });
+    let stream_handle = match opened {
+        Ok(stream) => stream,
+        Err(e) => {
+            eprintln!("Can't open the audio device: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let (device_latency_ms, device_latency_reported) = device_latency_ms(&stream_handle);
+    let device_sample_rate = stream_handle.config().sample_rate();
+
+    // The metronome's click joins the output only when asked for; otherwise
+    // the metronome stays purely visual
+    let metronome_grid = config.metronome_click.then(|| {
+        let grid = metronome::SharedGrid::default();
+        let latency = std::time::Duration::from_millis(device_latency_ms as u64);
+        stream_handle.mixer().add(metronome::MetronomeClick::new(grid.clone(), latency));
+        grid
+    });
 
-    // Calculate duration
-    let duration = wav_reader.duration() as f32 / spec.sample_rate as f32;
+    // --record-audio creates its file now, so a bad path fails before
+    // anything plays (the calibration clicks aren't worth keeping)
+    let (recording, recorder) = match &args.record_audio {
+        Some(path) if !args.calibrate => {
+            let (recording, recorder) = record::start(path)?;
+            (Some(recording), Some(recorder))
+        }
+        _ => (None, None),
+    };
+    // Every track plays through the one echo, so the 'E' panel reaches them all
+    let echo = config.echo.shared();
+    // --cava-raw makes its FIFO now; readers can come and go while playing
+    let cava_output = match &args.cava_raw {
+        Some(path) => {
+            let format = cava::BitFormat::parse(&args.cava_bit_format).unwrap_or_default();
+            Some(cava::start(path, args.cava_bars, format)?)
+        }
+        None => None,
+    };
+    // --control listens from now on, so a path that is taken is reported
+    // before anything plays
+    #[cfg(unix)]
+    let control = match &args.control {
+        Some(path) => Some(
+            control::Server::bind(path).map_err(|e| format!("Can't listen on {}: {}", display::path(path), e))?,
+        ),
+        None => None,
+    };
+
+    // Every ring must be able to hand the analysis a whole window; the
+    // config was checked already, but the sizes are worked out here
+    let ring_size = RingSize {
+        fft_size: config.fft_size,
+        capture_buffer: config.capture_buffer,
+    };
+    capture::check_capacity(ring_size.capacity(device_sample_rate), ring_size.fft_size)?;
+    let reach_ms = capture::reach_ms(ring_size.capacity(device_sample_rate), ring_size.fft_size, device_sample_rate);
+    // The window can't be read from ahead of the newest audio captured
+    let latency_offset_ms = config.latency_offset_ms;
+    let min_offset_ms = capture::min_offset_ms(device_latency_ms);
+    if latency_offset_ms < min_offset_ms && !args.quiet {
+        eprintln!(
+            "A latency offset of {} would read ahead of the newest audio captured on a {} device; using {}",
+            locale.ms(latency_offset_ms as f32, 0),
+            locale.ms(device_latency_ms as f32, 0),
+            locale.ms(min_offset_ms as f32, 0)
+        );
+    }
+    let latency_offset_ms = latency_offset_ms.max(min_offset_ms);
+    let wanted_ms = device_latency_ms + latency_offset_ms;
+    if reach_ms < wanted_ms && !args.quiet {
+        eprintln!(
+            "capture_buffer reaches back {} at {}, less than the {} of latency compensation; the visuals will run early",
+            locale.ms(reach_ms as f32, 0),
+            locale.rate(device_sample_rate),
+            locale.ms(wanted_ms as f32, 0)
+        );
+    }
 
-    println!("WAV File Loaded!");
-    println!("Sample Rate: {} Hz", spec.sample_rate);
-    println!("Channels: {}", spec.channels);
-    println!("Duration: {:.2} seconds", duration);
+    // One sink and capture per stream, paused until everything is queued so
+    // compared files start in lockstep
+    let mut inputs = Vec::new();
+    let mut sinks = Vec::new();
+    let mut notice = None;
+    let mut playlist = None;
+
+    if args.calibrate {
+        let sample_rate = calibrate::SAMPLE_RATE;
+        let source = calibrate::ClickTrack::new(sample_rate);
+        let buffer = SampleRing::shared(ring_size.capacity(sample_rate));
+        let dynamics = dynamics::DynamicsMeter::shared();
+        let flow = flow::FlowMeter::shared();
+        let wrapped_source = SampleCapture::new(source, (buffer.clone(), (dynamics.clone(), flow.clone())));
+        let sink = Sink::connect_new(stream_handle.mixer());
+        sink.append(wrapped_source);
+        sinks.push(sink);
+        inputs.push(StreamInput {
+            name: "click track".to_string(),
+            info: format!("click track · {} · mono", locale.rate(sample_rate)),
+            buffer,
+            dynamics,
+            flow,
+            sample_rate,
+            channels: 1,
+            // The click track never ends; the user quits once the offset looks right
+            duration: f32::INFINITY,
+            start: None,
+            overview: None,
+            warning: None,
+        });
+    } else {
+        for (path, inspection) in paths.iter().zip(&probed) {
+            if args.verbose {
+                let kind = if args.raw_pcm.is_some() { "Raw PCM" } else { "WAV" };
+                println!("{} File Loaded: {}", kind, path.display());
+                println!("Sample Rate: {}", locale.rate(inspection.spec.sample_rate));
+                println!("Channels: {}", inspection.spec.channels);
+                println!("Duration: {}", format::time(inspection.duration()));
+                if let Some(warning) = inspection.truncation_warning() {
+                    println!("Warning: {}", warning);
+                }
+                println!("{}", resampling_note(inspection.spec.sample_rate, device_sample_rate, locale));
+            }
+        }
 
-    // Create audio output stream
-    let stream_handle = OutputStreamBuilder::open_default_stream()?;
-    let sink = Sink::connect_new(&stream_handle.mixer());
+        if args.compare {
+            // Gains that bring every file down to the quietest one's RMS level
+            let gains = if args.level_match {
+                let levels = paths
+                    .iter()
+                    .map(|path| overview::track_rms(path, reading.raw, reading.segment))
+                    .collect::<Result<Vec<f32>, _>>()?;
+                let quietest = levels.iter().cloned().filter(|&l| l > 0.0).fold(f32::INFINITY, f32::min);
+                levels
+                    .iter()
+                    .map(|&level| if level > 0.0 && quietest.is_finite() { quietest / level } else { 1.0 })
+                    .collect()
+            } else {
+                vec![1.0; paths.len()]
+            };
 
-    // Open file again for playback (we consumed the first one)
-    let sample_rate = spec.sample_rate;
-    let file = File::open("src/sound4.wav")?;
-    let source = Decoder::new(BufReader::new(file))?;
-    let source = rodio::source::UniformSourceIterator::new(source, 1, sample_rate);
+            for (i, (path, &gain)) in paths.iter().zip(gains.iter()).enumerate() {
+                if args.verbose && gain != 1.0 {
+                    println!("Level match for {}: {}", path.display(), locale.db(20.0 * gain.log10(), 1));
+                }
+                // Only the first file is recorded
+                let chain = Chain { recorder: recorder.clone().filter(|_| i == 0), echo: echo.clone() };
+                let (input, source) = open_track(path, reading, gain, ring_size, None, chain, locale)?;
+                let sink = Sink::connect_new(stream_handle.mixer());
+                sink.pause();
+                sink.append(source);
+                sinks.push(sink);
+                inputs.push(input);
+            }
 
-    // Wrap source with our sample capture
-    let (wrapped_source, sample_buffer) = SampleCapture::new(source, sample_rate);
+            // Compared files end together at the shorter one
+            let durations: Vec<f32> = inputs.iter().map(|input| input.duration).collect();
+            let shortest = durations.iter().cloned().fold(f32::INFINITY, f32::min);
+            let longest = durations.iter().cloned().fold(0.0f32, f32::max);
+            if longest - shortest > 0.5 {
+                notice = Some(format!("Files differ in length; comparing the first {}", format::time(shortest)));
+            }
+        } else {
+            // Files play one after another; more can be queued while playing
+            let mut queue = playlist::Playlist::new(paths, config.shuffle, config.repeat);
+            let path = queue.advance().expect("the playlist starts with at least one file");
+            let chain = Chain { recorder: recorder.clone(), echo: echo.clone() };
+            let (input, source) = open_track(&path, reading, 1.0, ring_size, None, chain, locale)?;
+            let sink = Sink::connect_new(stream_handle.mixer());
+            sink.pause();
+            sink.append(source);
+            sinks.push(sink);
+            inputs.push(input);
+            playlist = Some(Arc::new(std::sync::Mutex::new(queue)));
+        }
+    }
 
-    // Add audio to sink and play
-    sink.append(wrapped_source);
+    // Only the selected stream is audible; the others play silently in sync
+    let audible = Arc::new(AtomicUsize::new(0));
+    let player = Player::new(sinks);
 
     // Shared flag to signal threads to stop
     let should_stop = Arc::new(AtomicBool::new(false));
     let should_stop_clone = should_stop.clone();
+    let mut player_events = events::EventBus::new();
+    let events = player_events.subscribe();
+    let (player_commands, commands) = mpsc::channel();
+    // --verbose prints what was played once the terminal is back, as far
+    // back as the last SESSION_LOG_LINES events
+    let session_log = args.verbose.then(|| player_events.record(SESSION_LOG_LINES, session_log_line));
 
     // Spawn thread to perform FFT and display
+    let calibrate = args.calibrate;
+    let options = VisualizerOptions {
+        watcher: config::Watcher::new(args.config.as_deref(), args.profile.as_deref()),
+        flags,
+        fft_size: config.fft_size,
+        smoothing: config.smoothing,
+        frame_interval: std::time::Duration::from_secs_f32(1.0 / config.fps as f32),
+        latency_offset_ms,
+        device_latency_ms,
+        device_latency_reported,
+        device_sample_rate,
+        calibrate,
+        bounce: config.bounce,
+        raw: config.raw,
+        metronome_grid,
+        render_delay: args.render_delay.map(std::time::Duration::from_millis),
+        idle_timeout: config.screensaver.then(|| std::time::Duration::from_secs_f32(config.idle_timeout)),
+        playlist: playlist.clone(),
+        reading,
+        recorder: recorder.clone(),
+        echo: echo.clone(),
+        cava: cava_output,
+        #[cfg(unix)]
+        control,
+        player_events: events,
+        audible: audible.clone(),
+        player_commands,
+        notice,
+        auto_exit: args.auto_exit,
+        export_svg: args.export_svg.clone(),
+        theme: theme_for(&config, args.no_color),
+        alternate_screen: !args.no_alt_screen,
+        measurement,
+        #[cfg(feature = "discord")]
+        presence: (!calibrate && !args.oneline).then(|| discord::Presence::start(config.discord_client_id.clone())),
+        config,
+        profile: args.profile.clone(),
+        config_path: args.config.clone(),
+    };
+    // Output devices opened when the first one stalls
+    let mut reopened = Vec::new();
+    let mut player = PlayerLoop {
+        player,
+        playlist: playlist.clone(),
+        events: player_events,
+        commands,
+        audible,
+        ring_size,
+        carry: (inputs[0].buffer.clone(), inputs[0].sample_rate),
+        chain: Chain { recorder, echo },
+        reading,
+        locale,
+        flows: inputs.iter().map(|input| input.flow.clone()).collect(),
+        rings: inputs.iter().map(|input| input.buffer.clone()).collect(),
+        open_output: Box::new(move || {
+            let stream = OutputStreamBuilder::open_default_stream().map_err(|e| format!("can't reopen the device: {}", e))?;
+            let sink = Sink::connect_new(stream.mixer());
+            sink.pause();
+            // Kept open for as long as the player can use the sink
+            reopened.push(stream);
+            Ok(sink)
+        }),
+        playing: playlist.as_ref().and_then(|playlist| playlist.lock().ok()?.current()),
+        duration: inputs.iter().map(|input| input.duration).fold(f32::INFINITY, f32::min),
+    };
+    if let Some(index) = player.playing {
+        player.events.publish(PlayerEvent::TrackStarted {
+            index,
+            input: inputs[0].clone(),
+            seamless: true,
+        });
+    }
+
+    let oneline = args.oneline;
     let handle = std::thread::spawn(move || {
-        if let Err(e) = visualize_frequencies(sample_buffer, sample_rate, duration, should_stop_clone) {
-            eprintln!("Visualization error: {}", e);
-        }
+        // However the visualizer closes, with an error too, the player stops
+        // with it rather than holding the finished state for nobody
+        let result = if oneline { show_oneline(inputs, options) } else { visualize_frequencies(inputs, options) };
+        should_stop_clone.store(true, Ordering::Relaxed);
+        result.map_err(|e| e.to_string())
     });
 
-    // Monitor for stop signal while playing
-    while !sink.empty() && !should_stop.load(Ordering::Relaxed) {
-        std::thread::sleep(std::time::Duration::from_millis(100));
+    // Play until stopped, routing audio to the selected stream and moving
+    // through the playlist
+    if player.run(&should_stop, !args.auto_exit) {
+        player.finish();
+    }
+
+    // Wait for visualization thread; by now the terminal is restored, so a
+    // failure is reported even with --quiet
+    let summary = handle.join().unwrap().map_err(|e| format!("Visualization error: {}", e))?;
+    // The terminal is gone after a hangup and printing to it would fail;
+    // the recording and --stats-json are still written
+    #[cfg(unix)]
+    if summary.hung_up
+        && let Ok(null) = std::fs::OpenOptions::new().write(true).open("/dev/null")
+    {
+        use std::os::fd::AsRawFd;
+        unsafe {
+            libc::dup2(null.as_raw_fd(), libc::STDOUT_FILENO);
+            libc::dup2(null.as_raw_fd(), libc::STDERR_FILENO);
+        }
+    }
+
+    player.player.stop();
+
+    if let Some(recording) = recording {
+        let path = recording.path().display().to_string();
+        let status = recording.finish();
+        if let Some(e) = &status.error {
+            eprintln!("Recording to {} {}", path, e);
+        }
+        if status.dropped > 0 {
+            eprintln!("Recording to {} is missing {} samples (the disk couldn't keep up)", path, status.dropped);
+        }
+        if !args.quiet {
+            println!("Recorded {} to {}", format::time(status.duration().as_secs_f32()), path);
+        }
+    }
+
+    if let Some(log) = session_log {
+        for line in log.lock().map(|log| log.clone()).unwrap_or_default() {
+            println!("{}", line);
+        }
+        if summary.dropped_frames > 0 {
+            println!("Dropped {} frames (drawing couldn't keep up with the frame rate)", summary.dropped_frames);
+        }
     }
 
-    // Stop audio immediately if requested
-    if should_stop.load(Ordering::Relaxed) {
-        sink.stop();
+    if !args.quiet {
+        for (name, dr) in summary.dynamic_range.iter().filter_map(|(name, dr)| Some((name, (*dr)?))) {
+            println!("Dynamic range of {}: DR{}", name, locale.number(dr.max(0.0), 0));
+        }
+    }
+
+    if args.stats {
+        let name_width = summary.stats.tracks().iter().map(|track| display::width(&track.name)).max().unwrap_or(0);
+        for line in stats_table(&summary.stats, name_width.clamp(5, 40), locale) {
+            println!("{}", line);
+        }
+        println!("Total listening time: {}", format::time(summary.stats.total_listened()));
+    }
+    if let Some(path) = &args.stats_json
+        && let Err(e) = std::fs::write(path, summary.stats.to_json())
+    {
+        eprintln!("Could not write the statistics to {}: {}", path.display(), e);
+    }
+
+    if calibrate && !args.quiet {
+        let offset = summary.latency_offset_ms;
+        println!(
+            "Calibrated latency offset: {} (run with --latency-offset={})",
+            locale.signed_ms(offset as f32),
+            offset
+        );
     }
 
-    // Wait for visualization thread
-    handle.join().unwrap();
+    // Everything above is still reported; the exit status says it went wrong
+    if let Some(error) = summary.error {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
 
     Ok(())
 }
@@ -0,0 +1,188 @@
+//! Measurement mode: the spectrum as measured, nothing done to it for looks.
+//!
+//! Most of what lies between the FFT and the bars is there to make them
+//! read well: a treble tilt, bars scaled to the loudest band, smoothing
+//! from frame to frame, a kick on each onset. A [`Shaping`] holds every one
+//! of those [`Stage`]s as set, and [`Shaping::measurement`] is the one with
+//! all of them off: a Hann window, no tilt or visual gain, a fixed dB scale
+//! from a floor instead of the loudest band, no smoothing across bands or
+//! frames, and the peak readouts on. Laid over the settings after
+//! everything else (config file, profile, environment, flags, an
+//! orientation's own settings), it holds whatever those say.
+//!
+//! ```
+//! use gruvberry::measurement::{Shaping, Stage, DEFAULT_FLOOR_DB};
+//!
+//! let shaping = Shaping::measurement(DEFAULT_FLOOR_DB);
+//! assert!(Stage::ALL.iter().all(|&stage| !shaping.shapes(stage)));
+//! // 0 dBFS at the top, the floor at the bottom
+//! assert_eq!(gruvberry::measurement::fixed_levels(&[0.0, -45.0, -120.0], -90.0), [100.0, 50.0, 0.0]);
+//! ```
+
+use crate::analysis::{self, Window};
+use crate::downsample::Aggregate;
+use crate::smoothing::SmootherKind;
+use crate::tilt::Tilt;
+
+/// Bottom of the fixed scale (dBFS) unless configured.
+pub const DEFAULT_FLOOR_DB: f32 = -90.0;
+
+/// A step between the FFT and the bars that changes what they show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// A rectangular window, whose leakage spreads a tone over many bands.
+    Window,
+    /// Gain by frequency.
+    Tilt,
+    /// Display-only gain on the bars.
+    VisualGain,
+    /// Bars scaled to the loudest band, an automatic gain on the display.
+    AutoScale,
+    /// Columns over several bands summing their energy.
+    SpatialSmoothing,
+    /// Smoothing from frame to frame.
+    TemporalSmoothing,
+    /// Frames blended between analyses.
+    Interpolation,
+    /// Bars kicked up on onsets.
+    Bounce,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 8] = [
+        Stage::Window,
+        Stage::Tilt,
+        Stage::VisualGain,
+        Stage::AutoScale,
+        Stage::SpatialSmoothing,
+        Stage::TemporalSmoothing,
+        Stage::Interpolation,
+        Stage::Bounce,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Window => "window",
+            Stage::Tilt => "tilt",
+            Stage::VisualGain => "visual gain",
+            Stage::AutoScale => "auto scale",
+            Stage::SpatialSmoothing => "spatial smoothing",
+            Stage::TemporalSmoothing => "temporal smoothing",
+            Stage::Interpolation => "interpolation",
+            Stage::Bounce => "bounce",
+        }
+    }
+
+    /// What holds while the stage is off, for the header.
+    pub fn off_label(self) -> &'static str {
+        match self {
+            Stage::Window => "Hann",
+            Stage::Tilt => "no tilt",
+            Stage::VisualGain => "no gain",
+            Stage::AutoScale => "fixed scale",
+            Stage::SpatialSmoothing => "max of bands",
+            Stage::TemporalSmoothing => "no smoothing",
+            Stage::Interpolation => "raw frames",
+            Stage::Bounce => "no bounce",
+        }
+    }
+}
+
+/// How each [`Stage`] is set, and whether the peaks are labeled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shaping {
+    pub window: Window,
+    pub tilt: Tilt,
+    pub visual_gain_db: f32,
+    /// Bottom of a fixed dB scale; None scales to the loudest band.
+    pub floor_db: Option<f32>,
+    pub aggregate: Aggregate,
+    pub smoother: SmootherKind,
+    /// The exponential smoother's factor (1.0 follows each frame as it is).
+    pub smoothing: f32,
+    pub interpolate: bool,
+    pub bounce: bool,
+    pub peaks: bool,
+}
+
+impl Default for Shaping {
+    /// As the display starts without any settings.
+    fn default() -> Self {
+        Shaping {
+            window: Window::default(),
+            tilt: Tilt::default(),
+            visual_gain_db: 0.0,
+            floor_db: None,
+            aggregate: Aggregate::default(),
+            smoother: SmootherKind::default(),
+            smoothing: analysis::DEFAULT_SMOOTHING,
+            interpolate: true,
+            bounce: false,
+            peaks: false,
+        }
+    }
+}
+
+impl Shaping {
+    /// Every stage off, on a fixed scale from `floor_db` up to 0 dBFS, with
+    /// the peaks labeled.
+    pub fn measurement(floor_db: f32) -> Shaping {
+        Shaping {
+            window: Window::Hann,
+            tilt: Tilt::Flat,
+            visual_gain_db: 0.0,
+            floor_db: Some(floor_db),
+            aggregate: Aggregate::Max,
+            smoother: SmootherKind::Passthrough,
+            smoothing: 1.0,
+            interpolate: false,
+            bounce: false,
+            peaks: true,
+        }
+    }
+
+    /// Whether `stage` changes what the bars show, as set.
+    pub fn shapes(&self, stage: Stage) -> bool {
+        match stage {
+            Stage::Window => self.window != Window::Hann,
+            Stage::Tilt => self.tilt != Tilt::Flat,
+            Stage::VisualGain => self.visual_gain_db != 0.0,
+            Stage::AutoScale => self.floor_db.is_none(),
+            Stage::SpatialSmoothing => self.aggregate != Aggregate::Max,
+            Stage::TemporalSmoothing => match self.smoother {
+                SmootherKind::Passthrough => false,
+                SmootherKind::Exponential => self.smoothing < 1.0,
+                SmootherKind::AttackRelease => true,
+            },
+            Stage::Interpolation => self.interpolate,
+            Stage::Bounce => self.bounce,
+        }
+    }
+
+    /// What holds of these settings, for the header: each stage that is off,
+    /// the floor of a fixed scale and the peak labels.
+    pub fn guarantees(&self) -> Vec<String> {
+        let mut guarantees: Vec<String> = Stage::ALL
+            .iter()
+            .filter(|&&stage| !self.shapes(stage))
+            .map(|stage| match (stage, self.floor_db) {
+                (Stage::AutoScale, Some(floor)) => format!("{} dB floor", floor),
+                _ => stage.off_label().to_string(),
+            })
+            .collect();
+        if self.peaks {
+            guarantees.push("peaks".to_string());
+        }
+        guarantees
+    }
+}
+
+/// Bar levels (0-100) of band levels in dBFS on a fixed scale: `floor_db`
+/// and below at the bottom, 0 dBFS and above at the top.
+pub fn fixed_levels(levels_db: &[f32], floor_db: f32) -> Vec<f32> {
+    levels_db
+        .iter()
+        .map(|&db| ((db - floor_db) / -floor_db * 100.0).clamp(0.0, 100.0))
+        .map(|level| if level.is_finite() { level } else { 0.0 })
+        .collect()
+}
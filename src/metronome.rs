@@ -0,0 +1,118 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use rodio::Source;
+
+// The beat grid as the visualizer last predicted it: when a beat will be
+// heard and how long a beat lasts
+#[derive(Clone, Copy, Debug)]
+pub struct BeatGrid {
+    pub next: Instant,
+    pub period: Duration,
+}
+
+// Written by the visualizer while the metronome shows, None otherwise
+pub type SharedGrid = Arc<Mutex<Option<BeatGrid>>>;
+
+const SAMPLE_RATE: u32 = 44100;
+// Quiet enough to sit under the music
+const CLICK_VOLUME: f32 = 0.15;
+const CLICK_LENGTH_SECS: f32 = 0.008;
+const CLICK_FREQUENCY: f32 = 1500.0;
+// Samples between looks at the shared grid, so the lock isn't taken per sample
+const GRID_CHECK_INTERVAL: u64 = 256;
+// A beat that came up longer ago than this (say, just after the grid
+// changed) is skipped rather than clicked late
+const LATE_CLICK: Duration = Duration::from_millis(20);
+
+// Endless mono source, mixed in with the music, that clicks whenever a beat
+// of the shared grid reaches the speakers. Silent while there's no grid.
+pub struct MetronomeClick {
+    grid: SharedGrid,
+    current: Option<BeatGrid>,
+    // How long a sample takes from being pulled to being heard
+    latency: Duration,
+    started: Option<Instant>,
+    position: u64,
+    // Sample the current click started at
+    click_start: Option<u64>,
+    last_click: Option<Instant>,
+}
+
+impl MetronomeClick {
+    pub fn new(grid: SharedGrid, latency: Duration) -> Self {
+        MetronomeClick {
+            grid,
+            current: None,
+            latency,
+            started: None,
+            position: 0,
+            click_start: None,
+            last_click: None,
+        }
+    }
+
+    // The last beat of the grid at or before `time`
+    fn beat_before(grid: &BeatGrid, time: Instant) -> Instant {
+        let period = grid.period.as_secs_f64().max(1e-3);
+        let beats = if time >= grid.next {
+            ((time - grid.next).as_secs_f64() / period).floor()
+        } else {
+            -((grid.next - time).as_secs_f64() / period).ceil()
+        };
+        if beats >= 0.0 {
+            grid.next + grid.period.mul_f64(beats)
+        } else {
+            grid.next - grid.period.mul_f64(-beats)
+        }
+    }
+}
+
+impl Iterator for MetronomeClick {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        if self.position.is_multiple_of(GRID_CHECK_INTERVAL) {
+            self.current = self.grid.lock().ok().and_then(|grid| *grid);
+        }
+        let heard = started + Duration::from_secs_f64(self.position as f64 / SAMPLE_RATE as f64) + self.latency;
+
+        if let Some(grid) = &self.current {
+            let beat = Self::beat_before(grid, heard);
+            let new_beat = self.last_click.is_none_or(|last| beat > last + grid.period / 2);
+            if new_beat && heard - beat <= LATE_CLICK {
+                self.click_start = Some(self.position);
+                self.last_click = Some(beat);
+            }
+        }
+
+        let click_len = (CLICK_LENGTH_SECS * SAMPLE_RATE as f32) as u64;
+        let sample = match self.click_start.map(|start| self.position - start) {
+            Some(t) if t < click_len => {
+                let phase = 2.0 * std::f32::consts::PI * CLICK_FREQUENCY * t as f32 / SAMPLE_RATE as f32;
+                phase.sin() * (1.0 - t as f32 / click_len as f32) * CLICK_VOLUME
+            }
+            _ => 0.0,
+        };
+        self.position += 1;
+        Some(sample)
+    }
+}
+
+impl Source for MetronomeClick {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
@@ -0,0 +1,138 @@
+//! Settings remembered per display mode.
+//!
+//! Some settings suit one way of looking at the spectrum and not another: a
+//! treble lift reads well as tall bars and badly as rows. A [`ModeMemory`]
+//! holds each mode's [`Overrides`] of the base settings; switching modes
+//! swaps in the new mode's, and a change made while in a mode is remembered
+//! for that mode alone. Whatever a mode doesn't override follows the base
+//! settings, so a later change to those still shows through.
+//!
+//! ```
+//! use gruvberry::modes::{ModeMemory, Overrides, Settings};
+//! use gruvberry::tilt::Tilt;
+//!
+//! let base = Settings::default();
+//! let mut memory = ModeMemory::new("bars", [("rows", Overrides { tilt: Some(Tilt::Flat), ..Overrides::default() })]);
+//! assert_eq!(memory.switch("rows", &base).tilt, Tilt::Flat);
+//! assert_eq!(memory.switch("bars", &base).tilt, base.tilt);
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::analysis;
+use crate::gradient::Anchor;
+use crate::smoothing::SmootherKind;
+use crate::tilt::Tilt;
+
+/// The settings a mode can have its own of.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub smoothing: f32,
+    pub smoother: SmootherKind,
+    pub tilt: Tilt,
+    pub gradient: Anchor,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            smoothing: analysis::DEFAULT_SMOOTHING,
+            smoother: SmootherKind::default(),
+            tilt: Tilt::default(),
+            gradient: Anchor::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// These settings with `overrides` on top.
+    pub fn with(&self, overrides: &Overrides) -> Settings {
+        Settings {
+            smoothing: overrides.smoothing.unwrap_or(self.smoothing),
+            smoother: overrides.smoother.unwrap_or(self.smoother),
+            tilt: overrides.tilt.clone().unwrap_or_else(|| self.tilt.clone()),
+            gradient: overrides.gradient.unwrap_or(self.gradient),
+        }
+    }
+}
+
+/// One mode's own settings; None follows the base setting.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Overrides {
+    pub smoothing: Option<f32>,
+    pub smoother: Option<SmootherKind>,
+    pub tilt: Option<Tilt>,
+    pub gradient: Option<Anchor>,
+}
+
+impl Overrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Overrides::default()
+    }
+}
+
+/// The current mode and every mode's overrides.
+#[derive(Clone, Debug)]
+pub struct ModeMemory<M> {
+    mode: M,
+    overrides: HashMap<M, Overrides>,
+}
+
+impl<M: Copy + Eq + Hash> ModeMemory<M> {
+    /// Start in `mode`, with the overrides known so far (e.g. from the
+    /// settings file).
+    pub fn new(mode: M, overrides: impl IntoIterator<Item = (M, Overrides)>) -> Self {
+        ModeMemory {
+            mode,
+            overrides: overrides.into_iter().collect(),
+        }
+    }
+
+    pub fn mode(&self) -> M {
+        self.mode
+    }
+
+    /// What `mode` overrides.
+    pub fn overrides(&self, mode: M) -> Overrides {
+        self.overrides.get(&mode).cloned().unwrap_or_default()
+    }
+
+    /// The settings in effect: `base` with the current mode's overrides.
+    pub fn settings(&self, base: &Settings) -> Settings {
+        base.with(&self.overrides(self.mode))
+    }
+
+    /// Switch to `mode`, returning the settings in effect there.
+    pub fn switch(&mut self, mode: M, base: &Settings) -> Settings {
+        self.mode = mode;
+        self.settings(base)
+    }
+
+    /// Remember `tilt` for the current mode; None goes back to the base
+    /// tilt.
+    pub fn set_tilt(&mut self, tilt: Option<Tilt>) {
+        self.edit(|overrides| overrides.tilt = tilt);
+    }
+
+    pub fn set_smoothing(&mut self, smoothing: Option<f32>) {
+        self.edit(|overrides| overrides.smoothing = smoothing);
+    }
+
+    pub fn set_smoother(&mut self, smoother: Option<SmootherKind>) {
+        self.edit(|overrides| overrides.smoother = smoother);
+    }
+
+    pub fn set_gradient(&mut self, gradient: Option<Anchor>) {
+        self.edit(|overrides| overrides.gradient = gradient);
+    }
+
+    // Change the current mode's overrides, forgetting the mode once it
+    // overrides nothing
+    fn edit(&mut self, change: impl FnOnce(&mut Overrides)) {
+        let overrides = self.overrides.entry(self.mode).or_default();
+        change(overrides);
+        if overrides.is_empty() {
+            self.overrides.remove(&self.mode);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! The `--oneline` display: the spectrum squeezed into a row of block
+//! characters with the time after it, small enough for a narrow tmux pane
+//! or a status bar.
+//!
+//! [`Layout::fit`] works out how many cells of spectrum a row has room for,
+//! from [`MIN_CELLS`] to [`MAX_CELLS`], and whether the time still fits
+//! after them. The row never takes the terminal's last column, so the
+//! cursor can't wrap onto a second row and a carriage return always goes
+//! back to the start of it.
+//!
+//! ```
+//! use gruvberry::oneline::{self, Layout};
+//!
+//! let time = oneline::time(83.0, 245.0);
+//! assert_eq!(time, "1:23/4:05");
+//! let layout = Layout::fit(80, time.chars().count());
+//! assert_eq!((layout.cells, layout.time), (16, true));
+//! // Levels as the visualizer draws them, 0 to 100
+//! let levels: Vec<f32> = (0..layout.cells).map(|cell| cell as f32 * 100.0 / 15.0).collect();
+//! assert_eq!(oneline::bars(&levels, layout.cells), "▁▁▂▂▃▃▄▄▅▅▆▆▇▇██");
+//! ```
+
+use crate::format;
+use crate::sparkline;
+
+/// Fewest cells of spectrum shown along with the time.
+pub const MIN_CELLS: usize = 8;
+/// Most cells of spectrum, however wide the terminal.
+pub const MAX_CELLS: usize = 16;
+
+/// How a row of a given width is shared out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout {
+    /// Cells of spectrum, as many bands as are analyzed.
+    pub cells: usize,
+    /// Whether the time follows them, after a space.
+    pub time: bool,
+}
+
+impl Layout {
+    /// The layout of a row `width` columns wide with a time `time_width`
+    /// columns long. When even [`MIN_CELLS`] and the time don't fit the
+    /// time goes, and the spectrum takes what there is.
+    pub fn fit(width: usize, time_width: usize) -> Layout {
+        // The last column stays free
+        let room = width.saturating_sub(1);
+        match room.checked_sub(time_width + 1) {
+            Some(left) if left >= MIN_CELLS => Layout { cells: left.min(MAX_CELLS), time: true },
+            _ => Layout { cells: room.min(MAX_CELLS), time: false },
+        }
+    }
+
+    /// Columns the row takes with a time `time_width` columns long.
+    pub fn width(&self, time_width: usize) -> usize {
+        if self.time { self.cells + 1 + time_width } else { self.cells }
+    }
+}
+
+/// Levels (0 to 100, as drawn) as `cells` block characters; with more
+/// levels than cells each cell shows the highest of those it covers.
+pub fn bars(levels: &[f32], cells: usize) -> String {
+    let values: Vec<f32> = levels.iter().map(|level| level / 100.0).collect();
+    sparkline::render(&values, cells)
+}
+
+/// Elapsed and total time, like `1:23/4:05`. The elapsed part is padded to
+/// the total's length so the row keeps its width as the time goes by; with
+/// no total (a stream that doesn't end) it is the elapsed time alone.
+pub fn time(elapsed: f32, total: f32) -> String {
+    if !total.is_finite() {
+        return format::time(elapsed);
+    }
+    let total = format::time(total);
+    format!("{:>width$}/{}", format::time(elapsed), total, width = total.len())
+}
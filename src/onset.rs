@@ -0,0 +1,398 @@
+//! Onset detection and what builds on it.
+//!
+//! [`SpectralFlux`] measures how much successive magnitude spectra rise, and
+//! [`OnsetDetector`] watches it for sudden rises well above what the last
+//! second has been doing, which is where drum hits and note attacks are.
+//! [`Activity`] is the same flux smoothed for a meter: it spikes on
+//! transients and sits low through sustained tones. [`BeatTracker`] estimates
+//! the tempo from the onsets and keeps a beat grid phase-locked to them.
+//! [`Bounce`] is a purely visual effect: each onset briefly scales all bars
+//! up, the kick fading out linearly over a fixed time.
+//!
+//! Timestamps are `f64` seconds, since the clock they come from may have run
+//! for days: at 48 hours an `f32` only tells times 16 ms apart, a whole frame.
+//! Intervals, which stay short, are `f32`.
+//!
+//! ```
+//! use gruvberry::onset::Bounce;
+//!
+//! let mut bounce = Bounce::new(1.2, 0.1);
+//! bounce.kick(0.0);
+//! let mut bands = vec![50.0, 10.0];
+//! bounce.apply(&mut bands, 0.05);
+//! assert_eq!(bands, [55.0, 11.0]);
+//! ```
+
+use std::collections::VecDeque;
+
+/// How far back the detector looks to judge what counts as sudden, in seconds.
+pub const THRESHOLD_WINDOW_SECS: f32 = 1.0;
+/// Shortest gap between two onsets, in seconds.
+pub const MIN_ONSET_GAP_SECS: f32 = 0.1;
+// Flux has to stand this many standard deviations above the recent mean...
+const THRESHOLD_DEVIATIONS: f32 = 2.0;
+// ...and above this floor, so noise in near silence doesn't trigger
+const MIN_FLUX: f32 = 0.02;
+
+/// Spectral flux of a stream of magnitude spectra: the mean rise in log
+/// magnitude across bins from one spectrum to the next, falls not counting.
+///
+/// Magnitudes are compared in log scale, so their overall scale doesn't
+/// matter.
+#[derive(Default)]
+pub struct SpectralFlux {
+    // Log-compressed magnitudes of the previous spectrum
+    previous: Vec<f32>,
+}
+
+impl SpectralFlux {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next spectrum; the flux since the one before, or None for
+    /// the first one and for one of a different length, which start over.
+    pub fn update(&mut self, magnitudes: &[f32]) -> Option<f32> {
+        let current: Vec<f32> = magnitudes.iter().map(|m| m.max(0.0).ln_1p()).collect();
+        let previous = std::mem::replace(&mut self.previous, current);
+        if previous.len() != self.previous.len() {
+            return None;
+        }
+        let rise: f32 = self.previous.iter().zip(&previous).map(|(c, p)| (c - p).max(0.0)).sum();
+        Some(rise / self.previous.len().max(1) as f32)
+    }
+
+    /// Forget the previous spectrum.
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+}
+
+/// Finds onsets in a stream of magnitude spectra.
+#[derive(Default)]
+pub struct OnsetDetector {
+    spectra: SpectralFlux,
+    // Recent (time, flux) values the threshold is taken from
+    recent: VecDeque<(f64, f32)>,
+    last_onset: Option<f64>,
+    flux: f32,
+}
+
+impl OnsetDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the magnitude spectrum analyzed at `time` (seconds, increasing);
+    /// returns true if an onset starts here.
+    ///
+    /// A spectrum of a different length than the last one starts over.
+    pub fn update(&mut self, magnitudes: &[f32], time: f64) -> bool {
+        let Some(flux) = self.spectra.update(magnitudes) else {
+            self.recent.clear();
+            self.last_onset = None;
+            self.flux = 0.0;
+            return false;
+        };
+        self.flux = flux;
+
+        let (mean, deviation) = mean_deviation(self.recent.iter().map(|&(_, flux)| flux));
+        let onset = self.flux > MIN_FLUX
+            && self.flux > mean + THRESHOLD_DEVIATIONS * deviation
+            && self.last_onset.is_none_or(|last| time - last >= MIN_ONSET_GAP_SECS as f64);
+        if onset {
+            self.last_onset = Some(time);
+        }
+
+        self.recent.push_back((time, self.flux));
+        while self.recent.front().is_some_and(|&(t, _)| t < time - THRESHOLD_WINDOW_SECS as f64) {
+            self.recent.pop_front();
+        }
+        onset
+    }
+
+    /// Spectral flux of the latest spectrum, as [`SpectralFlux`] has it.
+    pub fn flux(&self) -> f32 {
+        self.flux
+    }
+
+    /// Forget everything, e.g. after a jump in playback.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Flux that fills the activity meter: about what a hard hit after quiet
+/// gives, where steady noise sits around half of it.
+pub const ACTIVITY_FULL_SCALE: f32 = 0.5;
+/// Time constants the activity meter rises and falls with, in seconds.
+pub const ACTIVITY_ATTACK_SECS: f32 = 0.02;
+pub const ACTIVITY_RELEASE_SECS: f32 = 0.1;
+
+/// Spectral flux as a meter reading, lightly smoothed: it rises almost at
+/// once on a transient and falls back over a fraction of a second, so a
+/// drum hit or an edit shows as a spike and a sustained tone stays low.
+///
+/// Times are in seconds on any increasing clock, so the meter moves the
+/// same at any analysis rate.
+///
+/// ```
+/// use gruvberry::onset::{Activity, ACTIVITY_FULL_SCALE};
+///
+/// let mut activity = Activity::new();
+/// activity.update(0.0, 0.0);
+/// activity.update(ACTIVITY_FULL_SCALE, 0.1);
+/// assert!(activity.level() > 0.99);
+/// // Steady spectra have no flux
+/// activity.update(0.0, 1.0);
+/// assert!(activity.level() < 0.01);
+/// ```
+#[derive(Default)]
+pub struct Activity {
+    level: f32,
+    last: Option<f64>,
+}
+
+impl Activity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take in the flux of the spectrum analyzed at `time`.
+    pub fn update(&mut self, flux: f32, time: f64) {
+        let flux = if flux.is_finite() { flux.max(0.0) } else { 0.0 };
+        let Some(last) = self.last.replace(time) else {
+            self.level = flux;
+            return;
+        };
+        let dt = (time - last).max(0.0) as f32;
+        let secs = if flux > self.level { ACTIVITY_ATTACK_SECS } else { ACTIVITY_RELEASE_SECS };
+        self.level += (flux - self.level) * (1.0 - (-dt / secs).exp());
+    }
+
+    /// The reading, from 0.0 (the spectrum holding still) to 1.0 (at or
+    /// past [`ACTIVITY_FULL_SCALE`]).
+    pub fn level(&self) -> f32 {
+        (self.level / ACTIVITY_FULL_SCALE).clamp(0.0, 1.0)
+    }
+
+    /// Start over, e.g. when another track starts.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+fn mean_deviation(values: impl Iterator<Item = f32> + Clone) -> (f32, f32) {
+    let count = values.clone().count();
+    if count == 0 {
+        return (0.0, 0.0);
+    }
+    let mean = values.clone().sum::<f32>() / count as f32;
+    let variance = values.map(|v| (v - mean) * (v - mean)).sum::<f32>() / count as f32;
+    (mean, variance.sqrt())
+}
+
+/// Slowest tempo the beat tracker settles on, in beats per minute.
+pub const MIN_BPM: f32 = 60.0;
+/// Fastest tempo the beat tracker settles on, in beats per minute.
+pub const MAX_BPM: f32 = 180.0;
+// Recent onsets the tempo is estimated from
+const TEMPO_ONSETS: usize = 16;
+// Fewest onsets before there is a tempo to lock to
+const MIN_TEMPO_ONSETS: usize = 5;
+// How much of each onset's timing error the grid takes on, in phase and in tempo
+const PHASE_GAIN: f32 = 0.2;
+const PERIOD_GAIN: f32 = 0.02;
+// Onsets further than this fraction of a beat from the grid are offbeats,
+// and don't pull it
+const CAPTURE: f32 = 0.2;
+// Offbeats in a row after which the lock counts as lost and the tempo is
+// estimated afresh
+const MAX_MISSES: u32 = 8;
+
+/// A beat grid phase-locked to onsets: the tempo comes from the spacing of
+/// recent onsets, then every onset near a predicted beat nudges the grid's
+/// phase and tempo toward it (a simple second-order PLL).
+///
+/// Times are in seconds on any increasing clock.
+#[derive(Default)]
+pub struct BeatTracker {
+    onsets: VecDeque<f64>,
+    // Seconds per beat, once locked
+    period: Option<f32>,
+    // A beat on the grid (the most recently corrected one)
+    anchor: f64,
+    misses: u32,
+}
+
+impl BeatTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An onset was detected at `time`.
+    pub fn onset(&mut self, time: f64) {
+        self.onsets.push_back(time);
+        if self.onsets.len() > TEMPO_ONSETS {
+            self.onsets.pop_front();
+        }
+
+        let Some(period) = self.period else {
+            self.lock();
+            return;
+        };
+
+        let predicted = self.anchor + ((time - self.anchor) / period as f64).round() * period as f64;
+        let error = (time - predicted) as f32;
+        if error.abs() > CAPTURE * period {
+            self.misses += 1;
+            if self.misses >= MAX_MISSES {
+                self.period = None;
+                self.lock();
+            }
+            return;
+        }
+        self.misses = 0;
+        // Until a full set of onsets is on record, refitting the line to all
+        // of them beats correcting from one at a time
+        if self.onsets.len() < TEMPO_ONSETS {
+            self.lock();
+            return;
+        }
+        let min_period = 60.0 / MAX_BPM;
+        let max_period = 60.0 / MIN_BPM;
+        self.period = Some((period + PERIOD_GAIN * error).clamp(min_period, max_period));
+        self.anchor = predicted + (PHASE_GAIN * error) as f64;
+    }
+
+    // Set up the grid from the onsets on record: a rough tempo from their
+    // spacing, then the straight line through them (onset time against beat
+    // number) for a tempo and phase the jitter of any one onset can't skew
+    fn lock(&mut self) {
+        let (Some(rough), Some(&first)) = (estimate_period(&self.onsets), self.onsets.front()) else {
+            return;
+        };
+        // Times from the first onset, which stay small however long the clock has run
+        let points: Vec<(f32, f32)> = self
+            .onsets
+            .iter()
+            .map(|&t| (t - first) as f32)
+            .map(|t| ((t / rough).round(), t))
+            .collect();
+        let n = points.len() as f32;
+        let mean_beat = points.iter().map(|p| p.0).sum::<f32>() / n;
+        let mean_time = points.iter().map(|p| p.1).sum::<f32>() / n;
+        let spread: f32 = points.iter().map(|p| (p.0 - mean_beat).powi(2)).sum();
+        let period = if spread > 0.0 {
+            points.iter().map(|p| (p.0 - mean_beat) * (p.1 - mean_time)).sum::<f32>() / spread
+        } else {
+            rough
+        };
+        let period = period.clamp(60.0 / MAX_BPM, 60.0 / MIN_BPM);
+        self.period = Some(period);
+        // The fitted line at the latest onset's beat
+        let last_beat = points.last().map_or(0.0, |p| p.0);
+        self.anchor = first + (mean_time + (last_beat - mean_beat) * period) as f64;
+        self.misses = 0;
+    }
+
+    /// The tempo in beats per minute, once locked.
+    pub fn bpm(&self) -> Option<f32> {
+        self.period.map(|period| 60.0 / period)
+    }
+
+    /// The first predicted beat after `time`.
+    pub fn next_beat(&self, time: f64) -> Option<f64> {
+        let period = self.period? as f64;
+        let beats = ((time - self.anchor) / period).floor() + 1.0;
+        Some(self.anchor + beats * period)
+    }
+
+    /// How far `time` is into the current beat, from 0.0 right on it to just under 1.0.
+    pub fn phase(&self, time: f64) -> Option<f32> {
+        let period = self.period? as f64;
+        Some(((time - self.anchor) / period).rem_euclid(1.0) as f32)
+    }
+
+    /// Forget the tempo and grid, e.g. when another track starts.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// Median spacing of consecutive onsets, each folded by octaves into the
+// tracked tempo range so offbeats and skipped beats agree with the beat
+fn estimate_period(onsets: &VecDeque<f64>) -> Option<f32> {
+    if onsets.len() < MIN_TEMPO_ONSETS {
+        return None;
+    }
+    let min_period = 60.0 / MAX_BPM;
+    let max_period = 60.0 / MIN_BPM;
+    let mut intervals: Vec<f32> = onsets
+        .iter()
+        .zip(onsets.iter().skip(1))
+        .map(|(a, b)| (b - a) as f32)
+        .filter(|&interval| interval > 0.0)
+        .map(|mut interval| {
+            while interval < min_period {
+                interval *= 2.0;
+            }
+            while interval >= max_period {
+                interval /= 2.0;
+            }
+            interval
+        })
+        .collect();
+    if intervals.is_empty() {
+        return None;
+    }
+    intervals.sort_by(f32::total_cmp);
+    Some(intervals[intervals.len() / 2])
+}
+
+/// Scales bars up on each onset, easing back to normal over `decay` seconds.
+///
+/// The effect depends only on the time since the last kick, not on how
+/// often it is applied, so it looks the same at any frame rate.
+pub struct Bounce {
+    kick: f32,
+    decay: f32,
+    last_kick: Option<f64>,
+}
+
+impl Bounce {
+    /// `kick` is the scale right at an onset (e.g. 1.15), `decay` the seconds
+    /// it takes to fall back to 1.0.
+    pub fn new(kick: f32, decay: f32) -> Self {
+        Bounce {
+            kick,
+            decay,
+            last_kick: None,
+        }
+    }
+
+    /// An onset happened at `time` (seconds).
+    pub fn kick(&mut self, time: f64) {
+        self.last_kick = Some(time);
+    }
+
+    /// The scale at `time`: `kick` at the onset, falling linearly to 1.0.
+    pub fn gain(&self, time: f64) -> f32 {
+        let Some(last) = self.last_kick else {
+            return 1.0;
+        };
+        let since = (time - last) as f32;
+        if since < 0.0 || since >= self.decay {
+            return 1.0;
+        }
+        1.0 + (self.kick - 1.0) * (1.0 - since / self.decay)
+    }
+
+    /// Scale `bands` for display at `time`.
+    pub fn apply(&self, bands: &mut [f32], time: f64) {
+        let gain = self.gain(time);
+        for band in bands {
+            *band *= gain;
+        }
+    }
+}
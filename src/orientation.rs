@@ -0,0 +1,91 @@
+//! Which way the bars grow.
+//!
+//! The vertical orientations lay the bands out in columns, low frequencies
+//! on the left; the horizontal ones stack them in rows, low frequencies at
+//! the top, which suits wide, short panes. [`Orientation::cell`] maps a
+//! cell of the bars' area to its band and how far out from the base it is.
+//!
+//! ```
+//! use gruvberry::orientation::Orientation;
+//!
+//! // The bottom-left cell of a 4 x 3 area is the first band's base
+//! assert_eq!(Orientation::BottomUp.cell(0, 2, 4, 3, 4), Some((0, 0)));
+//! assert_eq!(Orientation::TopDown.cell(3, 2, 4, 3, 4), Some((3, 2)));
+//! assert_eq!(Orientation::parse("left-right").map(Orientation::horizontal), Some(true));
+//! ```
+
+/// Which way the bars grow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// Bars grow up from the bottom edge.
+    #[default]
+    BottomUp,
+    /// Bars hang from the top.
+    TopDown,
+    /// Bars extend rightwards from the left edge.
+    LeftRight,
+    /// Bars extend leftwards from the right edge.
+    RightLeft,
+}
+
+impl Orientation {
+    pub const NAMES: [&str; 4] = ["bottom-up", "top-down", "left-right", "right-left"];
+
+    pub fn parse(value: &str) -> Option<Orientation> {
+        match value {
+            "bottom-up" => Some(Orientation::BottomUp),
+            "top-down" => Some(Orientation::TopDown),
+            "left-right" => Some(Orientation::LeftRight),
+            "right-left" => Some(Orientation::RightLeft),
+            _ => None,
+        }
+    }
+
+    /// The orientation after this one, round to the first after the last.
+    pub fn next(self) -> Orientation {
+        match self {
+            Orientation::BottomUp => Orientation::TopDown,
+            Orientation::TopDown => Orientation::LeftRight,
+            Orientation::LeftRight => Orientation::RightLeft,
+            Orientation::RightLeft => Orientation::BottomUp,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Orientation::BottomUp => "bottom-up",
+            Orientation::TopDown => "top-down",
+            Orientation::LeftRight => "left-right",
+            Orientation::RightLeft => "right-left",
+        }
+    }
+
+    /// Bands in rows and magnitude across the columns.
+    pub fn horizontal(self) -> bool {
+        matches!(self, Orientation::LeftRight | Orientation::RightLeft)
+    }
+
+    /// Band and distance from the bars' base for the cell at (`col`, `row`)
+    /// of a `width` x `height` area holding `num_bands` bands; None for a
+    /// cell past the last band.
+    pub fn cell(self, col: usize, row: usize, width: usize, height: usize, num_bands: usize) -> Option<(usize, usize)> {
+        let (along, across, across_len) = match self {
+            Orientation::BottomUp => (height - 1 - row, col, width),
+            Orientation::TopDown => (row, col, width),
+            Orientation::LeftRight => (col, row, height),
+            Orientation::RightLeft => (width - 1 - col, row, height),
+        };
+        let band = across * num_bands / across_len.max(1);
+        (band < num_bands).then_some((band, along))
+    }
+
+    /// How many cells a full-scale bar spans in a `width` x `height` area.
+    pub fn bar_length(self, width: usize, height: usize) -> usize {
+        if self.horizontal() { width } else { height }
+    }
+
+    /// How many bars fit side by side in a `width` x `height` area.
+    pub fn band_cells(self, width: usize, height: usize) -> usize {
+        if self.horizontal() { height } else { width }
+    }
+}
@@ -0,0 +1,184 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use gruvberry::raw::RawFormat;
+use gruvberry::segment::Segment;
+use gruvberry::sparkline::LEVELS;
+use gruvberry::theme::Theme;
+use ratatui::style::Modifier;
+use ratatui::text::{Line, Span};
+
+// Resolution of the cached envelope; the strip is downsampled from this on
+// every resize so the file is only ever decoded once
+pub const ENVELOPE_RESOLUTION: usize = 4096;
+
+// Min/max envelope of the whole track (the segment playing), filled progressively by a background scan
+pub struct Envelope {
+    buckets: Vec<(f32, f32)>,
+    // Buckets scanned so far (the rest render as empty)
+    filled: usize,
+}
+
+pub type SharedEnvelope = Arc<Mutex<Envelope>>;
+
+impl Envelope {
+    fn new(resolution: usize) -> Self {
+        Envelope {
+            buckets: vec![(0.0, 0.0); resolution],
+            filled: 0,
+        }
+    }
+
+    // Lowest and highest sample (-1.0-1.0) per column, or None for columns
+    // not scanned yet
+    pub fn downsample(&self, columns: usize) -> Vec<Option<(f32, f32)>> {
+        let resolution = self.buckets.len();
+        (0..columns)
+            .map(|col| {
+                let start = col * resolution / columns;
+                let end = ((col + 1) * resolution / columns).max(start + 1).min(resolution);
+                if end > self.filled {
+                    return None;
+                }
+                let (min, max) = self.buckets[start..end]
+                    .iter()
+                    .fold((0.0f32, 0.0f32), |(low, high), &(min, max)| (low.min(min), high.max(max)));
+                Some((min.max(-1.0), max.min(1.0)))
+            })
+            .collect()
+    }
+}
+
+// Decode the file's `segment` on a background thread and fill the envelope
+// as we go
+pub fn spawn_envelope_scan(path: PathBuf, raw: Option<RawFormat>, segment: Segment) -> SharedEnvelope {
+    let envelope = Arc::new(Mutex::new(Envelope::new(ENVELOPE_RESOLUTION)));
+    let shared = envelope.clone();
+
+    std::thread::spawn(move || {
+        // The strip simply stays empty if the file can't be scanned
+        let _ = scan(&path, raw, segment, &shared);
+    });
+
+    envelope
+}
+
+type Samples = Box<dyn Iterator<Item = Result<f32, hound::Error>>>;
+
+// The samples of a WAV file's `segment`, or of a raw file's in `raw`'s
+// format (channels interleaved) normalized to -1.0..1.0, along with how many
+// there are
+fn normalized_samples(path: &PathBuf, raw: Option<RawFormat>, segment: Segment) -> Result<(usize, Samples), hound::Error> {
+    let (len, rate, channels, samples): (usize, u32, u16, Samples) = match raw {
+        Some(format) => {
+            let source = gruvberry::raw::open(path, format)?;
+            (source.len() as usize, format.sample_rate, format.channels, Box::new(source.map(Ok)))
+        }
+        None => {
+            let reader = hound::WavReader::new(BufReader::new(File::open(path)?))?;
+            let spec = reader.spec();
+            // Only the samples that are there in a file cut short
+            let inspection = gruvberry::wav::inspect(BufReader::new(File::open(path)?))
+                .map_err(|e| hound::Error::IoError(std::io::Error::other(e)))?;
+            let len = (inspection.frames * spec.channels as u64) as usize;
+            let samples: Samples = match spec.sample_format {
+                hound::SampleFormat::Int => {
+                    let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+                    Box::new(reader.into_samples::<i32>().map(move |s| s.map(|s| s as f32 * scale)))
+                }
+                hound::SampleFormat::Float => Box::new(reader.into_samples::<f32>()),
+            };
+            (len, spec.sample_rate, spec.channels, samples)
+        }
+    };
+    let range = segment.samples(rate, channels);
+    let (start, end) = (range.start.min(len), range.end.min(len));
+    Ok((end - start, Box::new(samples.skip(start).take(end - start))))
+}
+
+// RMS level of a whole file (its segment), used to level-match compared files
+pub fn track_rms(path: &PathBuf, raw: Option<RawFormat>, segment: Segment) -> Result<f32, hound::Error> {
+    let mut sum = 0.0f64;
+    let mut count = 0u64;
+    for sample in normalized_samples(path, raw, segment)?.1 {
+        let sample = sample? as f64;
+        sum += sample * sample;
+        count += 1;
+    }
+    Ok(if count == 0 { 0.0 } else { (sum / count as f64).sqrt() as f32 })
+}
+
+fn scan(path: &PathBuf, raw: Option<RawFormat>, segment: Segment, envelope: &SharedEnvelope) -> Result<(), hound::Error> {
+    let (total_samples, samples) = normalized_samples(path, raw, segment)?;
+    let total_samples = total_samples.max(1);
+
+    let mut current = 0;
+    let (mut min, mut max) = (0.0f32, 0.0f32);
+    for (i, sample) in samples.enumerate() {
+        let sample = sample?;
+        let bucket = i * ENVELOPE_RESOLUTION / total_samples;
+
+        // Publish each finished bucket so the strip fills in left to right
+        if bucket != current {
+            if let Ok(mut env) = envelope.lock() {
+                env.buckets[current] = (min, max);
+                env.filled = current + 1;
+            }
+            current = bucket;
+            min = 0.0;
+            max = 0.0;
+        }
+        min = min.min(sample);
+        max = max.max(sample);
+    }
+
+    if let Ok(mut env) = envelope.lock() {
+        env.buckets[current.min(ENVELOPE_RESOLUTION - 1)] = (min, max);
+        env.filled = ENVELOPE_RESOLUTION;
+    }
+    Ok(())
+}
+
+// Two-row strip, mirrored about the middle: the highest sample of each
+// column rises from the bottom of the top row and the lowest hangs from the
+// top of the bottom row. The played part is in the gradient's warm end, the
+// rest dimmed, with the playback cursor highlighted.
+pub fn overview_lines(envelope: &[Option<(f32, f32)>], position: f32, theme: &Theme) -> Vec<Line<'static>> {
+    let cursor = ((position.clamp(0.0, 1.0) * envelope.len() as f32) as usize).min(envelope.len().saturating_sub(1));
+    let style = |col: usize| {
+        if col == cursor {
+            theme.selected
+        } else if col < cursor {
+            theme.accent
+        } else {
+            theme.faint
+        }
+    };
+
+    let maxima: Vec<Span> = envelope
+        .iter()
+        .enumerate()
+        .map(|(col, column)| {
+            let symbol = column.map_or(' ', |(_, max)| gruvberry::sparkline::block(max));
+            Span::styled(symbol.to_string(), style(col))
+        })
+        .collect();
+    // There are no blocks growing down from the top of a cell: the one
+    // growing up over the rest of it is drawn reversed instead
+    let minima: Vec<Span> = envelope
+        .iter()
+        .enumerate()
+        .map(|(col, column)| match column {
+            Some((min, _)) => {
+                // The same steps as the top row's blocks
+                let level = ((-min).clamp(0.0, 1.0) * (LEVELS.len() - 1) as f32).round() as usize;
+                let symbol = if level + 1 == LEVELS.len() { ' ' } else { LEVELS[LEVELS.len() - 2 - level] };
+                Span::styled(symbol.to_string(), style(col).add_modifier(Modifier::REVERSED))
+            }
+            None => Span::styled(" ", style(col)),
+        })
+        .collect();
+
+    vec![Line::from(maxima), Line::from(minima)]
+}
@@ -0,0 +1,198 @@
+//! Themes: named gradients for the bars, kept in the config file.
+//!
+//! A theme is a [`Gradient::Sweep`] saved as a `[themes.<name>]` section of
+//! its two endpoints' hues and saturations; [`BUILT_IN`] names the VIBGYOR
+//! gradient, which needs no section. [`parse`] reads a section back and
+//! [`write`] saves one through `toml_edit`, changing nothing else in the
+//! file: comments, other sections and keys it doesn't know stay as they
+//! were. [`Editor`] is what the in-app editor adjusts before it saves.
+//!
+//! ```
+//! use gruvberry::palette::{self, Editor, Field};
+//!
+//! let mut document: toml_edit::DocumentMut = "# mine\nfps = 30\n".parse().unwrap();
+//! let mut editor = Editor::new(Default::default());
+//! editor.select(Field::HighHue);
+//! editor.adjust(-12);
+//! palette::write(&mut document, "sunset", &editor.gradient()).unwrap();
+//!
+//! let text = document.to_string();
+//! assert!(text.starts_with("# mine\nfps = 30\ntheme = \"sunset\"\n"));
+//! let table: toml::Table = text.parse().unwrap();
+//! let section = table["themes"]["sunset"].as_table().unwrap();
+//! assert_eq!(palette::parse(section), Ok(editor.gradient()));
+//! ```
+
+use crate::gradient::{Endpoint, Gradient};
+use crate::format::Locale;
+
+/// Table of the config file holding the themes: `[themes.<name>]`.
+pub const TABLE: &str = "themes";
+
+/// Name of the built-in VIBGYOR gradient.
+pub const BUILT_IN: &str = "vibgyor";
+
+/// Most themes that can be put in slots (Ctrl+1..5).
+pub const MAX_SLOTS: usize = 5;
+
+/// Keys of a theme's section.
+pub const KEYS: [&str; 4] = ["low_hue", "low_saturation", "high_hue", "high_saturation"];
+
+/// How far one step of the editor moves a hue (degrees) and a saturation.
+pub const HUE_STEP: f32 = 5.0;
+pub const SATURATION_STEP: f32 = 0.05;
+
+/// Endpoints closest to the VIBGYOR gradient: red to violet, saturated.
+pub const VIBGYOR_ENDPOINTS: (Endpoint, Endpoint) =
+    (Endpoint { hue: 0.0, saturation: 1.0 }, Endpoint { hue: 282.0, saturation: 1.0 });
+
+/// Whether `name` can name a saved theme: letters, digits, `-` and `_`,
+/// and not the built-in one's name.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("theme names are letters, digits, '-' and '_', got \"{}\"", name));
+    }
+    if name == BUILT_IN {
+        return Err(format!("\"{}\" is the built-in gradient", BUILT_IN));
+    }
+    Ok(())
+}
+
+/// The theme in a `[themes.<name>]` section: every key of [`KEYS`], hues
+/// from 0 to 360 and saturations from 0.0 to 1.0.
+pub fn parse(section: &toml::Table) -> Result<Gradient, String> {
+    if let Some(key) = section.keys().find(|key| !KEYS.contains(&key.as_str())) {
+        return Err(format!("unknown key `{}` in a theme (it has {})", key, KEYS.join(", ")));
+    }
+    let number = |key: &str, max: f64| {
+        let value = section.get(key).ok_or_else(|| format!("a theme needs {}", key))?;
+        let number = value.as_float().or_else(|| value.as_integer().map(|n| n as f64));
+        number
+            .filter(|n| (0.0..=max).contains(n))
+            .map(|n| n as f32)
+            .ok_or_else(|| format!("{} must be from 0 to {}, got {}", key, max, value))
+    };
+    Ok(Gradient::Sweep {
+        low: Endpoint { hue: number("low_hue", 360.0)?, saturation: number("low_saturation", 1.0)? },
+        high: Endpoint { hue: number("high_hue", 360.0)?, saturation: number("high_saturation", 1.0)? },
+    })
+}
+
+/// Save `gradient` into `document` as the theme `name` and make it the one
+/// in use (`theme = "<name>"`), replacing a theme of that name.
+pub fn write(document: &mut toml_edit::DocumentMut, name: &str, gradient: &Gradient) -> Result<(), String> {
+    check_name(name)?;
+    let (low, high) = endpoints(gradient);
+    let themes = document.entry(TABLE).or_insert_with(|| {
+        let mut table = toml_edit::Table::new();
+        table.set_implicit(true);
+        toml_edit::Item::Table(table)
+    });
+    let themes = themes.as_table_like_mut().ok_or_else(|| format!("`{}` isn't a table", TABLE))?;
+    let mut section = toml_edit::Table::new();
+    let round = |v: f32, places: i32| ((v as f64) * 10f64.powi(places)).round() / 10f64.powi(places);
+    section.insert("low_hue", toml_edit::value(round(low.hue, 1)));
+    section.insert("low_saturation", toml_edit::value(round(low.saturation, 2)));
+    section.insert("high_hue", toml_edit::value(round(high.hue, 1)));
+    section.insert("high_saturation", toml_edit::value(round(high.saturation, 2)));
+    themes.insert(name, toml_edit::Item::Table(section));
+    document["theme"] = toml_edit::value(name);
+    Ok(())
+}
+
+/// The two ends of `gradient` (VIBGYOR's closest, [`VIBGYOR_ENDPOINTS`]).
+pub fn endpoints(gradient: &Gradient) -> (Endpoint, Endpoint) {
+    match *gradient {
+        Gradient::Vibgyor => VIBGYOR_ENDPOINTS,
+        Gradient::Sweep { low, high } => (low, high),
+    }
+}
+
+/// One of the four numbers the editor adjusts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    LowHue,
+    LowSaturation,
+    HighHue,
+    HighSaturation,
+}
+
+impl Field {
+    pub const ALL: [Field; 4] = [Field::LowHue, Field::LowSaturation, Field::HighHue, Field::HighSaturation];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::LowHue => "Low hue",
+            Field::LowSaturation => "Low saturation",
+            Field::HighHue => "High hue",
+            Field::HighSaturation => "High saturation",
+        }
+    }
+}
+
+/// A gradient being edited, from the one in use when editing started.
+#[derive(Clone, Debug)]
+pub struct Editor {
+    original: Gradient,
+    low: Endpoint,
+    high: Endpoint,
+    field: Field,
+}
+
+impl Editor {
+    /// Editing from `gradient`'s endpoints, the low hue selected.
+    pub fn new(gradient: Gradient) -> Self {
+        let (low, high) = endpoints(&gradient);
+        Editor { original: gradient, low, high, field: Field::LowHue }
+    }
+
+    /// The gradient as edited so far.
+    pub fn gradient(&self) -> Gradient {
+        Gradient::Sweep { low: self.low, high: self.high }
+    }
+
+    /// The gradient before any edits, to go back to.
+    pub fn original(&self) -> Gradient {
+        self.original
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    pub fn select(&mut self, field: Field) {
+        self.field = field;
+    }
+
+    /// Select the field `delta` rows away, stopping at the first and last.
+    pub fn move_selection(&mut self, delta: isize) {
+        let index = Field::ALL.iter().position(|&field| field == self.field).unwrap_or(0);
+        let index = index.saturating_add_signed(delta).min(Field::ALL.len() - 1);
+        self.field = Field::ALL[index];
+    }
+
+    /// Move the selected number by `steps` of [`HUE_STEP`] or
+    /// [`SATURATION_STEP`], onto a whole step and within its range.
+    pub fn adjust(&mut self, steps: i32) {
+        let (value, step, max) = match self.field {
+            Field::LowHue => (&mut self.low.hue, HUE_STEP, 360.0),
+            Field::LowSaturation => (&mut self.low.saturation, SATURATION_STEP, 1.0),
+            Field::HighHue => (&mut self.high.hue, HUE_STEP, 360.0),
+            Field::HighSaturation => (&mut self.high.saturation, SATURATION_STEP, 1.0),
+        };
+        // Divided rather than multiplied out, so 0.95 is the 0.95 a file reads
+        let per_unit = 1.0 / step;
+        *value = (((*value * per_unit).round() + steps as f32) / per_unit).clamp(0.0, max);
+    }
+
+    /// `field`'s value as shown, with `locale`'s numbers: degrees or a
+    /// percentage.
+    pub fn value_text(&self, field: Field, locale: Locale) -> String {
+        match field {
+            Field::LowHue => locale.degrees(self.low.hue),
+            Field::LowSaturation => locale.percent(self.low.saturation),
+            Field::HighHue => locale.degrees(self.high.hue),
+            Field::HighSaturation => locale.percent(self.high.saturation),
+        }
+    }
+}
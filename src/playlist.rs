@@ -0,0 +1,280 @@
+//! The queue of files to play, and the order they play in.
+//!
+//! A [`Playlist`] is shared between the player thread, which asks it what
+//! comes next, and the visualizer, which edits it while audio keeps playing.
+//! Under shuffle every entry plays once, in random order, before any plays
+//! again; Previous always goes back through the order the entries were
+//! actually played in, and Next then replays the ones stepped back over.
+//!
+//! ```
+//! use std::path::PathBuf;
+//! use gruvberry::playlist::{Playlist, Repeat, Step};
+//!
+//! let mut playlist = Playlist::new(vec![PathBuf::from("a.wav"), PathBuf::from("b.wav")], false, Repeat::Off);
+//! assert_eq!(playlist.advance(), Some(PathBuf::from("a.wav")));
+//! assert_eq!(playlist.advance(), Some(PathBuf::from("b.wav")));
+//! playlist.skip(Step::Previous);
+//! assert_eq!(playlist.advance(), Some(PathBuf::from("a.wav")));
+//! ```
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use rand::seq::SliceRandom;
+
+// Most plays Previous can step back through; a playlist repeating for days
+// forgets the oldest
+const MAX_HISTORY: usize = 1000;
+
+/// What happens when a track ends on its own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Repeat {
+    #[default]
+    Off,
+    /// Play the same track again.
+    One,
+    /// Start over once the playlist is exhausted.
+    All,
+}
+
+impl Repeat {
+    pub fn parse(value: &str) -> Option<Repeat> {
+        match value {
+            "off" => Some(Repeat::Off),
+            "one" => Some(Repeat::One),
+            "all" => Some(Repeat::All),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Repeat::Off => "off",
+            Repeat::One => "one",
+            Repeat::All => "all",
+        }
+    }
+
+    /// off -> one -> all -> off, for the 'R' key.
+    pub fn cycle(self) -> Repeat {
+        match self {
+            Repeat::Off => Repeat::One,
+            Repeat::One => Repeat::All,
+            Repeat::All => Repeat::Off,
+        }
+    }
+}
+
+/// A jump requested from the UI, carried out by the player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Next,
+    Previous,
+}
+
+/// Files queued for playback. The player thread moves through it; the
+/// visualizer edits it from the playlist panel while audio keeps playing.
+pub struct Playlist {
+    entries: Vec<PathBuf>,
+    // Entry playing now (None before the first track starts)
+    current: Option<usize>,
+    // Highlighted entry in the playlist panel
+    selected: usize,
+    shuffle: bool,
+    repeat: Repeat,
+    // Entries in the order they were played, the playing one last
+    history: Vec<usize>,
+    // Entries stepped back over with Previous, replayed by Next
+    forward: Vec<usize>,
+    // Shuffle only: entries not played yet this round, in random order
+    // (taken from the end)
+    bag: Vec<usize>,
+    // Jump to make when the player next advances
+    pending: Option<Step>,
+    // Set when the playing track should be cut short, so the player skips ahead
+    interrupt: bool,
+}
+
+/// Shared handle the player thread and the visualizer both work on.
+pub type SharedPlaylist = Arc<Mutex<Playlist>>;
+
+impl Playlist {
+    pub fn new(entries: Vec<PathBuf>, shuffle: bool, repeat: Repeat) -> Self {
+        let mut playlist = Playlist {
+            entries,
+            current: None,
+            selected: 0,
+            shuffle: false,
+            repeat,
+            history: Vec::new(),
+            forward: Vec::new(),
+            bag: Vec::new(),
+            pending: None,
+            interrupt: false,
+        };
+        playlist.set_shuffle(shuffle);
+        playlist
+    }
+
+    pub fn entries(&self) -> &[PathBuf] {
+        &self.entries
+    }
+
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn shuffle(&self) -> bool {
+        self.shuffle
+    }
+
+    pub fn repeat(&self) -> Repeat {
+        self.repeat
+    }
+
+    pub fn set_repeat(&mut self, repeat: Repeat) {
+        self.repeat = repeat;
+    }
+
+    /// Turning shuffle on deals out the entries not played yet; turning it
+    /// off continues in list order after the playing entry.
+    pub fn set_shuffle(&mut self, shuffle: bool) {
+        self.shuffle = shuffle;
+        self.bag.clear();
+        if shuffle {
+            self.bag = (0..self.entries.len()).filter(|i| !self.history.contains(i)).collect();
+            self.bag.shuffle(&mut rand::rng());
+        }
+    }
+
+    /// Queue a file after everything else.
+    pub fn add(&mut self, path: PathBuf) {
+        let index = self.entries.len();
+        self.entries.push(path);
+        if self.shuffle {
+            let slot = rand::random_range(0..=self.bag.len());
+            self.bag.insert(slot, index);
+        }
+    }
+
+    /// Drop an entry; removing the playing one makes the player move on.
+    pub fn remove(&mut self, index: usize) -> Option<PathBuf> {
+        if index >= self.entries.len() {
+            return None;
+        }
+        let removed = self.entries.remove(index);
+
+        // Forget the entry everywhere and shift the indices after it
+        for list in [&mut self.history, &mut self.forward, &mut self.bag] {
+            list.retain(|&i| i != index);
+            for i in list.iter_mut().filter(|i| **i > index) {
+                *i -= 1;
+            }
+        }
+        match self.current {
+            Some(current) if index < current => self.current = Some(current - 1),
+            // The next entry has slid into the playing slot; step back so
+            // advancing in list order lands on it
+            Some(current) if index == current => {
+                self.current = current.checked_sub(1);
+                self.skip(Step::Next);
+            }
+            _ => {}
+        }
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+        Some(removed)
+    }
+
+    /// Start over as if nothing had played yet, for playing the list again
+    /// once it has ended.
+    pub fn rewind(&mut self) {
+        self.current = None;
+        self.history.clear();
+        self.forward.clear();
+        self.pending = None;
+        self.set_shuffle(self.shuffle);
+    }
+
+    /// Move the highlight up (negative) or down, staying within the list.
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.entries.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Ask the player to cut the playing track short and jump.
+    pub fn skip(&mut self, step: Step) {
+        self.pending = Some(step);
+        self.interrupt = true;
+    }
+
+    /// Whether the playing track should be cut short since the last call.
+    pub fn take_interrupt(&mut self) -> bool {
+        std::mem::take(&mut self.interrupt)
+    }
+
+    /// Move to the entry that should play next and return its path, or None
+    /// when playback is over. A track ending on its own repeats under
+    /// repeat-one; Next/Previous from the UI always move.
+    pub fn advance(&mut self) -> Option<PathBuf> {
+        let index = match self.pending.take() {
+            Some(Step::Previous) => self.previous(),
+            Some(Step::Next) => self.next(),
+            None if self.repeat == Repeat::One => self.current.filter(|&i| i < self.entries.len()).or_else(|| self.next()),
+            None => self.next(),
+        }?;
+
+        self.bag.retain(|&i| i != index);
+        if self.history.last() != Some(&index) {
+            if self.history.len() >= MAX_HISTORY {
+                self.history.remove(0);
+            }
+            self.history.push(index);
+        }
+        self.current = Some(index);
+        Some(self.entries[index].clone())
+    }
+
+    // Back through the actual play order (not the list or shuffle order);
+    // at the first track it restarts
+    fn previous(&mut self) -> Option<usize> {
+        if self.history.len() >= 2 {
+            let current = self.history.pop()?;
+            self.forward.push(current);
+            // advance() pushes it back as the playing entry
+            self.history.pop()
+        } else {
+            self.current.or_else(|| self.next())
+        }
+    }
+
+    fn next(&mut self) -> Option<usize> {
+        if let Some(index) = self.forward.pop() {
+            return Some(index);
+        }
+        if self.entries.is_empty() {
+            return None;
+        }
+        if self.shuffle {
+            if self.bag.is_empty() && self.repeat == Repeat::All {
+                // New round: everything again, but not the track just heard first
+                self.bag = (0..self.entries.len()).collect();
+                self.bag.shuffle(&mut rand::rng());
+                if self.bag.len() > 1 && self.bag.last() == self.current.as_ref() {
+                    self.bag.swap(0, self.entries.len() - 1);
+                }
+            }
+            return self.bag.pop();
+        }
+        let next = self.current.map_or(0, |current| current + 1);
+        if next < self.entries.len() {
+            Some(next)
+        } else if self.repeat == Repeat::All {
+            Some(0)
+        } else {
+            None
+        }
+    }
+}
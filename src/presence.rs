@@ -0,0 +1,160 @@
+//! What a chat client's "now playing" status says about the track.
+//!
+//! The `gruvberry` binary built with the `discord` feature publishes this as
+//! Discord Rich Presence; the formatting here is kept apart from the socket
+//! so it can be checked without Discord running. [`mini_spectrum`] squeezes
+//! the bands into [`SEGMENTS`] block characters, crude enough to survive the
+//! small font of a profile card:
+//!
+//! ```
+//! use gruvberry::presence::mini_spectrum;
+//!
+//! let bands: Vec<f32> = (0..64).map(|i| 100.0 - i as f32 * 1.5).collect();
+//! assert_eq!(mini_spectrum(&bands), "█▇▆▅▄▃▂▁");
+//! assert_eq!(mini_spectrum(&[]), "▁▁▁▁▁▁▁▁");
+//! ```
+
+/// Characters in the mini spectrum.
+pub const SEGMENTS: usize = 8;
+/// Longest text the status lines may hold (Discord's limit, in bytes).
+pub const MAX_TEXT_LEN: usize = 128;
+
+// Block characters from silent to full
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The bands (0.0 to 100.0, as drawn) as [`SEGMENTS`] block characters, each
+/// the average of an equal share of the bands. With fewer bands than
+/// segments a band spans several; with none, or levels that aren't numbers,
+/// the segments are at the bottom.
+pub fn mini_spectrum(bands: &[f32]) -> String {
+    (0..SEGMENTS)
+        .map(|segment| {
+            if bands.is_empty() {
+                return LEVELS[0];
+            }
+            let start = segment * bands.len() / SEGMENTS;
+            let end = ((segment + 1) * bands.len() / SEGMENTS).max(start + 1);
+            let share = &bands[start..end];
+            let level = share.iter().map(|&b| if b.is_finite() { b } else { 0.0 }).sum::<f32>() / share.len() as f32;
+            let index = (level / 100.0 * LEVELS.len() as f32).floor();
+            LEVELS[(index.max(0.0) as usize).min(LEVELS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Artist and title from a file name in the common "Artist - Title" form
+/// (extension dropped); a name without the separator is all title.
+pub fn split_name(name: &str) -> (Option<&str>, &str) {
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    match stem.split_once(" - ") {
+        Some((artist, title)) if !artist.trim().is_empty() && !title.trim().is_empty() => {
+            (Some(artist.trim()), title.trim())
+        }
+        _ => (None, stem.trim()),
+    }
+}
+
+/// Playback time as m:ss.
+pub fn clock(secs: f32) -> String {
+    let secs = secs.max(0.0) as u64;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// One "now playing" status.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Activity {
+    pub title: String,
+    pub artist: Option<String>,
+    /// Seconds into the track.
+    pub elapsed: f32,
+    /// Length of the track in seconds; infinite when it has none.
+    pub total: f32,
+    pub paused: bool,
+    /// [`mini_spectrum`] of what is playing.
+    pub spectrum: String,
+}
+
+impl Activity {
+    /// The main line: the title, and the artist when known.
+    pub fn details(&self) -> String {
+        let text = match &self.artist {
+            Some(artist) => format!("{} — {}", self.title, artist),
+            None => self.title.clone(),
+        };
+        status_text(&text)
+    }
+
+    /// The small line under it: the spectrum and where playback is.
+    pub fn state(&self) -> String {
+        let position = match self.total.is_finite() {
+            true => format!("{} / {}", clock(self.elapsed), clock(self.total)),
+            false => clock(self.elapsed),
+        };
+        let paused = if self.paused { " (paused)" } else { "" };
+        status_text(&format!("{} {}{}", self.spectrum, position, paused))
+    }
+
+    /// The terminal window's title, e.g. `gruvberry ▸ Artist – Title [3:12/4:01]`.
+    pub fn window_title(&self) -> String {
+        let name = match &self.artist {
+            Some(artist) => format!("{} – {}", artist, self.title),
+            None => self.title.clone(),
+        };
+        let position = match self.total.is_finite() {
+            true => format!("{}/{}", clock(self.elapsed), clock(self.total)),
+            false => clock(self.elapsed),
+        };
+        let paused = if self.paused { " (paused)" } else { "" };
+        format!("gruvberry ▸ {} [{}]{}", name, position, paused)
+    }
+
+    /// The activity as Discord's JSON, with the time bar running from when
+    /// the track started by `now_ms` (Unix time, in milliseconds). A paused
+    /// track has no time bar, as it would run on.
+    pub fn to_json(&self, now_ms: u64) -> String {
+        let mut json = format!(
+            "{{\"type\":2,\"details\":{},\"state\":{}",
+            json_string(&self.details()),
+            json_string(&self.state())
+        );
+        if !self.paused {
+            let start = now_ms.saturating_sub((self.elapsed.max(0.0) * 1000.0) as u64);
+            json.push_str(&format!(",\"timestamps\":{{\"start\":{}", start));
+            if self.total.is_finite() {
+                json.push_str(&format!(",\"end\":{}", start + (self.total.max(0.0) * 1000.0) as u64));
+            }
+            json.push('}');
+        }
+        json.push('}');
+        json
+    }
+}
+
+// Text cut to fit a status line, and padded to the two characters Discord
+// wants at least
+fn status_text(text: &str) -> String {
+    let mut end = text.len().min(MAX_TEXT_LEN);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{:<2}", &text[..end])
+}
+
+/// `text` as a JSON string literal.
+pub fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -0,0 +1,136 @@
+use std::path::{Path, PathBuf};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use gruvberry::theme::Theme;
+use ratatui::text::{Line, Span};
+
+// What a key press did to the prompt
+pub enum PromptAction {
+    // Still editing
+    Editing,
+    // Enter: the entered text
+    Submit(String),
+    // Esc: prompt dismissed
+    Cancel,
+}
+
+// Minimal single-line editor for the "add to queue" prompt, with tab
+// completion of file paths
+#[derive(Default)]
+pub struct LineEditor {
+    text: String,
+    // Cursor position in characters
+    cursor: usize,
+}
+
+impl LineEditor {
+    pub fn handle_key(&mut self, key: KeyEvent) -> PromptAction {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => return PromptAction::Submit(self.text.clone()),
+            KeyCode::Esc => return PromptAction::Cancel,
+            KeyCode::Char('u') if ctrl => {
+                self.text = self.text.chars().skip(self.cursor).collect();
+                self.cursor = 0;
+            }
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.len(),
+            KeyCode::Char(c) if !ctrl => {
+                self.text.insert(self.byte_index(self.cursor), c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.byte_index(self.cursor));
+            }
+            KeyCode::Delete if self.cursor < self.len() => {
+                self.text.remove(self.byte_index(self.cursor));
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            KeyCode::Tab => {
+                // Completion works on the whole line, so only at the end of it
+                if self.cursor == self.len()
+                    && let Some(completed) = complete_path(&self.text)
+                {
+                    self.text = completed;
+                    self.cursor = self.len();
+                }
+            }
+            _ => {}
+        }
+        PromptAction::Editing
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text.char_indices().nth(chars).map_or(self.text.len(), |(i, _)| i)
+    }
+
+    // The prompt text with the cursor shown as a highlighted cell
+    pub fn line(&self, label: &str, theme: &Theme) -> Line<'static> {
+        let before: String = self.text.chars().take(self.cursor).collect();
+        let at: String = self.text.chars().nth(self.cursor).map_or(" ".to_string(), String::from);
+        let after: String = self.text.chars().skip(self.cursor + 1).collect();
+        Line::from(vec![
+            Span::raw(label.to_string()),
+            Span::raw(before),
+            Span::styled(at, theme.selected),
+            Span::raw(after),
+        ])
+    }
+}
+
+// Expand a leading ~ to the home directory
+pub fn expand_home(input: &str) -> PathBuf {
+    match (input.strip_prefix('~'), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(format!("{}{}", home.to_string_lossy(), rest))
+        }
+        _ => PathBuf::from(input),
+    }
+}
+
+// Extend `input` as far as the matching directory entries agree, adding a
+// trailing '/' once it names a single directory. None when nothing matches.
+fn complete_path(input: &str) -> Option<String> {
+    let (dir_part, prefix) = match input.rfind('/') {
+        Some(slash) => input.split_at(slash + 1),
+        None => ("", input),
+    };
+    let dir = if dir_part.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_home(dir_part)
+    };
+
+    let matches: Vec<String> = std::fs::read_dir(&dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.')))
+        .collect();
+
+    let first = matches.first()?;
+    let common = matches.iter().fold(first.clone(), |common, name| {
+        common
+            .chars()
+            .zip(name.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a)
+            .collect()
+    });
+
+    let mut completed = format!("{}{}", dir_part, common);
+    if matches.len() == 1 && is_dir(&dir.join(&common)) {
+        completed.push('/');
+    }
+    (completed != input).then_some(completed)
+}
+
+fn is_dir(path: &Path) -> bool {
+    path.metadata().is_ok_and(|meta| meta.is_dir())
+}
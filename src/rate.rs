@@ -0,0 +1,124 @@
+//! The sample-rate contract.
+//!
+//! A file plays at whatever rate the output device runs at: rodio's mixer
+//! converts every source to the device rate (and channel count) as it mixes.
+//! The [`SampleCapture`](crate::capture::SampleCapture) tap sits before that
+//! conversion, between the decoder and the mixer, so the samples it hands on
+//! are still at the file's own rate, and every batch declares that rate. The
+//! analysis therefore always runs at the declared rate of the samples it got
+//! ([`SampleRing::sample_rate`](crate::capture::SampleRing::sample_rate)),
+//! never at the device's; a 44.1 kHz file on a 48 kHz device is analyzed at
+//! 44.1 kHz.
+//!
+//! Converting between FFT bins and frequencies happens here and nowhere
+//! else, so there is one place to check that the right rate goes in.
+//!
+//! Rates above [`HIGH_RATE`] (88.2, 96, 192 kHz) are analyzed at their own
+//! rate too, but two things follow the rate here so they look like any
+//! other file: the displayed range stops at [`AUDIBLE_MAX_FREQ`]
+//! ([`display_max_freq`]) instead of leaving most of a log axis to
+//! ultrasonic silence, and the FFT grows with the rate ([`fft_size_for`]) so
+//! its window lasts as long and its bins are as narrow as at 48 kHz.
+//!
+//! ```
+//! use gruvberry::rate;
+//!
+//! assert_eq!(rate::nyquist(44100), 22050.0);
+//! assert_eq!(rate::bin_frequency(93.0, 44100, 4096), 93.0 * 44100.0 / 4096.0);
+//! assert_eq!(rate::display_max_freq(44100, None), 22050.0);
+//! assert_eq!(rate::display_max_freq(192000, None), 20000.0);
+//! assert_eq!(rate::fft_size_for(1024, 192000), 4096);
+//! ```
+
+use rustfft::{FftPlanner, num_complex::Complex};
+use crate::analysis::MAX_FFT_SIZE;
+
+/// The highest rate taken as it is; above it a file counts as high-rate.
+pub const HIGH_RATE: u32 = 48000;
+
+/// Top of the displayed range for high-rate files unless configured: about
+/// where hearing ends.
+pub const AUDIBLE_MAX_FREQ: f32 = 20000.0;
+
+/// Width of one FFT bin in Hz for `fft_size` samples at `sample_rate`.
+pub fn freq_per_bin(sample_rate: u32, fft_size: usize) -> f32 {
+    sample_rate as f32 / fft_size.max(1) as f32
+}
+
+/// Center frequency of FFT bin `bin`.
+pub fn bin_frequency(bin: f32, sample_rate: u32, fft_size: usize) -> f32 {
+    bin * freq_per_bin(sample_rate, fft_size)
+}
+
+/// FFT bin (fractional) that `freq` falls on.
+pub fn frequency_bin(freq: f32, sample_rate: u32, fft_size: usize) -> f32 {
+    freq / freq_per_bin(sample_rate, fft_size)
+}
+
+/// Highest frequency samples at `sample_rate` can carry.
+pub fn nyquist(sample_rate: u32) -> f32 {
+    sample_rate as f32 / 2.0
+}
+
+/// Top of the displayed frequency range for samples at `sample_rate`:
+/// `configured` (Hz) when there is one, else Nyquist, or
+/// [`AUDIBLE_MAX_FREQ`] for rates above [`HIGH_RATE`]; never past Nyquist.
+pub fn display_max_freq(sample_rate: u32, configured: Option<f32>) -> f32 {
+    let nyquist = nyquist(sample_rate);
+    match configured {
+        Some(freq) => freq.min(nyquist),
+        None if sample_rate > HIGH_RATE => AUDIBLE_MAX_FREQ.min(nyquist),
+        None => nyquist,
+    }
+}
+
+/// FFT size for samples at `sample_rate`, `fft_size` being the one for
+/// rates up to [`HIGH_RATE`]: doubled for each doubling of the rate past
+/// it, so 1024 (21 ms at 48 kHz) becomes 2048 at 96 kHz and 4096 at
+/// 192 kHz, up to [`MAX_FFT_SIZE`].
+pub fn fft_size_for(fft_size: usize, sample_rate: u32) -> usize {
+    let (mut size, mut covered) = (fft_size, HIGH_RATE);
+    while sample_rate > covered && size < MAX_FFT_SIZE {
+        size *= 2;
+        covered *= 2;
+    }
+    size
+}
+
+/// Frequency of the strongest component of `samples` (mono, at
+/// `sample_rate`), refined between bins; None for silence.
+pub fn dominant_frequency(samples: &[f32], sample_rate: u32) -> Option<f32> {
+    let len = samples.len();
+    if len < 4 {
+        return None;
+    }
+    // Hann window, so the tone's energy stays near its bin
+    let mut spectrum: Vec<Complex<f32>> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, &s)| {
+            let window = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / len as f32).cos();
+            Complex { re: s * window, im: 0.0 }
+        })
+        .collect();
+    FftPlanner::new().plan_fft_forward(len).process(&mut spectrum);
+    let magnitudes: Vec<f32> = spectrum[..len / 2].iter().map(|c| c.norm()).collect();
+
+    let (peak, &magnitude) = magnitudes
+        .iter()
+        .enumerate()
+        .skip(1)
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+    if magnitude <= 0.0 {
+        return None;
+    }
+    // Parabola through the peak and its neighbours
+    let shift = match (magnitudes.get(peak - 1), magnitudes.get(peak + 1)) {
+        (Some(&left), Some(&right)) => {
+            let curvature = left - 2.0 * magnitude + right;
+            if curvature < 0.0 { 0.5 * (left - right) / curvature } else { 0.0 }
+        }
+        _ => 0.0,
+    };
+    Some(bin_frequency(peak as f32 + shift, sample_rate, len))
+}
@@ -0,0 +1,231 @@
+//! Headerless PCM files.
+//!
+//! A `.pcm` or `.raw` capture is nothing but samples, so how to read it has
+//! to be given: a [`RawFormat`] such as `s16le:44100:2` names the sample
+//! layout, the sample rate and the channel count. [`RawSource`] reads the
+//! file as a rodio [`Source`] in that format, a whole frame (one sample per
+//! channel) at a time; bytes at the end that don't make a whole frame are
+//! left out.
+//!
+//! ```
+//! use gruvberry::raw::{Layout, RawFormat};
+//!
+//! let format = RawFormat::parse("s24le:48000:2").unwrap();
+//! assert_eq!((format.layout, format.sample_rate, format.channels), (Layout::S24Le, 48000, 2));
+//! assert_eq!(format.frame_bytes(), 6);
+//! // A second of audio
+//! assert_eq!(format.duration(288_000), 1.0);
+//! assert!(RawFormat::parse("u8:8000:1").is_err());
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+use rodio::Source;
+use rodio::source::SeekError;
+
+/// Most channels a raw file can have.
+pub const MAX_CHANNELS: u16 = 8;
+
+/// How each sample is stored: signed integers of 16, 24 or 32 bits or 32-bit
+/// floats, all little-endian.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    S16Le,
+    S24Le,
+    S32Le,
+    F32Le,
+}
+
+impl Layout {
+    pub const NAMES: [&str; 4] = ["s16le", "s24le", "s32le", "f32le"];
+
+    pub fn parse(value: &str) -> Option<Layout> {
+        match value {
+            "s16le" => Some(Layout::S16Le),
+            "s24le" => Some(Layout::S24Le),
+            "s32le" => Some(Layout::S32Le),
+            "f32le" => Some(Layout::F32Le),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::S16Le => "s16le",
+            Layout::S24Le => "s24le",
+            Layout::S32Le => "s32le",
+            Layout::F32Le => "f32le",
+        }
+    }
+
+    /// Bytes per sample.
+    pub fn bytes(self) -> usize {
+        match self {
+            Layout::S16Le => 2,
+            Layout::S24Le => 3,
+            Layout::S32Le | Layout::F32Le => 4,
+        }
+    }
+
+    /// The sample in `bytes` (exactly [`bytes`](Layout::bytes) of them) as
+    /// -1.0 to 1.0.
+    pub fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            Layout::S16Le => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32_768.0,
+            // Into the top of an i32 so the sign comes along, then back down
+            Layout::S24Le => (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8_388_608.0,
+            Layout::S32Le => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32 / 2_147_483_648.0,
+            Layout::F32Le => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+}
+
+/// What a raw file holds, as `--raw` gives it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RawFormat {
+    pub layout: Layout,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl RawFormat {
+    /// Read `LAYOUT:RATE:CHANNELS`, e.g. `s16le:44100:2`, saying what is
+    /// wrong with anything else.
+    pub fn parse(value: &str) -> Result<RawFormat, String> {
+        let [layout, rate, channels] = value.split(':').collect::<Vec<_>>()[..] else {
+            return Err(format!("'{}' isn't LAYOUT:RATE:CHANNELS, e.g. s16le:44100:2", value));
+        };
+        let layout = Layout::parse(layout).ok_or_else(|| {
+            format!("unknown sample layout '{}' (expected one of {})", layout, Layout::NAMES.join(", "))
+        })?;
+        let sample_rate = match rate.parse() {
+            Ok(rate) if rate > 0 => rate,
+            _ => return Err(format!("sample rate '{}' must be a whole number of Hz above 0", rate)),
+        };
+        let channels = match channels.parse() {
+            Ok(channels) if (1..=MAX_CHANNELS).contains(&channels) => channels,
+            _ => return Err(format!("channel count '{}' must be from 1 to {}", channels, MAX_CHANNELS)),
+        };
+        Ok(RawFormat {
+            layout,
+            sample_rate,
+            channels,
+        })
+    }
+
+    /// Bytes per frame: one sample for each channel.
+    pub fn frame_bytes(&self) -> usize {
+        self.layout.bytes() * self.channels as usize
+    }
+
+    /// Whole frames in `bytes` bytes of a file.
+    pub fn frames(&self, bytes: u64) -> u64 {
+        bytes / self.frame_bytes() as u64
+    }
+
+    /// Length in seconds of a file of `bytes` bytes.
+    pub fn duration(&self, bytes: u64) -> f32 {
+        self.frames(bytes) as f32 / self.sample_rate as f32
+    }
+
+    /// The same format as a WAV header would give it, so a raw file can be
+    /// described like any other.
+    pub fn spec(&self) -> hound::WavSpec {
+        hound::WavSpec {
+            channels: self.channels,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 8 * self.layout.bytes() as u16,
+            sample_format: match self.layout {
+                Layout::F32Le => hound::SampleFormat::Float,
+                _ => hound::SampleFormat::Int,
+            },
+        }
+    }
+}
+
+/// A raw file's samples, channels interleaved.
+pub struct RawSource<R> {
+    reader: R,
+    format: RawFormat,
+    // Whole frames in the input
+    frames: u64,
+    // The frame being handed out, and how much of it has been
+    frame: Vec<u8>,
+    next: usize,
+}
+
+/// Open the raw file at `path` in `format`.
+pub fn open(path: &Path, format: RawFormat) -> io::Result<RawSource<BufReader<File>>> {
+    let file = File::open(path)?;
+    let bytes = file.metadata()?.len();
+    Ok(RawSource::new(BufReader::new(file), format, bytes))
+}
+
+impl<R: Read + Seek> RawSource<R> {
+    /// Read `bytes` bytes of samples from `reader`, which starts at the
+    /// first of them.
+    pub fn new(reader: R, format: RawFormat, bytes: u64) -> Self {
+        let frame_bytes = format.frame_bytes();
+        RawSource {
+            reader,
+            format,
+            frames: format.frames(bytes),
+            frame: vec![0; frame_bytes],
+            next: frame_bytes,
+        }
+    }
+
+    /// Samples in the whole input, across all channels.
+    pub fn len(&self) -> u64 {
+        self.frames * self.format.channels as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames == 0
+    }
+}
+
+impl<R: Read + Seek> Iterator for RawSource<R> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.next == self.frame.len() {
+            // A frame cut short by the end of the file is left out
+            self.reader.read_exact(&mut self.frame).ok()?;
+            self.next = 0;
+        }
+        let bytes = self.format.layout.bytes();
+        let sample = self.format.layout.decode(&self.frame[self.next..self.next + bytes]);
+        self.next += bytes;
+        Some(sample)
+    }
+}
+
+impl<R: Read + Seek> Source for RawSource<R> {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.format.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.format.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(self.frames as f64 / self.format.sample_rate as f64))
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let frame = ((pos.as_secs_f64() * self.format.sample_rate as f64) as u64).min(self.frames);
+        self.reader
+            .seek(SeekFrom::Start(frame * self.format.frame_bytes() as u64))
+            .map_err(|e| SeekError::Other(Box::new(e)))?;
+        self.next = self.frame.len();
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+//! Writing the played audio to a WAV file while it plays.
+//!
+//! [`start`] opens the file and spawns a writer thread; the [`Recorder`] it
+//! returns is a [`SampleObserver`] to put on a
+//! [`SampleCapture`](crate::capture::SampleCapture) tap. The tap runs on the
+//! audio thread, so the recorder never waits on the disk: it hands each batch
+//! to the writer through a bounded channel and, should the writer fall that
+//! far behind, drops the batch and counts it instead.
+//!
+//! Samples are written as 32-bit float at the rate and channel count they
+//! are played at, so nothing is lost to conversion. One WAV file holds one
+//! format, so the recording stops at a track with another rate or channel
+//! count. The header is brought up to date every second, so even a recording
+//! cut short by a full disk or a crash plays back up to about then;
+//! [`Recording::finish`] finalizes it properly.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use crate::capture::SampleObserver;
+
+/// Batches the writer may fall behind by before batches are dropped (about
+/// five seconds of 48 kHz stereo in batches of
+/// [`BATCH_LEN`](crate::capture::BATCH_LEN)).
+pub const QUEUE_BATCHES: usize = 8192;
+
+// How long the writer waits for samples before checking whether it should stop
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How a recording is going.
+#[derive(Clone, Debug, Default)]
+pub struct RecordStatus {
+    /// Frames (samples per channel) written.
+    pub frames: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Samples dropped because the writer fell behind.
+    pub dropped: u64,
+    /// Why the recording stopped early, if it did.
+    pub error: Option<String>,
+}
+
+impl RecordStatus {
+    /// Length of the audio written.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames as f64 / self.sample_rate.max(1) as f64)
+    }
+
+    /// Size of the audio data written, in bytes.
+    pub fn bytes(&self) -> u64 {
+        self.frames * self.channels as u64 * 4
+    }
+}
+
+// One batch of played samples
+struct Batch {
+    samples: Vec<f32>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+/// The audio thread's end of a recording.
+#[derive(Clone)]
+pub struct Recorder {
+    sender: SyncSender<Batch>,
+    status: Arc<Mutex<RecordStatus>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Recorder {
+    /// How the recording is going.
+    pub fn status(&self) -> RecordStatus {
+        let mut status = self.status.lock().map(|status| status.clone()).unwrap_or_default();
+        status.dropped = self.dropped.load(Ordering::Relaxed);
+        status
+    }
+}
+
+impl SampleObserver for Recorder {
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32) {
+        let batch = Batch {
+            samples: samples.to_vec(),
+            channels,
+            sample_rate,
+        };
+        // Once the writer has stopped there is nothing left to record
+        if let Err(TrySendError::Full(batch)) = self.sender.try_send(batch) {
+            self.dropped.fetch_add(batch.samples.len() as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The writer thread of a recording.
+pub struct Recording {
+    path: PathBuf,
+    recorder: Recorder,
+    closed: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// Start recording to `path`, which is created (or truncated) right away so
+/// a bad path is reported before anything plays.
+pub fn start(path: &Path) -> Result<(Recording, Recorder), String> {
+    let file = File::create(path).map_err(|e| format!("can't record to {}: {}", path.display(), e))?;
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_BATCHES);
+    let recorder = Recorder {
+        sender,
+        status: Arc::new(Mutex::new(RecordStatus::default())),
+        dropped: Arc::new(AtomicU64::new(0)),
+    };
+    let closed = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let status = recorder.status.clone();
+        let closed = closed.clone();
+        std::thread::spawn(move || write(file, receiver, &status, &closed))
+    };
+    let recording = Recording {
+        path: path.to_path_buf(),
+        recorder: recorder.clone(),
+        closed,
+        thread,
+    };
+    Ok((recording, recorder))
+}
+
+impl Recording {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write out what has been handed over so far, finalize the file and
+    /// report how it went. Batches played after this aren't recorded.
+    pub fn finish(self) -> RecordStatus {
+        self.closed.store(true, Ordering::Relaxed);
+        if self.thread.join().is_err() {
+            let mut status = self.recorder.status();
+            status.error.get_or_insert_with(|| "the writer thread panicked".to_string());
+            return status;
+        }
+        self.recorder.status()
+    }
+}
+
+// The writer thread: batches to the file until closed or unable to go on
+fn write(file: File, receiver: Receiver<Batch>, status: &Mutex<RecordStatus>, closed: &AtomicBool) {
+    let mut file = Some(file);
+    let mut writer: Option<hound::WavWriter<BufWriter<File>>> = None;
+    let mut unflushed = 0;
+    let result = loop {
+        let batch = if closed.load(Ordering::Relaxed) {
+            // What was handed over before the close still goes in
+            match receiver.try_recv() {
+                Ok(batch) => batch,
+                Err(_) => break Ok(()),
+            }
+        } else {
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(batch) => batch,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break Ok(()),
+            }
+        };
+        let wav = match writer.as_mut() {
+            Some(wav) => {
+                let spec = wav.spec();
+                if (spec.channels, spec.sample_rate) != (batch.channels, batch.sample_rate) {
+                    break Err(format!(
+                        "stopped at a track in {} Hz, {} ch (the recording is {} Hz, {} ch)",
+                        batch.sample_rate, batch.channels, spec.sample_rate, spec.channels
+                    ));
+                }
+                wav
+            }
+            None => {
+                let spec = hound::WavSpec {
+                    channels: batch.channels,
+                    sample_rate: batch.sample_rate,
+                    bits_per_sample: 32,
+                    sample_format: hound::SampleFormat::Float,
+                };
+                let Some(file) = file.take() else {
+                    break Ok(());
+                };
+                match hound::WavWriter::new(BufWriter::new(file), spec) {
+                    Ok(wav) => {
+                        if let Ok(mut status) = status.lock() {
+                            status.sample_rate = spec.sample_rate;
+                            status.channels = spec.channels;
+                        }
+                        writer.insert(wav)
+                    }
+                    Err(e) => break Err(e.to_string()),
+                }
+            }
+        };
+        if let Err(e) = batch.samples.iter().try_for_each(|&sample| wav.write_sample(sample)) {
+            break Err(e.to_string());
+        }
+        // Keep the header current about once a second
+        unflushed += batch.samples.len();
+        if unflushed >= batch.sample_rate as usize * batch.channels as usize {
+            unflushed = 0;
+            if let Err(e) = wav.flush() {
+                break Err(e.to_string());
+            }
+        }
+        if let Ok(mut status) = status.lock() {
+            status.frames = wav.len() as u64 / batch.channels.max(1) as u64;
+        }
+    };
+    // Stop taking batches before the (possibly slow) finalize
+    drop(receiver);
+    let finalized = writer.map_or(Ok(()), |wav| wav.finalize().map_err(|e| e.to_string()));
+    if let Ok(mut status) = status.lock() {
+        status.error = result.and(finalized).err();
+    }
+}
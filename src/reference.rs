@@ -0,0 +1,99 @@
+//! Frozen reference curves to hold the live spectrum against.
+//!
+//! A snapshot of the normalized bands goes into one of [`SLOTS`] slots, to be
+//! drawn over the bars while the music moves on: the verse against the
+//! chorus, one speaker position against another. The band count follows the
+//! terminal width, so a snapshot is resampled to whatever count the bars have
+//! when it is drawn; bands are log-spaced over the same range at any count,
+//! so a curve stays over the frequencies it was taken at.
+//!
+//! ```
+//! use gruvberry::reference::References;
+//!
+//! let mut references = References::new();
+//! references.freeze(&[0.0, 50.0, 100.0]);
+//! references.select(1);
+//! references.freeze(&[20.0, 20.0, 20.0]);
+//! assert_eq!(references.curves(3).count(), 2);
+//! assert_eq!(references.curve(0, 3), Some(vec![0.0, 50.0, 100.0]));
+//! ```
+
+/// How many curves can be kept at once.
+pub const SLOTS: usize = 3;
+
+/// `bands` resampled to `num_bands`, each new band taking the level at its
+/// center, interpolated between the two old bands around it.
+pub fn resample(bands: &[f32], num_bands: usize) -> Vec<f32> {
+    if bands.is_empty() {
+        return vec![0.0; num_bands];
+    }
+    if bands.len() == num_bands {
+        return bands.to_vec();
+    }
+    let last = bands.len() - 1;
+    (0..num_bands)
+        .map(|band| {
+            // Center of the new band in old band indices
+            let x = ((band as f32 + 0.5) * bands.len() as f32 / num_bands as f32 - 0.5).clamp(0.0, last as f32);
+            let below = x.floor() as usize;
+            let above = (below + 1).min(last);
+            let t = x - below as f32;
+            bands[below] + (bands[above] - bands[below]) * t
+        })
+        .collect()
+}
+
+/// The reference slots and which one the next snapshot goes into.
+#[derive(Clone, Debug, Default)]
+pub struct References {
+    slots: [Option<Vec<f32>>; SLOTS],
+    selected: usize,
+}
+
+impl References {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The slot [`References::freeze`] writes to, from 0.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Make `slot` (from 0, out of range ignored) the one frozen into next.
+    pub fn select(&mut self, slot: usize) {
+        if slot < SLOTS {
+            self.selected = slot;
+        }
+    }
+
+    /// Keep `bands` in the selected slot, replacing what was there.
+    pub fn freeze(&mut self, bands: &[f32]) {
+        self.slots[self.selected] = Some(bands.to_vec());
+    }
+
+    /// Empty every slot.
+    pub fn clear(&mut self) {
+        self.slots = Default::default();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    /// Slots holding a curve.
+    pub fn frozen(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..SLOTS).filter(|&slot| self.slots[slot].is_some())
+    }
+
+    /// The curve in `slot` at `num_bands` bands, if one was frozen there.
+    pub fn curve(&self, slot: usize, num_bands: usize) -> Option<Vec<f32>> {
+        let bands = self.slots.get(slot)?.as_ref()?;
+        Some(resample(bands, num_bands))
+    }
+
+    /// Every frozen curve at `num_bands` bands, with its slot.
+    pub fn curves(&self, num_bands: usize) -> impl Iterator<Item = (usize, Vec<f32>)> + '_ {
+        self.frozen().filter_map(move |slot| Some((slot, self.curve(slot, num_bands)?)))
+    }
+}
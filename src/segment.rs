@@ -0,0 +1,212 @@
+//! Playing part of a file.
+//!
+//! A [`Segment`] runs from a start offset to an optional end offset, both
+//! given as times such as `90`, `12:30` or `1:02:03.5` ([`parse_time`]).
+//! [`SegmentSource`] plays only that part of a rodio [`Source`]: it seeks
+//! to the start before the first sample goes out (or, for a source that
+//! can't seek, decodes and drops everything before it) and stops at the
+//! end. Times are the segment's own from there on: it starts at zero, its
+//! total duration is the segment's length, and a seek to a position lands
+//! that far into the segment.
+//!
+//! ```
+//! use std::time::Duration;
+//! use gruvberry::segment::{parse_time, Segment};
+//!
+//! assert_eq!(parse_time("12:30"), Ok(Duration::from_secs(750)));
+//! assert_eq!(parse_time("1:02:03.5"), Ok(Duration::from_secs_f64(3723.5)));
+//! assert!(parse_time("12:75").is_err());
+//!
+//! let segment = Segment::new(parse_time("12:30").ok(), parse_time("13:10").ok()).unwrap();
+//! // 40 seconds of a 20 minute file, but only 10 of a 12:40 one
+//! assert_eq!(segment.length(1200.0), 40.0);
+//! assert_eq!(segment.length(760.0), 10.0);
+//! assert!(segment.check(600.0).is_err());
+//! ```
+
+use std::ops::Range;
+use std::time::Duration;
+use rodio::Source;
+use rodio::source::SeekError;
+
+use crate::format;
+
+/// A time as whole or fractional seconds (`90`, `90.5`), minutes and
+/// seconds (`12:30`) or hours, minutes and seconds (`1:02:03`). Minutes
+/// and seconds after the first field must be below 60.
+pub fn parse_time(value: &str) -> Result<Duration, String> {
+    let fields: Vec<&str> = value.split(':').collect();
+    if fields.len() > 3 {
+        return Err(format!("'{}' is not a time (seconds, mm:ss or hh:mm:ss)", value));
+    }
+    let mut secs = 0.0f64;
+    for (i, field) in fields.iter().enumerate() {
+        let last = i + 1 == fields.len();
+        // Only the seconds may have a fraction, and no field a sign
+        let digits = |c: char| c.is_ascii_digit() || (last && c == '.');
+        if field.is_empty() || !field.chars().all(digits) {
+            return Err(format!("'{}' is not a time (seconds, mm:ss or hh:mm:ss)", value));
+        }
+        let number: f64 = field.parse().map_err(|_| format!("'{}' is not a time", value))?;
+        if i > 0 && number >= 60.0 {
+            let unit = if last { "seconds" } else { "minutes" };
+            return Err(format!("'{}' has {} {}; at most 59 after the first field", value, field, unit));
+        }
+        secs = secs * 60.0 + number;
+    }
+    Duration::try_from_secs_f64(secs).map_err(|_| format!("'{}' is too long", value))
+}
+
+/// Part of a file: from `start` up to `end`, or to the end of the file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Segment {
+    pub start: Duration,
+    pub end: Option<Duration>,
+}
+
+impl Segment {
+    /// From `start` (the file's beginning if None) to `end`, which must come
+    /// after it.
+    pub fn new(start: Option<Duration>, end: Option<Duration>) -> Result<Segment, String> {
+        let start = start.unwrap_or_default();
+        match end {
+            Some(end) if end <= start => Err(format!(
+                "the end ({}) must come after the start ({})",
+                format::time(end.as_secs_f32()),
+                format::time(start.as_secs_f32())
+            )),
+            _ => Ok(Segment { start, end }),
+        }
+    }
+
+    /// Whether this is the whole file.
+    pub fn is_whole(&self) -> bool {
+        self.start.is_zero() && self.end.is_none()
+    }
+
+    /// Seconds of a file `duration` seconds long that the segment plays.
+    pub fn length(&self, duration: f32) -> f32 {
+        let end = self.end.map_or(duration, |end| end.as_secs_f32().min(duration));
+        (end - self.start.as_secs_f32()).max(0.0)
+    }
+
+    /// Whether a file `duration` seconds long reaches the start.
+    pub fn check(&self, duration: f32) -> Result<(), String> {
+        if self.start.as_secs_f32() >= duration && !self.start.is_zero() {
+            return Err(format!(
+                "the start ({}) is past the end of the file ({})",
+                format::time(self.start.as_secs_f32()),
+                format::time(duration)
+            ));
+        }
+        Ok(())
+    }
+
+    /// The segment's interleaved samples, as indices into all of a file's.
+    pub fn samples(&self, sample_rate: u32, channels: u16) -> Range<usize> {
+        let index = |time: Duration| frames(time, sample_rate).saturating_mul(channels as u64) as usize;
+        index(self.start)..self.end.map_or(usize::MAX, index)
+    }
+}
+
+// Whole frames in `time` at `sample_rate`
+fn frames(time: Duration, sample_rate: u32) -> u64 {
+    (time.as_secs_f64() * sample_rate as f64) as u64
+}
+
+/// Source adapter playing only a [`Segment`] of `source`.
+pub struct SegmentSource<S> {
+    source: S,
+    segment: Segment,
+    // Samples left before the end (None plays to the source's end)
+    remaining: Option<u64>,
+}
+
+impl<S: Source> SegmentSource<S> {
+    /// `source` from the segment's start on, seeking there if it can and
+    /// skipping samples if it can't.
+    pub fn new(mut source: S, segment: Segment) -> Self {
+        if !segment.start.is_zero() && source.try_seek(segment.start).is_err() {
+            let skip = frames(segment.start, source.sample_rate()) * source.channels() as u64;
+            for _ in 0..skip {
+                if source.next().is_none() {
+                    break;
+                }
+            }
+        }
+        let mut segment_source = SegmentSource { source, segment, remaining: None };
+        segment_source.remaining = segment_source.remaining_after(Duration::ZERO);
+        segment_source
+    }
+
+    // Samples from `position` into the segment to its end
+    fn remaining_after(&self, position: Duration) -> Option<u64> {
+        let end = self.segment.end?;
+        let left = (end - self.segment.start).saturating_sub(position);
+        Some(frames(left, self.source.sample_rate()) * self.source.channels() as u64)
+    }
+}
+
+impl<S: Source> Iterator for SegmentSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(remaining) = &mut self.remaining {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+        }
+        self.source.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self.source.size_hint();
+        match self.remaining {
+            Some(remaining) => {
+                let remaining = remaining.min(usize::MAX as u64) as usize;
+                (low.min(remaining), Some(high.map_or(remaining, |high| high.min(remaining))))
+            }
+            None => (low, high),
+        }
+    }
+}
+
+impl<S: Source> Source for SegmentSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        // A span cut short by the end is only as long as what is left
+        match (self.source.current_span_len(), self.remaining) {
+            (Some(len), Some(remaining)) => Some(len.min(remaining.min(usize::MAX as u64) as usize)),
+            (len, _) => len,
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        let duration = self.source.total_duration().map(|duration| duration.saturating_sub(self.segment.start));
+        match self.segment.end {
+            Some(end) => {
+                let length = end - self.segment.start;
+                Some(duration.map_or(length, |duration| duration.min(length)))
+            }
+            None => duration,
+        }
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let pos = match self.segment.end {
+            Some(end) => pos.min(end - self.segment.start),
+            None => pos,
+        };
+        self.source.try_seek(self.segment.start + pos)?;
+        self.remaining = self.remaining_after(pos);
+        Ok(())
+    }
+}
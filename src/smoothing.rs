@@ -0,0 +1,159 @@
+//! How the bars move from one analysis to the next.
+//!
+//! A [`Smoother`] takes each frame's band levels and moves the levels shown
+//! toward them, `dt` seconds after the last frame. The analyzer owns one
+//! and can have it swapped at any time; which one is the `smoother` setting
+//! ([`SmootherKind`]):
+//!
+//! - [`ExponentialSmoother`] moves every band the same fraction of the way
+//!   (the `smoothing` setting) per 1/[`REFERENCE_FPS`] of a second, rising
+//!   and falling alike;
+//! - [`AttackReleaseSmoother`] rises with one time constant and falls with
+//!   another, so bars can jump up on a hit and sink slowly after it;
+//! - [`PassthroughSmoother`] shows each frame as it is.
+//!
+//! They all go by the time that has passed rather than by frames, so they
+//! look the same at any frame rate.
+//!
+//! ```
+//! use gruvberry::smoothing::{AttackReleaseSmoother, Smoother};
+//!
+//! // Instant attack, 100 ms release
+//! let mut smoother = AttackReleaseSmoother::new(0.0, 0.1);
+//! let mut shown = [0.0];
+//! smoother.process(&[1.0], &mut shown, 0.01);
+//! assert_eq!(shown, [1.0]);
+//! smoother.process(&[0.0], &mut shown, 0.1);
+//! assert!((shown[0] - (-1.0f32).exp()).abs() < 1e-6);
+//! ```
+
+/// Frame rate an [`ExponentialSmoother`]'s factor is given at: at 60 frames
+/// a second it moves exactly that fraction each frame.
+pub const REFERENCE_FPS: f32 = 60.0;
+/// Default [`AttackReleaseSmoother`] time constants (seconds).
+pub const DEFAULT_ATTACK: f32 = 0.01;
+pub const DEFAULT_RELEASE: f32 = 0.25;
+
+/// Moves the levels shown toward each frame's.
+pub trait Smoother: Send {
+    /// Move `output` (the levels shown, as left by the last call) toward
+    /// `input`, `dt` seconds later. Both have the same length.
+    fn process(&mut self, input: &[f32], output: &mut [f32], dt: f32);
+}
+
+/// The same fraction of the way for every band, either direction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExponentialSmoother {
+    factor: f32,
+}
+
+impl ExponentialSmoother {
+    /// Moving `factor` (above 0 up to 1) of the way per 1/[`REFERENCE_FPS`]
+    /// of a second; 1 follows the input as it is.
+    pub fn new(factor: f32) -> Self {
+        ExponentialSmoother { factor: factor.clamp(0.0, 1.0) }
+    }
+
+    /// Time constant in seconds: how long a step takes to get 63% of the
+    /// way there.
+    pub fn time_constant(&self) -> f32 {
+        -1.0 / (REFERENCE_FPS * (1.0 - self.factor).ln())
+    }
+}
+
+impl Smoother for ExponentialSmoother {
+    fn process(&mut self, input: &[f32], output: &mut [f32], dt: f32) {
+        let kept = (1.0 - self.factor).powf(dt.max(0.0) * REFERENCE_FPS);
+        for (shown, &level) in output.iter_mut().zip(input) {
+            *shown = level + (*shown - level) * kept;
+        }
+    }
+}
+
+/// Rising with one time constant and falling with another.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AttackReleaseSmoother {
+    attack: f32,
+    release: f32,
+}
+
+impl AttackReleaseSmoother {
+    /// Time constants in seconds (0 for instant): a bar gets 63% of the way
+    /// up in `attack` and 63% of the way down in `release`.
+    pub fn new(attack: f32, release: f32) -> Self {
+        AttackReleaseSmoother {
+            attack: attack.max(0.0),
+            release: release.max(0.0),
+        }
+    }
+}
+
+impl Smoother for AttackReleaseSmoother {
+    fn process(&mut self, input: &[f32], output: &mut [f32], dt: f32) {
+        let kept = |tau: f32| if tau > 0.0 { (-dt.max(0.0) / tau).exp() } else { 0.0 };
+        let (rising, falling) = (kept(self.attack), kept(self.release));
+        for (shown, &level) in output.iter_mut().zip(input) {
+            let kept = if level > *shown { rising } else { falling };
+            *shown = level + (*shown - level) * kept;
+        }
+    }
+}
+
+/// Every frame as it is.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PassthroughSmoother;
+
+impl Smoother for PassthroughSmoother {
+    fn process(&mut self, input: &[f32], output: &mut [f32], _dt: f32) {
+        output.copy_from_slice(input);
+    }
+}
+
+/// Which [`Smoother`] the bars get.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SmootherKind {
+    #[default]
+    Exponential,
+    AttackRelease,
+    Passthrough,
+}
+
+impl SmootherKind {
+    pub const NAMES: [&str; 3] = ["exponential", "attack-release", "none"];
+
+    pub fn parse(value: &str) -> Option<SmootherKind> {
+        match value {
+            "exponential" => Some(SmootherKind::Exponential),
+            "attack-release" => Some(SmootherKind::AttackRelease),
+            "none" => Some(SmootherKind::Passthrough),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SmootherKind::Exponential => "exponential",
+            SmootherKind::AttackRelease => "attack-release",
+            SmootherKind::Passthrough => "none",
+        }
+    }
+
+    /// The kind after this one, round to the first after the last.
+    pub fn next(self) -> SmootherKind {
+        match self {
+            SmootherKind::Exponential => SmootherKind::AttackRelease,
+            SmootherKind::AttackRelease => SmootherKind::Passthrough,
+            SmootherKind::Passthrough => SmootherKind::Exponential,
+        }
+    }
+
+    /// A smoother of this kind, with the exponential `factor` or the
+    /// `attack` and `release` time constants (seconds), whichever it uses.
+    pub fn build(self, factor: f32, attack: f32, release: f32) -> Box<dyn Smoother> {
+        match self {
+            SmootherKind::Exponential => Box::new(ExponentialSmoother::new(factor)),
+            SmootherKind::AttackRelease => Box::new(AttackReleaseSmoother::new(attack, release)),
+            SmootherKind::Passthrough => Box::new(PassthroughSmoother),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! Sparklines: a series of values drawn as one row of block characters.
+//!
+//! ```
+//! use gruvberry::sparkline;
+//!
+//! assert_eq!(sparkline::render(&[0.0, 0.5, 1.0], 3), "▁▅█");
+//! // Newest values stay at the right end; merged values keep their highest
+//! assert_eq!(sparkline::render(&[1.0], 3), "  █");
+//! assert_eq!(sparkline::render(&[0.0, 1.0, 0.0, 0.0], 2), "█▁");
+//! ```
+
+/// Block characters from lowest to highest.
+pub const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The block for `value` from 0.0 (lowest) to 1.0 (full); values outside the
+/// range are clamped and NaN counts as 0.0.
+pub fn block(value: f32) -> char {
+    let value = if value.is_nan() { 0.0 } else { value.clamp(0.0, 1.0) };
+    LEVELS[(value * (LEVELS.len() - 1) as f32).round() as usize]
+}
+
+/// Draw `values` (each 0.0 to 1.0, oldest first) in exactly `width` cells.
+///
+/// With more values than cells, each cell shows the highest of the values it
+/// covers, so short peaks aren't lost. With fewer, one cell per value aligned
+/// to the right with spaces before them, so the newest value is always last.
+pub fn render(values: &[f32], width: usize) -> String {
+    if values.len() <= width {
+        let padding = " ".repeat(width - values.len());
+        return padding + &values.iter().map(|&v| block(v)).collect::<String>();
+    }
+    (0..width)
+        .map(|cell| {
+            let start = cell * values.len() / width;
+            let end = (cell + 1) * values.len() / width;
+            let highest = values[start..end].iter().fold(0.0f32, |max, &v| max.max(v));
+            block(highest)
+        })
+        .collect()
+}
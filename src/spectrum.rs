@@ -0,0 +1,268 @@
+//! The spectrum's bars as a ratatui widget, for other TUI apps.
+//!
+//! [`SpectrumWidget`] draws normalized band levels (0-100, as in an
+//! [`AnalysisFrame`] or from [`normalize`](crate::analysis::normalize)) as blocks filling
+//! whatever [`Rect`] it is rendered into, and nothing else: no border, no
+//! layout of its own and no input handling, so it sits in an app's layout
+//! like any other widget. Bands that don't fit side by side are taken
+//! together (by [`Aggregate`]); a 13 x 5 area shows 13 bars, 5 cells tall.
+//!
+//! As a [`StatefulWidget`] it also draws heat trails, the cells the bars
+//! just left cooling in a [`HeatMap`] kept from frame to frame.
+//!
+//! ```
+//! use gruvberry::analysis::{self, Analyzer};
+//! use gruvberry::spectrum::SpectrumWidget;
+//! use gruvberry::theme::Theme;
+//! use gruvberry::tilt::Tilt;
+//! use ratatui::{Terminal, backend::TestBackend, layout::{Constraint, Layout}, widgets::Paragraph};
+//!
+//! // A second of a 1 kHz tone, analyzed the way gruvberry does
+//! let samples: Vec<f32> = (0..44100).map(|i| (i as f32 * 1000.0 / 44100.0 * std::f32::consts::TAU).sin()).collect();
+//! let mut analyzer = Analyzer::new(44100, analysis::DEFAULT_FFT_SIZE, analysis::DEFAULT_SMOOTHING, Tilt::Flat);
+//! let bands = analyzer.process(&samples[..analysis::DEFAULT_FFT_SIZE], 40);
+//! let levels = analysis::normalize(bands, analysis::max_amplitude(bands));
+//!
+//! // The app's own layout, with the spectrum in one part of it (a real app
+//! // has a CrosstermBackend here)
+//! let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+//! let theme = Theme::default();
+//! terminal
+//!     .draw(|frame| {
+//!         let [title, spectrum] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(frame.area());
+//!         frame.render_widget(Paragraph::new("Now playing: a tone"), title);
+//!         frame.render_widget(SpectrumWidget::new(&levels, &theme), spectrum);
+//!     })
+//!     .unwrap();
+//! // The loudest band reaches the top
+//! let buffer = terminal.backend().buffer();
+//! assert!((0..40).any(|x| buffer[(x, 1)].symbol() == "█"));
+//! ```
+
+use std::borrow::Cow;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{StatefulWidget, Widget},
+};
+
+use crate::analysis::FreqRange;
+use crate::downsample::{self, Aggregate};
+use crate::gradient::Anchor;
+use crate::heat::HeatMap;
+use crate::history::AnalysisFrame;
+use crate::orientation::Orientation;
+use crate::theme::Theme;
+
+/// How many cells a bar of `level` (0-100) spans when a full-scale one
+/// spans `bar_length`; never less than one, so the baseline always shows.
+pub fn length(level: f32, bar_length: usize) -> usize {
+    (((level / 100.0) * bar_length as f32) as usize).max(1)
+}
+
+/// The color of the theme's gradient for each of `num_bands` bands spread
+/// over `range`, as `theme` draws it.
+pub fn band_colors(num_bands: usize, range: FreqRange, anchor: Anchor, theme: &Theme) -> Vec<Color> {
+    (0..num_bands)
+        .map(|band| {
+            let (r, g, b) = theme.palette.band_rgb(band, num_bands, range, anchor);
+            theme.gradient(Color::Rgb(r, g, b))
+        })
+        .collect()
+}
+
+/// Band levels as bars, in the gradient's colors unless given others.
+///
+/// Written cell by cell straight into the buffer rather than built up as a
+/// Paragraph of one Span per cell: laying out every cell's text was most of
+/// the time a frame took on a large terminal. Unchanged cells come out
+/// identical from frame to frame, so ratatui's diff only sends the ones at
+/// the moving bar tops.
+pub struct SpectrumWidget<'a> {
+    bands: &'a [f32],
+    // None colors the bands by their place among them
+    colors: Option<Cow<'a, [Color]>>,
+    orientation: Orientation,
+    aggregate: Aggregate,
+    theme: &'a Theme,
+    cursor: Option<usize>,
+    references: Vec<(&'a str, Vec<f32>)>,
+    adapt: fn(Color) -> Color,
+    dt: f32,
+}
+
+impl<'a> SpectrumWidget<'a> {
+    /// `bands` (0-100 each), growing up from the bottom, colored along the
+    /// theme's gradient from the first band to the last (red to violet
+    /// unless the theme has another).
+    pub fn new(bands: &'a [f32], theme: &'a Theme) -> Self {
+        SpectrumWidget {
+            bands,
+            colors: None,
+            orientation: Orientation::default(),
+            aggregate: Aggregate::default(),
+            theme,
+            cursor: None,
+            references: Vec::new(),
+            adapt: |color| color,
+            dt: 0.0,
+        }
+    }
+
+    /// The bands of `frame`, colored by their frequencies.
+    pub fn from_frame(frame: &'a AnalysisFrame, theme: &'a Theme) -> Self {
+        let colors = band_colors(frame.bands.len(), frame.range, Anchor::Frequency, theme);
+        SpectrumWidget {
+            colors: Some(Cow::Owned(colors)),
+            ..SpectrumWidget::new(&frame.bands, theme)
+        }
+    }
+
+    /// One color per band, as [`band_colors`] gives them; bands past the
+    /// last color are left out.
+    pub fn colors(mut self, colors: &'a [Color]) -> Self {
+        self.colors = Some(Cow::Borrowed(colors));
+        self
+    }
+
+    /// Which way the bars grow.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// How bands that don't fit side by side are taken together; a bar
+    /// stands for several in the loudest one's color.
+    pub fn aggregate(mut self, aggregate: Aggregate) -> Self {
+        self.aggregate = aggregate;
+        self
+    }
+
+    /// Highlight one band's column (row when horizontal).
+    pub fn cursor(mut self, band: Option<usize>) -> Self {
+        self.cursor = band;
+        self
+    }
+
+    /// A frozen reference `curve` (0-100 per band) drawn over the bars as
+    /// `mark`s where bars of those levels would end, on the bar's own color
+    /// where the live bar reaches past it. Bands on the floor get no mark,
+    /// which would only hide the baseline.
+    pub fn reference(mut self, mark: &'a str, curve: &[f32]) -> Self {
+        self.references.push((mark, curve.to_vec()));
+        self
+    }
+
+    /// Pass every bar color through `adapt` on its way to the buffer, e.g.
+    /// to fit what the terminal can show.
+    pub fn adapt(mut self, adapt: fn(Color) -> Color) -> Self {
+        self.adapt = adapt;
+        self
+    }
+
+    /// Seconds since the heat trails last saw the bars, which they cool by
+    /// before taking these in; only drawn as a [`StatefulWidget`].
+    pub fn elapsed(mut self, dt: f32) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    fn draw(self, area: Rect, buf: &mut Buffer, mut trails: Option<&mut HeatMap>) {
+        // Only what is inside the buffer, however large the area
+        let area = area.intersection(buf.area);
+        let (width, height) = (area.width as usize, area.height as usize);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let colors = self.colors.unwrap_or_else(|| {
+            let colors = (0..self.bands.len())
+                .map(|band| {
+                    let ratio = band as f32 / self.bands.len().saturating_sub(1).max(1) as f32;
+                    let (r, g, b) = self.theme.palette.rgb(ratio);
+                    self.theme.gradient(Color::Rgb(r, g, b))
+                })
+                .collect();
+            Cow::Owned(colors)
+        });
+        let bands = &self.bands[..self.bands.len().min(colors.len())];
+        if bands.is_empty() {
+            return;
+        }
+        let bar_length = self.orientation.bar_length(width, height);
+        let cells = self.orientation.band_cells(width, height);
+        let lengths: Vec<usize> = downsample::downsample(bands, cells, self.aggregate)
+            .into_iter()
+            .map(|level| length(level, bar_length))
+            .collect();
+        let colors: Vec<Color> = downsample::representatives(bands, cells).into_iter().map(|band| colors[band]).collect();
+        if let Some(heat) = trails.as_deref_mut() {
+            heat.update(&lengths, bar_length, self.dt);
+        }
+        let cursor = self.cursor.map(|band| downsample::cell_of(band, cells, bands.len()));
+        let marks: Vec<(&str, Vec<usize>)> = self
+            .references
+            .iter()
+            .map(|(mark, curve)| {
+                let curve = downsample::downsample(curve, cells, self.aggregate);
+                (*mark, curve.into_iter().map(|level| length(level, bar_length)).collect())
+            })
+            .collect();
+
+        for row in 0..height {
+            for col in 0..width {
+                let Some((band, along)) = self.orientation.cell(col, row, width, height, lengths.len()) else {
+                    continue;
+                };
+                let filled = along < lengths[band];
+                let (mut symbol, mut style) = if cursor == Some(band) {
+                    (if filled { "█" } else { " " }, self.theme.cursor)
+                } else if filled {
+                    ("█", Style::default().fg((self.adapt)(colors[band])))
+                } else {
+                    let level = trails.as_deref().map_or(0.0, |heat| heat.level(band, along));
+                    if level > 0.0 {
+                        let shade = if level > 2.0 / 3.0 { "▓" } else if level > 1.0 / 3.0 { "▒" } else { "░" };
+                        (shade, Style::default().fg((self.adapt)(self.theme.heat(colors[band], level))))
+                    } else {
+                        (" ", Style::default())
+                    }
+                };
+                for (mark, lengths) in &marks {
+                    let length = lengths.get(band).copied().unwrap_or(1);
+                    if length == 1 || along + 1 != length {
+                        continue;
+                    }
+                    let background = if symbol == "█" { style.fg } else { style.bg };
+                    style = self.theme.label;
+                    if background == Some(Color::White) {
+                        style = style.fg(Color::Black);
+                    }
+                    if let Some(background) = background {
+                        style = style.bg(background);
+                    }
+                    symbol = mark;
+                }
+                buf[(area.x + col as u16, area.y + row as u16)].set_symbol(symbol).set_style(style);
+            }
+        }
+    }
+}
+
+impl Widget for SpectrumWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.draw(area, buf, None);
+    }
+}
+
+/// With heat trails ('w' in gruvberry): the state cools by
+/// [`elapsed`](SpectrumWidget::elapsed), takes in these bars, and the cells
+/// above each bar that are still warm are shaded darker the longer ago the
+/// bar reached them.
+impl StatefulWidget for SpectrumWidget<'_> {
+    type State = HeatMap;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut HeatMap) {
+        self.draw(area, buf, Some(state));
+    }
+}
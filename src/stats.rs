@@ -0,0 +1,218 @@
+//! What a listening session amounted to, track by track.
+//!
+//! A [`Session`] starts a [`TrackAccumulator`] when a track starts and
+//! finishes it into [`TrackStats`] when the track ends. In between the
+//! accumulator is fed what the analysis sees: the level of each analyzed
+//! window, the time spent listening, clipping, and the tempo and key as far
+//! as they are known. The session can be written out as JSON.
+//!
+//! A session may run for days, so it keeps the last [`MAX_TRACKS`] tracks;
+//! the ones before only count toward the totals.
+//!
+//! ```
+//! use gruvberry::stats::Session;
+//!
+//! let mut session = Session::default();
+//! session.start("Song", 180.0);
+//! if let Some(track) = session.current_mut() {
+//!     track.frame(&[0.5, -0.5, 0.5, -0.5]);
+//!     track.listen(90.0);
+//! }
+//! session.end();
+//! let track = &session.tracks()[0];
+//! assert_eq!(track.average_db.map(f32::round), Some(-6.0));
+//! assert_eq!(session.total_listened(), 90.0);
+//! ```
+
+use crate::presence::json_string;
+
+/// Clipping closer than this (seconds) to the last counts as the same event.
+pub const CLIP_GAP_SECS: f64 = 0.5;
+/// Most ended tracks a [`Session`] keeps the statistics of.
+pub const MAX_TRACKS: usize = 1000;
+
+/// One track's statistics, once it has ended.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackStats {
+    pub name: String,
+    /// Length of the file in seconds; infinite when it has none.
+    pub duration: f32,
+    /// Seconds spent listening to it, pauses left out.
+    pub listened: f32,
+    /// Mean level over what was analyzed (RMS, dBFS); None if nothing was.
+    pub average_db: Option<f32>,
+    /// Loudest sample (dBFS).
+    pub peak_db: Option<f32>,
+    /// Tempo and key as last detected.
+    pub bpm: Option<f32>,
+    pub key: Option<String>,
+    /// Stretches of clipping, [`CLIP_GAP_SECS`] or more apart.
+    pub clips: u32,
+}
+
+impl TrackStats {
+    fn to_json(&self) -> String {
+        let number = |value: Option<f32>| match value.filter(|value| value.is_finite()) {
+            Some(value) => format!("{:.2}", value),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"name\":{},\"duration\":{},\"listened\":{},\"average_db\":{},\"peak_db\":{},\"bpm\":{},\"key\":{},\"clips\":{}}}",
+            json_string(&self.name),
+            number(Some(self.duration)),
+            number(Some(self.listened)),
+            number(self.average_db),
+            number(self.peak_db),
+            number(self.bpm),
+            self.key.as_deref().map_or("null".to_string(), json_string),
+            self.clips
+        )
+    }
+}
+
+/// A track's statistics while it plays.
+#[derive(Clone, Debug)]
+pub struct TrackAccumulator {
+    name: String,
+    duration: f32,
+    // In f64, as frame-sized steps would be lost adding to an f32 of hours
+    listened: f64,
+    // Sum of squared samples and how many, over the analyzed windows
+    energy: f64,
+    samples: u64,
+    peak: f32,
+    clips: u32,
+    last_clip: Option<f64>,
+    bpm: Option<f32>,
+    key: Option<String>,
+}
+
+impl TrackAccumulator {
+    pub fn new(name: &str, duration: f32) -> Self {
+        TrackAccumulator {
+            name: name.to_string(),
+            duration,
+            listened: 0.0,
+            energy: 0.0,
+            samples: 0,
+            peak: 0.0,
+            clips: 0,
+            last_clip: None,
+            bpm: None,
+            key: None,
+        }
+    }
+
+    /// An analyzed window; samples that aren't numbers are left out.
+    pub fn frame(&mut self, samples: &[f32]) {
+        for &sample in samples.iter().filter(|sample| sample.is_finite()) {
+            self.energy += (sample as f64) * (sample as f64);
+            self.samples += 1;
+            self.peak = self.peak.max(sample.abs());
+        }
+    }
+
+    /// `secs` more of listening.
+    pub fn listen(&mut self, secs: f32) {
+        self.listened += secs.max(0.0) as f64;
+    }
+
+    /// The audio clipped at `time` (seconds on any steady clock).
+    pub fn clipped(&mut self, time: f64) {
+        if self.last_clip.is_none_or(|last| time - last >= CLIP_GAP_SECS) {
+            self.clips += 1;
+        }
+        self.last_clip = Some(time);
+    }
+
+    /// The tempo as detected now; an estimate that is lost again (None)
+    /// keeps the last one.
+    pub fn detect_bpm(&mut self, bpm: Option<f32>) {
+        self.bpm = bpm.or(self.bpm);
+    }
+
+    /// The key, the same way.
+    pub fn detect_key(&mut self, key: Option<String>) {
+        self.key = key.or(self.key.take());
+    }
+
+    pub fn finish(self) -> TrackStats {
+        let db = |level: f32| 20.0 * level.max(1e-10).log10();
+        let analyzed = self.samples > 0;
+        TrackStats {
+            average_db: analyzed.then(|| db((self.energy / self.samples as f64).sqrt() as f32)),
+            peak_db: analyzed.then(|| db(self.peak)),
+            name: self.name,
+            duration: self.duration,
+            listened: self.listened as f32,
+            bpm: self.bpm,
+            key: self.key,
+            clips: self.clips,
+        }
+    }
+}
+
+/// The tracks of a session, in the order they were heard.
+#[derive(Clone, Debug, Default)]
+pub struct Session {
+    tracks: Vec<TrackStats>,
+    current: Option<TrackAccumulator>,
+    // Tracks let go of past MAX_TRACKS, and their listening time
+    earlier: usize,
+    earlier_listened: f64,
+}
+
+impl Session {
+    /// A track started; one still going is finished first.
+    pub fn start(&mut self, name: &str, duration: f32) {
+        self.end();
+        self.current = Some(TrackAccumulator::new(name, duration));
+    }
+
+    /// The track playing ended (nothing if none is).
+    pub fn end(&mut self) {
+        if let Some(track) = self.current.take() {
+            self.tracks.push(track.finish());
+        }
+        if self.tracks.len() > MAX_TRACKS {
+            let dropped = self.tracks.remove(0);
+            self.earlier += 1;
+            self.earlier_listened += dropped.listened as f64;
+        }
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut TrackAccumulator> {
+        self.current.as_mut()
+    }
+
+    /// The last [`MAX_TRACKS`] tracks that have ended.
+    pub fn tracks(&self) -> &[TrackStats] {
+        &self.tracks
+    }
+
+    /// How many tracks ended before those in [`Session::tracks`].
+    pub fn earlier(&self) -> usize {
+        self.earlier
+    }
+
+    /// Seconds of listening over all the ended tracks.
+    pub fn total_listened(&self) -> f32 {
+        self.tracks.iter().fold(self.earlier_listened, |total, track| total + track.listened as f64) as f32
+    }
+
+    /// The ended tracks and the total as a JSON object, with the count of
+    /// tracks left out when there were more than [`MAX_TRACKS`].
+    pub fn to_json(&self) -> String {
+        let tracks: Vec<String> = self.tracks.iter().map(TrackStats::to_json).collect();
+        let earlier = match self.earlier {
+            0 => String::new(),
+            n => format!(",\"earlier_tracks\":{}", n),
+        };
+        format!(
+            "{{\"tracks\":[{}]{},\"total_listened\":{:.2}}}\n",
+            tracks.join(","),
+            earlier,
+            self.total_listened()
+        )
+    }
+}
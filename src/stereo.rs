@@ -0,0 +1,101 @@
+//! Stereo phase correlation, and the signals the analysis can be switched to.
+//!
+//! The correlation of the left and right channels tells how mono-compatible
+//! a mix is: +1 when both carry the same signal, around 0 for unrelated
+//! (very wide) channels and -1 when one is the other inverted, which cancels
+//! out when the channels are summed on a mono system.
+//!
+//! ```
+//! use gruvberry::stereo;
+//!
+//! let frames: Vec<[f32; 2]> = (0..100).map(|i| (i as f32 * 0.1).sin()).map(|s| [s, -s]).collect();
+//! assert!((stereo::correlation(&frames).unwrap() + 1.0).abs() < 1e-6);
+//! ```
+//!
+//! [`Channel`] picks what the spectrum shows of a stereo (or wider) file: the
+//! downmix of every channel, left or right alone, or the side (L−R) and mid
+//! ((L+R)/2) signals derived from them. The side signal holds only what
+//! differs between the channels, so stereo widening and reverb stand out in
+//! it while a centred voice disappears.
+//!
+//! ```
+//! use gruvberry::stereo::Channel;
+//!
+//! assert_eq!(Channel::Side.of([0.5, -0.5]), 1.0);
+//! assert_eq!(Channel::Mid.of([0.5, -0.5]), 0.0);
+//! assert_eq!(Channel::Mid.next(), Channel::Downmix);
+//! ```
+
+/// Below this mean square level (about -80 dBFS) the channels count as silent.
+pub const SILENCE: f32 = 1e-8;
+
+/// Correlation of the left and right channels of `frames`, -1.0 to 1.0, or
+/// None when either channel is silent.
+pub fn correlation(frames: &[[f32; 2]]) -> Option<f32> {
+    let (mut lr, mut ll, mut rr) = (0.0f64, 0.0f64, 0.0f64);
+    for &[left, right] in frames {
+        let (left, right) = (left as f64, right as f64);
+        lr += left * right;
+        ll += left * left;
+        rr += right * right;
+    }
+    let n = frames.len().max(1) as f64;
+    if ll / n < SILENCE as f64 || rr / n < SILENCE as f64 {
+        return None;
+    }
+    Some((lr / (ll * rr).sqrt()).clamp(-1.0, 1.0) as f32)
+}
+
+/// A signal of the captured audio the analysis can show.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Channel {
+    /// The mean of every channel, as the ring keeps it.
+    #[default]
+    Downmix,
+    Left,
+    Right,
+    /// Left minus right.
+    Side,
+    /// The mean of left and right.
+    Mid,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 5] = [Channel::Downmix, Channel::Left, Channel::Right, Channel::Side, Channel::Mid];
+
+    /// The one after this, back to the downmix after the last.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&channel| channel == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Channel::Downmix => "downmix",
+            Channel::Left => "left",
+            Channel::Right => "right",
+            Channel::Side => "side",
+            Channel::Mid => "mid",
+        }
+    }
+
+    /// This signal's sample in a left/right frame. Of the first two channels
+    /// only, the downmix is the same as the mid.
+    pub fn of(self, [left, right]: [f32; 2]) -> f32 {
+        match self {
+            Channel::Left => left,
+            Channel::Right => right,
+            Channel::Side => left - right,
+            Channel::Downmix | Channel::Mid => (left + right) / 2.0,
+        }
+    }
+}
+
+/// How a count of channels reads in the spectrum title.
+pub fn layout(channels: u16) -> String {
+    match channels {
+        1 => "mono".to_string(),
+        2 => "stereo".to_string(),
+        n => format!("{} ch", n),
+    }
+}
@@ -0,0 +1,123 @@
+//! A spectrum frame as a standalone SVG image.
+//!
+//! Terminal cells make a coarse picture; for documentation the same frame
+//! can be drawn as vector graphics instead. [`render`] lays the bands out on
+//! a grid of their own, [`BAR_WIDTH`] units per bar and [`PLOT_HEIGHT`] for a
+//! full one, colored with the bars' gradient, with the frequency axis
+//! labeled underneath (over the frame's range, zoomed in or not). It only serializes the [`AnalysisFrame`], so the
+//! output depends on nothing but the frame and the gradient anchor.
+//!
+//! ```
+//! use gruvberry::analysis::FreqRange;
+//! use gruvberry::gradient::Anchor;
+//! use gruvberry::history::AnalysisFrame;
+//!
+//! let frame = AnalysisFrame {
+//!     time: 0.0,
+//!     bands: vec![100.0, 50.0, 0.0],
+//!     levels_db: vec![0.0; 3],
+//!     rms: 0.5,
+//!     sample_rate: 44100,
+//!     range: FreqRange::full(22050.0),
+//! };
+//! let svg = gruvberry::svg::render(&frame, Anchor::Frequency);
+//! assert!(svg.starts_with("<svg "));
+//! assert_eq!(svg.matches("<rect class=\"bar\"").count(), 3);
+//! ```
+
+use std::fmt::Write;
+use crate::gradient::{self, Anchor};
+use crate::history::AnalysisFrame;
+
+/// Width of each bar, and of the gap between two.
+pub const BAR_WIDTH: u32 = 8;
+pub const BAR_GAP: u32 = 2;
+/// Height of a full bar (a level of 100).
+pub const PLOT_HEIGHT: u32 = 200;
+
+// Space around the plot, and under it for the frequency labels
+const MARGIN: u32 = 10;
+const AXIS_HEIGHT: u32 = 20;
+const BACKGROUND: &str = "#101010";
+const LABEL_COLOR: &str = "#a0a0a0";
+// Frequencies labeled on the axis, where they fall within the frame's range
+const LABELS: [(f32, &str); 10] = [
+    (20.0, "20"),
+    (50.0, "50"),
+    (100.0, "100"),
+    (200.0, "200"),
+    (500.0, "500"),
+    (1000.0, "1k"),
+    (2000.0, "2k"),
+    (5000.0, "5k"),
+    (10_000.0, "10k"),
+    (20_000.0, "20k"),
+];
+
+/// The frame's bands as an SVG document: one bar per band, as tall as its
+/// level (0 to 100, clamped; a level that isn't a number draws nothing),
+/// in the color [`gradient::band_rgb_in`] gives it with `anchor`.
+pub fn render(frame: &AnalysisFrame, anchor: Anchor) -> String {
+    let num_bands = frame.bands.len();
+    let plot_width = (num_bands as u32 * (BAR_WIDTH + BAR_GAP)).saturating_sub(BAR_GAP);
+    let width = plot_width + 2 * MARGIN;
+    let height = PLOT_HEIGHT + AXIS_HEIGHT + 2 * MARGIN;
+    let baseline = MARGIN + PLOT_HEIGHT;
+
+    let mut svg = String::new();
+    // Writing to a String can't fail
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = width,
+        h = height
+    );
+    let _ = writeln!(svg, "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>", width, height, BACKGROUND);
+
+    for (band, &level) in frame.bands.iter().enumerate() {
+        let level = if level.is_nan() { 0.0 } else { level.clamp(0.0, 100.0) };
+        let bar_height = level / 100.0 * PLOT_HEIGHT as f32;
+        let (r, g, b) = gradient::band_rgb_in(band, num_bands, frame.range, anchor);
+        let _ = writeln!(
+            svg,
+            "<rect class=\"bar\" x=\"{}\" y=\"{:.1}\" width=\"{}\" height=\"{:.1}\" fill=\"#{:02x}{:02x}{:02x}\"/>",
+            MARGIN + band as u32 * (BAR_WIDTH + BAR_GAP),
+            baseline as f32 - bar_height,
+            BAR_WIDTH,
+            bar_height,
+            r,
+            g,
+            b
+        );
+    }
+
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{m}\" y1=\"{y}\" x2=\"{x2}\" y2=\"{y}\" stroke=\"{c}\" stroke-width=\"1\"/>",
+        m = MARGIN,
+        x2 = MARGIN + plot_width,
+        y = baseline,
+        c = LABEL_COLOR
+    );
+    let _ = writeln!(
+        svg,
+        "<g font-family=\"monospace\" font-size=\"10\" fill=\"{}\" text-anchor=\"middle\">",
+        LABEL_COLOR
+    );
+    // Labels sit where their frequency falls on the bands' log scale
+    for (freq, label) in LABELS {
+        if num_bands == 0 || !(frame.range.low..=frame.range.high).contains(&freq) {
+            continue;
+        }
+        let position = frame.range.position(freq);
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{}\">{}</text>",
+            MARGIN as f32 + position * plot_width as f32,
+            baseline + AXIS_HEIGHT - 5,
+            label
+        );
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
@@ -0,0 +1,324 @@
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use crossterm::{
+    cursor, execute,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
+};
+use ratatui::style::Color;
+
+// How the terminal is set up: raw mode hands over single key presses
+// without echoing them, and the alternate screen leaves the shell's screen
+// as it was on exit. Without the alternate screen (--no-alt-screen) the
+// frames are drawn over the current screen, whose contents are scrolled
+// into the scrollback first, and the last frame stays there afterwards with
+// the prompt below it. Without `screen` (--oneline) none of the screen is
+// taken over: no alternate screen, no scrolling, the cursor and the mouse
+// left alone, only raw mode to read keys from, and the output stays on the
+// cursor's row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Setup {
+    pub raw_mode: bool,
+    pub alternate_screen: bool,
+    pub screen: bool,
+}
+
+impl Default for Setup {
+    fn default() -> Self {
+        Setup {
+            raw_mode: true,
+            alternate_screen: true,
+            screen: true,
+        }
+    }
+}
+
+// What restore() has to undo; the panic hook gets there without the guard
+static RAW_MODE: AtomicBool = AtomicBool::new(false);
+static ALTERNATE_SCREEN: AtomicBool = AtomicBool::new(false);
+static SCREEN: AtomicBool = AtomicBool::new(false);
+static ENTERED: AtomicBool = AtomicBool::new(false);
+
+// The terminal set up as `Setup` says, with the cursor hidden and mouse
+// reports on, for as long as it lives. Dropping it restores the terminal,
+// so that happens before any error from the visualizer gets printed.
+pub struct TerminalGuard {
+    setup: Setup,
+}
+
+impl TerminalGuard {
+    pub fn enter(setup: Setup) -> io::Result<TerminalGuard> {
+        // From the first step on Drop undoes whatever part of the setup succeeded
+        let guard = TerminalGuard { setup };
+        set_up(setup)?;
+        Ok(guard)
+    }
+
+    // Hand the terminal back and stop the process, as a shell's Ctrl+Z
+    // does; once continued (fg) the terminal is set up as it was again.
+    // Whatever was on screen is gone by then and has to be drawn afresh.
+    pub fn suspend(&mut self) -> io::Result<()> {
+        restore();
+        stop_process()?;
+        set_up(self.setup)
+    }
+
+    // Undo the setup and do it over, for a terminal that may have been
+    // changed under a process stopped from outside (SIGSTOP) and continued
+    pub fn reset(&mut self) -> io::Result<()> {
+        restore();
+        set_up(self.setup)
+    }
+}
+
+fn set_up(setup: Setup) -> io::Result<()> {
+    if setup.raw_mode {
+        enable_raw_mode()?;
+    }
+    RAW_MODE.store(setup.raw_mode, Ordering::Relaxed);
+    ALTERNATE_SCREEN.store(false, Ordering::Relaxed);
+    SCREEN.store(setup.screen, Ordering::Relaxed);
+    ENTERED.store(true, Ordering::Relaxed);
+    if !setup.screen {
+        return Ok(());
+    }
+    let mut stdout = io::stdout();
+    if setup.alternate_screen {
+        execute!(stdout, EnterAlternateScreen)?;
+        ALTERNATE_SCREEN.store(true, Ordering::Relaxed);
+    } else {
+        // Scroll what is on screen into the scrollback from the bottom
+        // row, leaving a blank screen to draw on
+        let (_, rows) = crossterm::terminal::size()?;
+        execute!(stdout, cursor::MoveTo(0, rows.saturating_sub(1)))?;
+        write!(stdout, "{}", "\n".repeat(rows as usize))?;
+        execute!(stdout, cursor::MoveTo(0, 0))?;
+    }
+    execute!(stdout, cursor::Hide, EnableMouseCapture)
+}
+
+// Stop until continued. SIGSTOP rather than SIGTSTP, which is caught (see
+// Signals) and would only come back round as a flag.
+fn stop_process() -> io::Result<()> {
+    #[cfg(unix)]
+    signal_hook::low_level::raise(signal_hook::consts::SIGSTOP)?;
+    Ok(())
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+// Undo the setup, once for each time it was done. Leave the alternate
+// screen before raw mode: legacy Windows consoles keep the alternate
+// buffer's input mode otherwise and are left unusable. On the main screen the cursor goes to the start of the
+// last row the frames took (the whole screen) and one line further once
+// raw mode is off, so the shell's prompt comes in below the last frame.
+// Kept to its row, only raw mode is undone, and the line ended on a
+// terminal (piped, every refresh already ends its line).
+fn restore() {
+    restore_title();
+    if !ENTERED.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    let mut stdout = io::stdout();
+    if !SCREEN.swap(false, Ordering::Relaxed) {
+        if RAW_MODE.swap(false, Ordering::Relaxed) {
+            let _ = disable_raw_mode();
+        }
+        if stdout.is_terminal() {
+            let _ = writeln!(stdout);
+            let _ = stdout.flush();
+        }
+        return;
+    }
+    let _ = execute!(stdout, DisableMouseCapture, cursor::Show);
+    let alternate_screen = ALTERNATE_SCREEN.swap(false, Ordering::Relaxed);
+    if alternate_screen {
+        let _ = execute!(stdout, LeaveAlternateScreen);
+    } else if let Ok((_, rows)) = crossterm::terminal::size() {
+        let _ = execute!(stdout, cursor::MoveTo(0, rows.saturating_sub(1)));
+    }
+    if RAW_MODE.swap(false, Ordering::Relaxed) {
+        let _ = disable_raw_mode();
+    }
+    if !alternate_screen {
+        let _ = writeln!(stdout);
+        let _ = stdout.flush();
+    }
+}
+
+// Least time between two window title updates
+const TITLE_INTERVAL: Duration = Duration::from_secs(1);
+
+// Set once the window title has been changed, and the terminal's own put on
+// its title stack to come back to
+static TITLE_SAVED: AtomicBool = AtomicBool::new(false);
+
+// Change the window title, saving the terminal's own (xterm's title stack,
+// CSI 22;0 t) the first time
+fn set_title(title: &str) {
+    let mut stdout = io::stdout();
+    if !TITLE_SAVED.swap(true, Ordering::Relaxed) {
+        let _ = write!(stdout, "\x1b[22;0t");
+    }
+    let _ = execute!(stdout, SetTitle(title));
+}
+
+// Put back the title there was before set_title (CSI 23;0 t); nothing when
+// it was never changed
+fn restore_title() {
+    if TITLE_SAVED.swap(false, Ordering::Relaxed) {
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "\x1b[23;0t");
+        let _ = stdout.flush();
+    }
+}
+
+// The window title while playing, written at most once per TITLE_INTERVAL
+// and only when it says something new, so frames don't each send escape
+// sequences
+#[derive(Default)]
+pub struct WindowTitle {
+    shown: Option<String>,
+    updated_at: Option<Instant>,
+}
+
+impl WindowTitle {
+    // Show the title `make` builds, when an update is due
+    pub fn update(&mut self, make: impl FnOnce() -> String) {
+        if self.updated_at.is_some_and(|at| at.elapsed() < TITLE_INTERVAL) {
+            return;
+        }
+        self.updated_at = Some(Instant::now());
+        let title = make();
+        if self.shown.as_ref() != Some(&title) {
+            set_title(&title);
+            self.shown = Some(title);
+        }
+    }
+
+    // Give the terminal its own title back (e.g. when switched off)
+    pub fn clear(&mut self) {
+        if self.shown.take().is_some() {
+            restore_title();
+        }
+        self.updated_at = None;
+    }
+}
+
+// Restore the terminal before a panic message is printed, so it doesn't end
+// up on the alternate screen (gone the moment the guard drops) or mangled by
+// raw mode. Restoring twice is harmless.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore();
+        previous(info);
+    }));
+}
+
+// Signals that a running session acts on, as flags its frame loop checks:
+// SIGTERM and SIGHUP quit the way 'q' does, so the terminal is restored and
+// what was played still summed up, SIGTSTP (Ctrl+Z from outside raw mode,
+// or `kill -TSTP`) asks to suspend, and SIGCONT says the process was
+// continued. Without a unix there are no signals and nothing is ever set.
+#[derive(Clone, Default)]
+pub struct Signals {
+    quit: Arc<AtomicBool>,
+    hung_up: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    continued: Arc<AtomicBool>,
+}
+
+impl Signals {
+    // Catch the signals from now on, in place of what they'd do by default
+    pub fn install() -> Signals {
+        let signals = Signals::default();
+        #[cfg(unix)]
+        {
+            use signal_hook::consts::{SIGCONT, SIGHUP, SIGTERM, SIGTSTP};
+            use signal_hook::flag;
+            for signal in [SIGTERM, SIGHUP] {
+                // A second one before the first was acted on ends the
+                // process there and then, should the loop be stuck
+                let _ = flag::register_conditional_shutdown(signal, 1, signals.quit.clone());
+                let _ = flag::register(signal, signals.quit.clone());
+            }
+            let _ = flag::register(SIGHUP, signals.hung_up.clone());
+            let _ = flag::register(SIGTSTP, signals.stop.clone());
+            let _ = flag::register(SIGCONT, signals.continued.clone());
+        }
+        signals
+    }
+
+    // Whether to quit (SIGTERM or SIGHUP came)
+    pub fn quit(&self) -> bool {
+        self.quit.load(Ordering::Relaxed)
+    }
+
+    // Whether the terminal went away (SIGHUP), leaving nowhere to print to
+    pub fn hung_up(&self) -> bool {
+        self.hung_up.load(Ordering::Relaxed)
+    }
+
+    // Whether SIGTSTP came since last asked
+    pub fn take_stop(&self) -> bool {
+        self.stop.swap(false, Ordering::Relaxed)
+    }
+
+    // Whether the process was continued since last asked
+    pub fn take_continued(&self) -> bool {
+        self.continued.swap(false, Ordering::Relaxed)
+    }
+}
+
+// Colors the terminal can show
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    // The 8 standard colors, e.g. legacy conhost or TERM=xterm
+    Basic,
+}
+
+impl ColorSupport {
+    pub fn detect() -> ColorSupport {
+        static SUPPORT: OnceLock<ColorSupport> = OnceLock::new();
+        *SUPPORT.get_or_init(|| match crossterm::style::available_color_count() {
+            u16::MAX => ColorSupport::TrueColor,
+            256.. => ColorSupport::Ansi256,
+            _ => ColorSupport::Basic,
+        })
+    }
+}
+
+// An RGB color as close as this terminal can show it; others pass through
+pub fn adapt(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    match ColorSupport::detect() {
+        ColorSupport::TrueColor => color,
+        // The 6x6x6 color cube of the 256-color palette
+        ColorSupport::Ansi256 => {
+            let level = |v: u8| (v as u16 * 5 + 127) / 255;
+            Color::Indexed((16 + 36 * level(r) + 6 * level(g) + level(b)) as u8)
+        }
+        // Each channel on or off
+        ColorSupport::Basic => match (r > 127, g > 127, b > 127) {
+            (false, false, false) => Color::Black,
+            (true, false, false) => Color::Red,
+            (false, true, false) => Color::Green,
+            (true, true, false) => Color::Yellow,
+            (false, false, true) => Color::Blue,
+            (true, false, true) => Color::Magenta,
+            (false, true, true) => Color::Cyan,
+            (true, true, true) => Color::White,
+        },
+    }
+}
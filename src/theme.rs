@@ -0,0 +1,171 @@
+//! Colors of the display.
+//!
+//! The bars and the idle animation are drawn in the VIBGYOR gradient of
+//! [`crate::gradient`]; [`frequency_to_color`] picks a position in it by
+//! index, for anything laid out in columns. Every input gives a real color:
+//! a single column (a one-cell-wide pane) is red and an index past the end
+//! is violet, never a color made from a division by zero.
+//!
+//! Everything else on screen takes its style from a [`Theme`], one per
+//! role (secondary text, a selection, a meter in the red...). The
+//! [`Theme::plain`] one, for `NO_COLOR` or `--no-color`, has no colors at
+//! all: the gradient comes out as the terminal's default color, so the bars
+//! are plain blocks, and what must stand out (a selection, the cursor) is
+//! shown reversed instead.
+//!
+//! ```
+//! use gruvberry::theme::frequency_to_color;
+//! use ratatui::style::Color;
+//!
+//! assert_eq!(frequency_to_color(0, 7), Color::Rgb(255, 0, 0));
+//! assert_eq!(frequency_to_color(6, 7), Color::Rgb(148, 0, 211));
+//! assert_eq!(frequency_to_color(0, 1), Color::Rgb(255, 0, 0));
+//! ```
+
+use std::ffi::OsStr;
+use ratatui::style::{Color, Modifier, Style};
+use crate::gradient;
+
+/// Color of column `index` out of `total`, red at the first to violet at the
+/// last. With one column (or none) the ratio is 0.0, so red; an index at or
+/// past `total` counts as the last column.
+pub fn frequency_to_color(index: usize, total: usize) -> Color {
+    let last = total.saturating_sub(1);
+    let ratio = match last {
+        0 => 0.0,
+        _ => index.min(last) as f32 / last as f32,
+    };
+    let (r, g, b) = gradient::vibgyor(ratio);
+    Color::Rgb(r, g, b)
+}
+
+/// Styles of the display by role.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    /// Whether the bars get the gradient's colors.
+    colored: bool,
+    /// The gradient the bars are colored with.
+    pub palette: gradient::Gradient,
+    /// Secondary text: axis numbers, table headers.
+    pub dim: Style,
+    /// The empty part of meters and gauges, what is still to play.
+    pub faint: Style,
+    /// Text drawn over the bars, like peak labels, and the reference marks.
+    pub label: Style,
+    /// A selected entry, the overview's playback position, a text cursor.
+    pub selected: Style,
+    /// The band cursor's column over the bars.
+    pub cursor: Style,
+    /// Meter readings that are fine, borderline and bad (and errors).
+    pub good: Style,
+    pub warn: Style,
+    pub bad: Style,
+    /// What has played: the level sparkline, the overview's played part.
+    pub accent: Style,
+    /// The spectrum border while an alert flashes it.
+    pub flash: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            colored: true,
+            palette: gradient::Gradient::default(),
+            dim: Style::default().fg(Color::Gray),
+            faint: Style::default().fg(Color::DarkGray),
+            label: Style::default().fg(Color::White),
+            selected: Style::default().fg(Color::Black).bg(Color::White),
+            cursor: Style::default().fg(Color::White).bg(Color::DarkGray),
+            good: Style::default().fg(Color::Green),
+            warn: Style::default().fg(Color::Yellow),
+            bad: Style::default().fg(Color::Red),
+            accent: Style::default().fg(Color::Rgb(255, 165, 0)),
+            flash: Style::default().fg(Color::White).bg(Color::White),
+        }
+    }
+}
+
+impl Theme {
+    /// No colors anywhere; a selection, the cursor and a flash are reversed.
+    pub fn plain() -> Theme {
+        let reversed = Style::default().add_modifier(Modifier::REVERSED);
+        Theme {
+            colored: false,
+            palette: gradient::Gradient::default(),
+            dim: Style::default(),
+            faint: Style::default(),
+            label: Style::default(),
+            selected: reversed,
+            cursor: reversed,
+            good: Style::default(),
+            warn: Style::default(),
+            bad: Style::default(),
+            accent: Style::default(),
+            flash: reversed,
+        }
+    }
+
+    /// [`Theme::plain`] when `no_color` (`--no-color`) or the `NO_COLOR`
+    /// environment variable asks for it, the default otherwise.
+    pub fn detect(no_color: bool) -> Theme {
+        if no_color || no_color_set(std::env::var_os("NO_COLOR").as_deref()) {
+            Theme::plain()
+        } else {
+            Theme::default()
+        }
+    }
+
+    /// Whether the bars get the gradient's colors.
+    pub fn is_colored(&self) -> bool {
+        self.colored
+    }
+
+    /// A color of the gradient as this theme draws it: as it is, or the
+    /// terminal's default in a plain theme.
+    pub fn gradient(&self, color: Color) -> Color {
+        if self.colored { color } else { Color::Reset }
+    }
+
+    /// A gradient `color` at `heat` (0.0-1.0) for a cooling heat trail cell:
+    /// darkened towards black as it cools, or the terminal's default in a
+    /// plain theme, where only the shade of the cell shows the heat.
+    pub fn heat(&self, color: Color, heat: f32) -> Color {
+        match self.gradient(color) {
+            Color::Rgb(r, g, b) => {
+                let scale = |v: u8| (v as f32 * heat.clamp(0.0, 1.0)).round() as u8;
+                Color::Rgb(scale(r), scale(g), scale(b))
+            }
+            color => color,
+        }
+    }
+
+    /// This theme with every color passed through `adapt`, e.g. to fit the
+    /// colors the terminal can show.
+    pub fn map_colors(self, adapt: impl Fn(Color) -> Color) -> Theme {
+        let map = |style: Style| Style {
+            fg: style.fg.map(&adapt),
+            bg: style.bg.map(&adapt),
+            ..style
+        };
+        Theme {
+            colored: self.colored,
+            palette: self.palette,
+            dim: map(self.dim),
+            faint: map(self.faint),
+            label: map(self.label),
+            selected: map(self.selected),
+            cursor: map(self.cursor),
+            good: map(self.good),
+            warn: map(self.warn),
+            bad: map(self.bad),
+            accent: map(self.accent),
+            flash: map(self.flash),
+        }
+    }
+}
+
+/// Whether a `NO_COLOR` value asks for no color: set to anything but the
+/// empty string, as <https://no-color.org> has it.
+pub fn no_color_set(value: Option<&OsStr>) -> bool {
+    value.is_some_and(|value| !value.is_empty())
+}
@@ -0,0 +1,173 @@
+//! Frequency-dependent display gain.
+//!
+//! Music averages roughly -3 dB per octave, so an untilted spectrum looks
+//! like a descending staircase. A [`Tilt`] lifts the treble back up; the
+//! [`Preset`]s are the common choices.
+//!
+//! ```
+//! use gruvberry::tilt::{Preset, Tilt};
+//!
+//! assert_eq!(Tilt::parse("pink-flat"), Ok(Preset::PinkFlat.tilt()));
+//! assert_eq!(Preset::of(&Tilt::parse("+3dB/oct").unwrap()), Some(Preset::PinkFlat));
+//! ```
+
+use crate::analysis::MIN_FREQ;
+
+/// Gain applied to each band by its frequency. Music loses energy toward the
+/// treble, so untilted bars sag to the right; a tilt lifts them back up.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tilt {
+    /// No gain anywhere.
+    Flat,
+    /// dB per octave, 0 dB at MIN_FREQ (+3 flattens pink noise).
+    Slope(f32),
+    /// (frequency in Hz, gain in dB) breakpoints in ascending frequency order,
+    /// interpolated on a log-frequency axis and held level beyond either end.
+    Curve(Vec<(f32, f32)>),
+}
+
+impl Default for Tilt {
+    /// Close to the old fixed boost, which rose linearly across the bands from
+    /// 1x at 20Hz to 3x at 22kHz.
+    fn default() -> Self {
+        Tilt::Curve(vec![(20.0, 0.0), (115.0, 3.5), (660.0, 6.0), (3800.0, 8.0), (22000.0, 9.5)])
+    }
+}
+
+impl Tilt {
+    /// "flat", a preset name, a slope like "+3dB/oct", or breakpoints like
+    /// "100:0, 1k:3, 10k:6".
+    pub fn parse(text: &str) -> Result<Tilt, String> {
+        let text = text.trim();
+        if text.eq_ignore_ascii_case("flat") {
+            return Ok(Tilt::Flat);
+        }
+        if let Some(preset) = Preset::from_name(&text.to_ascii_lowercase()) {
+            return Ok(preset.tilt());
+        }
+
+        let lower = text.to_ascii_lowercase();
+        if let Some(slope) = lower.strip_suffix("/oct") {
+            let slope = slope.trim().trim_end_matches("db").trim();
+            return parse_number(slope)
+                .map(Tilt::Slope)
+                .ok_or_else(|| format!("\"{}\" is not a slope like \"+3dB/oct\"", text));
+        }
+
+        let mut points = Vec::new();
+        for point in lower.split(',') {
+            let (freq, gain) = point
+                .split_once(':')
+                .ok_or_else(|| format!("\"{}\" is not a breakpoint like \"1k:3\" (Hz:dB)", point.trim()))?;
+            let freq = parse_frequency(freq.trim())
+                .filter(|&f| f > 0.0)
+                .ok_or_else(|| format!("\"{}\" is not a frequency in Hz", freq.trim()))?;
+            let gain = parse_number(gain.trim().trim_end_matches("db").trim())
+                .ok_or_else(|| format!("\"{}\" is not a gain in dB", gain.trim()))?;
+            if points.last().is_some_and(|&(last, _)| freq <= last) {
+                return Err("breakpoint frequencies must be in ascending order".to_string());
+            }
+            points.push((freq, gain));
+        }
+        Ok(Tilt::Curve(points))
+    }
+
+    /// Gain in dB at `freq`.
+    fn gain_db(&self, freq: f32) -> f32 {
+        match self {
+            Tilt::Flat => 0.0,
+            Tilt::Slope(slope) => slope * (freq / MIN_FREQ).log2(),
+            Tilt::Curve(points) => {
+                let Some(&(first_freq, first_gain)) = points.first() else {
+                    return 0.0;
+                };
+                if freq <= first_freq {
+                    return first_gain;
+                }
+                for pair in points.windows(2) {
+                    let ((f0, g0), (f1, g1)) = (pair[0], pair[1]);
+                    if freq <= f1 {
+                        let t = (freq / f0).ln() / (f1 / f0).ln();
+                        return g0 + (g1 - g0) * t;
+                    }
+                }
+                points.last().map_or(0.0, |&(_, gain)| gain)
+            }
+        }
+    }
+
+    /// Linear magnitude factor at `freq`.
+    pub fn gain(&self, freq: f32) -> f32 {
+        10f32.powf(self.gain_db(freq) / 20.0)
+    }
+}
+
+/// Slope of the "music" preset: half of pink-flat, for material that isn't
+/// quite as bottom-heavy as pink noise (or to keep some of the slope visible).
+pub const MUSIC_SLOPE: f32 = 1.5;
+
+/// Tilts for common kinds of material, cycled live with 't'.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    /// No tilt: the spectrum as analyzed.
+    Raw,
+    /// +3 dB per octave, which renders pink noise (and loosely, music) flat.
+    PinkFlat,
+    /// A gentler [`MUSIC_SLOPE`] dB per octave.
+    Music,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 3] = [Preset::Raw, Preset::PinkFlat, Preset::Music];
+
+    /// "raw", "pink-flat" or "music".
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Raw => "raw",
+            Preset::PinkFlat => "pink-flat",
+            Preset::Music => "music",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+
+    pub fn tilt(self) -> Tilt {
+        match self {
+            Preset::Raw => Tilt::Flat,
+            Preset::PinkFlat => Tilt::Slope(3.0),
+            Preset::Music => Tilt::Slope(MUSIC_SLOPE),
+        }
+    }
+
+    /// The preset `tilt` is, if any.
+    pub fn of(tilt: &Tilt) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|preset| preset.tilt() == *tilt)
+    }
+
+    /// The preset after this one, None after the last (back to the
+    /// configured tilt).
+    pub fn next(current: Option<Preset>) -> Option<Preset> {
+        match current {
+            None => Some(Preset::ALL[0]),
+            Some(preset) => {
+                let index = Preset::ALL.iter().position(|&p| p == preset).unwrap_or(0);
+                Preset::ALL.get(index + 1).copied()
+            }
+        }
+    }
+}
+
+fn parse_number(text: &str) -> Option<f32> {
+    text.parse::<f32>().ok().filter(|n| n.is_finite())
+}
+
+/// Hz, with an optional "k" (1k = 1000) and "hz" suffix.
+fn parse_frequency(text: &str) -> Option<f32> {
+    let text = text.trim_end_matches("hz").trim();
+    match text.strip_suffix('k') {
+        Some(khz) => parse_number(khz).map(|khz| khz * 1000.0),
+        None => parse_number(text),
+    }
+}
@@ -0,0 +1,382 @@
+//! Playback transport.
+//!
+//! The player drives playback through the [`Transport`] trait instead of
+//! rodio's `Sink` directly. `Sink` implements it for real playback;
+//! [`FakeTransport`] is a scripted clock with no audio device behind it, so
+//! the [`Player`] state machine (pause, seek, moving on to the next track)
+//! can be tested in milliseconds.
+//!
+//! ```
+//! use std::time::Duration;
+//! use gruvberry::transport::{FakeTransport, Player, Transport};
+//!
+//! let mut fake = FakeTransport::new();
+//! fake.append(Duration::from_secs(10));
+//! let mut player = Player::new(vec![fake]);
+//!
+//! player.transport_mut(0).advance(Duration::from_secs(2));
+//! player.toggle_pause();
+//! player.transport_mut(0).advance(Duration::from_secs(2));
+//! assert_eq!(player.position(), Duration::from_secs(2));
+//! ```
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use rodio::{Sink, Source};
+
+/// Controls of one playing stream.
+pub trait Transport {
+    /// What [`Transport::append`] queues: a rodio source for a `Sink`.
+    type Track;
+
+    /// Queue `track` to play after whatever is queued already.
+    fn append(&mut self, track: Self::Track);
+    /// Drop the current track; the next queued one, if any, starts.
+    fn skip(&mut self);
+    fn play(&mut self);
+    fn pause(&mut self);
+    fn is_paused(&self) -> bool;
+    /// Jump to `position` in the current track.
+    fn seek(&mut self, position: Duration) -> Result<(), String>;
+    /// Position in the current track.
+    fn position(&self) -> Duration;
+    fn set_volume(&mut self, volume: f32);
+    /// True once there is nothing left to play.
+    fn is_finished(&self) -> bool;
+    /// Drop everything queued.
+    fn stop(&mut self);
+}
+
+impl Transport for Sink {
+    type Track = Box<dyn Source + Send>;
+
+    fn append(&mut self, track: Self::Track) {
+        Sink::append(self, track);
+    }
+
+    fn skip(&mut self) {
+        self.skip_one();
+    }
+
+    fn play(&mut self) {
+        Sink::play(self);
+    }
+
+    fn pause(&mut self) {
+        Sink::pause(self);
+    }
+
+    fn is_paused(&self) -> bool {
+        Sink::is_paused(self)
+    }
+
+    fn seek(&mut self, position: Duration) -> Result<(), String> {
+        self.try_seek(position).map_err(|e| e.to_string())
+    }
+
+    fn position(&self) -> Duration {
+        self.get_pos()
+    }
+
+    fn set_volume(&mut self, volume: f32) {
+        Sink::set_volume(self, volume);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.empty()
+    }
+
+    fn stop(&mut self) {
+        Sink::stop(self);
+    }
+}
+
+/// A transport that plays nothing: tracks are just lengths, and time only
+/// passes when [`FakeTransport::advance`] says so. Seeks land instantly.
+#[derive(Clone, Debug)]
+pub struct FakeTransport {
+    // Length of the current track, then of the ones queued after it
+    tracks: VecDeque<Duration>,
+    position: Duration,
+    paused: bool,
+    volume: f32,
+}
+
+impl FakeTransport {
+    pub fn new() -> Self {
+        FakeTransport {
+            tracks: VecDeque::new(),
+            position: Duration::ZERO,
+            paused: false,
+            volume: 1.0,
+        }
+    }
+
+    /// Let `step` of time pass. While playing, the position moves on with it,
+    /// into the next queued track when the current one ends.
+    pub fn advance(&mut self, step: Duration) {
+        if self.paused {
+            return;
+        }
+        self.position += step;
+        while let Some(&length) = self.tracks.front() {
+            if self.position < length {
+                break;
+            }
+            self.position -= length;
+            self.tracks.pop_front();
+        }
+        if self.tracks.is_empty() {
+            self.position = Duration::ZERO;
+        }
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+}
+
+impl Default for FakeTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for FakeTransport {
+    /// The track's length.
+    type Track = Duration;
+
+    fn append(&mut self, track: Duration) {
+        self.tracks.push_back(track);
+    }
+
+    fn skip(&mut self) {
+        self.tracks.pop_front();
+        self.position = Duration::ZERO;
+    }
+
+    fn play(&mut self) {
+        self.paused = false;
+    }
+
+    fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Seeking to or past the end finishes the track, as with a decoder
+    fn seek(&mut self, position: Duration) -> Result<(), String> {
+        let &length = self.tracks.front().ok_or("nothing is playing")?;
+        if position >= length {
+            self.skip();
+        } else {
+            self.position = position;
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> Duration {
+        self.position
+    }
+
+    fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    fn stop(&mut self) {
+        self.tracks.clear();
+        self.position = Duration::ZERO;
+    }
+}
+
+/// The playback state machine: one transport per stream, all playing (and
+/// pausing and seeking) in lockstep with only the audible one heard. New
+/// tracks go to the first stream; the others are compared files.
+pub struct Player<T: Transport> {
+    transports: Vec<T>,
+    audible: usize,
+    paused: bool,
+}
+
+impl<T: Transport> Player<T> {
+    /// Start playing `transports`, with the first one audible.
+    pub fn new(transports: Vec<T>) -> Self {
+        let mut player = Player {
+            transports,
+            audible: 0,
+            paused: false,
+        };
+        player.hear(0);
+        for transport in &mut player.transports {
+            transport.play();
+        }
+        player
+    }
+
+    pub fn transports(&self) -> &[T] {
+        &self.transports
+    }
+
+    pub fn transport_mut(&mut self, index: usize) -> &mut T {
+        &mut self.transports[index]
+    }
+
+    pub fn audible(&self) -> usize {
+        self.audible
+    }
+
+    /// Make stream `index` the one heard; the others play on silently.
+    pub fn hear(&mut self, index: usize) {
+        self.audible = index;
+        for (i, transport) in self.transports.iter_mut().enumerate() {
+            transport.set_volume(if i == index { 1.0 } else { 0.0 });
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause, or resume when paused. Returns whether playback is now paused.
+    pub fn toggle_pause(&mut self) -> bool {
+        self.paused = !self.paused;
+        for transport in &mut self.transports {
+            if self.paused {
+                transport.pause();
+            } else {
+                transport.play();
+            }
+        }
+        self.paused
+    }
+
+    /// Position in the first stream's track.
+    pub fn position(&self) -> Duration {
+        self.transports.first().map_or(Duration::ZERO, Transport::position)
+    }
+
+    /// Jump every stream to `position`, so compared files stay in sync.
+    pub fn seek(&mut self, position: Duration) -> Result<(), String> {
+        for transport in &mut self.transports {
+            transport.seek(position)?;
+        }
+        Ok(())
+    }
+
+    /// Jump `delta_secs` back or forward from the current position, to no
+    /// earlier than the start and no later than `limit`. Returns where
+    /// playback landed.
+    pub fn seek_by(&mut self, delta_secs: f32, limit: Duration) -> Result<Duration, String> {
+        let target = (self.position().as_secs_f32() + delta_secs).clamp(0.0, limit.as_secs_f32());
+        let target = Duration::from_secs_f32(target);
+        self.seek(target)?;
+        Ok(target)
+    }
+
+    /// Whether the current track is over. Any stream running out ends it, so
+    /// compared files stop together at the shorter one.
+    pub fn track_ended(&self) -> bool {
+        self.transports.iter().any(Transport::is_finished)
+    }
+
+    /// Queue `track` on the first stream.
+    pub fn append(&mut self, track: T::Track) {
+        if let Some(transport) = self.transports.first_mut() {
+            transport.append(track);
+        }
+    }
+
+    /// Cut the first stream's track short.
+    pub fn skip(&mut self) {
+        if let Some(transport) = self.transports.first_mut() {
+            transport.skip();
+        }
+    }
+
+    /// Put `transport` in the place of stream `index`'s (one on a reopened
+    /// output device, say), heard and paused as the player is. Returns the
+    /// one it replaced.
+    pub fn replace(&mut self, index: usize, mut transport: T) -> T {
+        transport.set_volume(if index == self.audible { 1.0 } else { 0.0 });
+        if self.paused {
+            transport.pause();
+        } else {
+            transport.play();
+        }
+        std::mem::replace(&mut self.transports[index], transport)
+    }
+
+    /// Stop every stream.
+    pub fn stop(&mut self) {
+        for transport in &mut self.transports {
+            transport.stop();
+        }
+    }
+}
+
+/// The playback position as another thread follows it: told where playback
+/// is whenever it pauses, resumes or jumps, and running on its own clock in
+/// between, so it moves smoothly and never goes backwards on its own.
+#[derive(Clone, Copy, Debug)]
+pub struct Playhead {
+    // Position as of `since`, or the held position while paused (None)
+    position: Duration,
+    since: Option<Instant>,
+}
+
+impl Playhead {
+    /// At the start of a track at `now`, running unless `paused`.
+    pub fn start(now: Instant, paused: bool) -> Self {
+        Playhead {
+            position: Duration::ZERO,
+            since: (!paused).then_some(now),
+        }
+    }
+
+    /// Position at `now`.
+    pub fn position(&self, now: Instant) -> Duration {
+        match self.since {
+            Some(since) => self.position + now.saturating_duration_since(since),
+            None => self.position,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.since.is_none()
+    }
+
+    /// Hold at `position`.
+    pub fn pause(&mut self, position: Duration) {
+        self.position = position;
+        self.since = None;
+    }
+
+    /// Run on from `position` at `now`.
+    pub fn resume(&mut self, position: Duration, now: Instant) {
+        self.position = position;
+        self.since = Some(now);
+    }
+
+    /// Hold back by `by`, time that passed without anything being heard
+    /// (see [`crate::flow`]).
+    pub fn delay(&mut self, by: Duration) {
+        if let Some(since) = self.since.as_mut() {
+            *since += by;
+        }
+    }
+
+    /// Jump to `position` at `now`, staying paused if paused.
+    pub fn seek(&mut self, position: Duration, now: Instant) {
+        self.position = position;
+        if self.since.is_some() {
+            self.since = Some(now);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Noticing that the audio output has stopped pulling samples.
+//!
+//! Some audio setups wedge: the device callback stops asking for samples,
+//! nothing more goes through the capture tap, and the spectrum freezes on
+//! its last frame while the transport still says it's playing. The tap's
+//! [`FlowMeter`](crate::flow::FlowMeter) keeps when samples were last
+//! pulled; a [`Watchdog`] checks that against the time while playback is
+//! supposed to be running. After [`STALL_TIMEOUT`] without samples it asks
+//! for the output device to be reopened, once; stalling again after that
+//! (or right away, if the reopen couldn't be done) means giving up.
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//! use gruvberry::watchdog::{Verdict, Watchdog, STALL_TIMEOUT};
+//!
+//! let start = Instant::now();
+//! let mut watchdog = Watchdog::new(STALL_TIMEOUT, start);
+//! // Samples a second in, then nothing
+//! let pulled = Some(start + Duration::from_secs(1));
+//! assert_eq!(watchdog.check(pulled, true, start + Duration::from_secs(2)), Verdict::Running);
+//! let later = start + Duration::from_secs(1) + STALL_TIMEOUT;
+//! assert_eq!(watchdog.check(pulled, true, later), Verdict::Reopen);
+//! ```
+
+use std::time::{Duration, Instant};
+
+/// How long playback can go without samples being pulled before it counts
+/// as stalled.
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// What to do about the output, from [`Watchdog::check`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Running,
+    /// Stalled for the first time: reopen the device.
+    Reopen,
+    /// Stalled again after the reopen: stop.
+    GiveUp,
+}
+
+/// Watches for the output stalling while it should be playing.
+#[derive(Clone, Debug)]
+pub struct Watchdog {
+    timeout: Duration,
+    // Samples were last seen then, or the wait last restarted
+    last: Instant,
+    reopened: bool,
+}
+
+impl Watchdog {
+    /// Watching from `now`, stalled after `timeout` without samples.
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        Watchdog {
+            timeout,
+            last: now,
+            reopened: false,
+        }
+    }
+
+    /// Check at `now`, with samples last `pulled` then (None when the
+    /// playing track hasn't had any yet) and whether playback should be
+    /// running: paused or finished, nothing needs to be pulled, so the wait
+    /// starts over once it runs again.
+    pub fn check(&mut self, pulled: Option<Instant>, playing: bool, now: Instant) -> Verdict {
+        if !playing {
+            self.last = now;
+            return Verdict::Running;
+        }
+        if let Some(pulled) = pulled {
+            self.last = self.last.max(pulled);
+        }
+        if now.saturating_duration_since(self.last) < self.timeout {
+            Verdict::Running
+        } else if self.reopened {
+            Verdict::GiveUp
+        } else {
+            Verdict::Reopen
+        }
+    }
+
+    /// The device was reopened at `now` (or that was tried): the next stall
+    /// gives up.
+    pub fn reopened(&mut self, now: Instant) {
+        self.reopened = true;
+        self.last = now;
+    }
+
+    pub fn has_reopened(&self) -> bool {
+        self.reopened
+    }
+}
@@ -0,0 +1,206 @@
+//! Telling what a WAV file holds before trying to decode it.
+//!
+//! A WAV container can hold more than plain samples: µ-law and A-law
+//! telephone audio, ADPCM from old games and voice recorders, even MP3.
+//! Only integer PCM and 32-bit float can be played here, and the decoder's
+//! own error for the rest says little, so [`format_tag`] reads the codec
+//! from the `fmt ` chunk (from inside `WAVE_FORMAT_EXTENSIBLE` too) and
+//! [`unsupported_message`] names it, with a way to convert the file.
+//!
+//! Nor is every header to be believed. [`inspect`] checks one against the
+//! file it heads before anything divides by its sample rate or length: an
+//! empty file, a sample rate of 0 Hz and a file without any audio are
+//! errors, and a file cut short (a download that stopped, a recorder that
+//! lost power) is measured by the audio that is there, with
+//! [`Inspection::truncation_warning`] to say so.
+//!
+//! ```
+//! use gruvberry::wav;
+//!
+//! assert!(wav::is_supported(wav::PCM));
+//! assert!(!wav::is_supported(wav::IMA_ADPCM));
+//! assert_eq!(
+//!     wav::unsupported_message(wav::IMA_ADPCM),
+//!     "WAV file uses IMA ADPCM which is not supported; convert with ffmpeg -i in.wav -c:a pcm_s16le out.wav"
+//! );
+//! ```
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::format;
+
+/// Format tags (`wFormatTag`), from the Windows SDK's mmreg.h.
+pub const PCM: u16 = 0x0001;
+pub const MS_ADPCM: u16 = 0x0002;
+pub const IEEE_FLOAT: u16 = 0x0003;
+pub const A_LAW: u16 = 0x0006;
+pub const MU_LAW: u16 = 0x0007;
+pub const IMA_ADPCM: u16 = 0x0011;
+pub const GSM_610: u16 = 0x0031;
+pub const MPEG: u16 = 0x0050;
+pub const MP3: u16 = 0x0055;
+/// The real tag is in the first bytes of the extension's sub-format GUID.
+pub const EXTENSIBLE: u16 = 0xFFFE;
+
+// The `fmt ` chunk up to the end of WAVEFORMATEXTENSIBLE's sub-format
+// tag, all that is read of it
+const FMT_READ: usize = 26;
+// Where the sub-format tag sits in an extensible `fmt ` chunk
+const SUBFORMAT_OFFSET: usize = 24;
+
+/// The format tag of the WAV file `reader` starts at, the sub-format's for
+/// `WAVE_FORMAT_EXTENSIBLE`. None when it isn't a RIFF WAVE file or has no
+/// `fmt ` chunk, for the decoder to report.
+pub fn format_tag(mut reader: impl Read) -> io::Result<Option<u16>> {
+    let mut header = [0u8; 12];
+    if read_full(&mut reader, &mut header)? < header.len() || &header[..4] != b"RIFF" || &header[8..] != b"WAVE" {
+        return Ok(None);
+    }
+    loop {
+        let mut chunk = [0u8; 8];
+        if read_full(&mut reader, &mut chunk)? < chunk.len() {
+            return Ok(None);
+        }
+        let len = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+        if &chunk[..4] == b"fmt " {
+            let mut fmt = [0u8; FMT_READ];
+            let read = read_full(&mut (&mut reader).take(len), &mut fmt)?;
+            if read < 2 {
+                return Ok(None);
+            }
+            let tag = u16::from_le_bytes([fmt[0], fmt[1]]);
+            if tag == EXTENSIBLE && read >= SUBFORMAT_OFFSET + 2 {
+                return Ok(Some(u16::from_le_bytes([fmt[SUBFORMAT_OFFSET], fmt[SUBFORMAT_OFFSET + 1]])));
+            }
+            return Ok(Some(tag));
+        }
+        // Chunks are padded to an even length
+        let skip = len + (len & 1);
+        if io::copy(&mut (&mut reader).take(skip), &mut io::sink())? < skip {
+            return Ok(None);
+        }
+    }
+}
+
+/// Whether samples of this format can be decoded: integer PCM or float.
+pub fn is_supported(tag: u16) -> bool {
+    matches!(tag, PCM | IEEE_FLOAT)
+}
+
+/// What a format tag is called, for the ones met in the wild.
+pub fn codec_name(tag: u16) -> Option<&'static str> {
+    Some(match tag {
+        PCM => "PCM",
+        MS_ADPCM => "Microsoft ADPCM",
+        IEEE_FLOAT => "IEEE float",
+        A_LAW => "A-law",
+        MU_LAW => "µ-law",
+        IMA_ADPCM => "IMA ADPCM",
+        GSM_610 => "GSM 6.10",
+        MPEG => "MPEG audio",
+        MP3 => "MP3",
+        _ => return None,
+    })
+}
+
+/// Why a file with this format tag can't be played, and how to fix it.
+pub fn unsupported_message(tag: u16) -> String {
+    let codec = codec_name(tag).map_or_else(|| format!("format 0x{:04X}", tag), str::to_string);
+    format!(
+        "WAV file uses {} which is not supported; convert with ffmpeg -i in.wav -c:a pcm_s16le out.wav",
+        codec
+    )
+}
+
+/// A WAV file's header, checked against the file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Inspection {
+    pub spec: hound::WavSpec,
+    /// Frames (a sample of every channel) the file holds.
+    pub frames: u64,
+    /// Frames the header says it holds; more than `frames` when the file
+    /// was cut short.
+    pub declared_frames: u64,
+}
+
+impl Inspection {
+    /// Seconds of audio the file holds.
+    pub fn duration(&self) -> f32 {
+        self.frames as f32 / self.spec.sample_rate as f32
+    }
+
+    /// Whether the file ends before the audio its header promises.
+    pub fn is_truncated(&self) -> bool {
+        self.frames < self.declared_frames
+    }
+
+    /// What to tell about a file cut short, None for a whole one.
+    pub fn truncation_warning(&self) -> Option<String> {
+        let declared = self.declared_frames as f32 / self.spec.sample_rate as f32;
+        self.is_truncated().then(|| {
+            format!(
+                "the file is cut short: {} of the {} its header promises; playing what is there",
+                format::time(self.duration()),
+                format::time(declared)
+            )
+        })
+    }
+}
+
+/// Read the header of the WAV file `reader` holds and check it against the
+/// file, each problem with a message fit to show as it is: an empty file, a
+/// codec that can't be played, a header that doesn't parse, a sample rate
+/// of 0 Hz and a file with no audio after its header. A file cut short
+/// isn't an error; its [`Inspection`] counts only the frames that are there.
+pub fn inspect<R: Read + Seek>(mut reader: R) -> Result<Inspection, String> {
+    let unreadable = |e: io::Error| format!("could not read the file: {}", e);
+    let len = reader.seek(SeekFrom::End(0)).map_err(unreadable)?;
+    if len == 0 {
+        return Err("the file is empty".to_string());
+    }
+    reader.rewind().map_err(unreadable)?;
+    if let Some(tag) = format_tag(&mut reader).map_err(unreadable)?
+        && !is_supported(tag)
+    {
+        return Err(unsupported_message(tag));
+    }
+    reader.rewind().map_err(unreadable)?;
+
+    let wav = hound::WavReader::new(&mut reader).map_err(|e| match e {
+        hound::Error::IoError(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+            "not a WAV file, or its header is cut short".to_string()
+        }
+        hound::Error::IoError(e) => unreadable(e),
+        e => format!("not a WAV file that can be read ({})", e),
+    })?;
+    let spec = wav.spec();
+    if spec.sample_rate == 0 {
+        return Err("the header gives a sample rate of 0 Hz".to_string());
+    }
+    if spec.channels == 0 {
+        return Err("the header gives no channels".to_string());
+    }
+    let declared_frames = wav.len() as u64 / spec.channels as u64;
+    // The reader stops at the start of the samples
+    let data_start = wav.into_inner().stream_position().map_err(unreadable)?;
+    let frame_bytes = spec.channels as u64 * spec.bits_per_sample.div_ceil(8) as u64;
+    let frames = (len.saturating_sub(data_start) / frame_bytes.max(1)).min(declared_frames);
+    if frames == 0 {
+        return Err("there is no audio after the header".to_string());
+    }
+    Ok(Inspection { spec, frames, declared_frames })
+}
+
+// Fill `buf` as far as the reader goes, returning how much was read
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
@@ -0,0 +1,139 @@
+//! Zooming into part of the frequency range.
+//!
+//! The bands normally span [`MIN_FREQ`](crate::analysis::MIN_FREQ) to
+//! Nyquist; a [`Zoom`] narrows that to any range at least [`MIN_OCTAVES`]
+//! wide. A step narrows or widens the range [`STEP`] times around a
+//! frequency (the cursor's), which stays where it is on screen, and the
+//! range shown glides from the old one to the new over [`ANIMATION`] so it
+//! is clear what happened. The ranges are always given with the whole range
+//! at hand, since that moves with the sample rate.
+//!
+//! ```
+//! use std::time::Instant;
+//! use gruvberry::analysis::FreqRange;
+//! use gruvberry::format::Locale;
+//! use gruvberry::zoom::{self, Zoom, ANIMATION};
+//!
+//! let full = FreqRange::full(22050.0);
+//! let now = Instant::now();
+//! let mut zoom = Zoom::default();
+//! zoom.select(200.0, 3200.0, full, now);
+//! // Still the whole range at first, then gliding in
+//! assert_eq!(zoom.current(full, now), full);
+//! assert_eq!(zoom.current(full, now + ANIMATION), FreqRange { low: 200.0, high: 3200.0 });
+//! assert_eq!(zoom::label(zoom.target(full), Locale::default()), "200 Hz – 3.2 kHz");
+//! ```
+
+use std::time::{Duration, Instant};
+use crate::analysis::FreqRange;
+use crate::format::Locale;
+
+/// Narrowest range a zoom can show.
+pub const MIN_OCTAVES: f32 = 1.0;
+/// How many times narrower or wider a step makes the range.
+pub const STEP: f32 = 2.0;
+/// How long the range takes to glide to a new zoom.
+pub const ANIMATION: Duration = Duration::from_millis(250);
+
+/// The zoomed-in range, if any, and the glide toward it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Zoom {
+    // None for the whole range
+    target: Option<FreqRange>,
+    // The range shown when the last change was made, and when that was
+    transition: Option<(FreqRange, Instant)>,
+}
+
+impl Zoom {
+    pub fn is_zoomed(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// The range being zoomed to, within `full`.
+    pub fn target(&self, full: FreqRange) -> FreqRange {
+        self.target.and_then(|range| fit(range, full)).unwrap_or(full)
+    }
+
+    /// The range to show at `now`: the target, or on the way to it.
+    pub fn current(&self, full: FreqRange, now: Instant) -> FreqRange {
+        let to = self.target(full);
+        let Some((from, at)) = self.transition else {
+            return to;
+        };
+        let t = now.saturating_duration_since(at).as_secs_f32() / ANIMATION.as_secs_f32();
+        if t >= 1.0 {
+            return to;
+        }
+        // Fast at first and settling in, along the log scale
+        let eased = 1.0 - (1.0 - t) * (1.0 - t);
+        FreqRange {
+            low: from.low * (to.low / from.low).powf(eased),
+            high: from.high * (to.high / from.high).powf(eased),
+        }
+    }
+
+    /// Narrow the range [`STEP`] times around `center` (Hz).
+    pub fn narrow(&mut self, center: f32, full: FreqRange, now: Instant) {
+        self.scale(center, 1.0 / STEP, full, now);
+    }
+
+    /// Widen the range [`STEP`] times around `center` (Hz), up to the whole
+    /// range.
+    pub fn widen(&mut self, center: f32, full: FreqRange, now: Instant) {
+        self.scale(center, STEP, full, now);
+    }
+
+    /// Zoom to `low` to `high` (Hz, either way round), widened to
+    /// [`MIN_OCTAVES`] if narrower.
+    pub fn select(&mut self, low: f32, high: f32, full: FreqRange, now: Instant) {
+        let range = FreqRange {
+            low: low.min(high),
+            high: low.max(high),
+        };
+        self.set(fit(range, full), full, now);
+    }
+
+    /// Back to the whole range.
+    pub fn reset(&mut self, full: FreqRange, now: Instant) {
+        self.set(None, full, now);
+    }
+
+    // `factor` times the width, with `center` kept at the same place in it
+    fn scale(&mut self, center: f32, factor: f32, full: FreqRange, now: Instant) {
+        let range = self.target(full);
+        let position = range.position(center).clamp(0.0, 1.0);
+        let octaves = range.octaves() * factor;
+        let low = center / (position * octaves).exp2();
+        self.set(fit(FreqRange { low, high: low * octaves.exp2() }, full), full, now);
+    }
+
+    fn set(&mut self, target: Option<FreqRange>, full: FreqRange, now: Instant) {
+        self.transition = Some((self.current(full, now), now));
+        self.target = target;
+    }
+}
+
+// `range` made at least MIN_OCTAVES wide and moved inside `full`; None when
+// that leaves all of `full`
+fn fit(range: FreqRange, full: FreqRange) -> Option<FreqRange> {
+    if !(range.low > 0.0 && range.high.is_finite()) {
+        return None;
+    }
+    let octaves = range.octaves().max(MIN_OCTAVES);
+    if octaves >= full.octaves() {
+        return None;
+    }
+    // Widened around its middle, then slid back in from either end
+    let middle = (range.low * range.high).sqrt();
+    let low = (middle / (octaves / 2.0).exp2()).max(full.low);
+    let high = (low * octaves.exp2()).min(full.high);
+    Some(FreqRange {
+        low: high / octaves.exp2(),
+        high,
+    })
+}
+
+/// A range as it reads in the header, e.g. "200 Hz – 3.2 kHz".
+pub fn label(range: FreqRange, locale: Locale) -> String {
+    format!("{} – {}", locale.hz(range.low), locale.hz(range.high))
+}
@@ -0,0 +1,97 @@
+use gruvberry::agc::{self, AutoGain, LevelMonitor, LevelWarning};
+
+const RATE: u32 = 48000;
+// 100 ms blocks
+const BLOCK: usize = 4800;
+
+// A 1 kHz sine block with an RMS of `rms_db` dBFS
+fn sine_block(rms_db: f32) -> Vec<f32> {
+    let amplitude = 10f32.powf(rms_db / 20.0) * std::f32::consts::SQRT_2;
+    (0..BLOCK)
+        .map(|i| amplitude * (i as f32 * 1000.0 * std::f32::consts::TAU / RATE as f32).sin())
+        .collect()
+}
+
+// Seconds until the gained output is within 1 dB of the target (None within a minute)
+fn settle_secs(input_db: f32) -> Option<f32> {
+    let mut agc = AutoGain::new();
+    (1..=600).find_map(|block| {
+        let mut samples = sine_block(input_db);
+        agc.apply(&mut samples, 1, RATE);
+        let secs = block as f32 * BLOCK as f32 / RATE as f32;
+        ((agc::rms_db(&samples) - agc::TARGET_RMS_DB).abs() < 1.0).then_some(secs)
+    })
+}
+
+#[test]
+fn quiet_input_is_raised_slowly() {
+    let secs = settle_secs(-40.0).expect("never settled");
+    assert!((3.0..=10.0).contains(&secs), "settled in {secs}s");
+}
+
+#[test]
+fn loud_input_is_lowered_slowly() {
+    let secs = settle_secs(-6.0).expect("never settled");
+    assert!((2.0..=10.0).contains(&secs), "settled in {secs}s");
+}
+
+#[test]
+fn gain_stays_within_bounds() {
+    let mut quiet = AutoGain::new();
+    let mut loud = AutoGain::new();
+    for _ in 0..1000 {
+        quiet.update(10f32.powf(-68.0 / 20.0), 0.1);
+        loud.update(1.0, 0.1);
+    }
+    assert!(quiet.gain_db() <= agc::MAX_GAIN_DB && quiet.gain_db() > agc::MAX_GAIN_DB - 0.1);
+    assert!(loud.gain_db() >= agc::MIN_GAIN_DB && loud.gain_db() < -19.9);
+}
+
+#[test]
+fn silence_does_not_pump_the_gain() {
+    let mut agc = AutoGain::new();
+    for _ in 0..100 {
+        agc.update(0.0, 0.1);
+    }
+    assert_eq!(agc.gain_db(), 0.0);
+}
+
+#[test]
+fn disabled_leaves_the_input_alone() {
+    let mut agc = AutoGain::new();
+    for _ in 0..50 {
+        agc.update(0.01, 0.1);
+    }
+    assert!(agc.gain_db() > 10.0);
+    agc.set_enabled(false);
+    assert!(!agc.is_enabled());
+    let input = sine_block(-40.0);
+    let mut samples = input.clone();
+    agc.apply(&mut samples, 1, RATE);
+    assert_eq!(samples, input);
+    assert_eq!(agc.gain(), 1.0);
+}
+
+#[test]
+fn too_low_needs_to_last() {
+    let mut monitor = LevelMonitor::new();
+    for _ in 0..19 {
+        assert_eq!(monitor.update(-60.0, 0.1), None);
+    }
+    assert_eq!(monitor.update(-60.0, 0.1), Some(LevelWarning::TooLow));
+    // One normal block ends it
+    assert_eq!(monitor.update(-20.0, 0.1), None);
+}
+
+#[test]
+fn clipping_needs_to_last_and_wins() {
+    let mut monitor = LevelMonitor::new();
+    for _ in 0..30 {
+        monitor.update(-60.0, 0.1);
+    }
+    assert_eq!(monitor.warning(), Some(LevelWarning::TooLow));
+    for _ in 0..4 {
+        assert_ne!(monitor.update(-1.0, 0.1), Some(LevelWarning::Clipping));
+    }
+    assert_eq!(monitor.update(-1.0, 0.1), Some(LevelWarning::Clipping));
+}
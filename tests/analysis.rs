@@ -0,0 +1,258 @@
+use gruvberry::analysis::{self, Analyzer, BandTable, FreqRange, Rebuilds};
+use gruvberry::tilt::Tilt;
+use proptest::prelude::*;
+
+const SAMPLE_RATES: [u32; 11] = [8000, 11025, 16000, 22050, 32000, 44100, 48000, 88200, 96000, 176400, 192000];
+
+// Parameter sets that broke the aggregation before: bands narrower than a
+// bin, which used to come out empty and stuck at zero, at the extremes of
+// rate, size and band count
+const CORPUS: [(u32, usize, usize); 6] = [
+    (8000, 256, 512),
+    (8000, 8192, 512),
+    (44100, 1024, 512),
+    (192000, 256, 8),
+    (192000, 256, 512),
+    (192000, 8192, 8),
+];
+
+fn parameters() -> impl Strategy<Value = (u32, usize, usize)> {
+    (prop::sample::select(SAMPLE_RATES.to_vec()), 8u32..=13, 8usize..=512)
+        .prop_map(|(rate, log_size, bands)| (rate, 1 << log_size, bands))
+}
+
+fn tilts() -> impl Strategy<Value = Tilt> {
+    prop_oneof![Just(Tilt::Flat), (-6.0f32..6.0).prop_map(Tilt::Slope)]
+}
+
+// Every band draws on at least one bin, and only on bins below Nyquist
+// other than DC
+fn check_bins(table: &BandTable, fft_size: usize, num_bands: usize) -> Result<(), TestCaseError> {
+    prop_assert_eq!(table.len(), num_bands);
+    prop_assert_eq!(table.gains.len(), num_bands);
+    for (band, weights) in table.bins.iter().enumerate() {
+        prop_assert!(!weights.is_empty(), "band {} has no bins", band);
+        for &(bin, weight) in weights {
+            prop_assert!((1..fft_size / 2).contains(&bin), "band {} uses bin {}", band, bin);
+            prop_assert!(weight.is_finite() && weight >= 0.0);
+        }
+    }
+    Ok(())
+}
+
+// A band's weights add up to its tilt gain, so untilted bands are a mean of
+// their bins: a flat spectrum keeps its level and no band gains or loses
+// energy along the way
+fn check_level(table: &BandTable, fft_size: usize) -> Result<(), TestCaseError> {
+    for (weights, gain) in table.bins.iter().zip(&table.gains) {
+        let sum: f32 = weights.iter().map(|&(_, weight)| weight).sum();
+        prop_assert!((sum - gain).abs() <= gain * 1e-4, "weights sum to {} for gain {}", sum, gain);
+    }
+    let flat = vec![1.0; fft_size / 2];
+    for (level, gain) in table.aggregate(&flat).iter().zip(&table.gains) {
+        prop_assert!((level - gain).abs() <= gain * 1e-4);
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn bands_only_use_bins_below_nyquist((rate, fft_size, bands) in parameters(), tilt in tilts()) {
+        let table = BandTable::new(rate, fft_size, bands, &tilt);
+        check_bins(&table, fft_size, bands)?;
+    }
+
+    #[test]
+    fn aggregation_conserves_level((rate, fft_size, bands) in parameters(), tilt in tilts()) {
+        let table = BandTable::new(rate, fft_size, bands, &tilt);
+        check_level(&table, fft_size)?;
+    }
+
+    #[test]
+    fn untilted_bands_stay_within_their_bins(
+        (rate, fft_size, bands) in parameters(),
+        magnitudes in prop::collection::vec(0.0f32..10.0, 4096),
+    ) {
+        let table = BandTable::new(rate, fft_size, bands, &Tilt::Flat);
+        let magnitudes: Vec<f32> = magnitudes.iter().cycle().take(fft_size / 2).copied().collect();
+        for (level, weights) in table.aggregate(&magnitudes).iter().zip(&table.bins) {
+            let (low, high) = weights.iter().fold((f32::MAX, f32::MIN), |(low, high), &(bin, _)| {
+                (low.min(magnitudes[bin]), high.max(magnitudes[bin]))
+            });
+            prop_assert!(*level >= low - 1e-3 && *level <= high + 1e-3, "{} outside {}..{}", level, low, high);
+        }
+    }
+}
+
+#[test]
+fn corpus_of_past_failures() {
+    for (rate, fft_size, bands) in CORPUS {
+        for tilt in [Tilt::Flat, Tilt::Slope(3.0)] {
+            let table = BandTable::new(rate, fft_size, bands, &tilt);
+            check_bins(&table, fft_size, bands).unwrap_or_else(|e| panic!("{rate} Hz, {fft_size}, {bands} bands: {e}"));
+            check_level(&table, fft_size).unwrap_or_else(|e| panic!("{rate} Hz, {fft_size}, {bands} bands: {e}"));
+        }
+    }
+}
+
+fn rebuilds(fft: u32, band_table: u32, bands: u32) -> Rebuilds {
+    Rebuilds { fft, band_table, bands }
+}
+
+#[test]
+fn a_resize_rebuilds_only_the_band_table_and_buffers() {
+    let window = vec![0.1; 1024];
+    let mut analyzer = Analyzer::new(44100, 1024, 0.3, Tilt::Flat);
+    assert_eq!(analyzer.state().rebuilds(), rebuilds(1, 0, 0));
+    analyzer.process(&window, 60);
+    analyzer.process(&window, 60);
+    assert_eq!(analyzer.state().rebuilds(), rebuilds(1, 1, 1));
+    analyzer.process(&window, 96);
+    assert_eq!(analyzer.state().rebuilds(), rebuilds(1, 2, 2));
+    assert_eq!(analyzer.process(&window, 96).len(), 96);
+}
+
+#[test]
+fn a_settings_change_rebuilds_what_depends_on_it() {
+    let window = vec![0.1; 2048];
+    let mut analyzer = Analyzer::new(44100, 1024, 0.3, Tilt::Flat);
+    analyzer.process(&window, 60);
+    let check = |analyzer: &mut Analyzer, expected: Rebuilds| {
+        analyzer.process(&window, 60);
+        assert_eq!(analyzer.state().rebuilds(), expected);
+    };
+
+    // Nothing that the tables or plan depend on
+    analyzer.set_smoothing(0.7);
+    analyzer.set_tilt(Tilt::Flat);
+    analyzer.set_range(analyzer.range());
+    check(&mut analyzer, rebuilds(1, 1, 1));
+    // The band table only
+    analyzer.set_tilt(Tilt::Slope(3.0));
+    check(&mut analyzer, rebuilds(1, 2, 1));
+    analyzer.set_range(FreqRange { low: 100.0, high: 1600.0 });
+    check(&mut analyzer, rebuilds(1, 3, 1));
+    analyzer.set_sample_rate(48000);
+    check(&mut analyzer, rebuilds(1, 4, 1));
+    analyzer.set_sample_rate(48000);
+    check(&mut analyzer, rebuilds(1, 4, 1));
+    // A new FFT size needs a new plan and a table for its bins
+    analyzer.set_fft_size(2048);
+    check(&mut analyzer, rebuilds(2, 5, 1));
+    assert_eq!(analyzer.magnitudes().len(), 1024);
+    analyzer.set_fft_size(2048);
+    check(&mut analyzer, rebuilds(2, 5, 1));
+}
+
+#[test]
+fn six_db_of_visual_gain_doubles_the_bars_below_full_height() {
+    let levels: Vec<f32> = (0..=10).map(|i| i as f32 * 4.0).collect();
+    let mut louder = levels.clone();
+    analysis::apply_visual_gain(&mut louder, 6.0);
+    for (&level, &shown) in levels.iter().zip(&louder) {
+        assert!((shown - 2.0 * level).abs() <= 0.01 * level, "{} -> {}", level, shown);
+    }
+    // And halves them going the other way
+    let mut quieter = levels.clone();
+    analysis::apply_visual_gain(&mut quieter, -6.0);
+    for (&level, &shown) in levels.iter().zip(&quieter) {
+        assert!((shown - level / 2.0).abs() <= 0.01 * level);
+    }
+}
+
+#[test]
+fn visual_gain_saturates_at_full_height_and_its_limits() {
+    // 6 dB is a factor of 1.995: just over half height reaches the top
+    let mut levels = vec![30.0, 51.0, 60.0, 100.0];
+    analysis::apply_visual_gain(&mut levels, 6.0);
+    assert!((levels[0] - 60.0).abs() < 0.3);
+    assert_eq!(&levels[1..], &[100.0, 100.0, 100.0]);
+
+    // Past the limit counts as the limit
+    let mut clamped = vec![1.0];
+    analysis::apply_visual_gain(&mut clamped, 60.0);
+    let mut limit = vec![1.0];
+    analysis::apply_visual_gain(&mut limit, analysis::MAX_VISUAL_GAIN_DB);
+    assert_eq!(clamped, limit);
+    assert!((limit[0] - 15.85).abs() < 0.01);
+
+    // No gain leaves normalized levels as they were
+    let normalized = analysis::normalize(&[0.5, 2.0, 4.0], 4.0);
+    let mut unchanged = normalized.clone();
+    analysis::apply_visual_gain(&mut unchanged, 0.0);
+    assert_eq!(unchanged, normalized);
+}
+
+// A full-scale sine `cycles` times over a window of `len` samples
+fn sine(cycles: f32, len: usize) -> Vec<f32> {
+    (0..len).map(|i| (2.0 * std::f32::consts::PI * cycles * i as f32 / len as f32).sin()).collect()
+}
+
+#[test]
+fn a_full_scale_sine_reads_0_db_through_either_window() {
+    for window in [analysis::Window::Rectangular, analysis::Window::Hann] {
+        let mut analyzer = Analyzer::new(48000, 1024, 1.0, Tilt::Flat);
+        analyzer.set_window(window);
+        // Low enough that bands are narrower than a bin and read it alone
+        analyzer.process(&sine(8.0, 1024), 256);
+        let loudest = analyzer.levels_db().into_iter().fold(f32::NEG_INFINITY, f32::max);
+        assert!(loudest.abs() < 1.0, "{:?}: {} dB", window, loudest);
+    }
+}
+
+#[test]
+fn a_hann_window_keeps_a_tone_between_bins_out_of_distant_bands() {
+    // Halfway between two bins, the worst case for leakage
+    let samples = sine(64.5, 1024);
+    let lowest = |window| {
+        let mut analyzer = Analyzer::new(48000, 1024, 1.0, Tilt::Flat);
+        analyzer.set_window(window);
+        analyzer.process(&samples, 64);
+        analyzer.levels_db().into_iter().fold(f32::INFINITY, f32::min)
+    };
+    let (rectangular, hann) = (lowest(analysis::Window::Rectangular), lowest(analysis::Window::Hann));
+    assert!(hann < rectangular - 30.0, "rectangular {} dB, Hann {} dB", rectangular, hann);
+}
+
+#[test]
+fn peak_labels_find_both_tones_of_a_chord_within_a_percent() {
+    const RATE: u32 = 44100;
+    const FFT_SIZE: usize = 4096;
+    // A4 and E7, neither on a bin center, the upper one quieter
+    let tones = [(440.0, 0.5), (2637.02, 0.3)];
+    let samples: Vec<f32> = (0..FFT_SIZE)
+        .map(|i| {
+            let t = i as f32 / RATE as f32;
+            tones.iter().map(|(freq, amplitude)| amplitude * (std::f32::consts::TAU * freq * t).sin()).sum()
+        })
+        .collect();
+    for window in [analysis::Window::Rectangular, analysis::Window::Hann] {
+        let mut analyzer = Analyzer::new(RATE, FFT_SIZE, 1.0, Tilt::Flat);
+        analyzer.set_window(window);
+        analyzer.process(&samples, 64);
+        let peaks = analyzer.peaks(4);
+        assert!(peaks.len() >= 2, "{:?}: {:?}", window, peaks);
+        for ((freq, _), peak) in tones.iter().zip(&peaks) {
+            let error = (peak.freq - freq).abs() / freq;
+            assert!(error < 0.01, "{:?}: {} Hz labeled {} Hz", window, freq, peak.freq);
+        }
+    }
+}
+
+// A bin goes to the band its center frequency falls in, so the tallest bar
+// under a tone is the band its peak label names
+#[test]
+fn a_tone_lights_the_band_its_frequency_falls_in() {
+    const FFT_SIZE: usize = 1024;
+    const BANDS: usize = 32;
+    let mut analyzer = Analyzer::new(48000, FFT_SIZE, 1.0, Tilt::Flat);
+    for bin in 2..FFT_SIZE / 2 - 1 {
+        // On a bin center, so the tone is in that bin alone
+        analyzer.process(&sine(bin as f32, FFT_SIZE), BANDS);
+        let levels = analyzer.levels_db();
+        let tallest = (0..BANDS).fold(0, |best, band| if levels[band] > levels[best] { band } else { best });
+        let freq = bin as f32 * 48000.0 / FFT_SIZE as f32;
+        let named = analysis::band_of(freq, BANDS, analyzer.max_freq());
+        assert_eq!(tallest, named, "bin {} ({} Hz)", bin, freq);
+    }
+}
@@ -0,0 +1,334 @@
+use gruvberry::analysis::{self, Analyzer};
+use gruvberry::capture::{self, SampleCapture, SampleRing};
+use gruvberry::tilt::Tilt;
+use proptest::prelude::*;
+use rodio::buffer::SamplesBuffer;
+
+const RATE: u32 = 48000;
+const FFT_SIZE: usize = 1024;
+const BANDS: usize = 48;
+// Samples pulled per displayed frame, as at 60 fps
+const FRAME_LEN: usize = 800;
+// Where the bad samples are, a second and a half in
+const BURST: std::ops::Range<usize> = 72_000..72_012;
+
+fn sine(len: usize) -> Vec<f32> {
+    (0..len).map(|i| 0.5 * (std::f32::consts::TAU * 1000.0 * i as f32 / RATE as f32).sin()).collect()
+}
+
+// Play `samples` through a tap into a ring, analyzing the newest window
+// after each frame's worth the way the visualizer does; the drawn bands of
+// every frame, and the ring's count of replaced samples
+fn display(samples: Vec<f32>) -> (Vec<Vec<f32>>, u64) {
+    let ring = SampleRing::shared(FFT_SIZE * 2);
+    let mut tap = SampleCapture::new(SamplesBuffer::new(1, RATE, samples), ring.clone());
+    let mut analyzer = Analyzer::new(RATE, FFT_SIZE, 0.3, Tilt::Flat);
+    let mut frames = Vec::new();
+    loop {
+        let pulled = tap.by_ref().take(FRAME_LEN).count();
+        tap.flush();
+        if pulled < FRAME_LEN {
+            break;
+        }
+        let Some(window) = ring.lock().unwrap().window(FFT_SIZE, 0) else {
+            continue;
+        };
+        let bands = analyzer.process(&window, BANDS).to_vec();
+        frames.push(analysis::normalize(&bands, analysis::max_amplitude(&bands)));
+    }
+    let non_finite = ring.lock().unwrap().non_finite();
+    (frames, non_finite)
+}
+
+#[test]
+fn the_display_recovers_from_bad_samples() {
+    let (clean, none) = display(sine(RATE as usize * 3));
+    assert_eq!(none, 0);
+
+    let mut samples = sine(RATE as usize * 3);
+    for (i, sample) in samples[BURST].iter_mut().enumerate() {
+        *sample = [f32::NAN, f32::INFINITY, f32::NEG_INFINITY][i % 3];
+    }
+    let (frames, replaced) = display(samples);
+    assert_eq!(replaced, BURST.len() as u64);
+    assert_eq!(frames.len(), clean.len());
+
+    // Every frame has something to draw, and a few frames after the burst
+    // has left the window the bars are back to what they would have been
+    let settled = (BURST.end + FFT_SIZE) / FRAME_LEN + 3;
+    for (i, (frame, clean)) in frames.iter().zip(&clean).enumerate() {
+        assert!(frame.iter().all(|level| level.is_finite()), "frame {}: {:?}", i, frame);
+        if i >= settled {
+            let off = frame.iter().zip(clean).map(|(a, b)| (a - b).abs()).fold(0.0f32, f32::max);
+            assert!(off < 5.0, "frame {} is {} off", i, off);
+        }
+    }
+}
+
+#[test]
+fn played_samples_pass_unchanged() {
+    let samples = vec![0.25, f32::NAN, -0.5, f32::INFINITY];
+    let ring = SampleRing::shared(8);
+    let played: Vec<f32> = SampleCapture::new(SamplesBuffer::new(1, RATE, samples), ring.clone()).collect();
+    assert_eq!(played[0], 0.25);
+    assert!(played[1].is_nan());
+    assert_eq!(played[3], f32::INFINITY);
+    // Only the copy for the analysis is cleaned up
+    assert_eq!(ring.lock().unwrap().window(4, 0), Some(vec![0.25, 0.0, -0.5, 0.0]));
+    assert_eq!(ring.lock().unwrap().non_finite(), 2);
+}
+
+#[test]
+fn the_analyzer_shrugs_off_bad_samples() {
+    let mut analyzer = Analyzer::new(RATE, FFT_SIZE, 0.3, Tilt::Flat);
+    let clean = sine(FFT_SIZE);
+    let mut bad = clean.clone();
+    bad[100] = f32::NAN;
+    bad[200] = f32::INFINITY;
+    assert!(analyzer.process(&bad, BANDS).iter().all(|band| band.is_finite()));
+    let recovered = analyzer.process(&clean, BANDS).to_vec();
+    assert!(recovered.iter().all(|band| band.is_finite()));
+    assert!(analysis::max_amplitude(&recovered) > 1.0);
+}
+
+#[test]
+fn normalization_ignores_bands_that_are_not_numbers() {
+    let bands = [f32::NAN, 20.0, f32::INFINITY, 40.0];
+    assert_eq!(analysis::max_amplitude(&bands), 40.0);
+    assert_eq!(analysis::normalize(&bands, 40.0), vec![0.0, 50.0, 0.0, 100.0]);
+    assert_eq!(analysis::max_amplitude(&[f32::NAN; 3]), 1.0);
+}
+
+#[test]
+fn large_windows_get_full_frames() {
+    for fft_size in [1024, 4096, 16384] {
+        // The visualizer's automatic size: up to 1.5 s of latency compensation
+        let capacity = capture::ring_capacity(fft_size, RATE, 1500);
+        assert!(capture::check_capacity(capacity, fft_size).is_ok());
+        let ring = SampleRing::shared(capacity);
+        let samples: Vec<f32> = (0..RATE as usize * 3).map(|i| i as f32).collect();
+        let mut tap = SampleCapture::new(SamplesBuffer::new(1, RATE, samples), ring.clone());
+        let mut full = 0;
+        while tap.by_ref().take(FRAME_LEN).count() == FRAME_LEN {
+            tap.flush();
+            let ring = ring.lock().unwrap();
+            let offset = capture::offset_samples(1500, RATE);
+            if let Some(window) = ring.window(fft_size, offset) {
+                // Consecutive samples, all of them
+                assert_eq!(window.len(), fft_size);
+                assert!(window.windows(2).all(|pair| pair[1] == pair[0] + 1.0));
+                full += 1;
+            }
+        }
+        // Every frame once the ring has reached back far enough
+        let expected = (RATE as usize * 3 - (fft_size + capture::offset_samples(1500, RATE))) / FRAME_LEN;
+        assert!(full >= expected, "{}: {} full frames of {}", fft_size, full, expected);
+    }
+}
+
+#[test]
+fn a_ring_smaller_than_the_window_is_refused() {
+    assert!(capture::check_capacity(4096, 4096).is_ok());
+    let error = capture::check_capacity(2048, 4096).unwrap_err();
+    assert!(error.contains("2048") && error.contains("4096"), "{}", error);
+    // Such a ring indeed never fills a window
+    let mut ring = SampleRing::new(2048);
+    for i in 0..10_000 {
+        ring.push(i as f32);
+    }
+    assert_eq!(ring.window(4096, 0), None);
+}
+
+#[test]
+fn reach_is_what_is_left_after_the_window() {
+    assert_eq!(capture::reach_ms(4096 + 48_000, 4096, RATE), 1000);
+    assert_eq!(capture::reach_ms(2048, 4096, RATE), 0);
+    let capacity = capture::ring_capacity(4096, RATE, 1500);
+    assert!(capture::reach_ms(capacity, 4096, RATE) >= 1500);
+}
+
+#[test]
+fn a_negative_offset_takes_back_the_device_latency_and_no_more() {
+    // 40 ms of device latency less 15 ms
+    assert_eq!(capture::offset_samples(40 - 15, RATE), 1200);
+    assert_eq!(capture::min_offset_ms(40), -40);
+    assert_eq!(capture::offset_samples(40 + capture::min_offset_ms(40), RATE), 0);
+    // Below that the newest window is as early as it gets
+    assert_eq!(capture::offset_samples(40 - 100, RATE), 0);
+    assert_eq!(capture::min_offset_ms(-5), 0);
+}
+
+#[test]
+fn frame_times_follow_the_samples_played_through_a_seek_and_an_underrun() {
+    use rodio::Source;
+    use std::time::Duration;
+
+    let ring = SampleRing::shared(FFT_SIZE * 2);
+    let mut tap = SampleCapture::new(SamplesBuffer::new(2, RATE, sine(RATE as usize * 20)), ring.clone());
+    // Where the samples pulled so far put playback, from the last seek
+    let (mut origin, mut played) = (0.0f64, 0usize);
+    let mut times = Vec::new();
+    for frame in 0..90 {
+        if frame == 30 {
+            tap.try_seek(Duration::from_secs(4)).unwrap();
+            (origin, played) = (4.0, 0);
+        }
+        // The device takes nothing for a while: an underrun
+        let pull = if (50..60).contains(&frame) { 0 } else { FRAME_LEN * 2 };
+        played += tap.by_ref().take(pull).count() / 2;
+        tap.flush();
+
+        let ring = ring.lock().unwrap();
+        let expected = origin + played as f64 / RATE as f64;
+        assert!((ring.time() as f64 - expected).abs() < 1e-4, "frame {}", frame);
+        if let Some(time) = ring.window_time(FFT_SIZE, 0) {
+            assert_eq!(time, ring.time());
+            // A window further back ends that much earlier
+            let back = ring.window_time(FFT_SIZE, 480).unwrap();
+            assert!((time - back - 0.01).abs() < 1e-4);
+            times.push(time);
+        }
+    }
+    // Forward through the seek, and standing still through the underrun
+    assert!(times.windows(2).all(|pair| pair[1] >= pair[0]));
+    assert!(times.windows(2).filter(|pair| pair[1] == pair[0]).count() >= 9);
+    let last = origin + played as f64 / RATE as f64;
+    assert!((*times.last().unwrap() as f64 - last).abs() < 1e-4);
+}
+
+#[test]
+fn the_time_carries_on_at_a_new_rate() {
+    use gruvberry::capture::SampleObserver;
+
+    let mut ring = SampleRing::new(FFT_SIZE);
+    assert_eq!(ring.time(), 0.0);
+    assert_eq!(ring.window_time(FFT_SIZE, 0), None);
+    ring.on_samples(&vec![0.0; RATE as usize], 1, RATE);
+    assert!((ring.time() - 1.0).abs() < 1e-6);
+    // Half a second more at 44.1 kHz, in stereo
+    ring.on_samples(&vec![0.0; 44100], 2, 44100);
+    assert!((ring.time() - 1.5).abs() < 1e-6);
+    assert!((ring.window_time(FFT_SIZE, 0).unwrap() - 1.5).abs() < 1e-6);
+}
+
+// Keeps every sample it is handed
+#[derive(Default)]
+struct Collect(Vec<f32>);
+
+impl capture::SampleObserver for Collect {
+    fn on_samples(&mut self, samples: &[f32], _channels: u16, _sample_rate: u32) {
+        self.0.extend_from_slice(samples);
+    }
+}
+
+// A million samples of noise in [-1, 1), the same for the same seed
+fn noise(seed: u64) -> Vec<f32> {
+    let mut state = seed | 1;
+    (0..1_000_000)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+        })
+        .collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn the_tap_changes_nothing_in_a_million_samples(seed: u64, channels in 1u16..=8, rate in 8000u32..=192_000) {
+        use rodio::Source;
+
+        let samples = noise(seed);
+        let mut tap = SampleCapture::new(SamplesBuffer::new(channels, rate, samples.clone()), Collect::default());
+        let untapped = SamplesBuffer::new(channels, rate, samples.clone());
+        prop_assert_eq!(tap.channels(), channels);
+        prop_assert_eq!(tap.sample_rate(), rate);
+        prop_assert_eq!(tap.total_duration(), untapped.total_duration());
+        prop_assert_eq!(tap.current_span_len(), untapped.current_span_len());
+
+        let played: Vec<f32> = tap.by_ref().collect();
+        prop_assert!(played == samples, "the played samples differ");
+        // The observer saw the same samples, no more and no fewer
+        prop_assert!(tap.observer().0 == samples, "the observed samples differ");
+    }
+}
+
+// The tap sees a 16-bit WAV exactly as it sees the same samples given as
+// f32, so it can sit right on the decoder
+#[test]
+fn integer_and_float_sources_are_captured_alike() {
+    let path = std::env::temp_dir().join(format!("gruvberry-capture-{}.wav", std::process::id()));
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    // Both extremes, zero, and a tone in between
+    let mut integers = vec![i16::MIN, i16::MAX, 0, -1, 1, 0];
+    integers.extend(sine(RATE as usize).iter().map(|s| (s * i16::MAX as f32) as i16));
+    for sample in &integers {
+        writer.write_sample(*sample).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let decoder = rodio::Decoder::new(std::io::BufReader::new(std::fs::File::open(&path).unwrap())).unwrap();
+    let mut from_file = SampleCapture::new(decoder, Collect::default());
+    let played_from_file: Vec<f32> = from_file.by_ref().collect();
+    std::fs::remove_file(&path).unwrap();
+
+    let floats: Vec<f32> = integers.iter().map(|&s| s as f32 / 32768.0).collect();
+    let mut from_floats = SampleCapture::new(SamplesBuffer::new(2, RATE, floats.clone()), Collect::default());
+    let played_from_floats: Vec<f32> = from_floats.by_ref().collect();
+
+    assert_eq!(from_file.observer().0.len(), integers.len());
+    assert!(from_file.observer().0 == from_floats.observer().0, "the captured samples differ");
+    assert!(from_file.observer().0 == floats);
+    assert_eq!(&from_file.observer().0[..3], &[-1.0, 32767.0 / 32768.0, 0.0]);
+    assert!(played_from_file == played_from_floats);
+}
+
+// Two fixtures queued back to back as a gapless playlist plays them, each
+// through its own tap into the one ring carried from track to track (as
+// when the rates match). Every sample is its own index in the pair, so a
+// gap or a repeat at the seam shows. Neither length is a whole number of
+// batches, so the first track's last batch is a short one.
+#[test]
+fn the_seam_between_two_tracks_has_no_gap_and_no_repeat() {
+    let (first, second) = (30_001, 20_003);
+    let sample = |i: usize| (i + 1) as f32 / 100_000.0;
+    let ring = SampleRing::shared(FFT_SIZE * 2);
+    let (queue, mut output) = rodio::queue::queue(false);
+    queue.append(SampleCapture::new(SamplesBuffer::new(1, RATE, (0..first).map(sample).collect::<Vec<_>>()), ring.clone()));
+    queue.append(SampleCapture::new(
+        SamplesBuffer::new(1, RATE, (first..first + second).map(sample).collect::<Vec<_>>()),
+        ring.clone(),
+    ));
+
+    let mut played = Vec::new();
+    let mut windows = 0;
+    loop {
+        let pulled = output.by_ref().take(FRAME_LEN).collect::<Vec<_>>();
+        played.extend_from_slice(&pulled);
+        // What was analyzed is the newest window of what was heard, up to the
+        // last full batch of the track playing (all of it once it has ended),
+        // also in the frames where the seam is in it
+        let played_in = |start: usize| start + (played.len() - start) / capture::BATCH_LEN * capture::BATCH_LEN;
+        let end = match played.len() {
+            n if n == first + second => n,
+            n if n > first => played_in(first),
+            _ => played_in(0),
+        };
+        if let Some(window) = ring.lock().unwrap().window(FFT_SIZE, 0) {
+            assert_eq!(window, (end - FFT_SIZE..end).map(sample).collect::<Vec<_>>(), "window ending at {}", end);
+            windows += 1;
+        }
+        if pulled.len() < FRAME_LEN {
+            break;
+        }
+    }
+    assert_eq!(played, (0..first + second).map(sample).collect::<Vec<_>>());
+    assert_eq!(windows, (first + second) / FRAME_LEN);
+}
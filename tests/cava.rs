@@ -0,0 +1,100 @@
+// The output is a FIFO, which only Unix has
+#![cfg(unix)]
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+use gruvberry::cava::{self, BitFormat};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("gruvberry-cava-{}-{}.fifo", std::process::id(), name))
+}
+
+// A consumer the way cava's raw readers are written: fixed-size frames of
+// native-endian u16 (or u8) values, one per bar, nothing in between
+fn read_frames(path: &Path, bars: usize, format: BitFormat, frames: usize) -> Vec<Vec<u16>> {
+    let mut fifo = File::open(path).unwrap();
+    let mut frame = vec![0; bars * format.bytes()];
+    (0..frames)
+        .map(|_| {
+            fifo.read_exact(&mut frame).unwrap();
+            match format {
+                BitFormat::Bit8 => frame.iter().map(|&b| b as u16).collect(),
+                BitFormat::Bit16 => frame.chunks_exact(2).map(|b| u16::from_ne_bytes([b[0], b[1]])).collect(),
+            }
+        })
+        .collect()
+}
+
+// Read on another thread, so a writer that never shows up fails the test
+// instead of hanging it
+fn read_frames_within(path: &Path, bars: usize, format: BitFormat, frames: usize) -> Vec<Vec<u16>> {
+    let (sender, receiver) = mpsc::channel();
+    let path = path.to_path_buf();
+    std::thread::spawn(move || sender.send(read_frames(&path, bars, format, frames)));
+    receiver.recv_timeout(Duration::from_secs(10)).expect("no frames from the output")
+}
+
+// Feed `bands` to the output every few milliseconds until the flag drops
+fn feed(output: cava::CavaOutput, bands: Vec<f32>) -> Arc<AtomicBool> {
+    let running = Arc::new(AtomicBool::new(true));
+    let flag = running.clone();
+    std::thread::spawn(move || {
+        while flag.load(Ordering::Relaxed) {
+            output.send(&bands);
+            std::thread::sleep(Duration::from_millis(2));
+        }
+    });
+    running
+}
+
+#[test]
+fn frames_hold_one_value_per_bar() {
+    let bands = [0.0, 25.0, 50.0, 100.0];
+    assert_eq!(cava::encode(&bands, 4, BitFormat::Bit8), vec![0, 64, 128, 255]);
+    let frame = cava::encode(&bands, 4, BitFormat::Bit16);
+    assert_eq!(frame.len(), 8);
+    assert_eq!(&frame[6..], &u16::MAX.to_ne_bytes());
+    assert_eq!(&frame[2..4], &16384u16.to_ne_bytes());
+}
+
+#[test]
+fn frames_are_resampled_to_the_bar_count() {
+    for bars in [1, 7, 64, 200, cava::MAX_BARS] {
+        assert_eq!(cava::encode(&[50.0; 60], bars, BitFormat::Bit16).len(), bars * 2);
+        assert_eq!(cava::encode(&[], bars, BitFormat::Bit8), vec![0; bars]);
+    }
+    // Out of range and not-a-number levels stay within the format
+    assert_eq!(cava::encode(&[-20.0, 180.0, f32::NAN], 3, BitFormat::Bit8), vec![0, 255, 0]);
+}
+
+#[test]
+fn a_reader_gets_whole_frames() {
+    let path = temp_path("reader");
+    let output = cava::start(&path, 16, BitFormat::Bit16).unwrap();
+    let running = feed(output, vec![50.0; 40]);
+    for frame in read_frames_within(&path, 16, BitFormat::Bit16, 50) {
+        assert_eq!(frame, vec![32768; 16]);
+    }
+    running.store(false, Ordering::Relaxed);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn waits_for_the_next_reader_when_one_goes_away() {
+    let path = temp_path("reconnect");
+    let output = cava::start(&path, 8, BitFormat::Bit8).unwrap();
+    let running = feed(output, vec![100.0; 8]);
+    for _ in 0..3 {
+        // Each reader hangs up after a few frames; the next still gets them
+        let frames = read_frames_within(&path, 8, BitFormat::Bit8, 5);
+        assert!(frames.iter().all(|frame| frame == &vec![255; 8]));
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    running.store(false, Ordering::Relaxed);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,235 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use gruvberry::config::{self, Config};
+
+// A config file in the temp directory holding `text`
+fn file(name: &str, text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("gruvberry-config-{}-{}.toml", std::process::id(), name));
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+fn env(vars: &[(&str, &str)]) -> Vec<(OsString, OsString)> {
+    vars.iter().map(|(name, value)| (OsString::from(name), OsString::from(value))).collect()
+}
+
+// Run gruvberry with `text` as its config file and `args`, on a file that
+// doesn't exist, so nothing gets as far as the audio device or the terminal;
+// the config file's path and what came out
+fn run(name: &str, text: &str, args: &[&str]) -> (PathBuf, Output) {
+    let path = file(name, text);
+    let output = Command::new(env!("CARGO_BIN_EXE_gruvberry"))
+        .arg("--config")
+        .arg(&path)
+        .args(args)
+        .arg("no-such-file.wav")
+        .env_clear()
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    (path, output)
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+// A bad value is reported with the file, the line number, the line itself
+// and what would be accepted, before anything else happens
+#[test]
+fn a_bad_value_points_at_its_line() {
+    for (name, text, line, message) in [
+        (
+            "smoothing",
+            "fps = 30\nsmoothing = 2.5\n",
+            "2:\n    smoothing = 2.5\n",
+            "smoothing must be above 0.0 and at most 1.0 (0.3 = smooth, 1.0 = off), got 2.5",
+        ),
+        (
+            "fft_size",
+            "# sizes\n\nfft_size = 1000\n",
+            "3:\n    fft_size = 1000\n",
+            "fft_size must be a power of two from 256 to 16384 (e.g. 1024, 2048, 4096), got 1000",
+        ),
+    ] {
+        let (path, output) = run(name, text, &[]);
+        let stderr = stderr(&output);
+        assert_eq!(output.status.code(), Some(1), "{}: {}", name, stderr);
+        assert!(output.stdout.is_empty(), "{}", name);
+        let expected = format!("error in config file {} at line {}  {}\n", path.display(), line, message);
+        assert_eq!(stderr, expected, "{}", name);
+    }
+}
+
+// A profile's section is only read when it is picked, and then its mistakes
+// are reported like the rest of the file's
+#[test]
+fn a_bad_value_in_a_profile_counts_once_the_profile_is_picked() {
+    let text = "fps = 30\n\n[profile.couch]\nfps = 500\n";
+    let (_, output) = run("unpicked", text, &[]);
+    assert!(!stderr(&output).contains("fps"), "{}", stderr(&output));
+
+    let (path, output) = run("picked", text, &["--profile", "couch"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        format!(
+            "error in config file {} at line 4:\n    fps = 500\n  fps must be a whole number from 1 to 240, got 500\n",
+            path.display()
+        )
+    );
+}
+
+#[test]
+fn a_syntax_error_gives_the_line_and_column() {
+    let (path, output) = run("syntax", "fps = 30\nsmoothing = = 0.3\n", &[]);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.starts_with(&format!("error in config file {}:\n", path.display())), "{}", stderr);
+    assert!(stderr.contains("at line 2, column 13"), "{}", stderr);
+    assert!(stderr.contains("2 | smoothing = = 0.3"), "{}", stderr);
+}
+
+#[test]
+fn a_section_that_is_not_a_mode_is_named() {
+    let (_, output) = run("mode", "[mode.sideways]\ntilt = \"flat\"\n", &[]);
+    let stderr = stderr(&output);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("\n    [mode.sideways]\n"), "{}", stderr);
+    assert!(stderr.ends_with("no mode named `sideways`; modes are the orientations: bottom-up, top-down, left-right, right-left\n"));
+}
+
+// Unknown keys are probably typos: a warning with the near misses, and the
+// run goes on (here to the missing audio file)
+#[test]
+fn unknown_keys_warn_with_what_was_meant() {
+    let (path, output) = run("typos", "smothing = 0.3\nfft = 1000\n", &[]);
+    let stderr = stderr(&output);
+    let path = path.display();
+    assert_eq!(
+        stderr.lines().collect::<Vec<_>>(),
+        [
+            format!("warning: unknown key `smothing` in {} (did you mean `smoothing`?)", path).as_str(),
+            format!("warning: unknown key `fft` in {} (did you mean `fft_size` or `fps`?)", path).as_str(),
+            "no-such-file.wav: No such file or directory (os error 2)",
+        ]
+    );
+}
+
+#[test]
+fn the_environment_goes_over_the_file_and_its_profile() {
+    let path = file(
+        "precedence",
+        "fps = 30\nfft_size = 2048\nsmoothing = 0.2\n\n[profile.couch]\nfps = 24\nsmoothing = 0.5\n",
+    );
+    let vars = env(&[("GRUVBERRY_FPS", "45"), ("GRUVBERRY_TILT", "\"+3dB/oct\""), ("PATH", "/bin")]);
+
+    let (file_only, _) = config::load_with_env(Some(&path), None, Vec::new()).unwrap();
+    let (profile, _) = config::load_with_env(Some(&path), Some("couch"), Vec::new()).unwrap();
+    let (all, warnings) = config::load_with_env(Some(&path), Some("couch"), vars).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!((file_only.fps, file_only.smoothing, file_only.fft_size), (30, 0.2, 2048));
+    assert_eq!((profile.fps, profile.smoothing, profile.fft_size), (24, 0.5, 2048));
+    assert_eq!((all.fps, all.smoothing, all.fft_size), (45, 0.5, 2048));
+    assert_eq!(all.tilt, gruvberry::tilt::Tilt::Slope(3.0));
+    assert_eq!(file_only.tilt, Config::default().tilt);
+    assert!(warnings.is_empty(), "{:?}", warnings);
+}
+
+#[test]
+fn a_bad_environment_variable_is_named_in_the_error() {
+    let path = file("env-error", "fft_size = 2048\n");
+    let result = config::load_with_env(Some(&path), None, env(&[("GRUVBERRY_FFT_SIZE", "1000")]));
+    std::fs::remove_file(&path).unwrap();
+    let error = result.unwrap_err();
+    assert_eq!(error.location, "environment variable GRUVBERRY_FFT_SIZE");
+    assert_eq!(error.line, None);
+    assert!(error.message.starts_with("fft_size must be a power of two"), "{}", error.message);
+}
+
+#[test]
+fn an_unknown_environment_variable_warns_with_what_was_meant() {
+    let path = file("env-typo", "");
+    let (_, warnings) = config::load_with_env(Some(&path), None, env(&[("GRUVBERRY_SMOTHING", "0.3")])).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        warnings,
+        ["warning: unknown environment variable GRUVBERRY_SMOTHING (did you mean `GRUVBERRY_SMOOTHING`?)"]
+    );
+}
+
+// Linux allows any bytes in the environment; ours that aren't UTF-8 are
+// skipped with a warning, and everyone else's aren't looked at
+#[cfg(unix)]
+#[test]
+fn variables_that_are_not_utf8_are_skipped() {
+    use std::os::unix::ffi::OsStringExt;
+
+    let path = file("env-bytes", "");
+    let vars = vec![
+        (OsString::from("GRUVBERRY_FPS"), OsString::from_vec(b"3\xff".to_vec())),
+        (OsString::from_vec(b"GRUVBERRY_\xff".to_vec()), OsString::from("1")),
+        (OsString::from_vec(b"LANG\xff".to_vec()), OsString::from_vec(b"\xff".to_vec())),
+        (OsString::from("GRUVBERRY_FFT_SIZE"), OsString::from("4096")),
+    ];
+    let (config, warnings) = config::load_with_env(Some(&path), None, vars).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!((config.fps, config.fft_size), (Config::default().fps, 4096));
+    assert_eq!(
+        warnings,
+        [
+            "warning: environment variable GRUVBERRY_FPS is ignored (not valid UTF-8)",
+            "warning: environment variable GRUVBERRY_\u{FFFD} is ignored (not valid UTF-8)",
+        ]
+    );
+
+    // And the binary gets past them to the next problem, without a panic
+    let output = Command::new(env!("CARGO_BIN_EXE_gruvberry"))
+        .arg("no-such-file.wav")
+        .env_clear()
+        .env("GRUVBERRY_FPS", OsString::from_vec(b"3\xff".to_vec()))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "warning: environment variable GRUVBERRY_FPS is ignored (not valid UTF-8)\n\
+         no-such-file.wav: No such file or directory (os error 2)\n"
+    );
+}
+
+// Flags go over the environment, which goes over the file, and a reload
+// (the file read again, the flags laid over it again) keeps it that way
+#[test]
+fn flags_go_over_everything_on_every_reload() {
+    let flags = config::Flags {
+        latency_offset_ms: Some(-40),
+        idle_timeout: Some(3.0),
+        no_screensaver: true,
+        raw: true,
+        ..config::Flags::default()
+    };
+    let vars = env(&[("GRUVBERRY_LATENCY_OFFSET_MS", "25"), ("GRUVBERRY_FPS", "50")]);
+    let load = |text: &str| {
+        let path = file("flags", text);
+        let (mut config, _) = config::load_with_env(Some(&path), None, vars.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        config.apply_flags(&flags);
+        config
+    };
+
+    let first = load("latency_offset_ms = 10\nidle_timeout = 20\nfps = 30\n");
+    let reloaded = load("latency_offset_ms = 200\nidle_timeout = 5\nscreensaver = true\nraw = false\nfps = 24\nbounce = true\n");
+    for config in [&first, &reloaded] {
+        assert_eq!(config.latency_offset_ms, -40);
+        assert_eq!(config.idle_timeout, 3.0);
+        assert!(!config.screensaver && config.raw);
+        assert_eq!(config.fps, 50);
+    }
+    // What neither gives follows the file
+    assert!(!first.bounce && reloaded.bounce);
+}
@@ -0,0 +1,193 @@
+#![cfg(unix)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+use gruvberry::analysis::FreqRange;
+use gruvberry::control::{self, PlayState, Request, Server, Update};
+use gruvberry::history::AnalysisFrame;
+
+fn socket(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("gruvberry-control-{}-{}.sock", name, std::process::id()))
+}
+
+fn answer(reader: &mut impl BufRead) -> String {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    line
+}
+
+#[test]
+fn requests_are_read_a_line_at_a_time() {
+    assert_eq!(Request::parse("add song.wav"), Ok(Request::Add("song.wav".to_string())));
+    assert_eq!(Request::parse("  add   ~/My Music/a b.wav \r"), Ok(Request::Add("~/My Music/a b.wav".to_string())));
+    assert_eq!(Request::parse("add"), Err("add needs a path".to_string()));
+    assert_eq!(Request::parse("seek -12.5"), Ok(Request::Seek(-12.5)));
+    assert_eq!(Request::parse("seek"), Err("seek needs a number of seconds, not ''".to_string()));
+    assert!(Request::parse("seek inf").is_err());
+    assert_eq!(Request::parse("pause now"), Err("pause takes nothing after it".to_string()));
+    assert_eq!(
+        Request::parse("play it"),
+        Err("unknown request 'play' (expected add, pause, seek, next, previous or follow)".to_string())
+    );
+    for request in [
+        Request::Add("a b.wav".to_string()),
+        Request::Pause,
+        Request::Seek(-0.1),
+        Request::Next,
+        Request::Previous,
+        Request::Follow,
+    ] {
+        assert_eq!(Request::parse(&request.to_line()), Ok(request));
+    }
+
+    assert_eq!(control::parse_address("unix:/tmp/gruvberry.sock"), Ok(PathBuf::from("/tmp/gruvberry.sock")));
+    assert!(control::parse_address("/tmp/gruvberry.sock").is_err());
+    assert!(control::parse_address("unix:").is_err());
+}
+
+#[test]
+fn each_request_gets_its_answer() {
+    let path = socket("answers");
+    let server = Server::bind(&path).unwrap();
+    let mut client = UnixStream::connect(&path).unwrap();
+    let mut reader = BufReader::new(client.try_clone().unwrap());
+
+    client.write_all(b"add one.wav\nadd two.wav\n").unwrap();
+    for (name, result) in [("one.wav", Ok("Queued one.wav (#1)")), ("two.wav", Err("Not added: two.wav: no such file"))] {
+        let (request, reply) = server.poll(Duration::from_secs(5)).unwrap();
+        assert_eq!(request, Request::Add(name.to_string()));
+        reply.send(result.map(str::to_string).map_err(str::to_string));
+    }
+    assert_eq!(answer(&mut reader), "ok Queued one.wav (#1)\n");
+    assert_eq!(answer(&mut reader), "error Not added: two.wav: no such file\n");
+
+    // Lines that aren't requests are answered without the visualizer
+    client.write_all(b"\nfrobnicate\nadd\n").unwrap();
+    assert_eq!(
+        answer(&mut reader),
+        "error unknown request 'frobnicate' (expected add, pause, seek, next, previous or follow)\n"
+    );
+    assert_eq!(answer(&mut reader), "error add needs a path\n");
+    assert!(server.poll(Duration::from_millis(100)).is_none());
+}
+
+#[test]
+fn a_socket_left_behind_is_taken_over_but_a_live_one_is_not() {
+    let path = socket("stale");
+    // A socket file nothing listens on any more
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+    let server = Server::bind(&path).unwrap();
+    assert!(UnixStream::connect(&path).is_ok());
+
+    let taken = Server::bind(&path).err().unwrap();
+    assert_eq!(taken.kind(), std::io::ErrorKind::AddrInUse);
+
+    drop(server);
+    assert!(!path.exists());
+}
+
+fn frame(time: f32) -> AnalysisFrame {
+    AnalysisFrame {
+        time,
+        bands: vec![0.0, 33.333332, 100.0],
+        levels_db: vec![f32::NEG_INFINITY, -18.75, 1e-7],
+        rms: 0.123_456_79,
+        sample_rate: 96000,
+        range: FreqRange { low: 20.0, high: 20000.0 },
+    }
+}
+
+#[test]
+fn updates_read_back_exactly_as_they_were_written() {
+    let updates = [
+        Update::Track {
+            name: "Artist - Title (Live).wav".to_string(),
+            duration: 245.25,
+        },
+        Update::Track {
+            name: String::new(),
+            duration: f32::INFINITY,
+        },
+        Update::State(PlayState::Playing),
+        Update::State(PlayState::Paused),
+        Update::State(PlayState::Finished),
+        Update::Frame(frame(61.016)),
+    ];
+    for update in updates {
+        assert_eq!(Update::parse(&update.to_line()), Ok(update));
+    }
+    // A name can't break the line
+    let track = Update::Track {
+        name: "one\ntwo".to_string(),
+        duration: 1.0,
+    };
+    assert_eq!(track.to_line(), "track 1 one two");
+
+    assert!(Update::parse("state stopped").is_err());
+    assert!(Update::parse("frame 1 44100 20 22050 0.5 2 1 2 3").is_err());
+    assert!(Update::parse("frame 1 44100 20 22050 loud 0").is_err());
+    assert!(Update::parse("volume 11").is_err());
+}
+
+#[test]
+fn a_follower_gets_the_state_so_far_then_every_update() {
+    let path = socket("follow");
+    let server = Server::bind(&path).unwrap();
+    // Before anyone follows: only the latest track and state are kept
+    server.publish(&Update::Track {
+        name: "first.wav".to_string(),
+        duration: 10.0,
+    });
+    server.publish(&Update::Track {
+        name: "second.wav".to_string(),
+        duration: 20.0,
+    });
+    server.publish(&Update::State(PlayState::Paused));
+    server.publish(&Update::Frame(frame(1.0)));
+    assert!(!server.is_followed());
+
+    let mut client = UnixStream::connect(&path).unwrap();
+    let mut reader = BufReader::new(client.try_clone().unwrap());
+    client.write_all(b"follow\n").unwrap();
+    assert_eq!(answer(&mut reader), "ok Following\n");
+    assert_eq!(answer(&mut reader), "track 20 second.wav\n");
+    assert_eq!(answer(&mut reader), "state paused\n");
+    assert!(server.is_followed());
+
+    // The same state again isn't sent; frames always are
+    server.publish(&Update::State(PlayState::Paused));
+    server.publish(&Update::Frame(frame(2.0)));
+    server.publish(&Update::State(PlayState::Playing));
+    assert_eq!(Update::parse(&answer(&mut reader)), Ok(Update::Frame(frame(2.0))));
+    assert_eq!(answer(&mut reader), "state playing\n");
+
+    // Its keys come back as requests on the same connection
+    client.write_all(b"pause\nseek 5\n").unwrap();
+    let (request, reply) = server.poll(Duration::from_secs(5)).unwrap();
+    assert_eq!(request, Request::Pause);
+    reply.send(Ok(String::new()));
+    assert_eq!(server.poll(Duration::from_secs(5)).unwrap().0, Request::Seek(5.0));
+    assert_eq!(answer(&mut reader), "ok\n");
+
+    // One that hangs up is dropped at the next update
+    drop(reader);
+    drop(client);
+    server.publish(&Update::Frame(frame(3.0)));
+    assert!(!server.is_followed());
+}
+
+#[test]
+fn followers_see_the_stream_end_when_the_server_goes() {
+    let path = socket("end");
+    let server = Server::bind(&path).unwrap();
+    let mut client = UnixStream::connect(&path).unwrap();
+    let mut reader = BufReader::new(client.try_clone().unwrap());
+    client.write_all(b"follow\n").unwrap();
+    assert_eq!(answer(&mut reader), "ok Following\n");
+
+    drop(server);
+    assert_eq!(answer(&mut reader), "");
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+use gruvberry::analysis::BandTable;
+use gruvberry::curve::{self, MIN_SPAN_DB, STEP_DB};
+use gruvberry::legend;
+use gruvberry::tilt::{Preset, Tilt};
+
+// Compare with the file under tests/golden; GRUVBERRY_UPDATE_GOLDEN=1
+// writes it instead, after a deliberate change to the output
+fn check_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(name);
+    if std::env::var_os("GRUVBERRY_UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected, "{} differs; rerun with GRUVBERRY_UPDATE_GOLDEN=1 if that's intended", name);
+}
+
+// The plot the 'D' view draws for `tilt` with 60 bands, as text
+fn render(tilt: &Tilt) -> String {
+    let gains = BandTable::new(44100, 2048, 60, tilt).gains_db();
+    let plot = curve::plot(&gains, 60, 8, 10);
+    let mut text = format!("{:+.0} to {:+.0} dB\n", plot.bottom_db, plot.top_db);
+    for row in plot.rows {
+        text.push_str(&row);
+        text.push('\n');
+    }
+    text.push_str(&plot.axis);
+    text.push('\n');
+    text
+}
+
+#[test]
+fn the_default_tilt_matches_the_golden_file() {
+    check_golden("gain_curve_default.txt", &render(&Tilt::default()));
+}
+
+#[test]
+fn the_presets_match_the_golden_files() {
+    for preset in Preset::ALL {
+        check_golden(&format!("gain_curve_{}.txt", preset.name()), &render(&preset.tilt()));
+    }
+}
+
+#[test]
+fn the_axis_is_the_legend_axis() {
+    for (width, segments) in [(60, 10), (37, 4), (80, 12)] {
+        let plot = curve::plot(&[0.0, 2.0, 5.0, 9.0], width, 4, segments);
+        assert_eq!(plot.axis, legend::axis_line(width, segments));
+        assert!(plot.rows.iter().all(|row| row.chars().count() == width));
+        assert_eq!(plot.rows.len(), 4);
+    }
+}
+
+#[test]
+fn a_flat_curve_keeps_the_minimum_span() {
+    let plot = curve::plot(&[0.0; 20], 20, 6, 4);
+    assert_eq!(plot.top_db - plot.bottom_db, MIN_SPAN_DB);
+    assert!(plot.bottom_db <= 0.0 && plot.top_db >= 0.0);
+    // The line covers the 0 dB line: every column is drawn once
+    let drawn: usize = plot.rows.iter().map(|row| row.chars().filter(|&c| c != ' ').count()).sum();
+    assert_eq!(drawn, 20);
+}
+
+#[test]
+fn the_scale_is_rounded_out_to_steps() {
+    let plot = curve::plot(&[-4.2, 1.0, 7.9], 30, 5, 3);
+    assert_eq!((plot.bottom_db, plot.top_db), (-6.0, 9.0));
+    assert_eq!(plot.bottom_db % STEP_DB, 0.0);
+    // Each extreme sits in its edge row
+    assert!(plot.rows[0].chars().any(|c| c != ' ' && c != '┄'));
+    assert!(plot.rows[4].chars().any(|c| c != ' ' && c != '┄'));
+}
+
+#[test]
+fn bands_that_are_not_numbers_are_left_out() {
+    let plot = curve::plot(&[3.0, f32::NAN, f32::INFINITY, 3.0], 4, 3, 1);
+    assert!(plot.top_db.is_finite() && plot.bottom_db.is_finite());
+    let columns: Vec<Vec<char>> = plot.rows.iter().map(|row| row.chars().collect()).collect();
+    let drawn = |column: usize| columns.iter().any(|row| !matches!(row[column], ' ' | '┄'));
+    assert!(drawn(0) && drawn(3));
+    assert!(!drawn(1) && !drawn(2));
+}
+
+#[test]
+fn nothing_to_plot_is_an_empty_grid() {
+    let plot = curve::plot(&[], 10, 3, 2);
+    assert_eq!((plot.bottom_db, plot.top_db), (0.0, MIN_SPAN_DB));
+    assert!(plot.rows.iter().all(|row| row.chars().all(|c| c == ' ' || c == '┄')));
+}
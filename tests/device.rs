@@ -0,0 +1,66 @@
+use std::time::Duration;
+use gruvberry::device::{self, DeviceOpener, Retry, RetryPolicy};
+
+// An output that fails the first `failures` opens, like a sound server on
+// its way back from suspend
+struct Waking {
+    failures: u32,
+    opened: u32,
+}
+
+impl DeviceOpener for Waking {
+    type Device = u32;
+
+    fn open(&mut self) -> Result<u32, String> {
+        self.opened += 1;
+        if self.opened <= self.failures {
+            Err(format!("no server (try {})", self.opened))
+        } else {
+            Ok(self.opened)
+        }
+    }
+}
+
+#[test]
+fn a_device_that_never_comes_back_is_given_up_on_after_the_last_attempt() {
+    let mut waking = Waking { failures: u32::MAX, opened: 0 };
+    let mut retries: Vec<Retry> = Vec::new();
+    let result = device::open(&mut waking, &RetryPolicy::default(), |retry| retries.push(retry.clone()));
+
+    assert_eq!(result, Err("no server (try 5) (gave up after 5 attempts)".to_string()));
+    assert_eq!(waking.opened, 5);
+    let delays: Vec<u64> = retries.iter().map(|retry| retry.delay.as_millis() as u64).collect();
+    assert_eq!(delays, [200, 400, 800, 1600]);
+    assert_eq!(RetryPolicy::default().total_wait(), Duration::from_secs(3));
+    // Each wait knows which attempt it is for and why the last one failed
+    assert_eq!(retries[0].attempt, 2);
+    assert_eq!(retries[3].attempt, 5);
+    assert!(retries.iter().all(|retry| retry.attempts == 5));
+    assert_eq!(retries[1].error, "no server (try 2)");
+}
+
+#[test]
+fn retrying_stops_as_soon_as_the_device_opens() {
+    let policy = RetryPolicy { attempts: 5, first_delay: Duration::from_millis(10) };
+    let mut waking = Waking { failures: 2, opened: 0 };
+    let mut waits = 0;
+    assert_eq!(device::open(&mut waking, &policy, |_| waits += 1), Ok(3));
+    assert_eq!(waits, 2);
+
+    // Working at once, nothing waits
+    let mut waking = Waking { failures: 0, opened: 0 };
+    assert_eq!(device::open(&mut waking, &policy, |_| panic!("waited")), Ok(1));
+}
+
+#[test]
+fn a_single_attempt_fails_with_the_error_as_it_is() {
+    let policy = RetryPolicy { attempts: 1, first_delay: Duration::from_secs(1) };
+    assert_eq!(policy.total_wait(), Duration::ZERO);
+    let mut waking = Waking { failures: 1, opened: 0 };
+    assert_eq!(device::open(&mut waking, &policy, |_| panic!("waited")), Err("no server (try 1)".to_string()));
+
+    // No attempts at all still makes the one
+    let policy = RetryPolicy { attempts: 0, ..policy };
+    let mut opener = || Ok::<_, String>("device");
+    assert_eq!(device::open(&mut opener, &policy, |_| panic!("waited")), Ok("device"));
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+use gruvberry::display;
+
+// Names to break a title with: wide characters, combining marks, control
+// characters, right-to-left text and the controls that reorder it, emoji
+// sequences, and very long ones
+const NASTY: [&str; 10] = [
+    "交響曲第九番 ニ短調 作品125 「合唱付き」.flac",
+    "e\u{301}te\u{301} a\u{300} l\u{2019}ope\u{301}ra.wav",
+    "Z\u{351}\u{34b}\u{300}a\u{363}\u{36c}l\u{30f}g\u{30c}o\u{35b}.wav",
+    "line one\nline two\r\tand a bell\x07.wav",
+    "\x1b[2J\x1b]0;pwned\x07cleared.wav",
+    "\u{85}next line\u{9b}31m.wav",
+    "שלום עולם مرحبا بالعالم.wav",
+    "invoice\u{202e}vaw.exe",
+    "\u{2066}isolated\u{2069} and \u{200f}marked\u{200e}.wav",
+    "❤\u{fe0f} 👩\u{200d}👩\u{200d}👧 🇯🇵.wav",
+];
+
+fn long_name() -> String {
+    "交響曲 e\u{301} ".repeat(60)
+}
+
+#[test]
+fn nasty_names_fit_every_width() {
+    let long = long_name();
+    for name in NASTY.iter().copied().chain([long.as_str()]) {
+        let clean = display::clean(name);
+        for max in 0..=80 {
+            let fitted = display::fit(&clean, max);
+            assert!(display::width(&fitted) <= max, "{:?} at {}: {:?}", name, max, fitted);
+            if display::width(&clean) <= max {
+                assert_eq!(fitted, clean);
+            } else if max > 0 {
+                assert!(fitted.ends_with('…'), "{:?} at {}: {:?}", name, max, fitted);
+                // Nothing wider than a cell is lost that would have fit
+                assert!(display::width(&fitted) + 1 >= max, "{:?} at {}: {:?}", name, max, fitted);
+            }
+        }
+    }
+}
+
+#[test]
+fn nothing_the_terminal_would_act_on_survives() {
+    for name in NASTY {
+        let clean = display::clean(name);
+        assert!(!clean.chars().any(char::is_control), "{:?}", clean);
+        // Nor the bidi overrides and isolates that reorder what follows them
+        assert!(!clean.chars().any(|c| matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')), "{:?}", clean);
+    }
+    assert_eq!(display::clean("\x1b[2J\x1b]0;pwned\x07cleared.wav"), "[2J]0;pwnedcleared.wav");
+    assert_eq!(display::clean("invoice\u{202e}vaw.exe"), "invoicevaw.exe");
+    // Right-to-left text itself is kept
+    assert_eq!(display::clean("שלום עולם مرحبا بالعالم.wav"), "שלום עולם مرحبا بالعالم.wav");
+}
+
+#[test]
+fn cutting_keeps_characters_whole() {
+    // A wide character that doesn't fit goes entirely, leaving a cell short
+    assert_eq!(display::fit("交響曲第九番", 6), "交響…");
+    assert_eq!(display::fit("交響曲第九番", 7), "交響曲…");
+    // Combining marks stay with the letter they sit on
+    assert_eq!(display::fit("e\u{301}e\u{301}e\u{301}e\u{301}", 3), "e\u{301}e\u{301}…");
+    assert_eq!(display::width("e\u{301}te\u{301}"), 3);
+    assert_eq!(display::fit("abc", 0), "");
+    assert_eq!(display::fit("abc", 1), "…");
+}
+
+#[cfg(unix)]
+#[test]
+fn paths_that_are_not_utf8_show_a_replacement_character() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = Path::new(std::ffi::OsStr::from_bytes(b"/music/caf\xe9\n.wav"));
+    assert_eq!(display::path(path), "/music/caf\u{fffd}.wav");
+    assert_eq!(display::file_name(path), "caf\u{fffd}.wav");
+    assert_eq!(display::file_name(Path::new("/")), "/");
+}
@@ -0,0 +1,72 @@
+use gruvberry::downsample::{self, Aggregate};
+
+const BANDS: usize = 256;
+
+// A lone loud band, where picking one band per column would miss it
+fn spike(band: usize) -> Vec<f32> {
+    let mut bands = vec![5.0; BANDS];
+    bands[band] = 90.0;
+    bands
+}
+
+#[test]
+fn a_one_band_spike_shows_at_any_width() {
+    for width in [40, 80, 200] {
+        for band in [0, 1, 97, 131, 254, BANDS - 1] {
+            let columns = downsample::downsample(&spike(band), width, Aggregate::Max);
+            assert_eq!(columns.len(), width);
+            let column = downsample::cell_of(band, width, BANDS);
+            assert_eq!(columns[column], 90.0, "band {} at width {}", band, width);
+            assert_eq!(columns.iter().filter(|&&level| level == 90.0).count(), 1);
+            assert_eq!(downsample::representatives(&spike(band), width)[column], band);
+        }
+    }
+}
+
+#[test]
+fn energy_keeps_a_spike_and_adds_up_neighbours() {
+    for width in [40, 80, 200] {
+        let columns = downsample::downsample(&spike(131), width, Aggregate::Energy);
+        assert!(columns[downsample::cell_of(131, width, BANDS)] >= 90.0);
+    }
+    // Four bands at 30 in one column: twice as tall as any one of them
+    let columns = downsample::downsample(&[30.0; 8], 2, Aggregate::Energy);
+    assert_eq!(columns, vec![60.0, 60.0]);
+    // Never past full scale
+    assert_eq!(downsample::downsample(&[100.0; 8], 2, Aggregate::Energy), vec![100.0, 100.0]);
+}
+
+#[test]
+fn every_band_falls_in_exactly_one_column() {
+    for (cells, num_bands) in [(40, 256), (80, 256), (200, 256), (7, 7), (3, 10)] {
+        let mut next = 0;
+        for cell in 0..cells {
+            let span = downsample::span(cell, cells, num_bands);
+            assert_eq!(span.start, next);
+            assert!(!span.is_empty());
+            for band in span.clone() {
+                assert_eq!(downsample::cell_of(band, cells, num_bands), cell);
+            }
+            next = span.end;
+        }
+        assert_eq!(next, num_bands);
+    }
+}
+
+#[test]
+fn fewer_bands_than_columns_are_left_as_they_are() {
+    let bands = [10.0, 50.0, 20.0];
+    assert_eq!(downsample::downsample(&bands, 80, Aggregate::Max), bands.to_vec());
+    assert_eq!(downsample::downsample(&bands, 3, Aggregate::Energy), bands.to_vec());
+    assert_eq!(downsample::representatives(&bands, 80), vec![0, 1, 2]);
+    assert_eq!(downsample::cell_of(2, 80, 3), 2);
+}
+
+#[test]
+fn aggregates_parse_by_name() {
+    for name in Aggregate::NAMES {
+        assert_eq!(Aggregate::parse(name).map(Aggregate::name), Some(name));
+    }
+    assert_eq!(Aggregate::parse("sum"), None);
+    assert_eq!(Aggregate::default(), Aggregate::Max);
+}
@@ -0,0 +1,74 @@
+use gruvberry::capture::SampleObserver;
+use gruvberry::dynamics::{self, DynamicsMeter};
+
+const RATE: u32 = 44100;
+
+fn sine(secs: f32, amplitude: f32) -> Vec<f32> {
+    (0..(secs * RATE as f32) as usize)
+        .map(|i| amplitude * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / RATE as f32).sin())
+        .collect()
+}
+
+#[test]
+fn sine_has_a_crest_factor_of_3_db() {
+    let crest = dynamics::crest_factor_db(&sine(1.0, 0.5)).unwrap();
+    assert!((crest - 3.01).abs() < 0.01, "{}", crest);
+}
+
+#[test]
+fn square_wave_has_no_crest() {
+    let square: Vec<f32> = (0..4410).map(|i| if (i / 50) % 2 == 0 { 0.7 } else { -0.7 }).collect();
+    assert!(dynamics::crest_factor_db(&square).unwrap().abs() < 1e-4);
+}
+
+#[test]
+fn silence_has_no_crest() {
+    assert_eq!(dynamics::crest_factor_db(&[0.0; 1024]), None);
+    assert_eq!(dynamics::crest_factor_db(&[]), None);
+}
+
+// A 1% spike every 100 ms in a quiet sine, fed in small batches like the capture tap does
+#[test]
+fn live_crest_forgets_what_left_the_window() {
+    let mut meter = DynamicsMeter::new();
+    let mut loud = sine(2.0, 0.1);
+    for i in (0..loud.len()).step_by(RATE as usize / 10) {
+        loud[i] = 1.0;
+    }
+    for batch in loud.chunks(64) {
+        meter.on_samples(batch, 1, RATE);
+    }
+    assert!(meter.crest_db().unwrap() > 15.0);
+
+    for batch in sine(4.0, 0.1).chunks(64) {
+        meter.on_samples(batch, 1, RATE);
+    }
+    assert!((meter.crest_db().unwrap() - 3.01).abs() < 0.05);
+}
+
+#[test]
+fn stereo_sine_measures_dr_0() {
+    let mut meter = DynamicsMeter::new();
+    assert_eq!(meter.dr(), None);
+    let frames: Vec<f32> = sine(10.0, 0.8).into_iter().flat_map(|s| [s, s]).collect();
+    meter.on_samples(&frames, 2, RATE);
+    assert!(meter.dr().unwrap().abs() < 0.05);
+}
+
+// Quiet passages don't count against the loudest blocks, but peaks well
+// above them do: a 0.5 sine with 1.0 peaks is 6 dB more dynamic than a sine
+#[test]
+fn dr_is_peak_over_loudest_blocks() {
+    let mut meter = DynamicsMeter::new();
+    meter.on_samples(&sine(30.0, 0.01), 1, RATE);
+    let mut loud = sine(9.0, 0.5);
+    for i in (0..loud.len()).step_by(RATE as usize) {
+        loud[i] = 1.0;
+    }
+    meter.on_samples(&loud, 1, RATE);
+    let dr = meter.dr().unwrap();
+    assert!((dr - 6.02).abs() < 0.1, "{}", dr);
+
+    meter.reset();
+    assert_eq!(meter.dr(), None);
+}
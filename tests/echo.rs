@@ -0,0 +1,148 @@
+use std::f32::consts::TAU;
+use gruvberry::capture::{SampleCapture, SampleRing};
+use gruvberry::echo::{DelayLine, Echo, EchoSettings, Field, RAMP_SECS, Tap, Tapped};
+use gruvberry::format::Locale;
+use rodio::buffer::SamplesBuffer;
+
+const RATE: u32 = 8000;
+
+// Mono samples at RATE through an echo of `settings`, all played out
+fn echoed(samples: Vec<f32>, settings: EchoSettings) -> Vec<f32> {
+    Echo::new(SamplesBuffer::new(1, RATE, samples), settings.shared()).collect()
+}
+
+fn impulse(len: usize) -> Vec<f32> {
+    let mut samples = vec![0.0; len];
+    samples[0] = 1.0;
+    samples
+}
+
+#[test]
+fn the_delay_line_returns_an_impulse_after_its_length_and_again_at_the_feedback() {
+    let mut line = DelayLine::new(5, 0.5);
+    let out: Vec<f32> = impulse(21).into_iter().map(|sample| line.process(sample)).collect();
+    let expected: Vec<f32> = (0..21)
+        .map(|n| match n {
+            5 => 1.0,
+            10 => 0.5,
+            15 => 0.25,
+            20 => 0.125,
+            _ => 0.0,
+        })
+        .collect();
+    assert_eq!(out, expected);
+
+    // Without feedback it is a plain delay, and an empty line passes nothing
+    let mut line = DelayLine::new(3, 0.0);
+    let out: Vec<f32> = impulse(10).into_iter().map(|sample| line.process(sample)).collect();
+    assert_eq!(out, [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+    assert_eq!(DelayLine::new(0, 0.5).process(1.0), 0.0);
+}
+
+#[test]
+fn the_echo_of_an_impulse_comes_at_the_delay_scaled_by_mix_and_feedback() {
+    let settings = EchoSettings { enabled: true, delay_ms: 100.0, feedback: 0.5, mix: 0.6, ..EchoSettings::default() };
+    let out = echoed(impulse(4000), settings);
+    // 100 ms at 8 kHz
+    let delay = 800;
+    for (n, &sample) in out.iter().enumerate() {
+        let expected = match n {
+            0 => 1.0,
+            n if n % delay == 0 => 0.6 * 0.5f32.powi((n / delay) as i32 - 1),
+            _ => 0.0,
+        };
+        assert!((sample - expected).abs() < 1e-6, "sample {}: {} rather than {}", n, sample, expected);
+    }
+
+    // Each channel echoes into itself
+    let mut stereo = vec![0.0; 4000];
+    stereo[0] = 1.0;
+    let out: Vec<f32> = Echo::new(SamplesBuffer::new(2, RATE, stereo), settings.shared()).collect();
+    assert_eq!(out[2 * delay], 0.6);
+    assert_eq!(out[2 * delay + 1], 0.0);
+
+    // Off, nothing is touched
+    let off = EchoSettings { enabled: false, ..settings };
+    assert_eq!(echoed(impulse(4000), off), impulse(4000));
+}
+
+#[test]
+fn bypassing_fades_the_echo_out_without_a_click_and_leaves_no_tail_behind() {
+    let settings = EchoSettings { enabled: true, delay_ms: 50.0, feedback: 0.8, mix: 1.0, ..EchoSettings::default() };
+    let shared = settings.shared();
+    let tone: Vec<f32> = (0..RATE as usize * 2).map(|n| 0.5 * (TAU * 200.0 * n as f32 / RATE as f32).sin()).collect();
+    let mut echo = Echo::new(SamplesBuffer::new(1, RATE, tone.clone()), shared.clone());
+
+    let mut out: Vec<f32> = echo.by_ref().take(RATE as usize).collect();
+    shared.lock().unwrap().enabled = false;
+    out.extend(echo.by_ref().take(RATE as usize / 2));
+    // Back on half a second later
+    shared.lock().unwrap().enabled = true;
+    out.extend(echo);
+
+    // The largest step from one sample to the next is no bigger around the
+    // switch than while the echo played steadily
+    let step = |range: std::ops::Range<usize>| {
+        out[range].windows(2).map(|pair| (pair[1] - pair[0]).abs()).fold(0.0f32, f32::max)
+    };
+    let second = RATE as usize;
+    assert!(step(second - 200..second + 600) <= step(second / 2..second - 200) * 1.01);
+
+    // Once faded (a refresh and the ramp later) the tone is as it was
+    let faded = second + 256 + (RAMP_SECS * RATE as f32) as usize + 1;
+    assert_eq!(out[faded..second + second / 2], tone[faded..second + second / 2]);
+    // Turned back on, the line starts empty: nothing but the tone until the
+    // first echo of the new start is due, a delay (400 samples) later
+    let back_on = second + second / 2;
+    assert_eq!(out[back_on..back_on + 400], tone[back_on..back_on + 400]);
+    assert!(out[back_on + 400..].iter().zip(&tone[back_on + 400..]).any(|(out, tone)| out != tone));
+}
+
+#[test]
+fn the_analysis_taps_before_or_after_the_echo_as_set() {
+    for tap in [Tap::Dry, Tap::Wet] {
+        let settings = EchoSettings { enabled: true, delay_ms: 20.0, feedback: 0.0, mix: 1.0, tap }.shared();
+        let dry_ring = SampleRing::shared(2000);
+        let wet_ring = SampleRing::shared(2000);
+        let dry = SampleCapture::new(
+            SamplesBuffer::new(1, RATE, impulse(1000)),
+            Tapped::new(Tap::Dry, settings.clone(), dry_ring.clone()),
+        );
+        let echo = Echo::new(dry, settings.clone());
+        let wet = SampleCapture::new(echo, Tapped::new(Tap::Wet, settings, wet_ring.clone()));
+        assert_eq!(wet.count(), 1000);
+
+        let (dry_ring, wet_ring) = (dry_ring.lock().unwrap(), wet_ring.lock().unwrap());
+        let (seen, unseen) = if tap == Tap::Dry { (&dry_ring, &wet_ring) } else { (&wet_ring, &dry_ring) };
+        assert!(unseen.window(1, 0).is_none(), "{:?}", tap);
+        let window = seen.window(1000, 0).unwrap();
+        // 20 ms in, the echo is there after the echo and not before it
+        let expected = if tap == Tap::Wet { 1.0 } else { 0.0 };
+        assert_eq!(window[160], expected, "{:?}", tap);
+        assert_eq!(window[0], 1.0);
+    }
+}
+
+#[test]
+fn settings_step_within_their_ranges_and_switches_flip() {
+    let mut settings = EchoSettings::default();
+    settings.adjust(Field::Delay, -100);
+    assert_eq!(settings.value_text(Field::Delay, Locale::default()), "20 ms");
+    settings.adjust(Field::Delay, 3);
+    assert_eq!(settings.delay_ms, 50.0);
+    settings.adjust(Field::Feedback, 20);
+    assert_eq!(settings.value_text(Field::Feedback, Locale::default()), "90%");
+    settings.adjust(Field::Mix, -1);
+    assert_eq!(settings.mix, 0.25);
+    settings.adjust(Field::Enabled, -1);
+    assert_eq!(settings.value_text(Field::Enabled, Locale::default()), "on");
+    settings.adjust(Field::Tap, 1);
+    assert_eq!(settings.tap, Tap::Dry);
+    assert_eq!(Tap::parse(settings.tap.name()), Some(Tap::Dry));
+
+    assert_eq!(Field::Enabled.moved(-1), Field::Enabled);
+    assert_eq!(Field::Enabled.moved(2), Field::Feedback);
+    assert_eq!(Field::Mix.moved(10), Field::Tap);
+    let wild = EchoSettings { delay_ms: 1e6, feedback: 2.0, mix: -1.0, ..settings }.clamped();
+    assert_eq!((wild.delay_ms, wild.feedback, wild.mix), (2000.0, 0.9, 0.0));
+}
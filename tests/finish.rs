@@ -0,0 +1,136 @@
+use std::time::{Duration, Instant};
+use gruvberry::finish::{AUTO_EXIT_AFTER, FADE, Finish};
+use gruvberry::transport::{FakeTransport, Player, Transport};
+
+// The fade by the clock, sampled at a frame interval
+fn fades(finish: &Finish, frame: Duration) -> Vec<f32> {
+    let mut now = finish.at();
+    let mut fades = Vec::new();
+    while now <= finish.at() + FADE + frame {
+        fades.push(finish.fade(now));
+        now += frame;
+    }
+    fades
+}
+
+#[test]
+fn the_fade_ends_at_the_same_moment_at_any_frame_rate() {
+    let finish = Finish::new(Instant::now(), false);
+    for frame in [Duration::from_millis(7), Duration::from_millis(16), Duration::from_millis(33), Duration::from_millis(250)] {
+        let fades = fades(&finish, frame);
+        assert_eq!(fades[0], 1.0);
+        assert!(fades.windows(2).all(|pair| pair[1] <= pair[0]), "{:?}", frame);
+        assert_eq!(*fades.last().unwrap(), 0.0);
+    }
+    assert!(finish.fade(finish.at() + FADE - Duration::from_millis(1)) > 0.0);
+    assert_eq!(finish.fade(finish.at() + FADE), 0.0);
+    assert!(!finish.is_faded(finish.at() + FADE - Duration::from_millis(1)));
+    assert!(finish.is_faded(finish.at() + FADE));
+}
+
+#[test]
+fn an_interactive_display_waits_for_the_user() {
+    let finish = Finish::new(Instant::now(), false);
+    for after in [Duration::ZERO, FADE, AUTO_EXIT_AFTER, Duration::from_secs(3600)] {
+        assert!(!finish.should_exit(finish.at() + after));
+    }
+}
+
+#[test]
+fn an_auto_exiting_display_closes_after_the_fade() {
+    let finish = Finish::new(Instant::now(), true);
+    assert!(AUTO_EXIT_AFTER >= FADE);
+    assert!(!finish.should_exit(finish.at()));
+    assert!(!finish.should_exit(finish.at() + AUTO_EXIT_AFTER - Duration::from_millis(1)));
+    assert!(finish.should_exit(finish.at() + AUTO_EXIT_AFTER));
+}
+
+#[test]
+fn times_before_the_end_count_as_the_end() {
+    let at = Instant::now() + Duration::from_secs(1);
+    let finish = Finish::new(at, true);
+    assert_eq!(finish.fade(Instant::now()), 1.0);
+    assert!(!finish.should_exit(Instant::now()));
+}
+
+// The visualizer's end of playback on a fake clock, a frame at a time: the
+// player plays `length` seconds, a modal is open over the times in `modal`,
+// and the result is when the display closed by itself, if it did within a
+// minute (seconds from the start)
+fn auto_exit_at(length: f32, modal: &[(f32, f32)]) -> Option<f32> {
+    let start = Instant::now();
+    let frame = Duration::from_millis(20);
+    let mut fake = FakeTransport::new();
+    fake.append(Duration::from_secs_f32(length));
+    let mut player = Player::new(vec![fake]);
+    let mut finished: Option<Finish> = None;
+    for step in 0..3000 {
+        let t = step as f32 * frame.as_secs_f32();
+        let now = start + frame * step;
+        player.transport_mut(0).advance(frame);
+        if finished.is_none() && player.track_ended() {
+            finished = Some(Finish::new(now, true));
+        }
+        if let Some(finished) = &mut finished {
+            finished.hold(modal.iter().any(|&(open, close)| (open..close).contains(&t)), now);
+            if finished.should_exit(now) {
+                return Some(t);
+            }
+        }
+    }
+    None
+}
+
+fn close_to(actual: Option<f32>, expected: f32) -> bool {
+    actual.is_some_and(|actual| (actual - expected).abs() < 0.05)
+}
+
+#[test]
+fn without_a_modal_the_display_closes_after_the_wait() {
+    assert!(close_to(auto_exit_at(2.0, &[]), 2.0 + AUTO_EXIT_AFTER.as_secs_f32()));
+}
+
+#[test]
+fn the_end_comes_in_behind_an_open_modal() {
+    // Open from before the end until well after: nothing closes while it
+    // is, and the whole wait comes after it closes
+    let wait = AUTO_EXIT_AFTER.as_secs_f32();
+    assert!(close_to(auto_exit_at(2.0, &[(1.0, 10.0)]), 10.0 + wait));
+    // Never closed: the display stays
+    assert_eq!(auto_exit_at(2.0, &[(1.0, 1000.0)]), None);
+}
+
+#[test]
+fn a_modal_opened_during_the_wait_pauses_it() {
+    // Half a second of the wait passes, then five seconds with the modal open
+    let wait = AUTO_EXIT_AFTER.as_secs_f32();
+    assert!(close_to(auto_exit_at(2.0, &[(2.5, 7.5)]), 7.5 + wait - 0.5));
+    // Twice over: only the time without it counts
+    assert!(close_to(auto_exit_at(2.0, &[(2.5, 3.5), (3.8, 4.8)]), 2.0 + wait + 2.0));
+}
+
+#[test]
+fn the_fade_goes_on_behind_a_modal() {
+    let at = Instant::now();
+    let mut finish = Finish::new(at, true);
+    finish.hold(true, at);
+    assert!(finish.is_held());
+    assert_eq!(finish.fade(at + FADE / 2), 0.5);
+    assert!(finish.is_faded(at + FADE));
+    assert!(!finish.should_exit(at + AUTO_EXIT_AFTER * 10));
+    // Holding again, or letting go twice, changes nothing
+    finish.hold(true, at + FADE);
+    finish.hold(false, at + AUTO_EXIT_AFTER * 10);
+    finish.hold(false, at + AUTO_EXIT_AFTER * 20);
+    assert!(!finish.should_exit(at + AUTO_EXIT_AFTER * 11 - Duration::from_millis(1)));
+    assert!(finish.should_exit(at + AUTO_EXIT_AFTER * 11));
+}
+
+#[test]
+fn an_interactive_display_waits_with_or_without_a_modal() {
+    let at = Instant::now();
+    let mut finish = Finish::new(at, false);
+    finish.hold(true, at);
+    finish.hold(false, at + FADE);
+    assert!(!finish.should_exit(at + Duration::from_secs(3600)));
+}
@@ -0,0 +1,150 @@
+use std::time::{Duration, Instant};
+use gruvberry::capture::SampleCapture;
+use gruvberry::flow::{FlowMeter, SharedFlow};
+use gruvberry::transport::Playhead;
+use rodio::{Source, source::SineWave};
+
+const PERIOD: Duration = Duration::from_millis(10);
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+// A device pulling one PERIOD of audio every PERIOD, a period ahead, from a
+// source that delivers nothing between `stall_from` and `stall_until`;
+// calls `check` with the meter after each period
+fn simulate(stall_from: Duration, stall_until: Duration, len: Duration, mut check: impl FnMut(&FlowMeter, Instant)) -> FlowMeter {
+    let start = Instant::now();
+    let mut meter = FlowMeter::new();
+    let mut t = Duration::ZERO;
+    while t < len {
+        let now = start + t;
+        if t < stall_from || t >= stall_until {
+            meter.pulled(PERIOD.as_secs_f64(), now);
+        }
+        check(&meter, now);
+        t += PERIOD;
+    }
+    meter
+}
+
+#[test]
+fn a_steady_source_never_underruns() {
+    let meter = simulate(ms(10_000), ms(10_000), ms(2000), |meter, now| {
+        assert!(meter.fill(now) <= PERIOD);
+        assert_eq!(meter.underruns(now), 0);
+    });
+    assert_eq!(meter.lost(Instant::now()), Duration::ZERO);
+}
+
+#[test]
+fn a_stalled_source_is_an_underrun_that_holds_the_position() {
+    let start = Instant::now();
+    let mut playhead = Playhead::start(start, false);
+    let mut lost_seen = Duration::ZERO;
+    let mut positions = Vec::new();
+    let meter = simulate(ms(500), ms(800), ms(1500), |meter, now| {
+        // What the visualizer does each frame
+        let lost = meter.lost(now);
+        playhead.delay(lost.saturating_sub(lost_seen));
+        lost_seen = lost;
+        positions.push(playhead.position(now.max(start)));
+    });
+    let end = Instant::now() + ms(1500);
+    assert_eq!(meter.underruns(end), 1);
+    // The stall, less the underrun margin
+    let lost = meter.lost(end);
+    assert!(lost >= ms(260) && lost <= ms(280), "lost {:?}", lost);
+
+    // The position never goes back, stands still through the stall and ends
+    // at the audio actually played
+    assert!(positions.windows(2).all(|pair| pair[1] >= pair[0]));
+    let during: Vec<&Duration> = positions[55..78].iter().collect();
+    assert!(during.windows(2).all(|pair| pair[1].saturating_sub(*pair[0]) < ms(1)), "{:?}", during);
+    let played = positions.last().unwrap().as_secs_f32();
+    assert!((played - 1.2).abs() < 0.03, "played {}", played);
+}
+
+#[test]
+fn pausing_is_not_an_underrun() {
+    let start = Instant::now();
+    let at = |t: u64| start + ms(t);
+    let mut meter = FlowMeter::new();
+    for t in (0..500).step_by(10) {
+        meter.pulled(0.01, at(t));
+    }
+    meter.pause(at(500));
+    meter.resume(at(3500));
+    for t in (3500..4000).step_by(10) {
+        meter.pulled(0.01, at(t));
+    }
+    assert_eq!(meter.underruns(at(4000)), 0);
+    assert_eq!(meter.lost(at(4000)), Duration::ZERO);
+}
+
+// Plays on until `stall_at` samples, then takes `stall` to produce the next
+struct SlowSource<S> {
+    inner: S,
+    played: usize,
+    stall_at: usize,
+    stall: Duration,
+}
+
+impl<S: Source> Iterator for SlowSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.played == self.stall_at {
+            std::thread::sleep(self.stall);
+        }
+        self.played += 1;
+        self.inner.next()
+    }
+}
+
+impl<S: Source> Source for SlowSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[test]
+fn a_slow_source_through_the_tap_is_detected() {
+    let meter: SharedFlow = FlowMeter::shared();
+    let rate = 48000;
+    let period = rate / 100;
+    let source = SlowSource {
+        inner: SineWave::new(440.0),
+        played: 0,
+        stall_at: period * 10,
+        stall: ms(150),
+    };
+    let mut tap = SampleCapture::new(source, meter.clone());
+
+    // A device pulling a period every 10 ms, keeping one period ahead
+    let start = Instant::now();
+    for i in 0..25u32 {
+        tap.by_ref().take(period).for_each(drop);
+        if let Some(wait) = (start + PERIOD * i).checked_duration_since(Instant::now()) {
+            std::thread::sleep(wait);
+        }
+    }
+    let meter = meter.lock().unwrap();
+    let now = Instant::now();
+    // A busy machine may add a hiccup of its own, never take the stall away
+    assert!(meter.underruns(now) >= 1);
+    let lost = meter.lost(now);
+    assert!(lost >= ms(80), "lost {:?}", lost);
+}
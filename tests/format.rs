@@ -0,0 +1,97 @@
+use gruvberry::format::{self, Locale};
+
+#[test]
+fn hertz_turn_into_kilohertz_at_what_rounds_to_1000() {
+    let locale = Locale::default();
+    assert_eq!(locale.hz(20.0), "20 Hz");
+    assert_eq!(locale.hz(999.0), "999 Hz");
+    assert_eq!(locale.hz(999.4), "999 Hz");
+    assert_eq!(locale.hz(999.6), "1.0 kHz");
+    assert_eq!(locale.hz(1000.0), "1.0 kHz");
+    assert_eq!(locale.hz(1049.0), "1.0 kHz");
+    assert_eq!(locale.hz(1050.1), "1.1 kHz");
+}
+
+#[test]
+fn from_10_khz_whole_kilohertz_are_enough() {
+    let locale = Locale::default();
+    assert_eq!(locale.hz(9940.0), "9.9 kHz");
+    assert_eq!(locale.hz(9960.0), "10 kHz");
+    assert_eq!(locale.hz(11_300.0), "11 kHz");
+    assert_eq!(locale.hz(22_050.0), "22 kHz");
+    assert_eq!(locale.hz(f32::NAN), "-");
+}
+
+#[test]
+fn playback_times_count_whole_seconds() {
+    assert_eq!(format::time(0.0), "0:00");
+    assert_eq!(format::time(59.9), "0:59");
+    assert_eq!(format::time(60.0), "1:00");
+    assert_eq!(format::time(83.41), "1:23");
+    assert_eq!(format::time(3599.9), "59:59");
+    assert_eq!(format::time(3600.0), "1:00:00");
+    assert_eq!(format::time(-2.0), "0:00");
+    assert_eq!(format::time(f32::INFINITY), "--:--");
+}
+
+#[test]
+fn durations_read_in_seconds_under_a_minute() {
+    let locale = Locale::default();
+    assert_eq!(locale.duration(0.12), "0.1 s");
+    assert_eq!(locale.duration(59.9), "59.9 s");
+    // What would round up to 60.0 s is a minute
+    assert_eq!(locale.duration(59.96), "1:00");
+    assert_eq!(locale.duration(60.0), "1:00");
+    assert_eq!(locale.duration(83.41), "1:23");
+    assert_eq!(locale.duration(f32::INFINITY), "--:--");
+}
+
+#[test]
+fn scrubbed_history_keeps_its_tenths() {
+    let locale = Locale::default();
+    assert_eq!(locale.ago(0.0), "-0.0s");
+    assert_eq!(locale.ago(0.1), "-0.1s");
+    assert_eq!(locale.ago(4.23), "-4.2s");
+    assert_eq!(locale.ago(59.9), "-59.9s");
+    assert_eq!(locale.ago(59.96), "-1:00.0");
+    assert_eq!(locale.ago(83.41), "-1:23.4");
+    assert_eq!(Locale::new(',').ago(4.2), "-4,2s");
+    assert_eq!(Locale::new(',').ago(83.41), "-1:23,4");
+    // Every step of a scrub reads differently
+    let steps: Vec<String> = (1..=30).map(|step| locale.ago(step as f32 * 0.1)).collect();
+    assert!(steps.windows(2).all(|pair| pair[0] != pair[1]), "{:?}", steps);
+}
+
+#[test]
+fn levels_always_carry_their_sign() {
+    let locale = Locale::default();
+    assert_eq!(locale.db(-6.02, 1), "-6.0 dB");
+    assert_eq!(locale.db(3.0, 0), "+3 dB");
+    assert_eq!(locale.db(0.0, 1), "+0.0 dB");
+    assert_eq!(locale.signed(0.824, 2), "+0.82");
+}
+
+#[test]
+fn the_decimal_separator_goes_everywhere_a_decimal_does() {
+    let locale = Locale::new(',');
+    assert_eq!(locale.decimal_separator(), ',');
+    assert_eq!(locale.hz(3200.0), "3,2 kHz");
+    assert_eq!(locale.hz(440.0), "440 Hz");
+    assert_eq!(locale.db(-12.25, 2), "-12,25 dB");
+    assert_eq!(locale.duration(2.5), "2,5 s");
+    assert_eq!(locale.number(1234.5, 1), "1234,5");
+}
+
+#[test]
+fn the_readouts_use_the_locale_s_separator_too() {
+    let locale = Locale::new(',');
+    assert_eq!(locale.megabytes(1_549_999), "1,5 MB");
+    assert_eq!(locale.megabytes(0), "0,0 MB");
+    assert_eq!(locale.ms(12.5, 1), "12,5 ms");
+    assert_eq!(locale.signed_ms(40.0), "+40 ms");
+    assert_eq!(locale.signed_ms(-15.0), "-15 ms");
+    assert_eq!(locale.rate(44100), "44100 Hz");
+    assert_eq!(locale.percent(0.35), "35%");
+    assert_eq!(locale.degrees(210.0), "210°");
+    assert_eq!(Locale::default().megabytes(1_549_999), "1.5 MB");
+}
@@ -0,0 +1,51 @@
+use gruvberry::analysis;
+use gruvberry::gradient::{self, Anchor};
+
+const MAX_FREQ: f32 = 22050.0;
+
+fn color_at(freq: f32, num_bands: usize, anchor: Anchor) -> (u8, u8, u8) {
+    gradient::band_rgb(analysis::band_of(freq, num_bands, MAX_FREQ), num_bands, MAX_FREQ, anchor)
+}
+
+#[test]
+fn one_khz_keeps_its_color_at_any_width() {
+    assert_eq!(color_at(1000.0, 60, Anchor::Frequency), color_at(1000.0, 150, Anchor::Frequency));
+    for num_bands in [40, 76, 100, 158] {
+        assert_eq!(color_at(1000.0, num_bands, Anchor::Frequency), color_at(1000.0, 60, Anchor::Frequency));
+    }
+}
+
+#[test]
+fn index_anchor_follows_the_band_count() {
+    assert_ne!(color_at(1000.0, 60, Anchor::Index), color_at(1000.0, 150, Anchor::Index));
+    assert_eq!(gradient::band_rgb(0, 60, MAX_FREQ, Anchor::Index), gradient::vibgyor(0.0));
+    assert_eq!(gradient::band_rgb(59, 60, MAX_FREQ, Anchor::Index), gradient::vibgyor(1.0));
+}
+
+#[test]
+fn scale_ends_at_the_audible_range() {
+    assert_eq!(gradient::frequency_ratio(gradient::LOW_HZ), 0.0);
+    assert_eq!(gradient::frequency_ratio(10.0), 0.0);
+    assert_eq!(gradient::frequency_ratio(gradient::HIGH_HZ), 1.0);
+    assert_eq!(gradient::frequency_ratio(40_000.0), 1.0);
+}
+
+#[test]
+fn ratio_rises_in_third_octave_steps() {
+    // Within a third-octave the ratio doesn't move; across one it does
+    assert_eq!(gradient::frequency_ratio(950.0), gradient::frequency_ratio(1050.0));
+    let mut last = 0.0;
+    for third in -15..=13 {
+        let ratio = gradient::frequency_ratio(1000.0 * (third as f32 / 3.0).exp2());
+        assert!(ratio > last || third == -15, "{third}: {ratio} after {last}");
+        last = ratio;
+    }
+}
+
+#[test]
+fn names_parse() {
+    for name in Anchor::NAMES {
+        assert!(Anchor::parse(name).is_some());
+    }
+    assert_eq!(Anchor::parse("freq"), None);
+}
@@ -0,0 +1,67 @@
+use gruvberry::heat::{HeatMap, TRAIL_SECS};
+use gruvberry::theme::Theme;
+use ratatui::style::Color;
+
+const BAR_LENGTH: usize = 20;
+
+// A band pulsing every 2 seconds: up to full length for the first tenth of
+// a second, down to 2 cells for the rest. Fed to a heat map at `fps`
+// for `secs`, giving the heat along the bar at the end.
+fn pulse(fps: f32, secs: f32) -> Vec<f32> {
+    let mut heat = HeatMap::new(TRAIL_SECS);
+    let dt = 1.0 / fps;
+    let frames = (secs * fps).round() as usize;
+    for frame in 0..frames {
+        let t = frame as f32 * dt;
+        let length = if t % 2.0 < 0.1 { BAR_LENGTH } else { 2 };
+        heat.update(&[length], BAR_LENGTH, if frame == 0 { 0.0 } else { dt });
+    }
+    (0..BAR_LENGTH).map(|along| heat.level(0, along)).collect()
+}
+
+#[test]
+fn a_pulse_leaves_a_trail_that_cools_by_time_at_any_frame_rate() {
+    // Half a second after the last pulse dropped (it was up until 2.1 s)
+    let slow = pulse(20.0, 2.6);
+    let fast = pulse(240.0, 2.6);
+    for (along, (slow, fast)) in slow.iter().zip(&fast).enumerate() {
+        assert!((slow - fast).abs() < 0.02, "cell {}: {} at 20 fps, {} at 240 fps", along, slow, fast);
+    }
+    // Still covered at the base; the rest about half cooled
+    assert_eq!(fast[1], 1.0);
+    assert!(fast[2..].iter().all(|&level| (level - 0.5).abs() < 0.05), "{:?}", fast);
+}
+
+#[test]
+fn the_trail_is_gone_a_trail_length_after_the_bar_dropped() {
+    let heat = pulse(60.0, 2.1 + TRAIL_SECS + 0.05);
+    assert!(heat[2..].iter().all(|&level| level == 0.0), "{:?}", heat);
+    // And a new pulse heats it all up again
+    let heat = pulse(60.0, 4.05);
+    assert!(heat.iter().all(|&level| level == 1.0), "{:?}", heat);
+}
+
+#[test]
+fn a_new_size_starts_cold_and_stays_within_bounds() {
+    let mut heat = HeatMap::new(TRAIL_SECS);
+    heat.update(&[5, 5], 10, 0.0);
+    heat.update(&[1, 1, 1], 8, 0.1);
+    assert_eq!(heat.level(0, 4), 0.0);
+    assert_eq!(heat.level(2, 0), 1.0);
+    // Outside the area, and lengths past the bar's end
+    assert_eq!(heat.level(3, 0), 0.0);
+    assert_eq!(heat.level(0, 8), 0.0);
+    heat.update(&[100, 0, 0], 8, 0.1);
+    assert_eq!(heat.level(0, 7), 1.0);
+    heat.clear();
+    assert_eq!(heat.level(0, 0), 0.0);
+}
+
+#[test]
+fn the_theme_darkens_a_cooling_color_and_a_plain_one_has_none() {
+    let color = Color::Rgb(200, 100, 0);
+    assert_eq!(Theme::default().heat(color, 1.0), color);
+    assert_eq!(Theme::default().heat(color, 0.5), Color::Rgb(100, 50, 0));
+    assert_eq!(Theme::default().heat(color, 0.0), Color::Rgb(0, 0, 0));
+    assert_eq!(Theme::plain().heat(color, 0.5), Color::Reset);
+}
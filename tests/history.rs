@@ -0,0 +1,67 @@
+use gruvberry::analysis::FreqRange;
+use gruvberry::history::{AnalysisFrame, FrameHistory, frames_for};
+
+const FPS: u32 = 60;
+const BANDS: usize = 4;
+
+fn frame(index: usize) -> AnalysisFrame {
+    AnalysisFrame {
+        time: index as f32 / FPS as f32,
+        bands: vec![index as f32; BANDS],
+        levels_db: vec![-(index as f32); BANDS],
+        rms: 0.5,
+        sample_rate: 44100,
+        range: FreqRange::full(22050.0),
+    }
+}
+
+// An hour of frames at 60 fps never grows the history past its bound
+#[test]
+fn an_hour_of_frames_stays_bounded() {
+    let window_secs = 30.0;
+    let mut history = FrameHistory::for_rate(window_secs, FPS);
+    let max_frames = frames_for(window_secs, FPS);
+    assert_eq!(history.max_frames(), max_frames);
+
+    let total = 3600 * FPS as usize;
+    for index in 0..total {
+        history.push(frame(index));
+        assert!(history.len() <= max_frames);
+    }
+
+    assert_eq!(history.len(), max_frames);
+    let latest = history.latest().unwrap();
+    let oldest = history.oldest().unwrap();
+    assert_eq!(latest.bands[0], (total - 1) as f32);
+    assert!(latest.time - oldest.time <= window_secs);
+    assert!(history.iter().all(|f| f.bands.len() == BANDS));
+}
+
+// A snapshot keeps what it saw while the history moves on
+#[test]
+fn snapshot_outlives_later_pushes() {
+    let mut history = FrameHistory::new(10.0, 5);
+    for index in 0..5 {
+        history.push(frame(index));
+    }
+    let snapshot = history.snapshot();
+    for index in 5..20 {
+        history.push(frame(index));
+    }
+
+    let seen: Vec<f32> = snapshot.iter().map(|f| f.bands[0]).collect();
+    assert_eq!(seen, [0.0, 1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(history.oldest().unwrap().bands[0], 15.0);
+}
+
+// The time bound applies even when the frame bound has room
+#[test]
+fn old_frames_fall_out_of_the_window() {
+    let mut history = FrameHistory::new(1.0, 1000);
+    for index in 0..(3 * FPS as usize) {
+        history.push(frame(index));
+    }
+    let span = history.latest().unwrap().time - history.oldest().unwrap().time;
+    assert!(span <= 1.0);
+    assert!(history.at(0.0).unwrap().time >= 2.0 - 1.0 / FPS as f32);
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use gruvberry::input::{self, Scripted, Steps};
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent::new(code, modifiers))
+}
+
+fn release(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new_with_kind(code, KeyModifiers::NONE, KeyEventKind::Release))
+}
+
+// Fifty events queued in one frame, as from a held arrow key with a resize
+// caught behind the repeats: 30 steps right, 10 big ones, 5 back, the
+// releases Windows reports, and the resize last
+fn held_arrows() -> Vec<Event> {
+    let mut events = Vec::new();
+    for i in 0..45 {
+        events.push(match i % 9 {
+            0 | 4 => key(KeyCode::Right, KeyModifiers::SHIFT),
+            8 => key(KeyCode::Left, KeyModifiers::NONE),
+            _ => key(KeyCode::Right, KeyModifiers::NONE),
+        });
+    }
+    events.extend([release(KeyCode::Right), release(KeyCode::Left), release(KeyCode::Right), release(KeyCode::Left)]);
+    events.push(Event::Resize(120, 40));
+    events
+}
+
+#[test]
+fn a_frame_takes_every_queued_event_in_order() {
+    let mut source = Scripted::new(held_arrows());
+    let events = input::drain(&mut source, Duration::ZERO).unwrap();
+    assert_eq!(events, held_arrows());
+    assert!(source.is_empty());
+    // Nothing left for the next frame
+    assert!(input::drain(&mut source, Duration::ZERO).unwrap().is_empty());
+}
+
+#[test]
+fn held_arrow_steps_add_up_to_one_move() {
+    let mut source = Scripted::new(held_arrows());
+    let mut steps = Steps::default();
+    let mut resized = None;
+    for event in input::drain(&mut source, Duration::ZERO).unwrap() {
+        match event {
+            Event::Key(key) if key.kind != KeyEventKind::Release => steps.add(key, 0.1, 1.0),
+            Event::Resize(columns, rows) => resized = Some((columns, rows)),
+            _ => {}
+        }
+    }
+    assert!((steps.total() - (30.0 * 0.1 + 10.0 - 5.0 * 0.1)).abs() < 1e-4, "{}", steps.total());
+    // The resize behind them is taken in the same frame
+    assert_eq!(resized, Some((120, 40)));
+}
+
+#[test]
+fn only_arrows_step_and_opposite_ones_cancel_out() {
+    let mut steps = Steps::default();
+    for event in [key(KeyCode::Up, KeyModifiers::NONE), key(KeyCode::Char('l'), KeyModifiers::NONE)] {
+        if let Event::Key(key) = event {
+            steps.add(key, 0.1, 1.0);
+        }
+    }
+    assert_eq!(steps.total(), 0.0);
+    for code in [KeyCode::Left, KeyCode::Right] {
+        steps.add(KeyEvent::new(code, KeyModifiers::SHIFT), 0.1, 1.0);
+    }
+    assert_eq!(steps.total(), 0.0);
+}
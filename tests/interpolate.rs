@@ -0,0 +1,72 @@
+use gruvberry::interpolate::{lerp, FrameInterpolator};
+
+fn two_frames() -> FrameInterpolator {
+    let mut frames = FrameInterpolator::new();
+    frames.push(1.0, vec![0.0, 100.0, 40.0]);
+    frames.push(1.1, vec![20.0, 60.0, 40.0]);
+    frames
+}
+
+fn assert_close(actual: &[f32], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-3, "{actual:?} != {expected:?}");
+    }
+}
+
+#[test]
+fn blends_by_render_time() {
+    let frames = two_frames();
+    for (time, expected) in [
+        (1.1, [0.0, 100.0, 40.0]),
+        (1.125, [5.0, 90.0, 40.0]),
+        (1.15, [10.0, 80.0, 40.0]),
+        (1.175, [15.0, 70.0, 40.0]),
+        (1.2, [20.0, 60.0, 40.0]),
+    ] {
+        assert_close(&frames.at(time).unwrap(), &expected);
+    }
+}
+
+#[test]
+fn alpha_clamps_outside_the_interval() {
+    let frames = two_frames();
+    assert_eq!(frames.alpha(0.5), 0.0);
+    assert_eq!(frames.alpha(5.0), 1.0);
+    assert_close(&frames.at(5.0).unwrap(), &[20.0, 60.0, 40.0]);
+}
+
+#[test]
+fn single_frame_is_drawn_as_is() {
+    let mut frames = FrameInterpolator::new();
+    assert_eq!(frames.at(0.0), None);
+    frames.push(0.0, vec![30.0, 70.0]);
+    assert_eq!(frames.latest(), Some(&[30.0, 70.0][..]));
+    assert_eq!(frames.at(0.01), Some(vec![30.0, 70.0]));
+}
+
+#[test]
+fn band_count_change_is_not_blended() {
+    let mut frames = FrameInterpolator::new();
+    frames.push(0.0, vec![0.0; 4]);
+    frames.push(0.1, vec![50.0; 6]);
+    assert_eq!(frames.at(0.12), Some(vec![50.0; 6]));
+}
+
+#[test]
+fn clear_forgets_both_frames() {
+    let mut frames = two_frames();
+    frames.clear();
+    assert_eq!(frames.latest(), None);
+    frames.push(2.0, vec![10.0, 10.0, 10.0]);
+    assert_eq!(frames.at(2.0), Some(vec![10.0, 10.0, 10.0]));
+}
+
+#[test]
+fn lerp_ends_are_exact() {
+    let from = [0.0, 3.0];
+    let to = [10.0, -3.0];
+    assert_eq!(lerp(&from, &to, 0.0), from);
+    assert_eq!(lerp(&from, &to, 1.0), to);
+    assert_eq!(lerp(&from, &to, 0.5), [5.0, 0.0]);
+}
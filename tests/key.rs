@@ -0,0 +1,71 @@
+use std::f32::consts::PI;
+use gruvberry::key::{self, KeyEstimator};
+use rustfft::{FftPlanner, num_complex::Complex};
+
+const SAMPLE_RATE: f32 = 44100.0;
+const FFT_SIZE: usize = 4096;
+
+fn note_freq(midi: i32) -> f32 {
+    440.0 * 2f32.powf((midi - 69) as f32 / 12.0)
+}
+
+// One FFT window of the notes played together (with a couple of harmonics),
+// folded into a chromagram the way the visualizer does it
+fn chord_chroma(notes: &[i32], offset: usize) -> [f32; 12] {
+    let mut samples: Vec<Complex<f32>> = (0..FFT_SIZE)
+        .map(|i| {
+            let t = (i + offset) as f32 / SAMPLE_RATE;
+            let value: f32 = notes
+                .iter()
+                .flat_map(|&n| (1..=3).map(move |h| (2.0 * PI * note_freq(n) * h as f32 * t).sin() / h as f32))
+                .sum();
+            Complex { re: value, im: 0.0 }
+        })
+        .collect();
+    FftPlanner::new().plan_fft_forward(FFT_SIZE).process(&mut samples);
+    let magnitudes: Vec<f32> = samples[..FFT_SIZE / 2].iter().map(|c| c.norm()).collect();
+    key::chroma(&magnitudes, SAMPLE_RATE / FFT_SIZE as f32)
+}
+
+#[test]
+fn major_triad() {
+    // C4 E4 G4 over C3
+    let estimate = key::estimate(&chord_chroma(&[48, 60, 64, 67], 0)).unwrap();
+    assert_eq!(estimate.name(), "C major");
+}
+
+#[test]
+fn minor_triad() {
+    // A3 C4 E4 over A2
+    let estimate = key::estimate(&chord_chroma(&[45, 57, 60, 64], 0)).unwrap();
+    assert_eq!(estimate.name(), "A minor");
+}
+
+// A cadence in G major (G - C - D - G), two seconds a chord, through the
+// rolling estimator; no single chord gives the key away, the window does
+#[test]
+fn cadence_in_g_major() {
+    let chords = [[43, 55, 59, 62], [48, 60, 64, 67], [50, 62, 66, 69], [43, 55, 59, 62]];
+    let mut estimator = KeyEstimator::new(20.0);
+    let frame_secs = FFT_SIZE as f32 / SAMPLE_RATE;
+    let mut time = 0.0;
+    for chord in &chords {
+        for frame in 0..(2.0 / frame_secs) as usize {
+            estimator.push(time, chord_chroma(chord, frame * FFT_SIZE));
+            time += frame_secs as f64;
+        }
+    }
+    let estimate = estimator.estimate(5.0).unwrap();
+    assert_eq!(estimate.name(), "G major");
+    assert!(estimate.correlation > 0.7, "{}", estimate);
+}
+
+#[test]
+fn nothing_until_enough_audio() {
+    let mut estimator = KeyEstimator::new(20.0);
+    assert_eq!(estimator.estimate(5.0), None);
+    estimator.push(0.0, chord_chroma(&[48, 60, 64, 67], 0));
+    estimator.push(1.0, chord_chroma(&[48, 60, 64, 67], 0));
+    assert_eq!(estimator.estimate(5.0), None);
+    assert_eq!(key::estimate(&[0.0; 12]), None);
+}
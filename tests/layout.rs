@@ -0,0 +1,169 @@
+use std::time::{Duration, Instant};
+use gruvberry::analysis::Analyzer;
+use gruvberry::layout::{self, BandSettle, Fit, LayoutConfig, LayoutPlan, SETTLE_TIME};
+use gruvberry::tilt::Tilt;
+use ratatui::layout::Rect;
+
+const WIDTHS: [u16; 6] = [40, 79, 80, 120, 161, 500];
+const HEIGHTS: [u16; 5] = [19, 20, 24, 40, 100];
+
+// Every combination of what can be on show
+fn configs() -> Vec<LayoutConfig> {
+    let mut configs = Vec::new();
+    for horizontal in [false, true] {
+        for streams in [1, 2] {
+            for overview in [false, true] {
+                for playlist_rows in [None, Some(1), Some(8)] {
+                    configs.push(LayoutConfig { horizontal, streams, overview, playlist_rows });
+                }
+            }
+        }
+    }
+    configs
+}
+
+#[test]
+fn known_sizes() {
+    let config = LayoutConfig::default();
+    assert_eq!(LayoutPlan::compute(79, 24, &config).fit, Fit::TooSmall);
+    assert_eq!(LayoutPlan::compute(80, 19, &config).fit, Fit::TooSmall);
+
+    let smallest = LayoutPlan::compute(80, 20, &config);
+    assert_eq!(smallest.fit, Fit::Full);
+    assert_eq!((smallest.num_bands, smallest.num_legend_bands), (76, 8));
+
+    let huge = LayoutPlan::compute(500, 100, &config);
+    assert_eq!(huge.fit, Fit::Full);
+    assert_eq!(huge.width, 160);
+    assert_eq!((huge.num_bands, huge.num_legend_bands), (156, 15));
+}
+
+#[test]
+fn fits_exactly_from_the_minimum_size() {
+    for config in configs() {
+        for width in WIDTHS {
+            for height in HEIGHTS {
+                let plan = LayoutPlan::compute(width, height, &config);
+                let fits = width >= layout::MIN_WIDTH && height >= layout::MIN_HEIGHT;
+                assert_eq!(plan.fits(), fits, "{width}x{height} {config:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn blocks_tile_the_display() {
+    for config in configs() {
+        for width in WIDTHS {
+            for height in HEIGHTS.into_iter().filter(|&h| h >= layout::MIN_HEIGHT) {
+                let plan = LayoutPlan::compute(width, height, &config);
+                let areas = plan.areas(Rect::new(0, 0, width, height));
+                let context = format!("{width}x{height} {config:?}");
+
+                let mut blocks = vec![areas.spectrum, areas.legend, areas.details];
+                blocks.extend(areas.overview);
+                blocks.extend(areas.playlist);
+                blocks.push(areas.progress);
+                assert_eq!(areas.overview.is_some(), config.overview, "{context}");
+                assert_eq!(areas.playlist.is_some(), config.playlist_rows.is_some(), "{context}");
+                let mut y = 0;
+                for block in &blocks {
+                    assert_eq!((block.x, block.y), (0, y), "{context}");
+                    assert_eq!(block.width, width.min(layout::MAX_WIDTH), "{context}");
+                    y += block.height;
+                }
+                assert!(y <= height, "{context}");
+
+                assert_eq!(areas.spectra.len(), config.streams, "{context}");
+                let stacked: u16 = areas.spectra.iter().map(|area| area.height).sum();
+                assert_eq!(stacked, areas.spectrum.height, "{context}");
+            }
+        }
+    }
+}
+
+#[test]
+fn band_counts_match_what_is_drawn() {
+    for config in configs() {
+        for width in WIDTHS.into_iter().filter(|&w| w >= layout::MIN_WIDTH) {
+            for height in HEIGHTS.into_iter().filter(|&h| h >= layout::MIN_HEIGHT) {
+                let plan = LayoutPlan::compute(width, height, &config);
+                let areas = plan.areas(Rect::new(0, 0, width, height));
+                let context = format!("{width}x{height} {config:?}");
+                if config.horizontal {
+                    // One band per row of the shortest spectrum
+                    let rows = areas.spectra.iter().map(|area| area.height - 2).min().unwrap() as usize;
+                    assert_eq!(plan.num_bands, rows.max(1), "{context}");
+                    assert!(layout::ROW_LEGEND_SEGMENTS.contains(&plan.num_legend_bands), "{context}");
+                    // As many legend entries as the rows call for, or the fewest
+                    let legends = plan.num_legend_bands;
+                    assert!(legends <= (rows / 2).max(2), "{context}");
+                } else {
+                    assert_eq!(plan.num_bands, (areas.spectrum.width - layout::BAND_MARGIN) as usize, "{context}");
+                    assert!(layout::LEGEND_SEGMENTS.contains(&plan.num_legend_bands), "{context}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn too_small_still_plans_the_smallest_layout() {
+    let config = LayoutConfig::default();
+    let tiny = LayoutPlan::compute(20, 5, &config);
+    let smallest = LayoutPlan::compute(layout::MIN_WIDTH, layout::MIN_HEIGHT, &config);
+    assert_eq!(tiny.fit, Fit::TooSmall);
+    assert_eq!((tiny.num_bands, tiny.num_legend_bands), (smallest.num_bands, smallest.num_legend_bands));
+    assert_eq!(tiny.constraints, smallest.constraints);
+}
+
+#[test]
+fn a_burst_of_resizes_rebuilds_the_band_table_once() {
+    let plan = |width| LayoutPlan::compute(width, 40, &LayoutConfig::default());
+    let window = vec![0.1; 1024];
+    let mut analyzer = Analyzer::new(44100, 1024, 0.3, Tilt::Flat);
+    let mut settle = BandSettle::default();
+    let start = Instant::now();
+    // A frame every 16 ms on a fake clock, analyzing at the settled count
+    let mut frame = 0;
+    let mut next = |width: u16, analyzer: &mut Analyzer, settle: &mut BandSettle| {
+        frame += 1;
+        let bands = settle.update(&plan(width), start + Duration::from_millis(16 * frame));
+        analyzer.process(&window, bands).len()
+    };
+    assert_eq!(next(120, &mut analyzer, &mut settle), 116);
+    let built = analyzer.state().rebuilds().band_table;
+
+    // A corner dragged out to 132 columns and back to 126, a new width every
+    // frame: the bars keep their 116 bands all the while
+    let drag = (121..=132).chain((126..132).rev());
+    for width in drag {
+        assert_eq!(next(width, &mut analyzer, &mut settle), 116);
+        assert!(settle.settling());
+    }
+    assert_eq!(analyzer.state().rebuilds().band_table, built);
+
+    // Let go: the count follows once the width has held long enough
+    let frames = SETTLE_TIME.as_millis() / 16 + 2;
+    let counts: Vec<usize> = (0..frames).map(|_| next(126, &mut analyzer, &mut settle)).collect();
+    assert_eq!(counts.first(), Some(&116));
+    assert_eq!(counts.last(), Some(&122));
+    assert!(!settle.settling());
+    assert_eq!(analyzer.state().rebuilds().band_table, built + 1);
+    assert_eq!(settle.changes(), 2);
+}
+
+#[test]
+fn a_big_jump_is_taken_at_once_and_too_small_keeps_the_count() {
+    let plan = |width, height| LayoutPlan::compute(width, height, &LayoutConfig::default());
+    let start = Instant::now();
+    let mut settle = BandSettle::default();
+    assert_eq!(settle.update(&plan(100, 40), start), 96);
+    // Maximized: no reason to wait for the end of a drag
+    assert_eq!(settle.update(&plan(160, 40), start + Duration::from_millis(1)), 156);
+    // Too small to draw in, however long
+    assert_eq!(settle.update(&plan(60, 40), start + Duration::from_secs(5)), 156);
+    assert_eq!(settle.update(&plan(150, 40), start + Duration::from_secs(6)), 156);
+    assert_eq!(settle.update(&plan(150, 40), start + Duration::from_secs(6) + SETTLE_TIME), 146);
+}
+
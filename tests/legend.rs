@@ -0,0 +1,48 @@
+use gruvberry::legend;
+use proptest::prelude::*;
+
+// (width, segments) pairs the axis used to get wrong: the closing pipe ran
+// one cell past every width, and a one-cell partial segment by two
+const CORPUS: [(usize, usize); 6] = [(10, 8), (17, 8), (81, 8), (82, 8), (161, 16), (400, 16)];
+
+fn cells(line: &str) -> usize {
+    line.chars().count()
+}
+
+proptest! {
+    #[test]
+    fn axis_fills_exactly_its_width(width in 10usize..=400, segments in 8usize..=16) {
+        prop_assert_eq!(cells(&legend::axis_line(width, segments)), width);
+    }
+
+    #[test]
+    fn axis_numbers_every_segment_that_has_room(width in 10usize..=400, segments in 8usize..=16) {
+        let line = legend::axis_line(width, segments);
+        // Room for "|" and two digits in every segment
+        if (width - 1) / segments >= 3 {
+            let numbers: Vec<&str> = line.split('|').skip(1).map(|s| s.trim_matches('─')).collect();
+            prop_assert!(numbers.len() > segments);
+            for (i, number) in numbers.iter().take(segments).enumerate() {
+                prop_assert_eq!(number.to_string(), (i + 1).to_string());
+            }
+        }
+    }
+}
+
+#[test]
+fn corpus_of_past_failures() {
+    for (width, segments) in CORPUS {
+        assert_eq!(cells(&legend::axis_line(width, segments)), width, "width {width}, {segments} segments");
+    }
+}
+
+#[test]
+fn partial_segments() {
+    // One cell left over: just the pipe
+    assert_eq!(legend::axis_line(10, 2), "|─1─|─2─||");
+    assert!(legend::has_partial_segment(10, 2));
+    // Two cells: the number when it fits, "*" otherwise
+    assert_eq!(legend::axis_line(12, 3), "|1─|2─|3─|4|");
+    assert!(legend::axis_line(102, 9).ends_with("|*|"));
+    assert!(!legend::has_partial_segment(9, 2));
+}
@@ -0,0 +1,56 @@
+use gruvberry::analysis::Window;
+use gruvberry::downsample::Aggregate;
+use gruvberry::measurement::{self, Shaping, Stage};
+use gruvberry::smoothing::SmootherKind;
+use gruvberry::tilt::Tilt;
+
+// Every stage on, as far as the settings go
+fn everything_on() -> Shaping {
+    Shaping {
+        window: Window::Rectangular,
+        tilt: Tilt::Slope(3.0),
+        visual_gain_db: 6.0,
+        floor_db: None,
+        aggregate: Aggregate::Energy,
+        smoother: SmootherKind::AttackRelease,
+        smoothing: 0.3,
+        interpolate: true,
+        bounce: true,
+        peaks: false,
+    }
+}
+
+#[test]
+fn each_stage_reports_itself_on_as_configured() {
+    let shaping = everything_on();
+    for stage in Stage::ALL {
+        assert!(shaping.shapes(stage), "{} off", stage.name());
+    }
+    assert!(shaping.guarantees().is_empty());
+}
+
+#[test]
+fn each_stage_reports_itself_off_when_measuring() {
+    let shaping = Shaping::measurement(-80.0);
+    for stage in Stage::ALL {
+        assert!(!shaping.shapes(stage), "{} still on", stage.name());
+    }
+    assert_eq!(
+        shaping.guarantees(),
+        ["Hann", "no tilt", "no gain", "-80 dB floor", "max of bands", "no smoothing", "raw frames", "no bounce", "peaks"]
+    );
+}
+
+#[test]
+fn an_exponential_smoother_at_1_does_not_smooth() {
+    let shaping = Shaping { smoother: SmootherKind::Exponential, smoothing: 1.0, ..everything_on() };
+    assert!(!shaping.shapes(Stage::TemporalSmoothing));
+    let shaping = Shaping { smoothing: 0.99, ..shaping };
+    assert!(shaping.shapes(Stage::TemporalSmoothing));
+}
+
+#[test]
+fn the_fixed_scale_runs_from_the_floor_to_full_scale() {
+    let levels = measurement::fixed_levels(&[6.0, 0.0, -30.0, -60.0, -200.0, f32::NAN], -60.0);
+    assert_eq!(levels, [100.0, 100.0, 50.0, 0.0, 0.0, 0.0]);
+}
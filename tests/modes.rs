@@ -0,0 +1,90 @@
+use gruvberry::gradient::Anchor;
+use gruvberry::modes::{ModeMemory, Overrides, Settings};
+use gruvberry::smoothing::SmootherKind;
+use gruvberry::tilt::Tilt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Mode {
+    Bars,
+    Rows,
+    Mirror,
+}
+
+fn base() -> Settings {
+    Settings {
+        smoothing: 0.3,
+        smoother: SmootherKind::Exponential,
+        tilt: Tilt::Slope(3.0),
+        gradient: Anchor::Frequency,
+    }
+}
+
+fn rows() -> Overrides {
+    Overrides {
+        smoothing: Some(0.7),
+        smoother: Some(SmootherKind::AttackRelease),
+        tilt: Some(Tilt::Flat),
+        gradient: Some(Anchor::Index),
+    }
+}
+
+#[test]
+fn a_mode_without_overrides_follows_the_base() {
+    let memory = ModeMemory::new(Mode::Bars, [(Mode::Rows, rows())]);
+    assert_eq!(memory.settings(&base()), base());
+    assert!(memory.overrides(Mode::Bars).is_empty());
+}
+
+#[test]
+fn switching_modes_round_trips_the_overrides() {
+    let mut memory = ModeMemory::new(Mode::Bars, [(Mode::Rows, rows())]);
+    let in_rows = memory.switch(Mode::Rows, &base());
+    assert_eq!(in_rows, base().with(&rows()));
+    assert_eq!((in_rows.smoothing, in_rows.tilt.clone(), in_rows.gradient), (0.7, Tilt::Flat, Anchor::Index));
+    for _ in 0..3 {
+        assert_eq!(memory.switch(Mode::Bars, &base()), base());
+        assert_eq!(memory.switch(Mode::Mirror, &base()), base());
+        assert_eq!(memory.switch(Mode::Rows, &base()), in_rows);
+    }
+    assert_eq!(memory.mode(), Mode::Rows);
+}
+
+#[test]
+fn a_change_is_remembered_for_its_mode_only() {
+    let mut memory = ModeMemory::new(Mode::Bars, []);
+    memory.set_tilt(Some(Tilt::Flat));
+    memory.switch(Mode::Rows, &base());
+    memory.set_smoothing(Some(0.9));
+    memory.set_gradient(Some(Anchor::Index));
+    memory.set_smoother(Some(SmootherKind::Passthrough));
+
+    let in_bars = memory.switch(Mode::Bars, &base());
+    assert_eq!((in_bars.smoothing, in_bars.tilt, in_bars.gradient), (0.3, Tilt::Flat, Anchor::Frequency));
+    assert_eq!(in_bars.smoother, SmootherKind::Exponential);
+    let in_rows = memory.switch(Mode::Rows, &base());
+    assert_eq!((in_rows.smoothing, in_rows.tilt, in_rows.gradient), (0.9, Tilt::Slope(3.0), Anchor::Index));
+    assert_eq!(in_rows.smoother, SmootherKind::Passthrough);
+}
+
+#[test]
+fn partial_overrides_let_base_changes_through() {
+    let mut memory = ModeMemory::new(Mode::Rows, [(Mode::Rows, Overrides { tilt: Some(Tilt::Flat), ..Overrides::default() })]);
+    let mut changed = base();
+    changed.smoothing = 0.5;
+    changed.tilt = Tilt::Slope(1.5);
+    let settings = memory.switch(Mode::Rows, &changed);
+    assert_eq!(settings.smoothing, 0.5);
+    assert_eq!(settings.tilt, Tilt::Flat);
+}
+
+#[test]
+fn clearing_every_override_forgets_the_mode() {
+    let mut memory = ModeMemory::new(Mode::Rows, [(Mode::Rows, rows())]);
+    memory.set_smoothing(None);
+    memory.set_smoother(None);
+    memory.set_tilt(None);
+    assert!(!memory.overrides(Mode::Rows).is_empty());
+    memory.set_gradient(None);
+    assert!(memory.overrides(Mode::Rows).is_empty());
+    assert_eq!(memory.settings(&base()), base());
+}
@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+use gruvberry::oneline::{self, Layout, MAX_CELLS, MIN_CELLS};
+use gruvberry::sparkline;
+
+#[test]
+fn the_row_keeps_clear_of_the_last_column() {
+    let time = oneline::time(5.0, 200.0);
+    assert_eq!(time, "0:05/3:20");
+    for width in 0..120 {
+        let layout = Layout::fit(width, time.len());
+        assert!(layout.width(time.len()) < width.max(1), "{} columns: {:?}", width, layout);
+        assert!(layout.cells <= MAX_CELLS);
+    }
+    // The time goes before the spectrum drops below its fewest cells
+    assert_eq!(Layout::fit(80, 9), Layout { cells: MAX_CELLS, time: true });
+    assert_eq!(Layout::fit(MIN_CELLS + 11, 9), Layout { cells: MIN_CELLS, time: true });
+    assert_eq!(Layout::fit(MIN_CELLS + 10, 9), Layout { cells: MAX_CELLS, time: false });
+    assert_eq!(Layout::fit(6, 9), Layout { cells: 5, time: false });
+}
+
+#[test]
+fn the_time_keeps_its_width_as_it_goes_by() {
+    assert_eq!(oneline::time(7.0, 3725.0), "   0:07/1:02:05");
+    assert_eq!(oneline::time(3700.0, 3725.0), "1:01:40/1:02:05");
+    assert_eq!(oneline::time(61.0, 600.0), " 1:01/10:00");
+    // A stream without an end has no total
+    assert_eq!(oneline::time(61.0, f32::INFINITY), "1:01");
+}
+
+#[test]
+fn bands_are_merged_into_the_cells_by_their_highest() {
+    assert_eq!(oneline::bars(&[0.0, 100.0, 50.0, 0.0], 2), "█▅");
+    assert_eq!(oneline::bars(&[100.0; 64], MAX_CELLS), "█".repeat(MAX_CELLS));
+    assert_eq!(oneline::bars(&[], 8).chars().count(), 8);
+}
+
+// Three seconds of a 440 Hz tone to play
+fn tone_file() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("gruvberry-oneline-{}.wav", std::process::id()));
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 8000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for i in 0..3 * 8000 {
+        let sample = (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 8000.0).sin();
+        writer.write_sample((sample * 0.5 * i16::MAX as f32) as i16).unwrap();
+    }
+    writer.finalize().unwrap();
+    path
+}
+
+// The text of `output` without its escape sequences (colors)
+fn without_escapes(output: &str) -> String {
+    let mut text = String::new();
+    let mut chars = output.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI: up to and including the final byte
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+// gruvberry --oneline in a pty COLUMNS wide, quit with 'q' after a moment.
+// Every refresh starts with a carriage return and the row is only ended on
+// the way out, so all of them come before the first line break, and none
+// may reach the last column.
+#[cfg(unix)]
+#[test]
+fn the_row_refreshes_in_place_on_one_line() {
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use std::os::fd::{FromRawFd, OwnedFd};
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    const COLUMNS: u16 = 40;
+    let (mut master, mut slave) = (0, 0);
+    let size = libc::winsize { ws_row: 10, ws_col: COLUMNS, ws_xpixel: 0, ws_ypixel: 0 };
+    let opened = unsafe { libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null(), &size) };
+    assert_eq!(opened, 0, "no pty");
+    let master = unsafe { OwnedFd::from_raw_fd(master) };
+    let slave = unsafe { OwnedFd::from_raw_fd(slave) };
+
+    let path = tone_file();
+    let config = path.with_extension("toml");
+    std::fs::write(&config, "").unwrap();
+    let mut command = Command::new(env!("CARGO_BIN_EXE_gruvberry"));
+    command
+        .args(["--oneline", "--quiet", "--no-color", "--config"])
+        .arg(&config)
+        .arg(&path)
+        .stdin(Stdio::from(slave.try_clone().unwrap()))
+        .stdout(Stdio::from(slave.try_clone().unwrap()))
+        .stderr(Stdio::from(slave.try_clone().unwrap()));
+    unsafe {
+        command.pre_exec(|| {
+            libc::setsid();
+            libc::ioctl(0, libc::TIOCSCTTY, 0);
+            Ok(())
+        });
+    }
+    let mut child = command.spawn().unwrap();
+    drop(command);
+    drop(slave);
+    let mut reader = File::from(master.try_clone().unwrap());
+    let output = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = reader.read_to_end(&mut output);
+        output
+    });
+    std::thread::sleep(Duration::from_millis(800));
+    // It may have played out already
+    let _ = File::from(master.try_clone().unwrap()).write_all(b"q");
+    let status = child.wait().unwrap();
+    let output = String::from_utf8_lossy(&output.join().unwrap()).into_owned();
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&config);
+    if output.contains("Can't open the audio device") {
+        eprintln!("skipped: no audio device ({})", output.trim());
+        return;
+    }
+    assert!(status.success(), "{:?}", output);
+
+    assert!(!output.contains("\x1b[?1049h"), "took the alternate screen");
+    let text = without_escapes(&output);
+    // Only rodio's goodbye may follow the row
+    let (rows, after) = text.split_once("\r\n").unwrap_or_else(|| panic!("the row wasn't ended: {:?}", text));
+    assert!(!after.contains(sparkline::LEVELS), "more than one row: {:?}", text);
+    let refreshes: Vec<&str> = rows.split('\r').filter(|row| !row.is_empty()).collect();
+    assert!(refreshes.len() >= 2, "{:?}", rows);
+    for row in refreshes {
+        assert!(row.chars().count() < COLUMNS as usize, "{:?} reaches the last column", row);
+        assert!(row.contains('/'), "no time in {:?}", row);
+    }
+}
@@ -0,0 +1,229 @@
+use gruvberry::analysis::{self, Analyzer};
+use gruvberry::onset::{Activity, BeatTracker, Bounce, MIN_ONSET_GAP_SECS, OnsetDetector, SpectralFlux};
+use gruvberry::tilt::Tilt;
+
+const KICK: f32 = 1.15;
+const DECAY: f32 = 0.1;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+// A constant band vector kicked at t=0 follows the kick/decay curve
+#[test]
+fn bounce_envelope_matches_the_curve() {
+    let mut bounce = Bounce::new(KICK, DECAY);
+    bounce.kick(0.0);
+    for (time, expected) in [(0.0, 115.0), (0.025, 111.25), (0.05, 107.5), (0.075, 103.75), (0.1, 100.0), (0.5, 100.0)] {
+        let mut bands = vec![100.0; 8];
+        bounce.apply(&mut bands, time);
+        assert!(bands.iter().all(|&b| close(b, expected)), "at {}s: {:?}", time, bands);
+    }
+}
+
+// Sampling the envelope at different frame rates gives the same values at the same times
+#[test]
+fn bounce_is_frame_rate_independent() {
+    let mut bounce = Bounce::new(KICK, DECAY);
+    bounce.kick(1.0);
+    for fps in [30.0, 60.0, 144.0] {
+        let frame = 1.0 / fps;
+        let mut time: f64 = 1.0;
+        while time < 1.2 {
+            let expected = 1.0 + (KICK - 1.0) * (1.0 - (time - 1.0) as f32 / DECAY).max(0.0);
+            assert!(close(bounce.gain(time), expected), "{} fps at {}s", fps, time);
+            time += frame;
+        }
+    }
+}
+
+#[test]
+fn no_kick_leaves_bands_alone() {
+    let bounce = Bounce::new(KICK, DECAY);
+    let mut bands = vec![42.0, 7.0];
+    bounce.apply(&mut bands, 3.0);
+    assert_eq!(bands, [42.0, 7.0]);
+}
+
+// A burst after quiet is an onset; holding it steady isn't another one
+#[test]
+fn detects_a_burst_once() {
+    let mut detector = OnsetDetector::new();
+    let quiet = vec![0.01; 64];
+    let loud = vec![50.0; 64];
+    let frame = 1.0 / 60.0;
+    let mut time = 0.0;
+    for _ in 0..60 {
+        assert!(!detector.update(&quiet, time));
+        time += frame;
+    }
+    assert!(detector.update(&loud, time));
+    for _ in 0..60 {
+        time += frame;
+        assert!(!detector.update(&loud, time));
+    }
+}
+
+// A second hit sooner than the minimum gap doesn't count; a later one does
+#[test]
+fn onsets_are_spaced() {
+    let mut detector = OnsetDetector::new();
+    let quiet = vec![0.0; 32];
+    let loud = vec![100.0; 32];
+    let frame = 1.0 / 60.0;
+    let mut frames = vec![&quiet; 30];
+    frames.extend([&loud, &quiet, &quiet, &loud]);
+    frames.extend(vec![&quiet; 30]);
+    frames.push(&loud);
+    assert!(3.0 * frame < MIN_ONSET_GAP_SECS as f64);
+
+    let onsets: Vec<usize> = frames
+        .iter()
+        .enumerate()
+        .filter(|&(i, spectrum)| detector.update(spectrum, i as f64 * frame))
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(onsets, [30, 64]);
+}
+
+// Deterministic jitter in -max..=max seconds
+fn jitter(seed: &mut u32, max: f32) -> f32 {
+    *seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+    (*seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 * max - max
+}
+
+// Onsets at 120 BPM, each up to 20 ms off the beat: once locked, every
+// predicted beat lands within 30 ms of the true grid
+#[test]
+fn beat_grid_locks_to_jittered_onsets() {
+    let beat = 0.5;
+    let mut tracker = BeatTracker::new();
+    let mut seed = 7;
+    let mut last_onset = 0.0;
+    for n in 0..64 {
+        let truth = 1.0 + n as f64 * beat;
+        if n >= 8 {
+            // Asked halfway between the last onset and the beat to come
+            let predicted = tracker.next_beat(last_onset + beat / 2.0).expect("locked by now");
+            assert!((predicted - truth).abs() <= 0.03, "beat {}: predicted {} for {}", n, predicted, truth);
+        }
+        last_onset = truth + jitter(&mut seed, 0.02) as f64;
+        tracker.onset(last_onset);
+    }
+    let bpm = tracker.bpm().unwrap();
+    assert!((bpm - 120.0).abs() < 2.0, "{} BPM", bpm);
+}
+
+// Offbeat onsets (eighth notes between the beats) don't drag the grid
+#[test]
+fn beat_grid_ignores_offbeats() {
+    let mut tracker = BeatTracker::new();
+    for n in 0..32 {
+        tracker.onset(n as f64 * 0.5);
+    }
+    for n in 32..48 {
+        tracker.onset(n as f64 * 0.5);
+        tracker.onset(n as f64 * 0.5 + 0.25);
+    }
+    let predicted = tracker.next_beat(48.0 * 0.5 - 0.1).unwrap();
+    assert!((predicted - 24.0).abs() <= 0.03, "{}", predicted);
+}
+
+#[test]
+fn no_grid_before_enough_onsets() {
+    let mut tracker = BeatTracker::new();
+    tracker.onset(0.0);
+    tracker.onset(0.5);
+    assert_eq!(tracker.bpm(), None);
+    assert_eq!(tracker.next_beat(1.0), None);
+}
+
+const RATE: usize = 44100;
+// Analyses 60 times a second, as the display does
+const HOP: usize = RATE / 60;
+
+// Two clicks a second on silence, from 0.25 s on, `secs` long
+fn click_train(secs: usize) -> (Vec<f32>, Vec<f64>) {
+    let clicks: Vec<f64> = (0..secs * 2).map(|n| 0.25 + n as f64 * 0.5).collect();
+    let mut samples = vec![0.0; secs * RATE];
+    for &click in &clicks {
+        let start = (click * RATE as f64) as usize;
+        samples[start..start + 20].fill(0.9);
+    }
+    (samples, clicks)
+}
+
+// The magnitude spectrum of each hop's window, with the time it ends at
+fn spectra(samples: &[f32]) -> Vec<(f64, Vec<f32>)> {
+    let mut analyzer = Analyzer::new(RATE as u32, analysis::DEFAULT_FFT_SIZE, 1.0, Tilt::Flat);
+    (analysis::DEFAULT_FFT_SIZE..samples.len())
+        .step_by(HOP)
+        .map(|end| {
+            analyzer.process(&samples[end - analysis::DEFAULT_FFT_SIZE..end], 60);
+            (end as f64 / RATE as f64, analyzer.magnitudes())
+        })
+        .collect()
+}
+
+// The detector's flux is the shared one, and it finds each click of the train once
+#[test]
+fn flux_spikes_on_each_click_and_the_detector_uses_it() {
+    let (samples, clicks) = click_train(4);
+    let mut flux = SpectralFlux::new();
+    let mut detector = OnsetDetector::new();
+    let mut onsets = Vec::new();
+    let mut quiet = Vec::new();
+    for (time, magnitudes) in spectra(&samples) {
+        let onset = detector.update(&magnitudes, time);
+        let expected = flux.update(&magnitudes).unwrap_or(0.0);
+        assert_eq!(detector.flux(), expected, "at {}s", time);
+        if onset {
+            onsets.push(time);
+        }
+        // Once a click has left the window, until the next one comes in
+        let since = clicks.iter().rev().find(|&&click| click <= time).map_or(f64::MAX, |click| time - click);
+        if since > 0.1 && since < 0.45 {
+            quiet.push(detector.flux());
+        }
+    }
+    assert_eq!(onsets.len(), clicks.len(), "{:?}", onsets);
+    for onset in onsets {
+        let window = analysis::DEFAULT_FFT_SIZE as f64 / RATE as f64;
+        assert!(clicks.iter().any(|&click| onset >= click && onset <= click + window), "onset at {}s", onset);
+    }
+    assert!(!quiet.is_empty() && quiet.iter().all(|&flux| flux == 0.0), "{:?}", quiet);
+}
+
+// The meter jumps on the clicks and is back down well before the next one;
+// a steady tone keeps it low
+#[test]
+fn activity_spikes_on_clicks_and_stays_low_on_a_tone() {
+    let (samples, clicks) = click_train(4);
+    let mut detector = OnsetDetector::new();
+    let mut activity = Activity::new();
+    let mut readings = Vec::new();
+    for (time, magnitudes) in spectra(&samples) {
+        detector.update(&magnitudes, time);
+        activity.update(detector.flux(), time);
+        readings.push((time, activity.level()));
+    }
+    for &click in &clicks[1..] {
+        let around = |from: f64, to: f64| readings.iter().filter(move |(time, _)| *time >= from && *time < to);
+        let peak = around(click, click + 0.1).map(|&(_, level)| level).fold(0.0, f32::max);
+        assert!(peak > 0.8, "peak {} after the click at {}s", peak, click);
+        assert!(around(click + 0.4, click + 0.5).all(|&(_, level)| level < 0.1), "still up after {}s", click);
+    }
+
+    // Whole cycles of it per hop, so the window sees the same tone each time
+    let tone: Vec<f32> =
+        (0..2 * RATE).map(|i| ((i as f64 * 480.0 / RATE as f64 * std::f64::consts::TAU).sin() * 0.5) as f32).collect();
+    let mut detector = OnsetDetector::new();
+    let mut activity = Activity::new();
+    for (time, magnitudes) in spectra(&tone) {
+        detector.update(&magnitudes, time);
+        activity.update(detector.flux(), time);
+        if time > 0.5 {
+            assert!(activity.level() < 0.05, "{} at {}s", activity.level(), time);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use gruvberry::format::Locale;
+use gruvberry::gradient::{self, Endpoint, Gradient};
+use gruvberry::palette::{self, Editor, Field};
+
+const CONFIG: &str = "\
+# Settings for the living room
+fps = 30 # the TV can't keep up
+theme_slots = [\"sunset\", \"vibgyor\"]
+not_a_setting_yet = true
+
+[themes.sunset]
+low_hue = 350
+low_saturation = 0.9
+high_hue = 40.0
+high_saturation = 1.0
+
+[profile.quiet]
+# quieter at night
+fps = 20
+";
+
+fn config_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("gruvberry-palette-{}-{}.toml", std::process::id(), name))
+}
+
+fn themes(text: &str) -> toml::Table {
+    let table: toml::Table = text.parse().unwrap();
+    table[palette::TABLE].as_table().unwrap().clone()
+}
+
+#[test]
+fn a_saved_theme_round_trips_through_the_config_file_leaving_the_rest_alone() {
+    let path = config_path("round-trip");
+    std::fs::write(&path, CONFIG).unwrap();
+
+    let mut editor = Editor::new(palette::parse(themes(CONFIG)["sunset"].as_table().unwrap()).unwrap());
+    editor.select(Field::LowSaturation);
+    editor.adjust(1);
+    editor.move_selection(1);
+    editor.adjust(-4);
+    let mut document: toml_edit::DocumentMut = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+    palette::write(&mut document, "dusk", &editor.gradient()).unwrap();
+    std::fs::write(&path, document.to_string()).unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let saved = themes(&text);
+    assert_eq!(palette::parse(saved["dusk"].as_table().unwrap()), Ok(editor.gradient()));
+    assert_eq!(
+        editor.gradient(),
+        Gradient::Sweep {
+            low: Endpoint { hue: 350.0, saturation: 0.95 },
+            high: Endpoint { hue: 20.0, saturation: 1.0 },
+        }
+    );
+    // Everything that was there is still there, as it was written
+    for line in CONFIG.lines().filter(|line| !line.is_empty()) {
+        assert!(text.contains(line), "lost {:?} in:\n{}", line, text);
+    }
+    let table: toml::Table = text.parse().unwrap();
+    assert_eq!(table["theme"].as_str(), Some("dusk"));
+    assert_eq!(table["profile"]["quiet"]["fps"].as_integer(), Some(20));
+    let sunset = palette::parse(themes(CONFIG)["sunset"].as_table().unwrap());
+    assert_eq!(palette::parse(saved["sunset"].as_table().unwrap()), sunset);
+
+    // Saved again under the same name, it is replaced rather than repeated
+    let mut document: toml_edit::DocumentMut = text.parse().unwrap();
+    palette::write(&mut document, "dusk", &Gradient::Vibgyor).unwrap();
+    let text = document.to_string();
+    assert_eq!(text.matches("[themes.dusk]").count(), 1);
+    let (low, high) = palette::VIBGYOR_ENDPOINTS;
+    assert_eq!(palette::parse(themes(&text)["dusk"].as_table().unwrap()), Ok(Gradient::Sweep { low, high }));
+}
+
+#[test]
+fn theme_sections_need_every_key_in_range_and_names_are_plain() {
+    let section = |text: &str| palette::parse(&text.parse::<toml::Table>().unwrap());
+    assert!(section("low_hue = 10\nlow_saturation = 1\nhigh_hue = 300\nhigh_saturation = 0.5").is_ok());
+    assert!(section("low_hue = 10\nlow_saturation = 1\nhigh_hue = 300").is_err());
+    assert!(section("low_hue = 361\nlow_saturation = 1\nhigh_hue = 300\nhigh_saturation = 0.5").is_err());
+    assert!(section("low_hue = 10\nlow_saturation = 1.5\nhigh_hue = 300\nhigh_saturation = 0.5").is_err());
+    assert!(section("low_hue = 10\nlow_saturation = 1\nhigh_hue = 300\nhigh_saturation = 0.5\nvalue = 1").is_err());
+    assert!(section("low_hue = \"red\"\nlow_saturation = 1\nhigh_hue = 300\nhigh_saturation = 0.5").is_err());
+
+    assert!(palette::check_name("late-night_2").is_ok());
+    for bad in ["", "two words", "dot.ted", palette::BUILT_IN] {
+        assert!(palette::check_name(bad).is_err(), "{:?} accepted", bad);
+    }
+    let mut document = toml_edit::DocumentMut::new();
+    assert!(palette::write(&mut document, "a.b", &Gradient::Vibgyor).is_err());
+    assert_eq!(document.to_string(), "");
+}
+
+#[test]
+fn the_editor_steps_within_range_and_remembers_where_it_started() {
+    let mut editor = Editor::new(Gradient::Vibgyor);
+    assert_eq!(editor.field(), Field::LowHue);
+    editor.adjust(-1);
+    assert_eq!(editor.value_text(Field::LowHue, Locale::default()), "0°");
+    editor.move_selection(-3);
+    assert_eq!(editor.field(), Field::LowHue);
+    editor.move_selection(10);
+    assert_eq!(editor.field(), Field::HighSaturation);
+    editor.adjust(-3);
+    assert_eq!(editor.value_text(Field::HighSaturation, Locale::default()), "85%");
+    editor.adjust(100);
+    assert_eq!(editor.value_text(Field::HighSaturation, Locale::default()), "100%");
+    editor.select(Field::HighHue);
+    editor.adjust(100);
+    assert_eq!(editor.value_text(Field::HighHue, Locale::default()), "360°");
+    assert_eq!(editor.original(), Gradient::Vibgyor);
+}
+
+#[test]
+fn a_sweep_runs_from_its_low_color_to_its_high_one() {
+    assert_eq!(gradient::hsv(0.0, 1.0, 1.0), (255, 0, 0));
+    assert_eq!(gradient::hsv(120.0, 1.0, 1.0), (0, 255, 0));
+    assert_eq!(gradient::hsv(240.0, 0.0, 1.0), (255, 255, 255));
+    let sweep = Gradient::Sweep {
+        low: Endpoint { hue: 0.0, saturation: 1.0 },
+        high: Endpoint { hue: 240.0, saturation: 1.0 },
+    };
+    assert_eq!(sweep.rgb(0.0), (255, 0, 0));
+    assert_eq!(sweep.rgb(0.5), (0, 255, 0));
+    assert_eq!(sweep.rgb(1.0), (0, 0, 255));
+    // Out of range ratios stay at the ends
+    assert_eq!(sweep.rgb(2.0), (0, 0, 255));
+    assert_eq!(Gradient::Vibgyor.rgb(0.0), gradient::vibgyor(0.0));
+}
@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use gruvberry::playlist::{Playlist, Repeat, Step};
+
+fn paths(count: usize) -> Vec<PathBuf> {
+    (0..count).map(|i| PathBuf::from(format!("{}.wav", i))).collect()
+}
+
+// Let the player move on `count` times, as if each track ended on its own
+fn play(playlist: &mut Playlist, count: usize) -> Vec<PathBuf> {
+    (0..count).map_while(|_| playlist.advance()).collect()
+}
+
+fn step(playlist: &mut Playlist, step: Step) -> Option<PathBuf> {
+    playlist.skip(step);
+    assert!(playlist.take_interrupt());
+    playlist.advance()
+}
+
+#[test]
+fn shuffle_plays_everything_once_before_anything_twice() {
+    let mut playlist = Playlist::new(paths(20), true, Repeat::Off);
+    let played = play(&mut playlist, 20);
+    assert_eq!(played.iter().collect::<HashSet<_>>().len(), 20, "{:?}", played);
+    assert_eq!(playlist.advance(), None);
+}
+
+#[test]
+fn each_round_of_shuffle_repeat_all_is_a_new_order_of_everything() {
+    let mut playlist = Playlist::new(paths(20), true, Repeat::All);
+    let played = play(&mut playlist, 60);
+    for round in played.chunks(20) {
+        assert_eq!(round.iter().collect::<HashSet<_>>().len(), 20, "{:?}", round);
+    }
+    // The track just heard doesn't open the next round
+    for seam in [19, 39] {
+        assert_ne!(played[seam], played[seam + 1]);
+    }
+}
+
+#[test]
+fn previous_goes_back_through_what_was_played_and_next_replays_it() {
+    let mut playlist = Playlist::new(paths(20), true, Repeat::Off);
+    let played = play(&mut playlist, 6);
+
+    let back: Vec<PathBuf> = (0..5).filter_map(|_| step(&mut playlist, Step::Previous)).collect();
+    let expected: Vec<PathBuf> = played[..5].iter().rev().cloned().collect();
+    assert_eq!(back, expected);
+    // At the first track played, Previous starts it over
+    assert_eq!(step(&mut playlist, Step::Previous).as_ref(), Some(&played[0]));
+
+    let forward: Vec<PathBuf> = (0..5).filter_map(|_| step(&mut playlist, Step::Next)).collect();
+    assert_eq!(forward, played[1..]);
+    // Then on to what hasn't played yet, still with no repeats
+    let rest = play(&mut playlist, 14);
+    let all: HashSet<&PathBuf> = played.iter().chain(&rest).collect();
+    assert_eq!(all.len(), 20);
+    assert_eq!(playlist.advance(), None);
+}
+
+#[test]
+fn a_file_added_while_shuffling_plays_in_this_round() {
+    let mut playlist = Playlist::new(paths(5), true, Repeat::Off);
+    play(&mut playlist, 2);
+    playlist.add(PathBuf::from("new.wav"));
+    let rest = play(&mut playlist, 10);
+    assert_eq!(rest.len(), 4);
+    assert!(rest.contains(&PathBuf::from("new.wav")));
+}
+
+#[test]
+fn turning_shuffle_on_midway_leaves_out_what_already_played() {
+    let mut playlist = Playlist::new(paths(10), false, Repeat::Off);
+    let played = play(&mut playlist, 3);
+    playlist.set_shuffle(true);
+    let rest = play(&mut playlist, 10);
+    assert_eq!(rest.len(), 7);
+    assert!(rest.iter().all(|path| !played.contains(path)), "{:?} after {:?}", rest, played);
+}
@@ -0,0 +1,107 @@
+use gruvberry::presence::{self, Activity, SEGMENTS, mini_spectrum};
+
+fn activity() -> Activity {
+    Activity {
+        title: "Song".to_string(),
+        artist: Some("Band".to_string()),
+        elapsed: 83.4,
+        total: 296.0,
+        paused: false,
+        spectrum: "▁▃▅█▆▄▂▁".to_string(),
+    }
+}
+
+#[test]
+fn mini_spectrum_has_one_character_per_segment() {
+    for len in [0, 1, 3, 7, 8, 9, 60, 317] {
+        let bands: Vec<f32> = (0..len).map(|i| (i * 37 % 101) as f32).collect();
+        assert_eq!(mini_spectrum(&bands).chars().count(), SEGMENTS, "{} bands", len);
+    }
+}
+
+#[test]
+fn mini_spectrum_levels() {
+    assert_eq!(mini_spectrum(&[0.0; 40]), "▁▁▁▁▁▁▁▁");
+    assert_eq!(mini_spectrum(&[100.0; 40]), "████████");
+    // Past either end of the scale, and not a number at all
+    assert_eq!(mini_spectrum(&[250.0; 16]), "████████");
+    assert_eq!(mini_spectrum(&[-5.0, f32::NAN, f32::INFINITY, 0.0]), "▁▁▁▁▁▁▁▁");
+    // Each segment is the average of its share: 0 and 100 make 50
+    let halves: Vec<f32> = (0..16).map(|i| if i % 2 == 0 { 0.0 } else { 100.0 }).collect();
+    assert_eq!(mini_spectrum(&halves), "▅▅▅▅▅▅▅▅");
+}
+
+#[test]
+fn mini_spectrum_stretches_few_bands() {
+    // Four bands cover two segments each
+    assert_eq!(mini_spectrum(&[100.0, 0.0, 60.0, 30.0]), "██▁▁▅▅▃▃");
+}
+
+#[test]
+fn names_split_into_artist_and_title() {
+    assert_eq!(presence::split_name("Band - Song.wav"), (Some("Band"), "Song"));
+    assert_eq!(presence::split_name("Band - Song - Live.wav"), (Some("Band"), "Song - Live"));
+    assert_eq!(presence::split_name("long1.wav"), (None, "long1"));
+    assert_eq!(presence::split_name(" - Song.wav"), (None, "- Song"));
+    assert_eq!(presence::split_name("no extension"), (None, "no extension"));
+}
+
+#[test]
+fn status_lines() {
+    let mut activity = activity();
+    assert_eq!(activity.details(), "Song — Band");
+    assert_eq!(activity.state(), "▁▃▅█▆▄▂▁ 1:23 / 4:56");
+    activity.paused = true;
+    activity.total = f32::INFINITY;
+    assert_eq!(activity.state(), "▁▃▅█▆▄▂▁ 1:23 (paused)");
+    activity.artist = None;
+    activity.title = "x".to_string();
+    assert_eq!(activity.details(), "x ");
+}
+
+#[test]
+fn window_title() {
+    let mut activity = activity();
+    assert_eq!(activity.window_title(), "gruvberry ▸ Band – Song [1:23/4:56]");
+    activity.artist = None;
+    activity.paused = true;
+    assert_eq!(activity.window_title(), "gruvberry ▸ Song [1:23/4:56] (paused)");
+    activity.total = f32::INFINITY;
+    activity.paused = false;
+    assert_eq!(activity.window_title(), "gruvberry ▸ Song [1:23]");
+}
+
+#[test]
+fn status_lines_fit_discord_limits() {
+    let mut activity = activity();
+    activity.title = "ü".repeat(200);
+    let details = activity.details();
+    assert!(details.len() <= presence::MAX_TEXT_LEN);
+    assert!(details.chars().all(|c| c == 'ü' || c == ' ' || c == '—'));
+}
+
+#[test]
+fn json_of_a_playing_track() {
+    let mut activity = activity();
+    activity.title = "Say \"hi\"\\".to_string();
+    assert_eq!(
+        activity.to_json(1_000_000),
+        "{\"type\":2,\"details\":\"Say \\\"hi\\\"\\\\ — Band\",\"state\":\"▁▃▅█▆▄▂▁ 1:23 / 4:56\",\
+         \"timestamps\":{\"start\":916600,\"end\":1212600}}"
+    );
+}
+
+#[test]
+fn json_of_a_paused_track_has_no_time_bar() {
+    let mut activity = activity();
+    activity.paused = true;
+    assert!(!activity.to_json(1_000_000).contains("timestamps"));
+    activity.paused = false;
+    activity.total = f32::INFINITY;
+    assert!(activity.to_json(1_000_000).ends_with("\"timestamps\":{\"start\":916600}}"));
+}
+
+#[test]
+fn json_strings_escape_control_characters() {
+    assert_eq!(presence::json_string("a\nb\t\u{1}"), "\"a\\nb\\t\\u0001\"");
+}
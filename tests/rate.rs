@@ -0,0 +1,133 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use gruvberry::analysis::{Analyzer, FreqRange};
+use gruvberry::capture::{SampleCapture, SampleRing};
+use gruvberry::rate;
+use gruvberry::tilt::Tilt;
+use rodio::{Decoder, source::UniformSourceIterator};
+
+const FFT_SIZE: usize = 4096;
+
+// One second of a 1 kHz tone as a 16-bit stereo WAV at `sample_rate`
+fn tone_file(sample_rate: u32) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("gruvberry-rate-{}-{}.wav", std::process::id(), sample_rate));
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for i in 0..sample_rate {
+        let sample = (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin();
+        let sample = (sample * 0.5 * i16::MAX as f32) as i16;
+        writer.write_sample(sample).unwrap();
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+    path
+}
+
+// Play `path` through a capture tap into a device running at `device_rate`
+// (the conversion rodio's mixer does) and find the tone in what was captured
+fn captured_tone(path: &PathBuf, device_rate: u32) -> (u32, f32) {
+    let decoder = Decoder::new(BufReader::new(File::open(path).unwrap())).unwrap();
+    let ring = SampleRing::shared(FFT_SIZE * 2);
+    let tap = SampleCapture::new(decoder, ring.clone());
+    let played = UniformSourceIterator::new(tap, 2, device_rate).count();
+    assert!(played > 0);
+
+    let ring = ring.lock().unwrap();
+    let sample_rate = ring.sample_rate().unwrap();
+    let window = ring.window(FFT_SIZE, 0).unwrap();
+    (sample_rate, rate::dominant_frequency(&window, sample_rate).unwrap())
+}
+
+#[test]
+fn tone_is_found_at_its_frequency_whatever_the_file_rate() {
+    for file_rate in [44100, 48000] {
+        let path = tone_file(file_rate);
+        for device_rate in [44100, 48000] {
+            let (analysis_rate, freq) = captured_tone(&path, device_rate);
+            assert_eq!(analysis_rate, file_rate);
+            assert!((freq - 1000.0).abs() < 2.0, "{} Hz file on a {} Hz device: {} Hz", file_rate, device_rate, freq);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn bins_and_frequencies_round_trip() {
+    for sample_rate in [8000, 44100, 48000, 96000] {
+        let bin = rate::frequency_bin(1000.0, sample_rate, FFT_SIZE);
+        assert!((rate::bin_frequency(bin, sample_rate, FFT_SIZE) - 1000.0).abs() < 1e-3);
+    }
+    assert_eq!(rate::freq_per_bin(48000, 1024), 46.875);
+    assert_eq!(rate::nyquist(11025), 5512.5);
+}
+
+#[test]
+fn silence_has_no_dominant_frequency() {
+    assert_eq!(rate::dominant_frequency(&[0.0; 1024], 44100), None);
+}
+
+#[test]
+fn high_rates_stop_the_display_at_20_khz_and_grow_the_window() {
+    assert_eq!(rate::display_max_freq(48000, None), 24000.0);
+    assert_eq!(rate::display_max_freq(96000, None), rate::AUDIBLE_MAX_FREQ);
+    // A configured top wins, but can't go past Nyquist
+    assert_eq!(rate::display_max_freq(96000, Some(40000.0)), 40000.0);
+    assert_eq!(rate::display_max_freq(44100, Some(40000.0)), 22050.0);
+    // The window lasts about as long at any rate
+    assert_eq!(rate::fft_size_for(1024, 44100), 1024);
+    assert_eq!(rate::fft_size_for(1024, 88200), 2048);
+    assert_eq!(rate::fft_size_for(1024, 96000), 2048);
+    assert_eq!(rate::fft_size_for(1024, 176400), 4096);
+    assert_eq!(rate::fft_size_for(1024, 192000), 4096);
+    assert_eq!(rate::fft_size_for(16384, 192000), 16384);
+}
+
+// The first window of `path` (mono, its first channel) analyzed into
+// `num_bands`, with the analyzer that did it
+fn analyze_file(path: &PathBuf, max_display_freq: Option<f32>, num_bands: usize) -> (Analyzer, Vec<f32>) {
+    let mut reader = hound::WavReader::open(path).unwrap();
+    let spec = reader.spec();
+    let samples: Vec<f32> = reader
+        .samples::<i16>()
+        .step_by(spec.channels as usize)
+        .map(|sample| sample.unwrap() as f32 / i16::MAX as f32)
+        .collect();
+    let mut analyzer = Analyzer::new(spec.sample_rate, 1024, 1.0, Tilt::Flat);
+    analyzer.set_max_display_freq(max_display_freq);
+    let fft_size = analyzer.state().fft_size();
+    let bands = analyzer.process(&samples[..fft_size], num_bands).to_vec();
+    (analyzer, bands)
+}
+
+fn loudest(bands: &[f32]) -> usize {
+    bands.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0
+}
+
+#[test]
+fn a_96_khz_file_shows_like_a_44_1_khz_one() {
+    const BANDS: usize = 100;
+    let high = tone_file(96000);
+    let (analyzer, bands) = analyze_file(&high, None, BANDS);
+    std::fs::remove_file(&high).unwrap();
+    // Analyzed at its own rate, over as long a window
+    assert_eq!(analyzer.sample_rate(), 96000);
+    assert_eq!(analyzer.state().fft_size(), 2048);
+    assert_eq!(analyzer.range(), FreqRange::full(20000.0));
+
+    // The same axis as a 44.1 kHz file shown up to 20 kHz: the same band
+    // edges, and the tone in the same band
+    let low = tone_file(44100);
+    let (reference, reference_bands) = analyze_file(&low, Some(20000.0), BANDS);
+    std::fs::remove_file(&low).unwrap();
+    for band in 0..BANDS {
+        assert_eq!(analyzer.range().band_edges(band, BANDS), reference.range().band_edges(band, BANDS));
+    }
+    assert_eq!(loudest(&bands), analyzer.range().band_of(1000.0, BANDS));
+    assert_eq!(loudest(&bands), loudest(&reference_bands));
+}
@@ -0,0 +1,131 @@
+use std::io::Cursor;
+use std::time::Duration;
+use gruvberry::analysis::Analyzer;
+use gruvberry::raw::{self, Layout, RawFormat, RawSource};
+use gruvberry::tilt::Tilt;
+use rodio::Source;
+
+const LAYOUTS: [Layout; 4] = [Layout::S16Le, Layout::S24Le, Layout::S32Le, Layout::F32Le];
+const FFT_SIZE: usize = 4096;
+
+// `seconds` of a half-scale sine at `freq` in every channel, the way a
+// capture tool would write it
+fn sine(format: RawFormat, freq: f32, seconds: f32) -> Vec<u8> {
+    let frames = (seconds * format.sample_rate as f32) as usize;
+    let mut bytes = Vec::with_capacity(frames * format.frame_bytes());
+    for i in 0..frames {
+        let value = 0.5 * (std::f32::consts::TAU * freq * i as f32 / format.sample_rate as f32).sin();
+        for _ in 0..format.channels {
+            match format.layout {
+                Layout::S16Le => bytes.extend_from_slice(&((value * 32_767.0) as i16).to_le_bytes()),
+                Layout::S24Le => bytes.extend_from_slice(&((value * 8_388_607.0) as i32).to_le_bytes()[..3]),
+                Layout::S32Le => bytes.extend_from_slice(&((value as f64 * 2_147_483_647.0) as i32).to_le_bytes()),
+                Layout::F32Le => bytes.extend_from_slice(&value.to_le_bytes()),
+            }
+        }
+    }
+    bytes
+}
+
+fn source(format: RawFormat, bytes: Vec<u8>) -> RawSource<Cursor<Vec<u8>>> {
+    let len = bytes.len() as u64;
+    RawSource::new(Cursor::new(bytes), format, len)
+}
+
+// Strongest frequency in the first channel of a window from the start
+fn dominant_frequency(format: RawFormat, samples: &[f32]) -> f32 {
+    let mono: Vec<f32> = samples.iter().step_by(format.channels as usize).take(FFT_SIZE).cloned().collect();
+    let mut analyzer = Analyzer::new(format.sample_rate, FFT_SIZE, 1.0, Tilt::Flat);
+    analyzer.process(&mono, 64);
+    analyzer.peaks(1)[0].freq
+}
+
+#[test]
+fn every_layout_plays_the_sine_it_holds() {
+    for layout in LAYOUTS {
+        for (sample_rate, channels) in [(44100, 2), (48000, 1), (22050, 6)] {
+            let format = RawFormat {
+                layout,
+                sample_rate,
+                channels,
+            };
+            let source = source(format, sine(format, 1000.0, 0.5));
+            assert_eq!(source.total_duration(), Some(Duration::from_secs_f32(0.5)));
+            assert_eq!((source.channels(), source.sample_rate()), (channels, sample_rate));
+            let len = source.len();
+            let samples: Vec<f32> = source.collect();
+            assert_eq!(samples.len() as u64, len);
+            assert_eq!(samples.len(), sample_rate as usize / 2 * channels as usize);
+            assert!(samples.iter().all(|s| (-0.51..=0.51).contains(s)), "{:?}", layout);
+
+            let freq = dominant_frequency(format, &samples);
+            assert!((freq - 1000.0).abs() < 5.0, "{:?} at {} Hz: {} Hz", layout, sample_rate, freq);
+        }
+    }
+}
+
+#[test]
+fn samples_decode_to_full_scale() {
+    assert_eq!(Layout::S16Le.decode(&i16::MIN.to_le_bytes()), -1.0);
+    assert_eq!(Layout::S16Le.decode(&16384i16.to_le_bytes()), 0.5);
+    assert_eq!(Layout::S24Le.decode(&[0x00, 0x00, 0x80]), -1.0);
+    assert_eq!(Layout::S24Le.decode(&[0x00, 0x00, 0x40]), 0.5);
+    assert_eq!(Layout::S24Le.decode(&[0xff, 0xff, 0xff]), -1.0 / 8_388_608.0);
+    assert_eq!(Layout::S32Le.decode(&i32::MIN.to_le_bytes()), -1.0);
+    assert_eq!(Layout::F32Le.decode(&0.25f32.to_le_bytes()), 0.25);
+}
+
+#[test]
+fn a_partial_frame_at_the_end_is_left_out() {
+    let format = RawFormat::parse("s24le:8000:2").unwrap();
+    let mut bytes = sine(format, 440.0, 1.0);
+    bytes.extend_from_slice(&[1, 2, 3, 4]);
+    assert_eq!(format.duration(bytes.len() as u64), 1.0);
+    let source = source(format, bytes);
+    assert_eq!(source.len(), 16000);
+    assert_eq!(source.count(), 16000);
+}
+
+#[test]
+fn seeking_lands_on_a_frame() {
+    let format = RawFormat::parse("s16le:1000:2").unwrap();
+    // Each frame holds its own index in both channels
+    let bytes: Vec<u8> = (0..1000i16).flat_map(|i| [i.to_le_bytes(), i.to_le_bytes()]).flatten().collect();
+    let mut source = source(format, bytes);
+    source.next();
+    source.try_seek(Duration::from_millis(250)).unwrap();
+    assert_eq!(source.next(), Some(250.0 / 32_768.0));
+    assert_eq!(source.next(), Some(250.0 / 32_768.0));
+    source.try_seek(Duration::from_secs(5)).unwrap();
+    assert_eq!(source.next(), None);
+}
+
+#[test]
+fn a_raw_file_opens_by_path() {
+    let format = RawFormat::parse("f32le:16000:1").unwrap();
+    let path = std::env::temp_dir().join(format!("gruvberry-raw-{}.pcm", std::process::id()));
+    std::fs::write(&path, sine(format, 2000.0, 2.0)).unwrap();
+    let source = raw::open(&path, format).unwrap();
+    assert_eq!(source.total_duration(), Some(Duration::from_secs(2)));
+    let samples: Vec<f32> = source.collect();
+    std::fs::remove_file(&path).unwrap();
+    assert!((dominant_frequency(format, &samples) - 2000.0).abs() < 5.0);
+}
+
+#[test]
+fn bad_formats_say_what_is_wrong() {
+    for (value, expected) in [
+        ("s16le", "LAYOUT:RATE:CHANNELS"),
+        ("s16le:44100:2:1", "LAYOUT:RATE:CHANNELS"),
+        ("u8:44100:2", "unknown sample layout 'u8'"),
+        ("s16be:44100:2", "s16le, s24le, s32le, f32le"),
+        ("s16le:0:2", "sample rate '0'"),
+        ("s16le:44.1k:2", "sample rate '44.1k'"),
+        ("s16le:44100:0", "channel count '0'"),
+        ("s16le:44100:9", "from 1 to 8"),
+    ] {
+        let error = RawFormat::parse(value).unwrap_err();
+        assert!(error.contains(expected), "{}: {}", value, error);
+    }
+    assert_eq!(RawFormat::parse("f32le:192000:8").unwrap().frame_bytes(), 32);
+}
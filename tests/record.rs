@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use gruvberry::capture::{SampleCapture, SampleObserver};
+use gruvberry::record;
+use rodio::{Source, source::SineWave};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("gruvberry-record-{}-{}.wav", std::process::id(), name))
+}
+
+#[test]
+fn records_what_plays_sample_for_sample() {
+    let path = temp_path("sine");
+    let (recording, recorder) = record::start(&path).unwrap();
+    let source = SineWave::new(440.0).take_duration(Duration::from_millis(500));
+    let played: Vec<f32> = SampleCapture::new(source, recorder).collect();
+    let status = recording.finish();
+    assert_eq!(status.error, None);
+    assert_eq!(status.dropped, 0);
+    assert_eq!(status.frames, played.len() as u64);
+    assert_eq!(status.duration(), Duration::from_millis(500));
+
+    let mut reader = hound::WavReader::open(&path).unwrap();
+    let spec = reader.spec();
+    assert_eq!((spec.channels, spec.sample_rate, spec.bits_per_sample), (1, 48000, 32));
+    assert_eq!(spec.sample_format, hound::SampleFormat::Float);
+    let recorded: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+    assert_eq!(recorded, played);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn stops_at_another_format_and_keeps_what_came_before() {
+    let path = temp_path("format");
+    let (recording, mut recorder) = record::start(&path).unwrap();
+    recorder.on_samples(&[0.25; 64], 2, 44100);
+    recorder.on_samples(&[0.5; 64], 1, 48000);
+    recorder.on_samples(&[0.75; 64], 2, 44100);
+    let status = recording.finish();
+    assert!(status.error.as_deref().is_some_and(|e| e.contains("48000 Hz")), "{:?}", status.error);
+    assert_eq!(status.frames, 32);
+
+    let mut reader = hound::WavReader::open(&path).unwrap();
+    assert_eq!(reader.spec().channels, 2);
+    let recorded: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+    assert_eq!(recorded, vec![0.25; 64]);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn nothing_played_leaves_an_empty_file() {
+    let path = temp_path("empty");
+    let (recording, _recorder) = record::start(&path).unwrap();
+    let status = recording.finish();
+    assert_eq!(status.error, None);
+    assert_eq!(status.frames, 0);
+    assert!(path.exists());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn unwritable_path_fails_up_front() {
+    let path = std::env::temp_dir().join("gruvberry-no-such-dir").join("take.wav");
+    let error = record::start(&path).err().unwrap();
+    assert!(error.contains("can't record to"), "{error}");
+}
@@ -0,0 +1,70 @@
+use gruvberry::reference::{self, References};
+
+#[test]
+fn resample_keeps_same_count() {
+    let bands = [10.0, 40.0, 70.0, 20.0];
+    assert_eq!(reference::resample(&bands, 4), bands);
+}
+
+#[test]
+fn resample_up_interpolates_between_centers() {
+    // Two bands become four: the outer quarters hold the ends, the inner
+    // ones sit a quarter of the way from each end
+    let resampled = reference::resample(&[0.0, 100.0], 4);
+    assert_eq!(resampled, vec![0.0, 25.0, 75.0, 100.0]);
+}
+
+#[test]
+fn resample_down_follows_a_ramp() {
+    let ramp: Vec<f32> = (0..150).map(|band| band as f32).collect();
+    let resampled = reference::resample(&ramp, 60);
+    assert_eq!(resampled.len(), 60);
+    assert!(resampled.windows(2).all(|pair| pair[1] > pair[0]));
+    // Each new band's center lands on the same frequency in the old bands
+    for (band, level) in resampled.iter().enumerate() {
+        let center = (band as f32 + 0.5) * 150.0 / 60.0 - 0.5;
+        assert!((level - center).abs() < 1e-3, "band {band}: {level} vs {center}");
+    }
+}
+
+#[test]
+fn resample_of_nothing_is_silence() {
+    assert_eq!(reference::resample(&[], 3), vec![0.0; 3]);
+}
+
+#[test]
+fn slots_are_frozen_separately() {
+    let mut references = References::new();
+    assert!(references.is_empty());
+    references.freeze(&[1.0, 2.0]);
+    references.select(2);
+    references.freeze(&[3.0, 4.0]);
+    assert_eq!(references.frozen().collect::<Vec<_>>(), vec![0, 2]);
+    assert_eq!(references.curve(1, 2), None);
+    assert_eq!(references.curve(2, 2), Some(vec![3.0, 4.0]));
+
+    // Freezing again replaces the selected slot only
+    references.freeze(&[5.0, 6.0]);
+    assert_eq!(references.curve(0, 2), Some(vec![1.0, 2.0]));
+    assert_eq!(references.curve(2, 2), Some(vec![5.0, 6.0]));
+}
+
+#[test]
+fn select_ignores_missing_slots() {
+    let mut references = References::new();
+    references.select(1);
+    references.select(reference::SLOTS);
+    assert_eq!(references.selected(), 1);
+}
+
+#[test]
+fn clear_empties_every_slot() {
+    let mut references = References::new();
+    for slot in 0..reference::SLOTS {
+        references.select(slot);
+        references.freeze(&[50.0]);
+    }
+    references.clear();
+    assert!(references.is_empty());
+    assert_eq!(references.curves(10).count(), 0);
+}
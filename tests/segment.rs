@@ -0,0 +1,102 @@
+use std::time::Duration;
+use gruvberry::segment::{parse_time, Segment, SegmentSource};
+use rodio::Source;
+use rodio::buffer::SamplesBuffer;
+use rodio::source::SeekError;
+
+// Ten seconds of stereo at 100 Hz, each frame's samples holding its index
+fn counting() -> SamplesBuffer {
+    let samples: Vec<f32> = (0..1000).flat_map(|frame| [frame as f32, frame as f32]).collect();
+    SamplesBuffer::new(2, 100, samples)
+}
+
+// A source that can't seek, as some decoders can't
+struct Unseekable(SamplesBuffer);
+
+impl Iterator for Unseekable {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.0.next()
+    }
+}
+
+impl Source for Unseekable {
+    fn current_span_len(&self) -> Option<usize> {
+        self.0.current_span_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.0.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.0.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.0.total_duration()
+    }
+
+    fn try_seek(&mut self, _pos: Duration) -> Result<(), SeekError> {
+        Err(SeekError::NotSupported { underlying_source: "Unseekable" })
+    }
+}
+
+fn segment(start: &str, end: &str) -> Segment {
+    Segment::new(parse_time(start).ok(), parse_time(end).ok()).unwrap()
+}
+
+#[test]
+fn times_parse_as_seconds_minutes_or_hours() {
+    assert_eq!(parse_time("0"), Ok(Duration::ZERO));
+    assert_eq!(parse_time("90"), Ok(Duration::from_secs(90)));
+    assert_eq!(parse_time("2.25"), Ok(Duration::from_secs_f64(2.25)));
+    assert_eq!(parse_time("12:30"), Ok(Duration::from_secs(750)));
+    assert_eq!(parse_time("90:00"), Ok(Duration::from_secs(5400)));
+    assert_eq!(parse_time("1:02:03"), Ok(Duration::from_secs(3723)));
+    assert_eq!(parse_time("0:00:01.5"), Ok(Duration::from_secs_f64(1.5)));
+    for bad in ["", "-5", "1:60", "1:60:00", "1.5:30", "1:2:3:4", "12:", ":30", "1e3", "a:bc", " 12"] {
+        assert!(parse_time(bad).is_err(), "{:?} parsed", bad);
+    }
+}
+
+#[test]
+fn a_segment_ends_after_it_starts_and_within_the_file() {
+    assert!(Segment::new(parse_time("1:00").ok(), parse_time("0:30").ok()).is_err());
+    assert!(Segment::new(parse_time("1:00").ok(), parse_time("1:00").ok()).is_err());
+    assert!(Segment::new(None, None).unwrap().is_whole());
+    let open_ended = Segment::new(parse_time("5").ok(), None).unwrap();
+    assert_eq!(open_ended.length(8.0), 3.0);
+    assert!(open_ended.check(5.0).is_err());
+    assert!(Segment::default().check(0.0).is_ok());
+    assert_eq!(segment("1", "2").samples(100, 2), 200..400);
+}
+
+#[test]
+fn a_seekable_source_plays_from_the_start_offset_to_the_end_offset() {
+    let mut source = SegmentSource::new(counting(), segment("2", "3.5"));
+    assert_eq!(source.total_duration(), Some(Duration::from_secs_f32(1.5)));
+    let played: Vec<f32> = source.by_ref().collect();
+    assert_eq!(played.len(), 300);
+    assert_eq!((played[0], played[299]), (200.0, 349.0));
+    // Seeks are within the segment, and stop at its end
+    source.try_seek(Duration::from_secs(1)).unwrap();
+    assert_eq!(source.next(), Some(300.0));
+    assert_eq!(source.by_ref().count(), 99);
+    source.try_seek(Duration::from_secs(60)).unwrap();
+    assert_eq!(source.next(), None);
+}
+
+#[test]
+fn a_source_that_cannot_seek_is_skipped_through_instead() {
+    let source = SegmentSource::new(Unseekable(counting()), segment("2", "3.5"));
+    let played: Vec<f32> = source.collect();
+    assert_eq!(played.len(), 300);
+    assert_eq!((played[0], played[299]), (200.0, 349.0));
+
+    // Open-ended, to the source's own end
+    let source = SegmentSource::new(Unseekable(counting()), Segment::new(parse_time("9").ok(), None).unwrap());
+    assert_eq!(source.total_duration(), Some(Duration::from_secs(1)));
+    assert_eq!(source.collect::<Vec<f32>>().first(), Some(&900.0));
+}
@@ -0,0 +1,114 @@
+use gruvberry::analysis::Analyzer;
+use gruvberry::smoothing::{
+    AttackReleaseSmoother, ExponentialSmoother, PassthroughSmoother, Smoother, SmootherKind, REFERENCE_FPS,
+};
+use gruvberry::tilt::Tilt;
+
+const FRAME: f32 = 1.0 / REFERENCE_FPS;
+// 1 - 1/e: how far a step gets in one time constant
+const ONE_TAU: f32 = 0.632_120_6;
+
+// The shown level of one band over `frames` frames of `dt`, from 0, fed
+// `input(frame)`
+fn run(smoother: &mut dyn Smoother, frames: usize, dt: f32, input: impl Fn(usize) -> f32) -> Vec<f32> {
+    let mut shown = [0.0];
+    (0..frames)
+        .map(|frame| {
+            smoother.process(&[input(frame)], &mut shown, dt);
+            shown[0]
+        })
+        .collect()
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn exponential_moves_its_factor_per_reference_frame() {
+    // 0.3 a frame at 60 fps: a time constant of about 47 ms
+    let mut smoother = ExponentialSmoother::new(0.3);
+    assert!(close(smoother.time_constant(), 0.046_727));
+    let step = run(&mut smoother, 3, FRAME, |_| 1.0);
+    assert!(close(step[0], 0.3) && close(step[1], 0.51) && close(step[2], 0.657));
+    // One time constant in, 63% of the way
+    let tau = smoother.time_constant();
+    let step = run(&mut smoother, 1, tau, |_| 1.0);
+    assert!(close(step[0], ONE_TAU));
+
+    // An impulse decays by 0.7 a frame
+    let impulse = run(&mut smoother, 3, FRAME, |frame| if frame == 0 { 1.0 } else { 0.0 });
+    assert!(close(impulse[0], 0.3) && close(impulse[1], 0.21) && close(impulse[2], 0.147));
+}
+
+#[test]
+fn exponential_looks_the_same_at_any_frame_rate() {
+    let mut smoother = ExponentialSmoother::new(0.3);
+    let at_60 = run(&mut smoother, 60, 1.0 / 60.0, |_| 1.0)[29];
+    let at_30 = run(&mut smoother, 30, 1.0 / 30.0, |_| 1.0)[14];
+    let at_240 = run(&mut smoother, 240, 1.0 / 240.0, |_| 1.0)[119];
+    assert!(close(at_60, at_30) && close(at_60, at_240));
+    // A factor of 1 follows the input as it is
+    assert_eq!(run(&mut ExponentialSmoother::new(1.0), 2, FRAME, |frame| frame as f32), vec![0.0, 1.0]);
+}
+
+#[test]
+fn attack_release_rises_and_falls_at_their_own_rates() {
+    // 10 ms up, 250 ms down
+    let mut smoother = AttackReleaseSmoother::new(0.01, 0.25);
+    let step = run(&mut smoother, 1, 0.01, |_| 1.0);
+    assert!(close(step[0], ONE_TAU));
+    let rise = run(&mut smoother, 6, FRAME, |_| 1.0);
+    assert!(rise[5] > 0.99, "a step is all but there after 100 ms");
+
+    // An impulse jumps most of the way up in its frame, then takes 250 ms
+    // to lose 63% of that
+    let mut impulse = vec![0.0];
+    smoother.process(&[1.0], &mut impulse, FRAME);
+    let top = impulse[0];
+    assert!(close(top, 1.0 - (-FRAME / 0.01).exp()));
+    smoother.process(&[0.0], &mut impulse, 0.25);
+    assert!(close(impulse[0], top * (1.0 - ONE_TAU)));
+
+    // No time constant: instant
+    let mut instant = AttackReleaseSmoother::new(0.0, 0.0);
+    assert_eq!(run(&mut instant, 3, FRAME, |frame| [1.0, 0.0, 0.5][frame]), vec![1.0, 0.0, 0.5]);
+}
+
+#[test]
+fn passthrough_shows_every_frame_as_it_is() {
+    let mut smoother = PassthroughSmoother;
+    assert_eq!(run(&mut smoother, 3, FRAME, |_| 1.0), vec![1.0; 3]);
+    assert_eq!(run(&mut smoother, 3, FRAME, |frame| if frame == 0 { 1.0 } else { 0.0 }), vec![1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn kinds_parse_by_name_and_cycle() {
+    let mut kind = SmootherKind::default();
+    for _ in 0..SmootherKind::NAMES.len() {
+        assert_eq!(SmootherKind::parse(kind.name()), Some(kind));
+        kind = kind.next();
+    }
+    assert_eq!(kind, SmootherKind::default());
+    assert_eq!(SmootherKind::parse("passthrough"), None);
+}
+
+#[test]
+fn the_smoother_is_kept_through_a_resize() {
+    let window: Vec<f32> = (0..1024).map(|i| (i as f32 * 0.3).sin()).collect();
+    let mut analyzer = Analyzer::new(44100, 1024, 0.3, Tilt::Flat);
+    analyzer.set_smoother(SmootherKind::Passthrough.build(0.3, 0.0, 0.0));
+    let levels = analyzer.process(&window, 60).to_vec();
+    // Silence after a resize: with no smoothing the bars drop at once...
+    let silence = vec![0.0; 1024];
+    assert!(analyzer.process(&silence, 96).iter().all(|&band| band == 0.0));
+
+    // ...while a slow release sinks from the resampled levels
+    let mut analyzer = Analyzer::new(44100, 1024, 0.3, Tilt::Flat);
+    analyzer.set_smoother(SmootherKind::AttackRelease.build(0.3, 0.0, 10.0));
+    analyzer.process(&window, 60);
+    let resized = analyzer.process_after(&silence, 120, 0.001).to_vec();
+    assert_eq!(resized.len(), 120);
+    let loudest = |bands: &[f32]| bands.iter().fold(0.0f32, |max, &band| max.max(band));
+    assert!(loudest(&resized) > 0.5 * loudest(&levels));
+}
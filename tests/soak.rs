@@ -0,0 +1,194 @@
+//! Two simulated days of audio through the analysis pipeline, checking that
+//! memory stays flat and timing holds up once the clocks run into the
+//! hundreds of thousands of seconds. It takes a while, so it only builds with
+//! the `soak` feature and is meant for release mode:
+//!
+//! ```text
+//! cargo test --release --features soak --test soak -- --nocapture
+//! ```
+//!
+//! `SOAK_HOURS` shortens (or lengthens) the run.
+#![cfg(feature = "soak")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::f64::consts::TAU;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use gruvberry::analysis::Analyzer;
+use gruvberry::capture::{SampleCapture, SampleRing};
+use gruvberry::dynamics::DynamicsMeter;
+use gruvberry::history::{AnalysisFrame, FrameHistory};
+use gruvberry::interpolate::FrameInterpolator;
+use gruvberry::key::{self, KeyEstimator};
+use gruvberry::onset::{Activity, BeatTracker, Bounce, OnsetDetector};
+use gruvberry::stats::{self, Session};
+use gruvberry::stereo::Channel;
+use gruvberry::tilt::Tilt;
+use gruvberry::rate;
+use rodio::Source;
+
+// Bytes allocated and not yet freed, by anything in the test binary
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        LIVE.fetch_add(new_size, Ordering::Relaxed);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const RATE: u32 = 8000;
+const FFT_SIZE: usize = 256;
+const FPS: u32 = 10;
+const HOP: u64 = (RATE / FPS) as u64;
+// A click every half second: 120 BPM
+const CLICK_PERIOD: u64 = RATE as u64 / 2;
+
+// An endless stereo source: A and E under a click track, computed from the
+// frame count so it is as clean after two days as at the start
+struct Synth {
+    frame: u64,
+    channel: u16,
+}
+
+impl Iterator for Synth {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let t = self.frame as f64 / RATE as f64;
+        let tone = if self.channel == 0 { 440.0 } else { 659.25 };
+        let click = if (self.frame + 100) % CLICK_PERIOD < 32 { 0.6 } else { 0.0 };
+        let sample = 0.2 * (TAU * tone * t).sin() + click;
+        self.channel = (self.channel + 1) % 2;
+        if self.channel == 0 {
+            self.frame += 1;
+        }
+        Some(sample as f32)
+    }
+}
+
+impl Source for Synth {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        2
+    }
+
+    fn sample_rate(&self) -> u32 {
+        RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[test]
+fn two_days_of_audio_keep_memory_flat_and_timing_exact() {
+    let hours: f64 = std::env::var("SOAK_HOURS").ok().and_then(|hours| hours.parse().ok()).unwrap_or(48.0);
+    let frames = (hours * 3600.0 * FPS as f64) as u64;
+    // Enough "tracks" that the session outgrows its cap a third of the way in
+    let track_frames = (frames / (3 * stats::MAX_TRACKS as u64)).max(1);
+
+    let ring = SampleRing::shared(FFT_SIZE * 2);
+    let dynamics = DynamicsMeter::shared();
+    let mut tap = SampleCapture::new(Synth { frame: 0, channel: 0 }, (ring.clone(), dynamics.clone()));
+    let mut analyzer = Analyzer::new(RATE, FFT_SIZE, 0.3, Tilt::Flat);
+    let mut onsets = OnsetDetector::new();
+    let mut activity = Activity::new();
+    let mut beats = BeatTracker::new();
+    let mut bounce = Bounce::new(1.15, 0.1);
+    let mut keys = KeyEstimator::new(30.0);
+    let mut history = FrameHistory::for_rate(30.0, FPS);
+    let mut interpolator = FrameInterpolator::new();
+    let mut session = Session::default();
+    let freq_per_bin = rate::freq_per_bin(RATE, FFT_SIZE);
+
+    let mut halfway = None;
+    for frame in 0..frames {
+        if frame % track_frames == 0 {
+            session.start(&format!("hour {}", frame / (3600 * FPS as u64)), track_frames as f32 / FPS as f32);
+        }
+        tap.by_ref().take(HOP as usize * 2).for_each(drop);
+        // The clock, counted in frames: as exact at the end as at the start
+        let now = frame as f64 / FPS as f64;
+
+        let (window, side, time) = {
+            let ring = ring.lock().unwrap();
+            let Some(window) = ring.window(FFT_SIZE, 0) else {
+                continue;
+            };
+            (window, ring.channel_window(Channel::Side, FFT_SIZE, 0), ring.time())
+        };
+        assert!(side.is_some());
+        let bands = analyzer.process_after(&window, 32, 1.0 / FPS as f32).to_vec();
+        let magnitudes = analyzer.magnitudes();
+        if onsets.update(&magnitudes, now) {
+            beats.onset(now);
+            bounce.kick(now);
+        }
+        activity.update(onsets.flux(), now);
+        keys.push(now, key::chroma(&magnitudes, freq_per_bin));
+        interpolator.push(now, bands.clone());
+        history.push(AnalysisFrame {
+            time,
+            levels_db: analyzer.levels_db(),
+            rms: (window.iter().map(|s| s * s).sum::<f32>() / window.len() as f32).sqrt(),
+            sample_rate: RATE,
+            range: analyzer.range(),
+            bands,
+        });
+        if let Some(track) = session.current_mut() {
+            track.frame(&window);
+            track.listen(1.0 / FPS as f32);
+            if window.iter().any(|s| s.abs() >= 0.75) {
+                track.clipped(now);
+            }
+        }
+
+        if frame == frames / 2 {
+            halfway = Some(LIVE.load(Ordering::Relaxed));
+        }
+    }
+    session.end();
+    let end = LIVE.load(Ordering::Relaxed);
+    let halfway = halfway.unwrap();
+    println!("{} hours: {} bytes live halfway, {} at the end", hours, halfway, end);
+    // Everything has reached its bounds by halfway; past that nothing grows
+    assert!(end <= halfway + 16 * 1024, "grew from {} to {} bytes", halfway, end);
+
+    let end_time = frames as f64 / FPS as f64;
+    assert_eq!(session.tracks().len(), stats::MAX_TRACKS);
+    assert!(session.earlier() > 0);
+    assert!((session.total_listened() as f64 - end_time).abs() < 1.0, "{} listened", session.total_listened());
+    assert!(history.len() <= history.max_frames());
+    assert!((ring.lock().unwrap().time() as f64 - end_time).abs() < 0.1);
+    assert!(dynamics.lock().unwrap().dr().is_some());
+    // Still locked to the clicks, with the grid and the effects on time
+    let bpm = beats.bpm().unwrap();
+    assert!((bpm - 120.0).abs() < 0.5, "{} BPM", bpm);
+    let next = beats.next_beat(end_time).unwrap();
+    assert!(next > end_time && next - end_time <= 0.5 + 1e-6);
+    assert!(bounce.gain(end_time + 1.0) == 1.0);
+    let interval = 1.0 / FPS as f64;
+    assert!((interpolator.alpha(end_time - interval / 2.0) - 0.5).abs() < 1e-3);
+    assert!(keys.estimate(5.0).is_some());
+}
@@ -0,0 +1,37 @@
+use gruvberry::sparkline::{self, LEVELS};
+
+#[test]
+fn empty_history_is_blank() {
+    assert_eq!(sparkline::render(&[], 5), "     ");
+    assert_eq!(sparkline::render(&[], 0), "");
+}
+
+#[test]
+fn constant_input_is_flat() {
+    let line = sparkline::render(&[0.4; 100], 10);
+    assert_eq!(line.chars().count(), 10);
+    assert!(line.chars().all(|c| c == sparkline::block(0.4)));
+}
+
+#[test]
+fn width_one_shows_the_highest_value() {
+    assert_eq!(sparkline::render(&[0.0, 1.0, 0.2], 1), "█");
+    assert_eq!(sparkline::render(&[0.0], 1), "▁");
+}
+
+#[test]
+fn width_zero_is_empty() {
+    assert_eq!(sparkline::render(&[0.5, 0.7], 0), "");
+}
+
+#[test]
+fn every_value_gets_a_cell_when_there_is_room() {
+    let values: Vec<f32> = (0..LEVELS.len()).map(|i| i as f32 / (LEVELS.len() - 1) as f32).collect();
+    let line = sparkline::render(&values, LEVELS.len());
+    assert_eq!(line, LEVELS.iter().collect::<String>());
+}
+
+#[test]
+fn out_of_range_values_are_clamped() {
+    assert_eq!(sparkline::render(&[-1.0, f32::NAN, 2.0], 3), "▁▁█");
+}
@@ -0,0 +1,122 @@
+use gruvberry::analysis::FreqRange;
+use gruvberry::heat::{HeatMap, TRAIL_SECS};
+use gruvberry::history::AnalysisFrame;
+use gruvberry::orientation::Orientation;
+use gruvberry::spectrum::{self, SpectrumWidget};
+use gruvberry::theme::Theme;
+use ratatui::{
+    Terminal,
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    widgets::{Paragraph, Widget},
+};
+
+// The rows of a `width` x `height` terminal after `draw`, as text
+fn render(width: u16, height: u16, draw: impl FnOnce(&mut ratatui::Frame)) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(draw).unwrap();
+    rows(terminal.backend().buffer())
+}
+
+fn rows(buffer: &Buffer) -> Vec<String> {
+    (0..buffer.area.height)
+        .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+        .collect()
+}
+
+#[test]
+fn bars_fill_an_odd_sized_area_from_the_bottom() {
+    // 40 bands over 13 columns: a full-scale third, half and the floor (a
+    // column over both sides of a step takes the louder)
+    let bands: Vec<f32> = (0..40).map(|band| [100.0, 50.0, 0.0][band * 3 / 40]).collect();
+    let theme = Theme::default();
+    let screen = render(13, 5, |frame| frame.render_widget(SpectrumWidget::new(&bands, &theme), frame.area()));
+    assert_eq!(
+        screen,
+        [
+            "█████        ",
+            "█████        ",
+            "█████        ",
+            "█████████    ",
+            "█████████████",
+        ]
+    );
+}
+
+#[test]
+fn only_the_area_it_is_given_is_drawn() {
+    let bands = [100.0; 8];
+    let theme = Theme::default();
+    let screen = render(17, 7, |frame| {
+        frame.render_widget(Paragraph::new(vec!["x".repeat(17).into(); 7]), frame.area());
+        frame.render_widget(SpectrumWidget::new(&bands, &theme), Rect::new(3, 2, 11, 3));
+    });
+    for (y, row) in screen.iter().enumerate() {
+        for (x, symbol) in row.chars().enumerate() {
+            let inside = (3..14).contains(&x) && (2..5).contains(&y);
+            assert_eq!(symbol, if inside { '█' } else { 'x' }, "({}, {})", x, y);
+        }
+    }
+
+    // An area running past the buffer is cut to it, and an empty one draws nothing
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 13, 5));
+    SpectrumWidget::new(&bands, &theme).render(Rect::new(10, 3, 13, 5), &mut buffer);
+    SpectrumWidget::new(&bands, &theme).render(Rect::new(0, 0, 0, 5), &mut buffer);
+    SpectrumWidget::new(&[], &theme).render(buffer.area, &mut buffer);
+    assert_eq!(rows(&buffer)[4], "          ███");
+    assert_eq!(rows(&buffer)[2], " ".repeat(13));
+}
+
+#[test]
+fn horizontal_bars_grow_from_their_edge_in_a_tiny_area() {
+    let bands = [100.0, 40.0, 0.0];
+    let theme = Theme::default();
+    let right_left = SpectrumWidget::new(&bands, &theme).orientation(Orientation::RightLeft);
+    assert_eq!(render(7, 3, |frame| frame.render_widget(right_left, frame.area())), ["███████", "     ██", "      █"]);
+    // A single cell still shows the baseline
+    assert_eq!(render(1, 1, |frame| frame.render_widget(SpectrumWidget::new(&bands, &theme), frame.area())), ["█"]);
+}
+
+#[test]
+fn a_frame_is_colored_by_frequency_and_a_plain_theme_has_no_colors() {
+    let frame = AnalysisFrame {
+        time: 0.0,
+        bands: vec![100.0; 5],
+        levels_db: vec![0.0; 5],
+        rms: 1.0,
+        sample_rate: 44100,
+        range: FreqRange::full(22050.0),
+    };
+    let theme = Theme::default();
+    let mut terminal = Terminal::new(TestBackend::new(5, 2)).unwrap();
+    terminal.draw(|f| f.render_widget(SpectrumWidget::from_frame(&frame, &theme), f.area())).unwrap();
+    let colors = spectrum::band_colors(5, frame.range, Default::default(), &theme);
+    let buffer = terminal.backend().buffer();
+    for (x, &color) in colors.iter().enumerate() {
+        assert_eq!(buffer[(x as u16, 1)].fg, color);
+    }
+
+    let plain = Theme::plain();
+    terminal.draw(|f| f.render_widget(SpectrumWidget::from_frame(&frame, &plain), f.area())).unwrap();
+    assert!(terminal.backend().buffer().content().iter().all(|cell| cell.fg == Color::Reset));
+}
+
+#[test]
+fn as_a_stateful_widget_the_bars_leave_trails_that_cool() {
+    let theme = Theme::default();
+    let mut heat = HeatMap::new(TRAIL_SECS);
+    let mut terminal = Terminal::new(TestBackend::new(3, 5)).unwrap();
+    terminal
+        .draw(|f| f.render_stateful_widget(SpectrumWidget::new(&[100.0; 3], &theme), f.area(), &mut heat))
+        .unwrap();
+    // Half a trail later, down to the baseline
+    let low = SpectrumWidget::new(&[0.0; 3], &theme).elapsed(TRAIL_SECS / 2.0);
+    terminal.draw(|f| f.render_stateful_widget(low, f.area(), &mut heat)).unwrap();
+    assert_eq!(rows(terminal.backend().buffer()), ["▒▒▒", "▒▒▒", "▒▒▒", "▒▒▒", "███"]);
+
+    // Without the state there are no trails
+    terminal.draw(|f| f.render_widget(SpectrumWidget::new(&[0.0; 3], &theme), f.area())).unwrap();
+    assert_eq!(rows(terminal.backend().buffer())[0], "   ");
+}
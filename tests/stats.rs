@@ -0,0 +1,78 @@
+use gruvberry::stats::{Session, TrackAccumulator, CLIP_GAP_SECS};
+
+#[test]
+fn levels_come_from_the_analyzed_windows() {
+    let mut track = TrackAccumulator::new("Song", 60.0);
+    track.frame(&[0.5; 4]);
+    track.frame(&[0.25, -1.0, f32::NAN, f32::INFINITY]);
+    let stats = track.finish();
+    // The non-numbers are left out of both the mean and the peak
+    let mean = ((4.0 * 0.25 + 0.0625 + 1.0) / 6.0f32).sqrt();
+    assert!((stats.average_db.unwrap() - 20.0 * mean.log10()).abs() < 1e-3);
+    assert_eq!(stats.peak_db, Some(0.0));
+}
+
+#[test]
+fn nothing_analyzed_has_no_levels() {
+    let mut track = TrackAccumulator::new("Song", 60.0);
+    track.listen(2.0);
+    let stats = track.finish();
+    assert_eq!((stats.average_db, stats.peak_db), (None, None));
+    assert_eq!(stats.listened, 2.0);
+}
+
+#[test]
+fn clipping_close_together_is_one_event() {
+    let mut track = TrackAccumulator::new("Song", 60.0);
+    for time in [1.0, 1.1, 1.2, 1.2 + CLIP_GAP_SECS * 0.9] {
+        track.clipped(time);
+    }
+    track.clipped(10.0);
+    track.clipped(10.0 + CLIP_GAP_SECS);
+    assert_eq!(track.finish().clips, 3);
+}
+
+#[test]
+fn tempo_and_key_keep_the_last_estimate() {
+    let mut track = TrackAccumulator::new("Song", 60.0);
+    track.detect_bpm(Some(120.0));
+    track.detect_bpm(None);
+    track.detect_key(Some("A minor".to_string()));
+    track.detect_key(None);
+    let stats = track.finish();
+    assert_eq!(stats.bpm, Some(120.0));
+    assert_eq!(stats.key.as_deref(), Some("A minor"));
+}
+
+#[test]
+fn starting_a_track_ends_the_one_playing() {
+    let mut session = Session::default();
+    session.start("One", 10.0);
+    session.current_mut().unwrap().listen(4.0);
+    session.start("Two", 20.0);
+    session.current_mut().unwrap().listen(5.0);
+    assert_eq!(session.tracks().len(), 1);
+    session.end();
+    session.end();
+    let names: Vec<&str> = session.tracks().iter().map(|track| track.name.as_str()).collect();
+    assert_eq!(names, ["One", "Two"]);
+    assert_eq!(session.total_listened(), 9.0);
+    assert!(session.current_mut().is_none());
+}
+
+#[test]
+fn the_json_has_every_track_and_the_total() {
+    let mut session = Session::default();
+    session.start("Say \"hi\"", f32::INFINITY);
+    if let Some(track) = session.current_mut() {
+        track.listen(1.5);
+        track.detect_key(Some("C major".to_string()));
+    }
+    session.end();
+    assert_eq!(
+        session.to_json(),
+        "{\"tracks\":[{\"name\":\"Say \\\"hi\\\"\",\"duration\":null,\"listened\":1.50,\"average_db\":null,\
+         \"peak_db\":null,\"bpm\":null,\"key\":\"C major\",\"clips\":0}],\"total_listened\":1.50}\n"
+    );
+    assert_eq!(Session::default().to_json(), "{\"tracks\":[],\"total_listened\":0.00}\n");
+}
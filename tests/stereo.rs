@@ -0,0 +1,102 @@
+use gruvberry::analysis::Analyzer;
+use gruvberry::capture::{SampleObserver, SampleRing};
+use gruvberry::stereo::{self, Channel};
+use gruvberry::tilt::Tilt;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+fn noise(seed: u64, len: usize) -> Vec<f32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..len).map(|_| rng.random_range(-1.0..1.0)).collect()
+}
+
+#[test]
+fn identical_channels_are_fully_correlated() {
+    let frames: Vec<[f32; 2]> = noise(1, 4096).into_iter().map(|s| [s, s]).collect();
+    assert!((stereo::correlation(&frames).unwrap() - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn inverted_channels_are_anticorrelated() {
+    let frames: Vec<[f32; 2]> = noise(2, 4096).into_iter().map(|s| [s, -s]).collect();
+    assert!((stereo::correlation(&frames).unwrap() + 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn uncorrelated_noise_is_near_zero() {
+    let frames: Vec<[f32; 2]> = noise(3, 8192).into_iter().zip(noise(4, 8192)).map(|(l, r)| [l, r]).collect();
+    assert!(stereo::correlation(&frames).unwrap().abs() < 0.05);
+}
+
+#[test]
+fn silence_has_no_reading() {
+    assert_eq!(stereo::correlation(&[[0.0, 0.0]; 512]), None);
+    assert_eq!(stereo::correlation(&[]), None);
+    let one_sided: Vec<[f32; 2]> = noise(5, 512).into_iter().map(|s| [s, 0.0]).collect();
+    assert_eq!(stereo::correlation(&one_sided), None);
+}
+
+const RATE: u32 = 44100;
+const WINDOW: usize = 1024;
+
+// Spectral energy the analyzer finds in each channel's window of a second
+// of 1 kHz sine, placed in left and right by `place`
+fn channel_energies(place: impl Fn(f32) -> [f32; 2]) -> Vec<(Channel, f32)> {
+    let mut ring = SampleRing::new(WINDOW * 2);
+    let interleaved: Vec<f32> = (0..RATE)
+        .map(|i| 0.5 * (std::f32::consts::TAU * 1000.0 * i as f32 / RATE as f32).sin())
+        .flat_map(place)
+        .collect();
+    ring.on_samples(&interleaved, 2, RATE);
+    Channel::ALL
+        .iter()
+        .map(|&channel| {
+            let window = ring.channel_window(channel, WINDOW, 0).unwrap();
+            let mut analyzer = Analyzer::new(RATE, WINDOW, 1.0, Tilt::Flat);
+            analyzer.process(&window, 64);
+            (channel, analyzer.magnitudes().iter().map(|m| m * m).sum())
+        })
+        .collect()
+}
+
+fn energy(energies: &[(Channel, f32)], channel: Channel) -> f32 {
+    energies.iter().find(|(c, _)| *c == channel).unwrap().1
+}
+
+#[test]
+fn a_signal_only_on_the_right_shows_under_right_and_side_but_not_left() {
+    let energies = channel_energies(|s| [0.0, s]);
+    let right = energy(&energies, Channel::Right);
+    assert!(right > 1.0);
+    assert_eq!(energy(&energies, Channel::Left), 0.0);
+    // The side is the right channel inverted, at the same level
+    assert!((energy(&energies, Channel::Side) / right - 1.0).abs() < 1e-3);
+    // Mid and downmix, (L+R)/2, carry it at half the amplitude (-6 dB)
+    assert!((energy(&energies, Channel::Mid) / right - 0.25).abs() < 1e-3);
+    assert!((energy(&energies, Channel::Downmix) / right - 0.25).abs() < 1e-3);
+}
+
+#[test]
+fn side_and_mid_split_what_differs_from_what_is_shared() {
+    // Out of phase: nothing in common, all of it in the side
+    let energies = channel_energies(|s| [s, -s]);
+    assert!(energy(&energies, Channel::Side) > 1.0);
+    assert_eq!(energy(&energies, Channel::Mid), 0.0);
+    // Centred: all of it in the mid, nothing in the side
+    let energies = channel_energies(|s| [s, s]);
+    assert!(energy(&energies, Channel::Mid) > 1.0);
+    assert_eq!(energy(&energies, Channel::Side), 0.0);
+}
+
+#[test]
+fn channels_cycle_back_to_the_downmix_and_mono_has_only_that() {
+    let cycle: Vec<Channel> = std::iter::successors(Some(Channel::default()), |channel| Some(channel.next())).take(6).collect();
+    let names: Vec<&str> = cycle.iter().map(|channel| channel.name()).collect();
+    assert_eq!(names, ["downmix", "left", "right", "side", "mid", "downmix"]);
+
+    let mut ring = SampleRing::new(64);
+    ring.on_samples(&[0.25; 64], 1, RATE);
+    assert!(ring.channel_window(Channel::Downmix, 64, 0).is_some());
+    assert!(ring.channel_window(Channel::Side, 64, 0).is_none());
+    assert_eq!(stereo::layout(1), "mono");
+    assert_eq!(stereo::layout(6), "6 ch");
+}
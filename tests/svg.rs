@@ -0,0 +1,74 @@
+use std::path::Path;
+use gruvberry::analysis::FreqRange;
+use gruvberry::gradient::Anchor;
+use gruvberry::history::AnalysisFrame;
+use gruvberry::svg::{self, BAR_GAP, BAR_WIDTH, PLOT_HEIGHT};
+
+fn frame(bands: Vec<f32>) -> AnalysisFrame {
+    AnalysisFrame {
+        time: 12.5,
+        levels_db: vec![-20.0; bands.len()],
+        bands,
+        rms: 0.25,
+        sample_rate: 44100,
+        range: FreqRange::full(22050.0),
+    }
+}
+
+// Compare with the file under tests/golden; GRUVBERRY_UPDATE_GOLDEN=1
+// writes it instead, after a deliberate change to the output
+fn check_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(name);
+    if std::env::var_os("GRUVBERRY_UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected, "{} differs; rerun with GRUVBERRY_UPDATE_GOLDEN=1 if that's intended", name);
+}
+
+#[test]
+fn matches_the_golden_file() {
+    let bands = vec![90.0, 100.0, 75.5, 60.0, 42.0, 55.0, 30.0, 20.25, 12.0, 8.0, 3.0, 0.0];
+    check_golden("spectrum.svg", &svg::render(&frame(bands), Anchor::Frequency));
+}
+
+#[test]
+fn the_image_follows_the_band_grid() {
+    for bands in [1, 12, 64, 300] {
+        let svg = svg::render(&frame(vec![50.0; bands]), Anchor::Index);
+        let width = bands as u32 * (BAR_WIDTH + BAR_GAP) - BAR_GAP + 20;
+        assert!(svg.starts_with(&format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" ", width)));
+        assert_eq!(svg.matches("<rect class=\"bar\"").count(), bands);
+        assert!(svg.contains(&format!("height=\"{:.1}\"", PLOT_HEIGHT as f32 / 2.0)));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
+
+#[test]
+fn levels_stay_within_the_plot() {
+    let svg = svg::render(&frame(vec![-10.0, f32::NAN, 250.0]), Anchor::Frequency);
+    let heights: Vec<&str> = svg
+        .lines()
+        .filter(|line| line.starts_with("<rect class=\"bar\""))
+        .map(|line| line.split("height=\"").nth(1).unwrap().split('"').next().unwrap())
+        .collect();
+    assert_eq!(heights, ["0.0", "0.0", "200.0"]);
+}
+
+#[test]
+fn labels_only_cover_the_frames_range() {
+    let mut low = frame(vec![50.0; 40]);
+    low.sample_rate = 8000;
+    low.range = FreqRange::full(4000.0);
+    let svg = svg::render(&low, Anchor::Frequency);
+    assert!(svg.contains(">2k</text>"));
+    assert!(!svg.contains(">5k</text>"));
+    // Zoomed in, the axis starts further up as well
+    let mut zoomed = frame(vec![50.0; 40]);
+    zoomed.range = FreqRange { low: 200.0, high: 3200.0 };
+    let svg = svg::render(&zoomed, Anchor::Frequency);
+    assert!(svg.contains(">200</text>") && svg.contains(">2k</text>"));
+    assert!(!svg.contains(">100</text>") && !svg.contains(">5k</text>"));
+    // Nothing to place labels on
+    assert!(!svg::render(&frame(Vec::new()), Anchor::Frequency).contains("<text"));
+}
@@ -0,0 +1,436 @@
+// The terminal setup and teardown, checked by running term-smoke in a
+// pseudo-terminal and looking at what it wrote and the state it left
+#![cfg(unix)]
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const ROWS: u16 = 12;
+
+// What `command` wrote to a ROWS-high pty while `drive` did its part
+// (given the child's pid and the pty), whether it exited successfully, and
+// whether the pty was back in cooked mode (echo, line editing) afterwards
+fn session(mut command: Command, drive: impl FnOnce(libc::pid_t, &OwnedFd)) -> (String, bool, bool) {
+    let (mut master, mut slave) = (0, 0);
+    let size = libc::winsize { ws_row: ROWS, ws_col: 60, ws_xpixel: 0, ws_ypixel: 0 };
+    let opened = unsafe { libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null(), &size) };
+    assert_eq!(opened, 0, "no pty");
+    let master = unsafe { OwnedFd::from_raw_fd(master) };
+    let slave = unsafe { OwnedFd::from_raw_fd(slave) };
+
+    command
+        .stdin(Stdio::from(slave.try_clone().unwrap()))
+        .stdout(Stdio::from(slave.try_clone().unwrap()))
+        .stderr(Stdio::from(slave.try_clone().unwrap()));
+    // The pty as the controlling terminal, for /dev/tty
+    unsafe {
+        command.pre_exec(|| {
+            libc::setsid();
+            libc::ioctl(0, libc::TIOCSCTTY, 0);
+            Ok(())
+        });
+    }
+    let mut child = command.spawn().unwrap();
+    drop(command);
+
+    let mut reader = File::from(master.try_clone().unwrap());
+    let output = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        // Ends with EIO once the child has exited
+        let _ = reader.read_to_end(&mut output);
+        output
+    });
+    drive(child.id() as libc::pid_t, &master);
+    let success = child.wait().unwrap().success();
+    // Held open until the child has exited: with no slave left the reader
+    // can get EIO before the last of what the child wrote has come through
+    drop(slave);
+    let output = String::from_utf8_lossy(&output.join().unwrap()).into_owned();
+    (output, success, cooked(&master))
+}
+
+// term-smoke with `args` until 'q' (or its panic, with --panic): what it
+// wrote, and whether the pty was left in cooked mode
+fn run(args: &[&str]) -> (String, bool) {
+    let panic = args.contains(&"--panic");
+    let (output, success, cooked) = session(smoke(args), |_, master| {
+        if !panic {
+            std::thread::sleep(Duration::from_millis(500));
+            type_keys(master, b"q");
+        }
+    });
+    assert_eq!(success, !panic);
+    (output, cooked)
+}
+
+fn smoke(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_term-smoke"));
+    command.args(args);
+    command
+}
+
+fn type_keys(master: &OwnedFd, keys: &[u8]) {
+    File::from(master.try_clone().unwrap()).write_all(keys).unwrap();
+}
+
+// Whether the pty has echo and line editing on
+fn cooked(master: &OwnedFd) -> bool {
+    let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+    assert_eq!(unsafe { libc::tcgetattr(master.as_raw_fd(), &mut termios) }, 0);
+    termios.c_lflag & (libc::ECHO | libc::ICANON) == (libc::ECHO | libc::ICANON)
+}
+
+// Wait for the child to stop or exit, leaving an exit to be waited for
+// again; whether it stopped
+fn stopped(pid: libc::pid_t) -> bool {
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let options = libc::WSTOPPED | libc::WEXITED | libc::WNOWAIT;
+    assert_eq!(unsafe { libc::waitid(libc::P_PID, pid as libc::id_t, &mut info, options) }, 0);
+    info.si_code == libc::CLD_STOPPED
+}
+
+// A 440 Hz tone at half of full scale (-6 dBFS) of `secs` seconds in a
+// temporary WAV file, after `silent` seconds of silence
+fn tone_after(name: &str, silent: u32, secs: u32) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("gruvberry-{}-{}.wav", name, std::process::id()));
+    let spec = hound::WavSpec { channels: 1, sample_rate: 8000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for _ in 0..silent * 8000 {
+        writer.write_sample(0i16).unwrap();
+    }
+    for i in 0..secs * 8000 {
+        let sample = (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 8000.0).sin();
+        writer.write_sample((sample * 0.5 * i16::MAX as f32) as i16).unwrap();
+    }
+    writer.finalize().unwrap();
+    path
+}
+
+fn tone(name: &str, secs: u32) -> PathBuf {
+    tone_after(name, 0, secs)
+}
+
+// The output from the last `marker` on
+fn after<'a>(output: &'a str, marker: &str) -> &'a str {
+    &output[output.rfind(marker).unwrap_or_else(|| panic!("no {:?} in {:?}", marker, output))..]
+}
+
+#[test]
+fn the_alternate_screen_is_left_and_the_terminal_restored() {
+    let (output, cooked) = run(&[]);
+    assert!(cooked, "left in raw mode");
+    assert!(output.contains("\x1b[?1049h") && output.contains("gruvberry terminal check"));
+    // Mouse reports off and the cursor shown before leaving the screen
+    let end = after(&output, "\x1b[?1000l");
+    assert!(end.contains("\x1b[?25h"));
+    assert!(after(end, "\x1b[?1049l").contains("Terminal restored"));
+}
+
+#[test]
+fn inline_drawing_leaves_the_last_frame_with_the_prompt_below() {
+    let (output, cooked) = run(&["--no-alt-screen"]);
+    assert!(cooked, "left in raw mode");
+    assert!(!output.contains("\x1b[?1049"), "used the alternate screen");
+    // What was on screen scrolled away first
+    assert!(output.contains(&"\n".repeat(ROWS as usize)));
+    let end = after(&output, "\x1b[?1000l");
+    assert!(end.contains("\x1b[?25h"));
+    // Nothing clears the last frame; the cursor goes to the last row and
+    // a line on (the pty adding the carriage return) before anything else
+    // is printed
+    assert!(!end.contains("\x1b[2J"));
+    let last_row = format!("\x1b[{};1H\r\n", ROWS);
+    assert!(after(end, &last_row).contains("Terminal restored"), "{:?}", end);
+}
+
+#[test]
+fn a_panic_inline_restores_the_terminal_too() {
+    let (output, cooked) = run(&["--no-alt-screen", "--panic"]);
+    assert!(cooked, "left in raw mode");
+    let end = after(&output, "\x1b[?1000l");
+    assert!(after(end, &format!("\x1b[{};1H\r\n", ROWS)).contains("panicking on purpose"));
+}
+
+#[test]
+fn sigterm_and_sighup_quit_the_way_q_does() {
+    for signal in [libc::SIGTERM, libc::SIGHUP] {
+        let (output, success, cooked) = session(smoke(&[]), |pid, _| {
+            std::thread::sleep(Duration::from_millis(500));
+            assert_eq!(unsafe { libc::kill(pid, signal) }, 0);
+        });
+        assert!(success, "signal {} didn't end it cleanly: {:?}", signal, output);
+        assert!(cooked, "left in raw mode by signal {}", signal);
+        let end = after(&output, "\x1b[?1049l");
+        // Hung up, nothing is printed to the terminal that went away
+        assert_eq!(end.contains("Terminal restored"), signal == libc::SIGTERM, "{:?}", end);
+    }
+}
+
+#[test]
+fn a_suspend_hands_the_terminal_back_until_continued() {
+    // Ctrl+Z typed (a key in raw mode), and SIGTSTP sent from outside
+    for by_key in [true, false] {
+        let (output, success, cooked) = session(smoke(&[]), |pid, master| {
+            std::thread::sleep(Duration::from_millis(500));
+            if by_key {
+                type_keys(master, b"\x1a");
+            } else {
+                assert_eq!(unsafe { libc::kill(pid, libc::SIGTSTP) }, 0);
+            }
+            assert!(stopped(pid), "exited rather than stopping");
+            assert!(cooked(master), "stopped in raw mode");
+            assert_eq!(unsafe { libc::kill(pid, libc::SIGCONT) }, 0);
+            std::thread::sleep(Duration::from_millis(300));
+            assert!(!cooked(master), "raw mode not back after continuing");
+            type_keys(master, b"q");
+        });
+        assert!(success && cooked, "{:?}", output);
+        // The alternate screen left for the stop, then taken again and the
+        // check drawn afresh on it
+        let back = after(&output, "\x1b[?1049h");
+        assert_eq!(output.matches("\x1b[?1049h").count(), 2, "{:?}", output);
+        assert!(output[..output.len() - back.len()].contains("\x1b[?1049l"));
+        assert!(back.contains("\x1b[2J") && back.contains("gruvberry terminal check"), "{:?}", back);
+        assert!(after(back, "\x1b[?1049l").contains("Terminal restored"));
+    }
+}
+
+// The player itself: Ctrl+Z pauses playback before stopping and resumes it
+// once continued, and SIGTERM still ends with the summary printed. The
+// --verbose session log, printed last, says what the player did.
+#[test]
+fn the_player_pauses_while_suspended_and_sigterm_ends_the_session() {
+    // Long enough to be still playing however fast the device takes it
+    let path = tone("signals", 120);
+    let config = path.with_extension("toml");
+    std::fs::write(&config, "").unwrap();
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_gruvberry"));
+    command.args(["--verbose", "--no-color", "--config"]).arg(&config).arg(&path);
+    let mut suspended = false;
+    let (output, success, cooked) = session(command, |pid, master| {
+        std::thread::sleep(Duration::from_millis(1000));
+        type_keys(master, b"\x1a");
+        // Gone already when there is no audio device
+        if stopped(pid) {
+            suspended = true;
+            assert!(cooked(master), "stopped in raw mode");
+            assert_eq!(unsafe { libc::kill(pid, libc::SIGCONT) }, 0);
+            std::thread::sleep(Duration::from_millis(500));
+            assert_eq!(unsafe { libc::kill(pid, libc::SIGTERM) }, 0);
+        }
+    });
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&config);
+    if output.contains("Can't open the audio device") {
+        eprintln!("skipped: no audio device");
+        return;
+    }
+    assert!(suspended, "never stopped: {:?}", output);
+    assert!(success && cooked, "{:?}", output);
+    let end = after(&output, "\x1b[?1049l");
+    let paused = end.find("Paused at").unwrap_or_else(|| panic!("not paused: {:?}", end));
+    let resumed = end.find("Resumed at").unwrap_or_else(|| panic!("not resumed: {:?}", end));
+    assert!(paused < resumed);
+    assert!(end.contains("Dynamic range of"), "no summary: {:?}", end);
+}
+
+// What the player publishes, in order, for a two-track playlist with one
+// pause and one seek: the --verbose session log is a subscriber to the bus.
+// The first track is cut short with 'n' so the run doesn't take its length.
+#[test]
+fn the_player_publishes_its_events_in_the_order_they_happen() {
+    let first = tone("order-first", 120);
+    let second = tone("order-second", 1);
+    let config = first.with_extension("toml");
+    std::fs::write(&config, "").unwrap();
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_gruvberry"));
+    command.args(["--verbose", "--no-color", "--auto-exit", "--config"]).arg(&config).arg(&first).arg(&second);
+    let (output, success, cooked) = session(command, |_, master| {
+        for keys in [&b" "[..], b" ", b"\x1b[C", b"n"] {
+            std::thread::sleep(Duration::from_millis(500));
+            type_keys(master, keys);
+        }
+    });
+    for path in [&first, &second, &config] {
+        let _ = std::fs::remove_file(path);
+    }
+    if output.contains("Can't open the audio device") {
+        eprintln!("skipped: no audio device");
+        return;
+    }
+    assert!(success && cooked, "{:?}", output);
+    let end = after(&output, "\x1b[?1049l");
+    let expected = ["Started #1", "Paused at", "Resumed at", "Seeked to", "Ended #1", "Started #2", "Ended #2", "Finished"];
+    let events: Vec<&str> = end["\x1b[?1049l".len()..]
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| expected.iter().find(|event| line.starts_with(**event)).copied())
+        .collect();
+    assert_eq!(events, expected, "{:?}", end);
+}
+
+// A terminal too slow for the frame rate (--render-delay stalls every frame
+// a quarter of a second, at 30 fps): the frames that couldn't be drawn are
+// counted, and the position, read from the window title, still keeps up
+// with the time played rather than with the frames drawn.
+#[test]
+fn a_slow_terminal_drops_frames_but_keeps_the_position() {
+    let path = tone("slow", 120);
+    let config = path.with_extension("toml");
+    std::fs::write(&config, "fps = 30\nterminal_title = true\n").unwrap();
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_gruvberry"));
+    command.args(["--verbose", "--no-color", "--render-delay", "250", "--config"]).arg(&config).arg(&path);
+    let (output, success, cooked) = session(command, |_, master| {
+        std::thread::sleep(Duration::from_millis(4000));
+        type_keys(master, b"q");
+    });
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&config);
+    if output.contains("Can't open the audio device") {
+        eprintln!("skipped: no audio device");
+        return;
+    }
+    assert!(success && cooked, "{:?}", output);
+
+    // The last title set, e.g. "gruvberry ▸ gruvberry-slow-1 [0:03/2:00]"
+    let title = output.rsplit("\x1b]0;").next().and_then(|title| title.split('\x07').next()).unwrap();
+    let elapsed = title.rsplit('[').next().and_then(|position| position.split('/').next()).unwrap();
+    let (minutes, seconds) = elapsed.split_once(':').unwrap_or_else(|| panic!("no position in {:?}", title));
+    let elapsed = minutes.parse::<u32>().unwrap() * 60 + seconds.parse::<u32>().unwrap();
+    // Four seconds in, less the startup and the title's once-a-second
+    // updates; going by the frames drawn it would still be at 0:00
+    assert!(elapsed >= 2, "position {} s in {:?}", elapsed, title);
+
+    let end = after(&output, "Dropped ");
+    let dropped: u64 = end["Dropped ".len()..].split(' ').next().unwrap().parse().unwrap();
+    // Most of the 120 frames due in four seconds
+    assert!(dropped >= 60, "{:?}", end);
+}
+
+// The end of the last track isn't cut off: a file silent but for a tone in
+// its last second still has the tone's level in its statistics, so the
+// visualizer went on analyzing until the audio had played out before the
+// finished state and the exit
+#[test]
+fn the_tone_at_the_very_end_is_analyzed_before_the_exit() {
+    let path = tone_after("tail", 3, 1);
+    let config = path.with_extension("toml");
+    std::fs::write(&config, "").unwrap();
+    let json = path.with_extension("json");
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_gruvberry"));
+    command.args(["--verbose", "--no-color", "--auto-exit", "--stats-json"]).arg(&json);
+    command.arg("--config").arg(&config).arg(&path);
+    let (output, success, cooked) = session(command, |_, _| {});
+    let stats = std::fs::read_to_string(&json).unwrap_or_default();
+    for path in [&path, &config, &json] {
+        let _ = std::fs::remove_file(path);
+    }
+    if output.contains("Can't open the audio device") {
+        eprintln!("skipped: no audio device");
+        return;
+    }
+    assert!(success && cooked, "{:?}", output);
+    let end = after(&output, "\x1b[?1049l");
+    assert!(end.contains("Ended #1") && end.contains("Finished"), "{:?}", end);
+
+    assert!(stats.contains("\"duration\":4"), "{}", stats);
+    let peak = stats.split("\"peak_db\":").nth(1).and_then(|rest| rest.split(',').next()).unwrap();
+    let peak: f32 = peak.parse().unwrap_or_else(|_| panic!("no peak level in {}", stats));
+    assert!((peak + 6.0).abs() < 0.5, "{}", stats);
+}
+
+// A file added over the control socket (--control) joins the queue and
+// plays next, and one that can't be played is refused with the reason
+#[test]
+fn files_can_be_queued_over_the_control_socket() {
+    let first = tone("control-first", 120);
+    let second = tone("control-second", 1);
+    let config = first.with_extension("toml");
+    std::fs::write(&config, "").unwrap();
+    let socket = first.with_extension("sock");
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_gruvberry"));
+    command.args(["--verbose", "--no-color", "--auto-exit", "--config"]).arg(&config);
+    command.arg("--control").arg(format!("unix:{}", socket.display())).arg(&first);
+    let mut answers = Vec::new();
+    let (output, success, cooked) = session(command, |_, master| {
+        std::thread::sleep(Duration::from_millis(1000));
+        if let Ok(mut client) = std::os::unix::net::UnixStream::connect(&socket) {
+            let mut reader = std::io::BufReader::new(client.try_clone().unwrap());
+            for request in [format!("add {}\n", second.display()), "add /no/such/file.wav\n".to_string()] {
+                client.write_all(request.as_bytes()).unwrap();
+                let mut answer = String::new();
+                std::io::BufRead::read_line(&mut reader, &mut answer).unwrap();
+                answers.push(answer);
+            }
+        }
+        type_keys(master, b"n");
+    });
+    for path in [&first, &second, &config] {
+        let _ = std::fs::remove_file(path);
+    }
+    if output.contains("Can't open the audio device") {
+        eprintln!("skipped: no audio device");
+        return;
+    }
+    assert!(success && cooked, "{:?}", output);
+    assert!(!socket.exists(), "the socket file was left behind");
+    assert_eq!(answers.len(), 2, "{:?}", output);
+    let name = second.file_name().unwrap().to_string_lossy();
+    assert_eq!(answers[0], format!("ok Queued {} (#2)\n", name));
+    assert!(answers[1].starts_with("error Not added: /no/such/file.wav: "), "{:?}", answers[1]);
+    assert!(after(&output, "\x1b[?1049l").contains(&format!("Started #2: {}", name)), "{:?}", output);
+}
+
+#[test]
+fn a_follower_mirrors_the_primary_and_sends_its_keys_back() {
+    let played = tone("follow", 120);
+    let config = played.with_extension("toml");
+    std::fs::write(&config, "").unwrap();
+    let socket = played.with_extension("sock");
+    let address = format!("unix:{}", socket.display());
+
+    let mut primary = Command::new(env!("CARGO_BIN_EXE_gruvberry"));
+    primary.args(["--verbose", "--no-color", "--auto-exit", "--config"]).arg(&config);
+    primary.arg("--control").arg(&address).arg(&played);
+    let mut follower = None;
+    let (output, success, cooked) = session(primary, |_, primary| {
+        std::thread::sleep(Duration::from_millis(1000));
+        let mut command = Command::new(env!("CARGO_BIN_EXE_gruvberry"));
+        command.args(["--no-color", "--config"]).arg(&config).args(["--follow", &address]);
+        follower = Some(session(command, |_, master| {
+            std::thread::sleep(Duration::from_millis(1000));
+            type_keys(master, b" ");
+            std::thread::sleep(Duration::from_millis(1000));
+            // The follower goes when the primary does
+            type_keys(primary, b"q");
+        }));
+    });
+    for path in [&played, &config] {
+        let _ = std::fs::remove_file(path);
+    }
+    if output.contains("Can't open the audio device") {
+        eprintln!("skipped: no audio device");
+        return;
+    }
+    assert!(success && cooked, "{:?}", output);
+    let (mirrored, success, cooked) = follower.unwrap();
+    assert!(success && cooked, "{:?}", mirrored);
+
+    // Space on the follower paused the primary, and the follower showed it
+    assert!(after(&output, "\x1b[?1049l").contains("Paused at"), "{:?}", output);
+    let name = played.file_name().unwrap().to_string_lossy();
+    assert!(mirrored.contains("Following") && mirrored.contains(&*name), "{:?}", mirrored);
+    assert!(mirrored.contains('█'), "no bars drawn: {:?}", mirrored);
+    assert!(mirrored.contains("(paused)"), "{:?}", mirrored);
+    assert!(after(&mirrored, "\x1b[?1049l").contains("Stopped following"), "{:?}", mirrored);
+}
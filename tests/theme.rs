@@ -0,0 +1,159 @@
+use std::ffi::OsStr;
+use gruvberry::gradient;
+use gruvberry::theme::{self, frequency_to_color, Theme};
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Terminal;
+
+// Segment boundaries of the gradient (red, orange, yellow, green, cyan, blue, violet)
+const BOUNDARIES: [f32; 7] = [0.0, 0.167, 0.333, 0.5, 0.667, 0.833, 1.0];
+
+fn rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        other => panic!("not an RGB color: {other:?}"),
+    }
+}
+
+// Hue in degrees; violet comes out past blue (about 282) rather than wrapping round to red
+fn hue((r, g, b): (u8, u8, u8)) -> f32 {
+    let (r, g, b) = (r as f32, g as f32, b as f32);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    assert!(chroma > 0.0, "gray in the gradient: {:?}", (r, g, b));
+    if max == r {
+        60.0 * (g - b) / chroma
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    }
+}
+
+#[test]
+fn single_column_is_red() {
+    assert_eq!(frequency_to_color(0, 1), Color::Rgb(255, 0, 0));
+    assert_eq!(frequency_to_color(0, 0), Color::Rgb(255, 0, 0));
+    assert_eq!(frequency_to_color(5, 1), Color::Rgb(255, 0, 0));
+}
+
+#[test]
+fn index_past_the_end_is_the_last_color() {
+    for total in [2, 7, 60, 150] {
+        let last = frequency_to_color(total - 1, total);
+        assert_eq!(last, Color::Rgb(148, 0, 211));
+        assert_eq!(frequency_to_color(total, total), last);
+        assert_eq!(frequency_to_color(usize::MAX, total), last);
+    }
+}
+
+#[test]
+fn hue_rises_across_every_width() {
+    for total in 0..=300 {
+        let mut previous = -1.0;
+        for index in 0..total + 2 {
+            let hue = hue(rgb(frequency_to_color(index, total)));
+            assert!(hue >= previous, "total {total}, index {index}: hue {hue} after {previous}");
+            previous = hue;
+        }
+    }
+}
+
+#[test]
+fn hue_rises_through_segment_boundaries() {
+    // Just below, at and just above each boundary
+    let mut ratios: Vec<f32> = BOUNDARIES
+        .iter()
+        .flat_map(|&b| [b - 1e-4, b, b + 1e-4])
+        .filter(|r| (0.0..=1.0).contains(r))
+        .collect();
+    ratios.sort_by(f32::total_cmp);
+    let hues: Vec<f32> = ratios.iter().map(|&r| hue(gradient::vibgyor(r))).collect();
+    for pair in hues.windows(2) {
+        assert!(pair[1] >= pair[0], "{hues:?}");
+    }
+}
+
+#[test]
+fn out_of_range_ratios_are_clamped() {
+    assert_eq!(gradient::vibgyor(f32::NAN), (255, 0, 0));
+    assert_eq!(gradient::vibgyor(-1.0), (255, 0, 0));
+    assert_eq!(gradient::vibgyor(f32::NEG_INFINITY), (255, 0, 0));
+    assert_eq!(gradient::vibgyor(2.0), (148, 0, 211));
+    assert_eq!(gradient::vibgyor(f32::INFINITY), (148, 0, 211));
+}
+
+// A screen using every role of `theme`: a bordered block as the spectrum's,
+// gradient bars and a line of text in each style
+fn draw(theme: &Theme) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(40, 8)).unwrap();
+    terminal
+        .draw(|f| {
+            let area = f.area();
+            f.render_widget(Block::default().borders(Borders::ALL).border_style(theme.flash), area);
+            let bars: Vec<Span> =
+                (0..20).map(|band| Span::styled("█", Style::default().fg(theme.gradient(frequency_to_color(band, 20))))).collect();
+            let roles = [
+                theme.dim,
+                theme.faint,
+                theme.label,
+                theme.selected,
+                theme.cursor,
+                theme.good,
+                theme.warn,
+                theme.bad,
+                theme.accent,
+            ];
+            let text = Line::from(roles.iter().map(|&style| Span::styled("ab ", style)).collect::<Vec<_>>());
+            f.render_widget(Paragraph::new(vec![Line::from(bars), text]), area.inner(ratatui::layout::Margin::new(1, 1)));
+        })
+        .unwrap();
+    terminal.backend().buffer().clone()
+}
+
+fn colored_cells(buffer: &Buffer) -> usize {
+    buffer
+        .content()
+        .iter()
+        .filter(|cell| cell.fg != Color::Reset || cell.bg != Color::Reset || cell.underline_color != Color::Reset)
+        .count()
+}
+
+#[test]
+fn no_color_draws_without_any_color() {
+    let plain = Theme::detect(true);
+    assert_eq!(plain, Theme::plain());
+    assert!(!plain.is_colored());
+    let buffer = draw(&plain);
+    assert_eq!(colored_cells(&buffer), 0);
+    // Only what has to stand out is set apart, by reversing it
+    assert!(buffer.content().iter().all(|cell| cell.modifier.difference(Modifier::REVERSED).is_empty()));
+    assert!(buffer.content().iter().any(|cell| cell.modifier == Modifier::REVERSED));
+
+    // The same screen in the default theme is in color
+    assert!(colored_cells(&draw(&Theme::default())) > 20);
+}
+
+#[test]
+fn no_color_counts_when_set_to_anything() {
+    assert!(theme::no_color_set(Some(OsStr::new("1"))));
+    assert!(theme::no_color_set(Some(OsStr::new("false"))));
+    assert!(!theme::no_color_set(Some(OsStr::new(""))));
+    assert!(!theme::no_color_set(None));
+}
+
+#[test]
+fn adapting_a_theme_keeps_its_roles_apart() {
+    let basic = Theme::default().map_colors(|color| match color {
+        Color::Rgb(..) => Color::Yellow,
+        other => other,
+    });
+    assert_eq!(basic.accent, Style::default().fg(Color::Yellow));
+    assert_eq!(basic.bad, Theme::default().bad);
+    // Nothing to adapt in a plain theme
+    assert_eq!(Theme::plain().map_colors(|_| Color::Red), Theme::plain());
+}
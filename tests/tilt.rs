@@ -0,0 +1,82 @@
+use gruvberry::analysis::{self, Analyzer};
+use gruvberry::tilt::{Preset, Tilt};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rustfft::{FftPlanner, num_complex::Complex};
+
+const RATE: u32 = 44100;
+const FFT_SIZE: usize = 4096;
+const BANDS: usize = 48;
+
+// Pink noise (power falling 3 dB per octave), shaped in the frequency domain
+fn pink_noise(len: usize) -> Vec<f32> {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut spectrum = vec![Complex { re: 0.0f32, im: 0.0 }; len];
+    for k in 1..len / 2 {
+        let phase = rng.random_range(0.0..std::f32::consts::TAU);
+        let value = Complex::from_polar(1.0 / (k as f32).sqrt(), phase);
+        spectrum[k] = value;
+        spectrum[len - k] = value.conj();
+    }
+    FftPlanner::new().plan_fft_inverse(len).process(&mut spectrum);
+    let peak = spectrum.iter().fold(0.0f32, |peak, c| peak.max(c.re.abs()));
+    spectrum.iter().map(|c| c.re / peak * 0.5).collect()
+}
+
+// Band levels in dB as drawn with `tilt`, averaged over many windows
+fn drawn_levels_db(samples: &[f32], tilt: Tilt) -> Vec<f32> {
+    let mut analyzer = Analyzer::new(RATE, FFT_SIZE, 1.0, tilt);
+    let mut sums = [0.0f32; BANDS];
+    let windows = samples.chunks_exact(FFT_SIZE);
+    let count = windows.len() as f32;
+    for window in windows {
+        for (sum, band) in sums.iter_mut().zip(analyzer.process(window, BANDS)) {
+            *sum += band;
+        }
+    }
+    sums.iter().map(|sum| 20.0 * (sum / count).log10()).collect()
+}
+
+// Bands lying wholly above 100 Hz
+fn above_100_hz(levels: &[f32]) -> Vec<f32> {
+    let max_freq = RATE as f32 / 2.0;
+    (0..BANDS)
+        .filter(|&band| analysis::band_edges(band, BANDS, max_freq).0 >= 100.0)
+        .map(|band| levels[band])
+        .collect()
+}
+
+#[test]
+fn pink_flat_renders_pink_noise_flat() {
+    let levels = above_100_hz(&drawn_levels_db(&pink_noise(1 << 19), Preset::PinkFlat.tilt()));
+    let mean = levels.iter().sum::<f32>() / levels.len() as f32;
+    for level in &levels {
+        assert!((level - mean).abs() <= 2.0, "{:?}", levels);
+    }
+}
+
+#[test]
+fn raw_renders_pink_noise_falling() {
+    let levels = above_100_hz(&drawn_levels_db(&pink_noise(1 << 19), Preset::Raw.tilt()));
+    // Over seven octaves from 100 Hz, 3 dB each
+    assert!(levels[0] - levels[levels.len() - 1] > 15.0, "{:?}", levels);
+}
+
+#[test]
+fn presets_parse_by_name_and_cycle_back_to_the_config() {
+    for preset in Preset::ALL {
+        assert_eq!(Tilt::parse(preset.name()), Ok(preset.tilt()));
+        assert_eq!(Preset::of(&preset.tilt()), Some(preset));
+    }
+    assert_eq!(Preset::of(&Tilt::default()), None);
+
+    let mut cycle = vec![];
+    let mut current = None;
+    loop {
+        current = Preset::next(current);
+        cycle.push(current);
+        if current.is_none() {
+            break;
+        }
+    }
+    assert_eq!(cycle, [Some(Preset::Raw), Some(Preset::PinkFlat), Some(Preset::Music), None]);
+}
@@ -0,0 +1,341 @@
+use std::f32::consts::{PI, TAU};
+use std::time::{Duration, Instant};
+use gruvberry::analysis::Analyzer;
+use gruvberry::capture::{SampleCapture, SampleObserver, SampleRing, SharedRing};
+use gruvberry::tilt::Tilt;
+use gruvberry::transport::{FakeTransport, Playhead, Player, Transport};
+use rodio::buffer::SamplesBuffer;
+use rodio::{Source, source::SineWave};
+
+fn secs(secs: f32) -> Duration {
+    Duration::from_secs_f32(secs)
+}
+
+// A player on one fake stream with `tracks` queued
+fn player(tracks: &[f32]) -> Player<FakeTransport> {
+    let mut fake = FakeTransport::new();
+    for &length in tracks {
+        fake.append(secs(length));
+    }
+    Player::new(vec![fake])
+}
+
+fn advance(player: &mut Player<FakeTransport>, step: f32) {
+    for i in 0..player.transports().len() {
+        player.transport_mut(i).advance(secs(step));
+    }
+}
+
+#[test]
+fn pause_freezes_the_position() {
+    let mut player = player(&[10.0]);
+    advance(&mut player, 3.0);
+    assert!(player.toggle_pause());
+    advance(&mut player, 5.0);
+    assert_eq!(player.position(), secs(3.0));
+    assert!(player.transports()[0].is_paused());
+
+    assert!(!player.toggle_pause());
+    advance(&mut player, 1.0);
+    assert_eq!(player.position(), secs(4.0));
+}
+
+#[test]
+fn seek_moves_every_stream_and_stays_in_the_track() {
+    let mut fakes = vec![FakeTransport::new(), FakeTransport::new()];
+    fakes[0].append(secs(10.0));
+    fakes[1].append(secs(12.0));
+    let mut player = Player::new(fakes);
+    advance(&mut player, 2.0);
+
+    assert_eq!(player.seek_by(5.0, secs(10.0)), Ok(secs(7.0)));
+    assert!(player.transports().iter().all(|fake| fake.position() == secs(7.0)));
+    assert_eq!(player.seek_by(-30.0, secs(10.0)), Ok(Duration::ZERO));
+    assert_eq!(player.position(), Duration::ZERO);
+}
+
+#[test]
+fn seek_while_paused_stays_paused() {
+    let mut player = player(&[10.0]);
+    player.toggle_pause();
+    player.seek(secs(6.0)).unwrap();
+    advance(&mut player, 2.0);
+    assert_eq!(player.position(), secs(6.0));
+    assert!(!player.track_ended());
+}
+
+#[test]
+fn seek_past_the_end_ends_the_track() {
+    let mut player = player(&[10.0]);
+    assert_eq!(player.seek_by(60.0, secs(10.0)), Ok(secs(10.0)));
+    assert!(player.track_ended());
+    assert_eq!(player.seek(secs(1.0)), Err("nothing is playing".to_string()));
+}
+
+#[test]
+fn end_of_track_advances_to_the_next() {
+    let mut player = player(&[4.0]);
+    let mut queue = vec![6.0, 5.0];
+    let mut started = 1;
+    // The player loop: at the end of a track, the next one from the queue
+    for _ in 0..200 {
+        advance(&mut player, 0.1);
+        if player.track_ended() {
+            if queue.is_empty() {
+                break;
+            }
+            player.append(secs(queue.remove(0)));
+            started += 1;
+            assert!(player.position() < secs(0.1));
+        }
+    }
+    assert_eq!(started, 3);
+    assert!(player.track_ended());
+}
+
+#[test]
+fn a_new_track_starts_paused_when_paused() {
+    let mut player = player(&[4.0]);
+    player.toggle_pause();
+    player.skip();
+    assert!(player.track_ended());
+    player.append(secs(8.0));
+    advance(&mut player, 2.0);
+    assert_eq!(player.position(), Duration::ZERO);
+    player.toggle_pause();
+    advance(&mut player, 2.0);
+    assert_eq!(player.position(), secs(2.0));
+}
+
+#[test]
+fn compared_streams_end_at_the_shorter_one() {
+    let mut fakes = vec![FakeTransport::new(), FakeTransport::new()];
+    fakes[0].append(secs(10.0));
+    fakes[1].append(secs(6.0));
+    let mut player = Player::new(fakes);
+    advance(&mut player, 5.9);
+    assert!(!player.track_ended());
+    advance(&mut player, 0.2);
+    assert!(player.track_ended());
+}
+
+#[test]
+fn only_the_audible_stream_is_heard() {
+    let mut player = Player::new(vec![FakeTransport::new(), FakeTransport::new()]);
+    assert_eq!(player.transports()[0].volume(), 1.0);
+    assert_eq!(player.transports()[1].volume(), 0.0);
+    player.hear(1);
+    assert_eq!(player.audible(), 1);
+    assert_eq!(player.transports()[0].volume(), 0.0);
+    assert_eq!(player.transports()[1].volume(), 1.0);
+}
+
+#[test]
+fn playhead_follows_pause_resume_and_seek() {
+    let start = Instant::now();
+    let at = |t: f32| start + secs(t);
+    let mut playhead = Playhead::start(start, false);
+    assert_eq!(playhead.position(at(2.0)), secs(2.0));
+
+    playhead.pause(secs(2.0));
+    assert!(playhead.is_paused());
+    assert_eq!(playhead.position(at(9.0)), secs(2.0));
+    playhead.seek(secs(30.0), at(9.0));
+    assert_eq!(playhead.position(at(12.0)), secs(30.0));
+
+    playhead.resume(secs(30.0), at(12.0));
+    assert_eq!(playhead.position(at(13.5)), secs(31.5));
+    playhead.seek(secs(5.0), at(14.0));
+    assert_eq!(playhead.position(at(15.0)), secs(6.0));
+}
+
+#[test]
+fn seeking_through_the_tap_starts_the_ring_over() {
+    let ring = SampleRing::shared(4096);
+    let mut tap = SampleCapture::new(SineWave::new(440.0), ring.clone());
+    tap.by_ref().take(2048).for_each(drop);
+    assert!(ring.lock().unwrap().window(1024, 0).is_some());
+
+    tap.try_seek(secs(1.0)).unwrap();
+    assert!(ring.lock().unwrap().window(1, 0).is_none());
+    tap.by_ref().take(1024).for_each(drop);
+    assert!(ring.lock().unwrap().window(1024, 0).is_some());
+}
+
+// A live sine behind a fake transport, paused for a while mid-play. Its
+// source runs on unheard meanwhile (a radio stream, a capture device), so
+// playback picks up at another phase. The sine sits on FFT bin `BIN` of a
+// `WINDOW`-sample window, and pulls of `HOP` samples are no whole number of
+// its periods, so a window across the pause mixes two phases.
+const RATE: u32 = 8000;
+const WINDOW: usize = 256;
+const BIN: usize = 16;
+const HOP: usize = 100;
+
+struct Frame {
+    // The sine bin's magnitude, and how far its phase is from that of the
+    // newest samples' sine
+    magnitude: f32,
+    phase_error: f32,
+    // Loudest smoothed band
+    level: f32,
+}
+
+// Analysis frames of the paused sine, with the ring told of the pause and
+// resume when `mark` (as the player does)
+fn paused_sine(mark: bool) -> (Vec<Frame>, SampleRing, Player<FakeTransport>) {
+    let mut player = player(&[60.0]);
+    let mut ring = SampleRing::new(WINDOW * 2);
+    let mut analyzer = Analyzer::new(RATE, WINDOW, 0.5, Tilt::Flat);
+    let sine = |clock: usize| (TAU * (BIN * clock) as f32 / WINDOW as f32).sin();
+    let mut frames = Vec::new();
+    let mut clock = 0;
+    for hop in 0..40 {
+        if hop == 15 || hop == 18 {
+            if mark {
+                ring.resume();
+            }
+            if player.toggle_pause() && mark {
+                ring.pause();
+            }
+        }
+        if !player.is_paused() {
+            let samples: Vec<f32> = (clock..clock + HOP).map(sine).collect();
+            ring.on_samples(&samples, 1, RATE);
+        }
+        advance(&mut player, HOP as f32 / RATE as f32);
+        clock += HOP;
+
+        if ring.holding(WINDOW) {
+            continue;
+        }
+        let Some(window) = ring.window(WINDOW, 0) else {
+            continue;
+        };
+        let (re, im) = window.iter().enumerate().fold((0.0, 0.0), |(re, im), (n, x)| {
+            let angle = TAU * (BIN * n) as f32 / WINDOW as f32;
+            (re + x * angle.cos(), im - x * angle.sin())
+        });
+        let expected = TAU * (BIN * (clock - WINDOW)) as f32 / WINDOW as f32 - PI / 2.0;
+        let error = im.atan2(re) - expected;
+        let phase_error = error.sin().atan2(error.cos());
+        let level = analyzer.process(&window, 32).iter().fold(0.0f32, |max, &band| max.max(band));
+        frames.push(Frame { magnitude: re.hypot(im), phase_error, level });
+    }
+    (frames, ring, player)
+}
+
+#[test]
+fn a_paused_ring_resumes_from_fresh_samples_without_mixing_phases() {
+    let (frames, ring, player) = paused_sine(true);
+    let full = WINDOW as f32 / 2.0;
+    for (i, frame) in frames.iter().enumerate() {
+        assert!((frame.magnitude - full).abs() < full * 0.01, "frame {}: magnitude {}", i, frame.magnitude);
+        assert!(frame.phase_error.abs() < 0.01, "frame {}: phase off by {}", i, frame.phase_error);
+    }
+    // The bars go on where they were, and the audio time with the transport
+    let levels: Vec<f32> = frames.iter().map(|frame| frame.level).collect();
+    let (low, high) = levels.iter().fold((f32::MAX, 0.0f32), |(low, high), &level| (low.min(level), high.max(level)));
+    assert!(high - low < high * 0.01, "levels {:?}", levels);
+    assert!((ring.time() - player.position().as_secs_f32()).abs() < 1e-3);
+
+    // Left unmarked, the windows across the pause mix two phases
+    let (frames, _, _) = paused_sine(false);
+    assert!(frames.iter().any(|frame| frame.phase_error.abs() > 0.5 && frame.magnitude < full * 0.9));
+}
+
+#[test]
+fn holding_lasts_from_pause_until_a_fresh_window_and_a_seek_ends_it() {
+    let mut ring = SampleRing::new(64);
+    ring.on_samples(&[0.5; 64], 1, RATE);
+    ring.resume();
+    assert_eq!(ring.window(64, 0).map(|window| window.len()), Some(64));
+
+    ring.pause();
+    assert!(ring.is_paused() && ring.holding(32));
+    ring.resume();
+    assert!(ring.holding(32));
+    assert!(ring.window(1, 0).is_none());
+    ring.on_samples(&[0.25; 31], 1, RATE);
+    assert!(ring.holding(32));
+    ring.on_samples(&[0.25], 1, RATE);
+    assert!(!ring.holding(32));
+
+    // Seeking while paused empties the ring: there is nothing left to hold
+    ring.pause();
+    ring.on_seek(secs(4.0));
+    assert!(!ring.holding(32));
+    ring.resume();
+    assert!(ring.holding(32));
+    assert_eq!(ring.time(), 4.0);
+}
+
+// A fixture of two disjoint tones, one on FFT bin `BIN` of a `WINDOW`-sample
+// window for its first two seconds and one on bin `LATE_BIN` for the last
+// two, played through the tap in `HOP`-sample pulls behind a fake transport,
+// and sought from the middle of the first tone into the second. Each frame
+// is the magnitude of the two tones' bins in the ring's window, at most
+// WINDOW / 2 for a window of one tone alone.
+const LATE_BIN: usize = 80;
+
+fn seek_across_tones<O: SampleObserver>(observer: O, ring: &SharedRing) -> (Vec<(f32, f32)>, Player<FakeTransport>) {
+    let tone = |n: usize| {
+        let bin = if n < 2 * RATE as usize { BIN } else { LATE_BIN };
+        (TAU * (bin * n) as f32 / WINDOW as f32).sin()
+    };
+    let fixture = SamplesBuffer::new(1, RATE, (0..4 * RATE as usize).map(tone).collect::<Vec<_>>());
+    let mut tap = SampleCapture::new(fixture, observer);
+    let mut player = player(&[4.0]);
+    let bin = |window: &[f32], bin: usize| {
+        let (re, im) = window.iter().enumerate().fold((0.0, 0.0), |(re, im), (n, x)| {
+            let angle = TAU * (bin * n) as f32 / WINDOW as f32;
+            (re + x * angle.cos(), im - x * angle.sin())
+        });
+        re.hypot(im)
+    };
+    let mut frames = Vec::new();
+    for hop in 0..150 {
+        if hop == 80 {
+            let landed = player.seek_by(2.0, secs(4.0)).unwrap();
+            tap.try_seek(landed).unwrap();
+        }
+        tap.by_ref().take(HOP).for_each(drop);
+        tap.flush();
+        advance(&mut player, HOP as f32 / RATE as f32);
+        if let Some(window) = ring.lock().unwrap().window(WINDOW, 0) {
+            frames.push((bin(&window, BIN), bin(&window, LATE_BIN)));
+        }
+    }
+    (frames, player)
+}
+
+// The ring without being told of seeks
+struct Unmarked(SharedRing);
+
+impl SampleObserver for Unmarked {
+    fn on_samples(&mut self, samples: &[f32], channels: u16, sample_rate: u32) {
+        self.0.on_samples(samples, channels, sample_rate);
+    }
+}
+
+#[test]
+fn no_frame_after_a_seek_mixes_audio_from_before_it() {
+    let ring = SampleRing::shared(WINDOW * 2);
+    let (frames, player) = seek_across_tones(ring.clone(), &ring);
+    let full = WINDOW as f32 / 2.0;
+    let one_tone = |(early, late): (f32, f32)| {
+        (early > full * 0.99 && late < full * 0.01) || (late > full * 0.99 && early < full * 0.01)
+    };
+    for (i, &frame) in frames.iter().enumerate() {
+        assert!(one_tone(frame), "frame {} mixes the tones: {:?}", i, frame);
+    }
+    assert!(frames.iter().any(|&(early, _)| early > full * 0.99));
+    assert!(frames.iter().any(|&(_, late)| late > full * 0.99));
+    // The ring's clock landed where the transport did
+    assert!((ring.lock().unwrap().time() - player.position().as_secs_f32()).abs() < 1e-3);
+
+    // Left unmarked, the windows just after the seek hold both tones
+    let ring = SampleRing::shared(WINDOW * 2);
+    let (frames, _) = seek_across_tones(Unmarked(ring.clone()), &ring);
+    assert!(frames.iter().any(|&(early, late)| early > full * 0.1 && late > full * 0.1));
+}
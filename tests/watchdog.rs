@@ -0,0 +1,115 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use gruvberry::capture::SampleCapture;
+use gruvberry::flow::FlowMeter;
+use gruvberry::watchdog::{Verdict, Watchdog};
+use rodio::Source;
+
+// A source that plays `samples` of silence and then stops yielding: the
+// next sample waits until `resume` says so, the way an output whose device
+// callback has wedged never asks for it
+struct Stalling {
+    samples: usize,
+    resume: mpsc::Receiver<()>,
+}
+
+impl Iterator for Stalling {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.samples == 0 {
+            self.resume.recv().ok()?;
+        }
+        self.samples = self.samples.saturating_sub(1);
+        Some(0.0)
+    }
+}
+
+impl Source for Stalling {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        44100
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[test]
+fn a_source_that_stops_yielding_sets_off_the_watchdog() {
+    let timeout = Duration::from_millis(100);
+    let flow = FlowMeter::shared();
+    let (resume, stalled) = mpsc::channel();
+    let mut capture = SampleCapture::new(Stalling { samples: 4096, resume: stalled }, flow.clone());
+    // The "device" pulls on its own thread, until the source stalls
+    let device = thread::spawn(move || capture.by_ref().take(8192).count());
+
+    let start = Instant::now();
+    let mut watchdog = Watchdog::new(timeout, start);
+    let mut verdicts = Vec::new();
+    while start.elapsed() < timeout * 5 {
+        let pulled = flow.lock().unwrap().last_pulled();
+        let verdict = watchdog.check(pulled, true, Instant::now());
+        if verdict == Verdict::Reopen {
+            watchdog.reopened(Instant::now());
+        }
+        if verdicts.last() != Some(&verdict) {
+            verdicts.push(verdict);
+        }
+        if verdict == Verdict::GiveUp {
+            break;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    // Samples came through, then the stall was noticed, the reopen didn't
+    // help (the same source is still stuck) and the watchdog gave up
+    assert!(flow.lock().unwrap().last_pulled().is_some());
+    assert_eq!(verdicts, [Verdict::Running, Verdict::Reopen, Verdict::Running, Verdict::GiveUp]);
+    assert!(watchdog.has_reopened());
+
+    drop(resume);
+    assert_eq!(device.join().unwrap(), 4096);
+}
+
+#[test]
+fn samples_keep_the_watchdog_quiet() {
+    let start = Instant::now();
+    let at = |ms: u64| start + Duration::from_millis(ms);
+    let mut watchdog = Watchdog::new(Duration::from_millis(100), start);
+    for ms in (0..1000).step_by(50) {
+        assert_eq!(watchdog.check(Some(at(ms)), true, at(ms + 20)), Verdict::Running);
+    }
+}
+
+#[test]
+fn nothing_needs_pulling_while_paused() {
+    let start = Instant::now();
+    let at = |ms: u64| start + Duration::from_millis(ms);
+    let mut watchdog = Watchdog::new(Duration::from_millis(100), start);
+    // Paused for a long time: no stall, and the wait starts over on resuming
+    assert_eq!(watchdog.check(Some(start), false, at(5000)), Verdict::Running);
+    assert_eq!(watchdog.check(Some(start), true, at(5050)), Verdict::Running);
+    assert_eq!(watchdog.check(Some(start), true, at(5100)), Verdict::Reopen);
+}
+
+#[test]
+fn a_track_that_never_starts_counts_from_the_watch() {
+    let start = Instant::now();
+    let mut watchdog = Watchdog::new(Duration::from_millis(100), start);
+    assert_eq!(watchdog.check(None, true, start + Duration::from_millis(99)), Verdict::Running);
+    assert_eq!(watchdog.check(None, true, start + Duration::from_millis(100)), Verdict::Reopen);
+    // Reopened, and the new device plays: quiet again, until it stalls too
+    let reopened = start + Duration::from_millis(110);
+    watchdog.reopened(reopened);
+    assert_eq!(watchdog.check(Some(reopened + Duration::from_millis(50)), true, reopened + Duration::from_millis(120)), Verdict::Running);
+    assert_eq!(watchdog.check(None, true, reopened + Duration::from_millis(150)), Verdict::GiveUp);
+}
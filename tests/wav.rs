@@ -0,0 +1,165 @@
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use gruvberry::wav;
+use rodio::Decoder;
+
+fn fixture_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+fn fixture(name: &str) -> File {
+    File::open(fixture_path(name)).unwrap()
+}
+
+// The broken files, each with what gruvberry says about it
+const BROKEN: [(&str, &str); 3] = [
+    ("empty.wav", "the file is empty"),
+    ("no_data.wav", "there is no audio after the header"),
+    ("zero_rate.wav", "the header gives a sample rate of 0 Hz"),
+];
+
+#[test]
+fn compressed_codecs_are_named_with_a_way_out() {
+    for (name, tag, codec) in [
+        ("mulaw.wav", wav::MU_LAW, "µ-law"),
+        ("mulaw_extensible.wav", wav::MU_LAW, "µ-law"),
+        ("ima_adpcm.wav", wav::IMA_ADPCM, "IMA ADPCM"),
+        ("ms_adpcm.wav", wav::MS_ADPCM, "Microsoft ADPCM"),
+    ] {
+        assert_eq!(wav::format_tag(fixture(name)).unwrap(), Some(tag), "{}", name);
+        assert!(!wav::is_supported(tag));
+        assert_eq!(
+            wav::unsupported_message(tag),
+            format!("WAV file uses {} which is not supported; convert with ffmpeg -i in.wav -c:a pcm_s16le out.wav", codec)
+        );
+        // Which the decoder would only have called unsupported
+        assert!(hound::WavReader::new(fixture(name)).is_err());
+    }
+}
+
+#[test]
+fn pcm_and_float_files_pass() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/sound.wav");
+    let tag = wav::format_tag(File::open(path).unwrap()).unwrap();
+    assert_eq!(tag, Some(wav::PCM));
+    assert!(wav::is_supported(wav::PCM) && wav::is_supported(wav::IEEE_FLOAT));
+
+    let mut float = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 48000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::new(&mut float, spec).unwrap();
+    writer.write_sample(0.5f32).unwrap();
+    writer.write_sample(-0.5f32).unwrap();
+    writer.finalize().unwrap();
+    assert_eq!(wav::format_tag(Cursor::new(float.into_inner())).unwrap(), Some(wav::IEEE_FLOAT));
+}
+
+#[test]
+fn chunks_before_fmt_are_skipped_and_other_files_left_alone() {
+    // A LIST chunk of odd length (padded) ahead of `fmt `
+    let mut file = b"RIFF\0\0\0\0WAVELIST\x03\0\0\0abc\0".to_vec();
+    file.extend_from_slice(b"fmt \x10\0\0\0\x55\0");
+    file.extend_from_slice(&[0; 14]);
+    assert_eq!(wav::format_tag(Cursor::new(file)).unwrap(), Some(wav::MP3));
+
+    assert_eq!(wav::format_tag(Cursor::new(b"ID3\x04 an mp3".to_vec())).unwrap(), None);
+    assert_eq!(wav::format_tag(Cursor::new(b"RIFF\0\0\0\0WAVEdata".to_vec())).unwrap(), None);
+    assert_eq!(wav::format_tag(Cursor::new(Vec::new())).unwrap(), None);
+    assert_eq!(
+        wav::unsupported_message(0x1234),
+        "WAV file uses format 0x1234 which is not supported; convert with ffmpeg -i in.wav -c:a pcm_s16le out.wav"
+    );
+}
+
+#[test]
+fn broken_headers_are_refused_with_a_readable_reason() {
+    for (name, reason) in BROKEN {
+        assert_eq!(wav::inspect(BufReader::new(fixture(name))), Err(reason.to_string()), "{}", name);
+    }
+    assert_eq!(
+        wav::inspect(BufReader::new(fixture("mulaw.wav"))),
+        Err(wav::unsupported_message(wav::MU_LAW))
+    );
+    assert!(wav::inspect(Cursor::new(b"RIFF\x10\0\0\0WAVEfmt ".to_vec())).is_err());
+    assert!(wav::inspect(Cursor::new(b"not a wav file at all".to_vec())).is_err());
+}
+
+#[test]
+fn a_file_cut_short_plays_what_is_there() {
+    // The header promises a second at 8 kHz; 1000 samples made it
+    let inspection = wav::inspect(BufReader::new(fixture("truncated.wav"))).unwrap();
+    assert_eq!((inspection.frames, inspection.declared_frames), (1000, 8000));
+    assert!(inspection.is_truncated());
+    assert_eq!(inspection.duration(), 0.125);
+    assert_eq!(
+        inspection.truncation_warning().as_deref(),
+        Some("the file is cut short: 0:00 of the 0:01 its header promises; playing what is there")
+    );
+    // And the decoder ends there like at the end of any file
+    let decoder = Decoder::new(BufReader::new(fixture("truncated.wav"))).unwrap();
+    assert_eq!(decoder.count(), 1000);
+
+    let sound = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/sound.wav");
+    let whole = wav::inspect(BufReader::new(File::open(sound).unwrap())).unwrap();
+    assert!(!whole.is_truncated() && whole.truncation_warning().is_none());
+    assert!(whole.duration() > 0.0);
+}
+
+// Run on a broken file, gruvberry says why on stderr and exits before
+// touching the terminal or the audio device: nothing on stdout, not one
+// escape sequence, no panic
+#[test]
+fn broken_files_stop_gruvberry_before_the_terminal_is_touched() {
+    for (name, reason) in BROKEN {
+        let output = Command::new(env!("CARGO_BIN_EXE_gruvberry"))
+            .arg(fixture_path(name))
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(1), "{}: {}", name, stderr);
+        assert!(output.stdout.is_empty(), "{}", name);
+        assert!(stderr.ends_with(&format!("{}: {}\n", name, reason)), "{}: {:?}", name, stderr);
+        assert!(!stderr.contains('\x1b') && !stderr.contains("panicked"), "{}: {:?}", name, stderr);
+    }
+}
+
+// A 16-bit WAV of `frames` frames of silence with `channels` channels
+fn silence(channels: u16, sample_rate: u32, frames: u32) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("gruvberry-wav-{}-{}ch.wav", std::process::id(), channels));
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for _ in 0..frames * channels as u32 {
+        writer.write_sample(0i16).unwrap();
+    }
+    writer.finalize().unwrap();
+    path
+}
+
+#[test]
+fn the_duration_counts_frames_whatever_the_channels() {
+    // 2.5 s at 44.1 kHz in each layout; a 4-channel file is WAVE_FORMAT_EXTENSIBLE
+    for channels in [1, 2, 4] {
+        let path = silence(channels, 44100, 110_250);
+        let inspection = wav::inspect(BufReader::new(File::open(&path).unwrap())).unwrap();
+        assert_eq!(inspection.spec.channels, channels);
+        assert_eq!(inspection.frames, 110_250, "{} channels", channels);
+        assert_eq!(inspection.duration(), 2.5, "{} channels", channels);
+        assert!(!inspection.is_truncated());
+        // What the decoder plays agrees
+        let decoder = Decoder::new(BufReader::new(File::open(&path).unwrap())).unwrap();
+        assert_eq!(decoder.count(), 110_250 * channels as usize);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+use std::time::{Duration, Instant};
+use gruvberry::analysis::FreqRange;
+use gruvberry::format::Locale;
+use gruvberry::zoom::{self, Zoom, ANIMATION, MIN_OCTAVES, STEP};
+
+const FULL: FreqRange = FreqRange { low: 20.0, high: 22050.0 };
+
+fn close(a: FreqRange, b: FreqRange) -> bool {
+    (a.low / b.low - 1.0).abs() < 1e-3 && (a.high / b.high - 1.0).abs() < 1e-3
+}
+
+#[test]
+fn narrowing_keeps_the_center_in_place() {
+    let now = Instant::now();
+    let mut zoom = Zoom::default();
+    let position = FULL.position(1000.0);
+    zoom.narrow(1000.0, FULL, now);
+    let range = zoom.target(FULL);
+    assert!((range.octaves() - FULL.octaves() / STEP).abs() < 1e-3);
+    assert!((range.position(1000.0) - position).abs() < 1e-3);
+    assert!(zoom.is_zoomed());
+}
+
+#[test]
+fn widening_back_out_reaches_the_whole_range() {
+    let now = Instant::now();
+    let mut zoom = Zoom::default();
+    for _ in 0..3 {
+        zoom.narrow(500.0, FULL, now);
+    }
+    for _ in 0..3 {
+        zoom.widen(500.0, FULL, now);
+    }
+    assert!(!zoom.is_zoomed());
+    assert_eq!(zoom.target(FULL), FULL);
+}
+
+#[test]
+fn a_zoom_stays_within_the_whole_range() {
+    let now = Instant::now();
+    let mut zoom = Zoom::default();
+    // A selection past the top is slid back down instead of past Nyquist
+    zoom.select(15000.0, 60000.0, FULL, now);
+    let range = zoom.target(FULL);
+    assert!((range.high - FULL.high).abs() < 1.0);
+    assert!((range.octaves() - 2.0).abs() < 1e-3);
+
+    // A lower sample rate later takes the same zoom in with it
+    zoom.select(5000.0, 20000.0, FULL, now);
+    let low_rate = FreqRange::full(8000.0);
+    let range = zoom.target(low_rate);
+    assert!(range.high <= 8000.0 && range.low >= 20.0);
+}
+
+#[test]
+fn a_zoom_is_never_narrower_than_the_minimum() {
+    let now = Instant::now();
+    let mut zoom = Zoom::default();
+    zoom.select(1000.0, 1010.0, FULL, now);
+    assert!((zoom.target(FULL).octaves() - MIN_OCTAVES).abs() < 1e-3);
+    for _ in 0..20 {
+        zoom.narrow(1000.0, FULL, now);
+    }
+    assert!((zoom.target(FULL).octaves() - MIN_OCTAVES).abs() < 1e-3);
+    // Dragged from right to left
+    zoom.select(3200.0, 200.0, FULL, now);
+    assert!(close(zoom.target(FULL), FreqRange { low: 200.0, high: 3200.0 }));
+}
+
+#[test]
+fn the_range_glides_to_a_new_zoom() {
+    let now = Instant::now();
+    let mut zoom = Zoom::default();
+    let target = FreqRange { low: 200.0, high: 3200.0 };
+    zoom.select(target.low, target.high, FULL, now);
+    let halfway = zoom.current(FULL, now + ANIMATION / 2);
+    assert!(halfway.low > FULL.low && halfway.low < target.low);
+    assert!(halfway.high < FULL.high && halfway.high > target.high);
+    assert!(close(zoom.current(FULL, now + ANIMATION), target));
+
+    // Reset halfway back: from where it was, not from the target
+    let at = now + ANIMATION / 2;
+    zoom.reset(FULL, at);
+    assert!(close(zoom.current(FULL, at), halfway));
+    assert_eq!(zoom.current(FULL, at + ANIMATION + Duration::from_millis(1)), FULL);
+}
+
+#[test]
+fn labels_read_as_hertz_and_kilohertz() {
+    let locale = Locale::default();
+    assert_eq!(zoom::label(FreqRange { low: 200.0, high: 3200.0 }, locale), "200 Hz – 3.2 kHz");
+    assert_eq!(zoom::label(FULL, locale), "20 Hz – 22 kHz");
+    assert_eq!(zoom::label(FreqRange { low: 1000.0, high: 16000.0 }, locale), "1.0 kHz – 16 kHz");
+    assert_eq!(zoom::label(FreqRange { low: 1000.0, high: 3200.0 }, Locale::new(',')), "1,0 kHz – 3,2 kHz");
+}
+
+#[test]
+fn band_ranges_follow_the_zoom() {
+    let range = FreqRange { low: 100.0, high: 1600.0 };
+    assert_eq!(range.band_edges(0, 4).0, 100.0);
+    assert!((range.band_edges(0, 4).1 - 200.0).abs() < 1e-3);
+    assert_eq!(range.band_of(250.0, 4), 1);
+    assert_eq!(range.band_of(50.0, 4), 0);
+    assert_eq!(range.band_of(5000.0, 4), 3);
+    // A selection the other way round covers the same bands
+    let selected = range.bands(2, 1, 4);
+    assert!(close(selected, FreqRange { low: 200.0, high: 800.0 }));
+}